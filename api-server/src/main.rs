@@ -0,0 +1,44 @@
+// HTTP API for WBA auction house data, served from the database the indexer writes.
+//
+// Configuration comes from the environment:
+// - DATABASE_URL: the Postgres connection string of the indexer database.
+// - BIND_ADDR: the address to listen on (defaults to 0.0.0.0:8080).
+
+// Declare the route handlers.
+mod routes;
+
+// Import the error handling helpers from the anyhow library.
+use anyhow::{Context, Result};
+// Import the router from the axum library.
+use axum::{routing::get, Router};
+// Import the shared pointer used for the database client.
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL is not set")?;
+    let bind_addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string());
+
+    // Connect to the indexer database.
+    let (client, connection) = tokio_postgres::connect(&database_url, tokio_postgres::NoTls)
+        .await
+        .context("connecting to Postgres")?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            eprintln!("postgres connection error: {err}");
+        }
+    });
+
+    let app = Router::new()
+        .route("/auctions", get(routes::auctions))
+        .route("/auctions/:id/bids", get(routes::auction_bids))
+        .route("/collections/:mint/stats", get(routes::collection_stats))
+        .with_state(Arc::new(client));
+
+    let listener = tokio::net::TcpListener::bind(&bind_addr)
+        .await
+        .with_context(|| format!("binding {bind_addr}"))?;
+    println!("listening on {bind_addr}");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+// Import the extractors, status codes and response types from the axum library.
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+// Import the JSON value type and constructor from the serde_json library.
+use serde_json::{json, Value};
+// Import the map type used for query parameters.
+use std::collections::HashMap;
+// Import the shared pointer used for the database client.
+use std::sync::Arc;
+// Import the Postgres client and row types from the tokio_postgres library.
+use tokio_postgres::{Client, Row};
+
+// Define the largest page of results a request can ask for.
+const MAX_LIMIT: i64 = 100;
+
+// Define the result of a handler: a JSON body, or a status code and message.
+type ApiResult = Result<Json<Value>, (StatusCode, String)>;
+
+// List auctions, newest first. Supports `status` (the numeric AuctionStatus), `limit` and `offset`.
+pub async fn auctions(
+    State(client): State<Arc<Client>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let status = query
+        .get("status")
+        .map(|status| status.parse::<i16>())
+        .transpose()
+        .map_err(|_| bad_request("status must be a number"))?;
+    let (limit, offset) = page(&query)?;
+    let rows = client
+        .query(
+            "SELECT address, auction_id, status, exhibitor, nft_mint, payment_mint, price::TEXT,
+                 highest_bidder, timing_mode, end_at, title, uri, slot
+             FROM auctions
+             WHERE $1::SMALLINT IS NULL OR status = $1
+             ORDER BY auction_id DESC
+             LIMIT $2 OFFSET $3",
+            &[&status, &limit, &offset],
+        )
+        .await
+        .map_err(internal)?;
+    Ok(Json(Value::Array(rows.iter().map(auction_json).collect())))
+}
+
+// List the bids of an auction, highest first. The auction is given by its registry ID or its escrow
+// account address.
+pub async fn auction_bids(
+    State(client): State<Arc<Client>>,
+    Path(id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> ApiResult {
+    let (limit, offset) = page(&query)?;
+    let auction_id = id.parse::<i64>().ok();
+    let rows = client
+        .query(
+            "SELECT bids.signature, bids.bidder, bids.price::TEXT, bids.referrer, bids.slot
+             FROM bids JOIN auctions ON auctions.address = bids.auction
+             WHERE auctions.auction_id = $1 OR auctions.address = $2
+             ORDER BY bids.price DESC, bids.slot DESC
+             LIMIT $3 OFFSET $4",
+            &[&auction_id, &id, &limit, &offset],
+        )
+        .await
+        .map_err(internal)?;
+    let bids = rows
+        .iter()
+        .map(|row| {
+            json!({
+                "signature": row.get::<_, String>(0),
+                "bidder": row.get::<_, String>(1),
+                "price": row.get::<_, String>(2),
+                "referrer": row.get::<_, Option<String>>(3),
+                "slot": row.get::<_, i64>(4),
+            })
+        })
+        .collect();
+    Ok(Json(Value::Array(bids)))
+}
+
+// Summarize the auctions of a mint: counts, sales volume, the lowest live price and the last sale.
+// The indexer records each auction's NFT mint, so the stats cover the auctions of that mint.
+pub async fn collection_stats(
+    State(client): State<Arc<Client>>,
+    Path(mint): Path<String>,
+) -> ApiResult {
+    let row = client
+        .query_one(
+            "SELECT
+                 COUNT(*),
+                 COUNT(*) FILTER (WHERE auctions.status IN (0, 1)),
+                 COUNT(settlements.auction) FILTER (WHERE settlements.sold),
+                 COALESCE(SUM(settlements.price) FILTER (WHERE settlements.sold), 0)::TEXT,
+                 (MIN(auctions.price) FILTER (WHERE auctions.status IN (0, 1)))::TEXT,
+                 (ARRAY_AGG(settlements.price::TEXT ORDER BY settlements.slot DESC)
+                     FILTER (WHERE settlements.sold))[1]
+             FROM auctions LEFT JOIN settlements ON settlements.auction = auctions.address
+             WHERE auctions.nft_mint = $1",
+            &[&mint],
+        )
+        .await
+        .map_err(internal)?;
+    Ok(Json(json!({
+        "mint": mint,
+        "auctions": row.get::<_, i64>(0),
+        "live": row.get::<_, i64>(1),
+        "sold": row.get::<_, i64>(2),
+        "volume": row.get::<_, String>(3),
+        "floor": row.get::<_, Option<String>>(4),
+        "last_sale": row.get::<_, Option<String>>(5),
+    })))
+}
+
+// Read the `limit` and `offset` query parameters.
+fn page(query: &HashMap<String, String>) -> Result<(i64, i64), (StatusCode, String)> {
+    let limit = match query.get("limit") {
+        Some(limit) => limit
+            .parse::<i64>()
+            .map_err(|_| bad_request("limit must be a number"))?,
+        None => MAX_LIMIT,
+    };
+    let offset = match query.get("offset") {
+        Some(offset) => offset
+            .parse::<i64>()
+            .map_err(|_| bad_request("offset must be a number"))?,
+        None => 0,
+    };
+    Ok((limit.clamp(1, MAX_LIMIT), offset.max(0)))
+}
+
+// Convert an auction row to JSON. Token amounts are strings since they may not fit a JSON number.
+fn auction_json(row: &Row) -> Value {
+    json!({
+        "address": row.get::<_, String>(0),
+        "auction_id": row.get::<_, i64>(1),
+        "status": row.get::<_, i16>(2),
+        "exhibitor": row.get::<_, String>(3),
+        "nft_mint": row.get::<_, String>(4),
+        "payment_mint": row.get::<_, String>(5),
+        "price": row.get::<_, String>(6),
+        "highest_bidder": row.get::<_, String>(7),
+        "timing_mode": row.get::<_, i16>(8),
+        "end_at": row.get::<_, i64>(9),
+        "title": row.get::<_, String>(10),
+        "uri": row.get::<_, String>(11),
+        "slot": row.get::<_, i64>(12),
+    })
+}
+
+// Build a 400 response.
+fn bad_request(message: &str) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.to_string())
+}
+
+// Build a 500 response for a database error.
+fn internal(err: tokio_postgres::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
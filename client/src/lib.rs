@@ -0,0 +1,3693 @@
+// Anchor's error type is larger than clippy's default threshold, and instruction builder arguments
+// mirror the program's positional instruction arguments.
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the traits used to build instructions and decode accounts from the program's generated types.
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+// Import the instruction type from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+// Import the message, hash and system instruction types used to build sponsored and durable nonce
+// transactions from the solana_program library.
+use anchor_lang::solana_program::{hash::Hash, message::Message, system_instruction};
+// Import the system program, upgradeable loader and sysvar IDs from the solana_program library.
+use anchor_lang::solana_program::{bpf_loader_upgradeable, system_program, sysvar};
+// Import the associated token account derivation from the anchor_spl library.
+use anchor_spl::associated_token::get_associated_token_address;
+// Import the program's generated account and instruction types.
+use wba_auction_house::{accounts, instruction};
+
+// Re-export the events and their seed so indexers can decode them.
+pub use wba_auction_house::events::{
+    AuctionCancelledByAdmin, AuctionRelisted, AuctionSettled, BidPlaced, EditionPurchased,
+    InstallmentsDefaulted, Outbid, SaleReversed, SeriesLotOpened, CANCEL_REASON_BROKEN_STATE,
+    CANCEL_REASON_OTHER, CANCEL_REASON_STOLEN_NFT, EVENT_AUTHORITY_SEED,
+    REVERSAL_REASON_NOT_AS_DESCRIBED, REVERSAL_REASON_NOT_DELIVERED, REVERSAL_REASON_OTHER,
+};
+
+// Re-export the interface fractionalization vault programs implement to settle buyouts.
+pub use wba_auction_house::fractions::{
+    buyout_authority_address, SettleBuyout, BUYOUT_AUTHORITY_SEED, SETTLE_BUYOUT_IX_NAME,
+};
+
+// Re-export the flash loan programs bids are checked against while the house's guard is on.
+pub use wba_auction_house::flash_loans::FLASH_LOAN_PROGRAM_IDS;
+
+// Re-export the Metaplex metadata helpers, used to build collection gate and settlement accounts.
+pub use wba_auction_house::metadata::{
+    decode_metadata, edition_address, edition_marker_address, metadata_address,
+    TOKEN_METADATA_PROGRAM_ID,
+};
+
+// Re-export the Solana Name Service helpers, used to build domain auction accounts.
+pub use wba_auction_house::sns::{name_owner, NAME_SERVICE_PROGRAM_ID};
+
+// Re-export the stake account helpers, used to build stake account auction accounts.
+pub use wba_auction_house::stake::{load_stake_meta, STAKE_PROGRAM_ID};
+
+// Re-export the swap aggregator ID, the program bid_with_swap_ix's swap must target.
+pub use wba_auction_house::swaps::JUPITER_PROGRAM_ID;
+
+// Re-export the Dutch auction pricing shared with the program, so clients quote the exact price a bid
+// pays (see also Auction::current_price and Auction::dutch_pricing).
+pub use wba_auction_house::pricing::{dutch_price, DutchCurve, DutchPricing};
+
+// Re-export the open edition pricing shared with the program (see also EditionSale::current_price).
+pub use wba_auction_house::pricing::{edition_price, BondingCurve};
+
+// Re-export the program ID, seeds and state types so clients need only this crate.
+pub use wba_auction_house::{
+    reserve_commitment, round_seed, Auction, AuctionHouse, AuctionSeries, AuctionState,
+    AuctionStatus, BidPool, BidReceipt, BidSession, BlacklistEntry, CollectionStats,
+    EditionReceipt, EditionSale, HouseRole, HouseStats, ItemType, ListingMode, ListingReceipt,
+    PartnerEntry, Payout, PoolContribution, PurchaseReceipt, Registry, Rental, RoyaltyOverride,
+    SellerAllowlist, TimingMode, VestingSchedule, AFFILIATE_VAULT_SEED, AUCTION_HOUSE_SEED,
+    AUCTION_SEED, AUCTION_VERSION, BASIS_POINTS, BID_POOL_FRACTIONS_SEED, BID_POOL_SEED,
+    BID_POOL_VAULT_SEED, BID_RECEIPT_SEED, BID_SESSION_SEED, BLACKLIST_SEED, COLLECTION_STATS_SEED,
+    EDITION_RECEIPT_SEED, EDITION_SALE_SEED, ESCROW_PDA_SEED, FEATURES_ALL, FEATURE_BUYOUTS,
+    FEATURE_DOMAINS, FEATURE_DUTCH, FEATURE_EDITIONS, FEATURE_PENNY, FEATURE_ROYALTIES,
+    FEATURE_SERIES, FEATURE_SOL_BIDS, FEATURE_STAKE, HOUSE_STATS_SEED, ID, LISTING_RECEIPT_SEED,
+    LISTING_TOKEN_SEED, MAX_CLOSE_BATCH, MAX_CREATORS, MAX_DISPUTE_WINDOW_SEC, MAX_EXHIBIT_BATCH,
+    MAX_MEMO_LEN, MAX_PAYOUT_RECIPIENTS, MAX_SERIES_LOTS, MAX_SESSION_DURATION_SEC, MAX_TITLE_LEN,
+    MAX_URI_LEN, PARTNER_SEED, POOL_CONTRIBUTION_SEED, PURCHASE_RECEIPT_SEED, REGISTRY_SEED,
+    RENTAL_SEED, ROYALTY_OVERRIDE_SEED, ROYALTY_VAULT_SEED, SELLER_ALLOWLIST_SEED, SERIES_SEED,
+    VESTING_SEED, VESTING_VAULT_SEED,
+};
+
+// Derive the escrow authority PDA, which owns every escrowed token account.
+pub fn escrow_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_PDA_SEED], &ID)
+}
+
+// Derive the auction house configuration PDA.
+pub fn auction_house_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_HOUSE_SEED], &ID)
+}
+
+// Derive the event authority PDA, which signs the self-CPI that records events.
+pub fn event_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &ID)
+}
+
+// Derive the buyout authority PDA of a vault, which signs the vault program's settle_buyout calls.
+pub fn buyout_authority_pda(vault: Pubkey) -> (Pubkey, u8) {
+    buyout_authority_address(&vault)
+}
+
+// Derive the registry PDA, which numbers auctions.
+pub fn registry_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_SEED], &ID)
+}
+
+// Derive the receipt PDA of an auction's bid from its bidder and index.
+pub fn bid_receipt_pda(escrow_account: Pubkey, bidder: Pubkey, bid_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_RECEIPT_SEED,
+            escrow_account.as_ref(),
+            bidder.as_ref(),
+            &bid_index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+// Derive the listing receipt PDA of an auction in a round of its escrow account (see Auction::round).
+pub fn listing_receipt_pda(escrow_account: Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LISTING_RECEIPT_SEED,
+            escrow_account.as_ref(),
+            &round_seed(round),
+        ],
+        &ID,
+    )
+}
+
+// Derive the purchase receipt PDA of an auction in a round of its escrow account (see Auction::round).
+pub fn purchase_receipt_pda(escrow_account: Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_SEED,
+            escrow_account.as_ref(),
+            &round_seed(round),
+        ],
+        &ID,
+    )
+}
+
+// Derive the blacklist entry PDA of a mint, which exists while the mint is banned.
+pub fn blacklist_entry_pda(mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLACKLIST_SEED, mint.as_ref()], &ID)
+}
+
+// Derive the partner entry PDA of a partner frontend, which exists while the partner is whitelisted.
+pub fn partner_entry_pda(partner: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PARTNER_SEED, partner.as_ref()], &ID)
+}
+
+// Derive the seller allowlist PDA of a seller, which exists while the seller is approved.
+pub fn seller_allowlist_pda(seller: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SELLER_ALLOWLIST_SEED, seller.as_ref()], &ID)
+}
+
+// Derive the royalty override PDA of a verified collection, which exists while the house overrides its
+// royalty.
+pub fn royalty_override_pda(collection: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROYALTY_OVERRIDE_SEED, collection.as_ref()], &ID)
+}
+
+// Derive the stats PDA of a collection's sales in a payment mint.
+pub fn collection_stats_pda(collection: Pubkey, payment_mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            COLLECTION_STATS_SEED,
+            collection.as_ref(),
+            payment_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+// Derive the house stats PDA of a payment mint.
+pub fn house_stats_pda(payment_mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOUSE_STATS_SEED, payment_mint.as_ref()], &ID)
+}
+
+// Derive the house's royalty vault PDA of a payment mint, which holds the royalties escrowed at
+// settlement until distribute_royalties pays them.
+pub fn royalty_vault_pda(payment_mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROYALTY_VAULT_SEED, payment_mint.as_ref()], &ID)
+}
+
+// Derive the house's affiliate vault PDA of a payment mint, which holds the affiliate fees escrowed at
+// settlement until claim_affiliate_earnings pays them.
+pub fn affiliate_vault_pda(payment_mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AFFILIATE_VAULT_SEED, payment_mint.as_ref()], &ID)
+}
+
+// Derive the vesting schedule PDA of an auction.
+pub fn vesting_schedule_pda(escrow_account: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VESTING_SEED, escrow_account.as_ref()], &ID)
+}
+
+// Derive the vault PDA holding the proceeds of a vesting schedule while they vest.
+pub fn vesting_vault_pda(vesting_schedule: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VESTING_VAULT_SEED, vesting_schedule.as_ref()], &ID)
+}
+
+// Derive the rental PDA of an auction.
+pub fn rental_pda(escrow_account: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RENTAL_SEED, escrow_account.as_ref()], &ID)
+}
+
+// Derive the open edition sale PDA of an edition mint.
+pub fn edition_sale_pda(edition_mint: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EDITION_SALE_SEED, edition_mint.as_ref()], &ID)
+}
+
+// Derive the receipt PDA of an open edition purchase from its index in the sale.
+pub fn edition_receipt_pda(edition_sale: Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            EDITION_RECEIPT_SEED,
+            edition_sale.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+// Derive the series PDA of an authority from its series ID.
+pub fn series_pda(authority: Pubkey, series_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SERIES_SEED, authority.as_ref(), &series_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+// Derive the listing token mint PDA of an auction round.
+pub fn listing_token_mint_pda(escrow_account: Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LISTING_TOKEN_SEED,
+            escrow_account.as_ref(),
+            &round_seed(round),
+        ],
+        &ID,
+    )
+}
+
+// Derive the bid pool PDA of an auction and operator.
+pub fn bid_pool_pda(escrow_account: Pubkey, operator: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BID_POOL_SEED, escrow_account.as_ref(), operator.as_ref()],
+        &ID,
+    )
+}
+
+// Derive the bid session PDA of a wallet and session key.
+pub fn bid_session_pda(wallet: Pubkey, session_key: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BID_SESSION_SEED, wallet.as_ref(), session_key.as_ref()],
+        &ID,
+    )
+}
+
+// Derive the vault PDA holding a bid pool's funds.
+pub fn bid_pool_vault_pda(bid_pool: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_POOL_VAULT_SEED, bid_pool.as_ref()], &ID)
+}
+
+// Derive the fraction mint PDA of a bid pool.
+pub fn bid_pool_fractions_pda(bid_pool: Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_POOL_FRACTIONS_SEED, bid_pool.as_ref()], &ID)
+}
+
+// Derive the receipt PDA of a contribution to a bid pool from its index in the pool.
+pub fn pool_contribution_pda(bid_pool: Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            POOL_CONTRIBUTION_SEED,
+            bid_pool.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+// Derive the escrow account PDA of an auction from its ID.
+pub fn auction_pda(auction_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_SEED, &auction_id.to_le_bytes()], &ID)
+}
+
+// Deserialize a zero-copy Auction account from its raw data, checking the discriminator. The data is
+// copied out, so it need not be aligned.
+pub fn decode_auction(data: &[u8]) -> Result<Auction> {
+    require!(data.len() >= 8, ErrorCode::AccountDiscriminatorNotFound);
+    require!(
+        data[..8] == Auction::discriminator(),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let body = data
+        .get(8..Auction::LEN)
+        .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+    Ok(bytemuck::pod_read_unaligned(body))
+}
+
+// Define the offsets of the Auction fields fetches filter on, into the account data, past its discriminator.
+pub const AUCTION_STATUS_OFFSET: usize = 8 + std::mem::offset_of!(Auction, status);
+pub const AUCTION_EXHIBITOR_OFFSET: usize = 8 + std::mem::offset_of!(Auction, exhibitor);
+pub const AUCTION_NFT_MINT_OFFSET: usize = 8 + std::mem::offset_of!(Auction, nft_mint);
+
+// Define the Memcmp struct, a getProgramAccounts filter matching the bytes at an offset of the account
+// data. RPC clients encode the bytes as the node expects, usually in base58.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+// Implement the Memcmp struct.
+impl Memcmp {
+    // Match the Auction discriminator, which every auction filter starts with.
+    pub fn auction() -> Self {
+        Self {
+            offset: 0,
+            bytes: Auction::discriminator().to_vec(),
+        }
+    }
+
+    // Match the auctions of an exhibitor.
+    pub fn auction_exhibitor(exhibitor: Pubkey) -> Self {
+        Self {
+            offset: AUCTION_EXHIBITOR_OFFSET,
+            bytes: exhibitor.to_bytes().to_vec(),
+        }
+    }
+
+    // Match the auctions of an NFT mint.
+    pub fn auction_nft_mint(nft_mint: Pubkey) -> Self {
+        Self {
+            offset: AUCTION_NFT_MINT_OFFSET,
+            bytes: nft_mint.to_bytes().to_vec(),
+        }
+    }
+
+    // Match the auctions with a stored status. The status derived from the time (see Auction::status) may
+    // have moved on.
+    pub fn auction_status(status: AuctionStatus) -> Self {
+        Self {
+            offset: AUCTION_STATUS_OFFSET,
+            bytes: vec![status as u8],
+        }
+    }
+}
+
+// Define the ProgramAccounts trait for the RPC clients auctions are fetched with.
+pub trait ProgramAccounts {
+    type Error;
+
+    // Fetch the program accounts of a given size matching every filter, returning each address and its data.
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        data_size: usize,
+        filters: &[Memcmp],
+    ) -> std::result::Result<Vec<(Pubkey, Vec<u8>)>, Self::Error>;
+}
+
+// Fetch the auctions in the current layout matching every filter. Accounts that do not decode, such as
+// auctions still to be migrated, are left out.
+pub fn fetch_auctions<C: ProgramAccounts>(
+    client: &C,
+    filters: &[Memcmp],
+) -> std::result::Result<Vec<(Pubkey, Auction)>, C::Error> {
+    let mut filters = filters.to_vec();
+    filters.insert(0, Memcmp::auction());
+    Ok(client
+        .get_program_accounts(&ID, Auction::LEN, &filters)?
+        .into_iter()
+        .filter_map(|(address, data)| Some((address, decode_auction(&data).ok()?)))
+        .collect())
+}
+
+// Fetch the auctions of an exhibitor, whatever their status.
+pub fn fetch_auctions_by_exhibitor<C: ProgramAccounts>(
+    client: &C,
+    exhibitor: Pubkey,
+) -> std::result::Result<Vec<(Pubkey, Auction)>, C::Error> {
+    fetch_auctions(client, &[Memcmp::auction_exhibitor(exhibitor)])
+}
+
+// Fetch the auctions of an NFT mint, whatever their status.
+pub fn fetch_auctions_for_mint<C: ProgramAccounts>(
+    client: &C,
+    nft_mint: Pubkey,
+) -> std::result::Result<Vec<(Pubkey, Auction)>, C::Error> {
+    fetch_auctions(client, &[Memcmp::auction_nft_mint(nft_mint)])
+}
+
+// Fetch the auctions taking bids at the cluster's clock, with or without a bid. Those past their end time
+// are left out, though their stored status still matches until they are finalized.
+pub fn fetch_live_auctions<C: ProgramAccounts>(
+    client: &C,
+    clock: &Clock,
+) -> std::result::Result<Vec<(Pubkey, Auction)>, C::Error> {
+    let mut auctions = fetch_auctions(client, &[Memcmp::auction_status(AuctionStatus::Created)])?;
+    auctions.extend(fetch_auctions(
+        client,
+        &[Memcmp::auction_status(AuctionStatus::Live)],
+    )?);
+    auctions.retain(|(_, auction)| {
+        matches!(
+            auction.status(clock),
+            Ok(AuctionStatus::Created | AuctionStatus::Live)
+        )
+    });
+    Ok(auctions)
+}
+
+// Deserialize an AuctionHouse account from its raw data, checking the discriminator.
+pub fn decode_auction_house(mut data: &[u8]) -> Result<AuctionHouse> {
+    AuctionHouse::try_deserialize(&mut data)
+}
+
+// Deserialize a Registry account from its raw data, checking the discriminator.
+pub fn decode_registry(mut data: &[u8]) -> Result<Registry> {
+    Registry::try_deserialize(&mut data)
+}
+
+// Deserialize a BidReceipt account from its raw data, checking the discriminator.
+pub fn decode_bid_receipt(mut data: &[u8]) -> Result<BidReceipt> {
+    BidReceipt::try_deserialize(&mut data)
+}
+
+// Deserialize a BidSession account from its raw data, checking the discriminator.
+pub fn decode_bid_session(mut data: &[u8]) -> Result<BidSession> {
+    BidSession::try_deserialize(&mut data)
+}
+
+// Deserialize a ListingReceipt account from its raw data, checking the discriminator.
+pub fn decode_listing_receipt(mut data: &[u8]) -> Result<ListingReceipt> {
+    ListingReceipt::try_deserialize(&mut data)
+}
+
+// Deserialize a PurchaseReceipt account from its raw data, checking the discriminator. Receipts recorded
+// before royalties were escrowed lack the royalty fields, and decode as owing nothing.
+pub fn decode_purchase_receipt(data: &[u8]) -> Result<PurchaseReceipt> {
+    let mut padded = data.to_vec();
+    if padded.len() < PurchaseReceipt::LEN {
+        padded.resize(PurchaseReceipt::LEN, 0);
+    }
+    PurchaseReceipt::try_deserialize(&mut padded.as_slice())
+}
+
+// Deserialize a HouseStats account from its raw data, checking the discriminator. Fetch the account at
+// house_stats_pda to get the house's volume, sales and fees in a payment mint.
+pub fn decode_house_stats(mut data: &[u8]) -> Result<HouseStats> {
+    HouseStats::try_deserialize(&mut data)
+}
+
+// Deserialize a CollectionStats account from its raw data, checking the discriminator.
+pub fn decode_collection_stats(mut data: &[u8]) -> Result<CollectionStats> {
+    CollectionStats::try_deserialize(&mut data)
+}
+
+// Deserialize a VestingSchedule account from its raw data, checking the discriminator.
+pub fn decode_vesting_schedule(mut data: &[u8]) -> Result<VestingSchedule> {
+    VestingSchedule::try_deserialize(&mut data)
+}
+
+// Deserialize a PartnerEntry account from its raw data, checking the discriminator.
+pub fn decode_partner_entry(mut data: &[u8]) -> Result<PartnerEntry> {
+    PartnerEntry::try_deserialize(&mut data)
+}
+
+// Deserialize a Rental account from its raw data, checking the discriminator.
+pub fn decode_rental(mut data: &[u8]) -> Result<Rental> {
+    Rental::try_deserialize(&mut data)
+}
+
+// Deserialize an EditionSale account from its raw data, checking the discriminator.
+pub fn decode_edition_sale(mut data: &[u8]) -> Result<EditionSale> {
+    EditionSale::try_deserialize(&mut data)
+}
+
+// Deserialize an EditionReceipt account from its raw data, checking the discriminator.
+pub fn decode_edition_receipt(mut data: &[u8]) -> Result<EditionReceipt> {
+    EditionReceipt::try_deserialize(&mut data)
+}
+
+// Deserialize a BidPool account from its raw data, checking the discriminator.
+pub fn decode_bid_pool(mut data: &[u8]) -> Result<BidPool> {
+    BidPool::try_deserialize(&mut data)
+}
+
+// Deserialize a PoolContribution account from its raw data, checking the discriminator.
+pub fn decode_pool_contribution(mut data: &[u8]) -> Result<PoolContribution> {
+    PoolContribution::try_deserialize(&mut data)
+}
+
+// Deserialize an AuctionSeries account from its raw data, checking the discriminator.
+pub fn decode_series(mut data: &[u8]) -> Result<AuctionSeries> {
+    AuctionSeries::try_deserialize(&mut data)
+}
+
+// Deserialize the AuctionState written as return data by get_auction_state.
+pub fn decode_auction_state(mut return_data: &[u8]) -> Result<AuctionState> {
+    Ok(AuctionState::deserialize(&mut return_data)?)
+}
+
+// Define the AuctionEvent enum to hold an event recorded by the program.
+pub enum AuctionEvent {
+    // A bid became the highest.
+    BidPlaced(BidPlaced),
+    // A bid refunded the previous highest bidder.
+    Outbid(Outbid),
+    // An auction reached the Settled status.
+    AuctionSettled(AuctionSettled),
+    // The house authority cancelled an auction.
+    AuctionCancelledByAdmin(AuctionCancelledByAdmin),
+    // The arbiter reversed a sale during its dispute window.
+    SaleReversed(SaleReversed),
+    // A winning bidder missed an installment and the NFT went back to the exhibitor.
+    InstallmentsDefaulted(InstallmentsDefaulted),
+    // An open edition was bought.
+    EditionPurchased(EditionPurchased),
+    // An auction without a bid was restarted.
+    AuctionRelisted(AuctionRelisted),
+    // A series opened bidding on its next lot.
+    SeriesLotOpened(SeriesLotOpened),
+}
+
+// Decode the event carried by the data of an emit_event inner instruction. Returns None for any other
+// instruction or an unknown event.
+pub fn decode_event_cpi(data: &[u8]) -> Option<AuctionEvent> {
+    let emit_event = instruction::EmitEvent { _event: Vec::new() }.data();
+    let mut args = data.strip_prefix(&emit_event[..8])?;
+    let event = Vec::<u8>::deserialize(&mut args).ok()?;
+    let (discriminator, mut body) = event.split_at_checked(8)?;
+    if discriminator == BidPlaced::discriminator() {
+        BidPlaced::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::BidPlaced)
+    } else if discriminator == Outbid::discriminator() {
+        Outbid::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::Outbid)
+    } else if discriminator == AuctionSettled::discriminator() {
+        AuctionSettled::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::AuctionSettled)
+    } else if discriminator == AuctionCancelledByAdmin::discriminator() {
+        AuctionCancelledByAdmin::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::AuctionCancelledByAdmin)
+    } else if discriminator == SaleReversed::discriminator() {
+        SaleReversed::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::SaleReversed)
+    } else if discriminator == InstallmentsDefaulted::discriminator() {
+        InstallmentsDefaulted::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::InstallmentsDefaulted)
+    } else if discriminator == EditionPurchased::discriminator() {
+        EditionPurchased::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::EditionPurchased)
+    } else if discriminator == AuctionRelisted::discriminator() {
+        AuctionRelisted::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::AuctionRelisted)
+    } else if discriminator == SeriesLotOpened::discriminator() {
+        SeriesLotOpened::deserialize(&mut body)
+            .ok()
+            .map(AuctionEvent::SeriesLotOpened)
+    } else {
+        None
+    }
+}
+
+// Define the ExhibitAccounts struct to hold the accounts chosen by the exhibitor.
+pub struct ExhibitAccounts {
+    // The exhibitor's wallet, which must sign.
+    pub exhibitor: Pubkey,
+    // The payer of the transaction and of the escrow and temporary NFT accounts, which must sign too.
+    pub payer: Pubkey,
+    // The exhibitor's NFT account holding the item.
+    pub exhibitor_nft_token_account: Pubkey,
+    // The exhibitor's freshly created temporary NFT account.
+    pub exhibitor_nft_temp_account: Pubkey,
+    // The exhibitor's FT receiving account, whose mint becomes the payment mint.
+    pub exhibitor_ft_receiving_account: Pubkey,
+    // The mint of the NFT, which must be a one-of-one and not be blacklisted.
+    pub nft_mint: Pubkey,
+    // The mint of the exhibitor's FT receiving account, which must not be blacklisted.
+    pub payment_mint: Pubkey,
+    // The ID the registry will assign the auction (see Registry::next_auction_id), which its escrow
+    // account is derived from.
+    pub auction_id: u64,
+    // The house treasury wallet (see AuctionHouse::treasury), which receives the listing fee. Only exhibit
+    // uses it.
+    pub treasury: Pubkey,
+}
+
+// Define the ExhibitParams struct to hold the settings of a new auction.
+pub struct ExhibitParams {
+    // Initial price for the auction.
+    pub initial_price: u64,
+    // Duration of the auction in seconds or slots, depending on the timing mode.
+    pub auction_duration_sec: u64,
+    // Additional recipients of the proceeds and their shares.
+    pub payouts: Vec<Payout>,
+    // Optional FT receiving account of a charity.
+    pub charity: Option<Pubkey>,
+    // The charity's share of the proceeds in basis points.
+    pub charity_bps: u16,
+    // Optional reserve price in US cents (zero for no reserve).
+    pub reserve_usd_cents: u64,
+    // Pyth price feed of the payment token in USD.
+    pub reserve_price_feed: Pubkey,
+    // Whether the duration is in seconds or slots.
+    pub timing_mode: TimingMode,
+    // Optional title of the lot (empty for none).
+    pub title: String,
+    // Optional URI of the lot's description (empty for none).
+    pub uri: String,
+    // Whether the NFT moves into escrow or stays in the exhibitor's wallet. A delegate listing passes the
+    // exhibitor's NFT account as the temporary NFT account too.
+    pub listing_mode: ListingMode,
+    // Optional whitelisted partner frontend listing the auction, which receives a share of its house fee.
+    pub partner: Option<PartnerFee>,
+    // Whether the auction may start at a zero price.
+    pub free_listing: bool,
+}
+
+// Define the PartnerFee struct to hold the partner frontend listing an auction.
+pub struct PartnerFee {
+    // The partner frontend's wallet, whose partner entry whitelists it.
+    pub partner: Pubkey,
+    // The partner's FT account receiving its share of the house fee, in the payment mint.
+    pub fee_account: Pubkey,
+}
+
+// Build an initialize_registry instruction.
+pub fn initialize_registry_ix(payer: Pubkey) -> Instruction {
+    let accounts = accounts::InitializeRegistry {
+        payer,
+        registry: registry_pda().0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::InitializeRegistry {}.data(),
+    )
+}
+
+// Derive the program data account of the program, which records its upgrade authority.
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+// Build an initialize_house instruction, signed by the program's upgrade authority.
+pub fn initialize_house_ix(
+    authority: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+    referral_bps: u16,
+    min_duration_sec: u64,
+    max_duration_sec: u64,
+) -> Instruction {
+    let accounts = accounts::InitializeHouse {
+        authority,
+        program: ID,
+        program_data: program_data_address(),
+        treasury,
+        auction_house: auction_house_pda().0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::InitializeHouse {
+        fee_bps,
+        referral_bps,
+        min_duration_sec,
+        max_duration_sec,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_fees instruction, signed by the house authority or its fee manager.
+pub fn update_house_fees_ix(authority: Pubkey, fee_bps: u16, referral_bps: u16) -> Instruction {
+    let accounts = accounts::UpdateHouseFees {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseFees {
+        fee_bps,
+        referral_bps,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_durations instruction.
+pub fn update_house_durations_ix(
+    authority: Pubkey,
+    min_duration_sec: u64,
+    max_duration_sec: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseDurations {
+        min_duration_sec,
+        max_duration_sec,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_flash_loan_guard instruction to turn the flash loan guard on bids on or off.
+pub fn update_house_flash_loan_guard_ix(authority: Pubkey, enabled: bool) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseFlashLoanGuard { enabled };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_listing_fee instruction, signed by the house authority or its fee manager, to
+// charge a flat lamport fee per listing.
+pub fn update_house_listing_fee_ix(authority: Pubkey, listing_fee_lamports: u64) -> Instruction {
+    let accounts = accounts::UpdateHouseFees {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseListingFee {
+        listing_fee_lamports,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_affiliate_fee instruction, signed by the house authority or its fee manager, to set
+// the share of the house fee earned by the affiliates of winning bids.
+pub fn update_house_affiliate_fee_ix(authority: Pubkey, affiliate_bps: u16) -> Instruction {
+    let accounts = accounts::UpdateHouseFees {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseAffiliateFee { affiliate_bps };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_buyers_premium instruction to charge the house fee to winning bidders on top of
+// their bids, or to take it from the proceeds again. Auctions already exhibited keep their mode.
+pub fn update_house_buyers_premium_ix(authority: Pubkey, enabled: bool) -> Instruction {
+    let accounts = accounts::UpdateHouseFees {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseBuyersPremium { enabled };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_features instruction for the house authority to set the enabled FEATURE_ flags.
+pub fn update_house_features_ix(authority: Pubkey, features: u32) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseFeatures { features };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build a set_house_role instruction for the house authority to give a role to a key, or take it away
+// with the default pubkey.
+pub fn set_house_role_ix(authority: Pubkey, role: HouseRole, holder: Pubkey) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::SetHouseRole { role, holder };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build a pause_house instruction, signed by the house authority or its pauser, to stop or resume new
+// listings, bids and edition purchases.
+pub fn pause_house_ix(authority: Pubkey, paused: bool) -> Instruction {
+    let accounts = accounts::PauseHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::PauseHouse { paused };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build a nominate_authority instruction for the house authority to nominate its successor.
+pub fn nominate_authority_ix(authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::NominateAuthority { new_authority };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an accept_authority instruction, signed by the nominated authority to take over the house.
+pub fn accept_authority_ix(pending_authority: Pubkey) -> Instruction {
+    let accounts = accounts::AcceptAuthority {
+        pending_authority,
+        auction_house: auction_house_pda().0,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AcceptAuthority {}.data(),
+    )
+}
+
+// Build a blacklist_mint instruction for the house authority or its whitelist manager to ban a mint.
+pub fn blacklist_mint_ix(authority: Pubkey, mint: Pubkey) -> Instruction {
+    let accounts = accounts::BlacklistMint {
+        authority,
+        auction_house: auction_house_pda().0,
+        mint,
+        blacklist_entry: blacklist_entry_pda(mint).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::BlacklistMint {}.data(),
+    )
+}
+
+// Build a create_edition_sale instruction for the mint authority of an edition mint to sell open
+// editions of it, paid into their FT receiving account.
+pub fn create_edition_sale_ix(
+    seller: Pubkey,
+    payer: Pubkey,
+    edition_mint: Pubkey,
+    seller_ft_account: Pubkey,
+    payment_mint: Pubkey,
+    curve: BondingCurve,
+    base_price: u64,
+    curve_param: u64,
+    max_supply: u64,
+) -> Instruction {
+    let accounts = accounts::CreateEditionSale {
+        seller,
+        payer,
+        edition_mint,
+        seller_ft_account,
+        edition_sale: edition_sale_pda(edition_mint).0,
+        nft_blacklist_entry: blacklist_entry_pda(edition_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(payment_mint).0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateEditionSale {
+            curve,
+            base_price,
+            curve_param,
+            max_supply,
+        }
+        .data(),
+    )
+}
+
+// Build a buy_edition instruction against the sale's current state, paying at most max_price for the
+// next edition.
+pub fn buy_edition_ix(
+    edition_sale: Pubkey,
+    sale: &EditionSale,
+    buyer: Pubkey,
+    payer: Pubkey,
+    buyer_edition_account: Pubkey,
+    buyer_ft_account: Pubkey,
+    treasury_ft_account: Pubkey,
+    max_price: u64,
+) -> Instruction {
+    let accounts = accounts::BuyEdition {
+        buyer,
+        payer,
+        edition_sale,
+        edition_mint: sale.edition_mint,
+        buyer_edition_account,
+        buyer_ft_account,
+        seller_ft_account: sale.seller_ft_pubkey,
+        auction_house: auction_house_pda().0,
+        treasury_ft_account,
+        edition_receipt: edition_receipt_pda(edition_sale, sale.sold).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::BuyEdition { max_price }.data(),
+    )
+}
+
+// Build an end_edition_sale instruction for the seller to stop a sale and take the mint authority back.
+pub fn end_edition_sale_ix(seller: Pubkey, edition_mint: Pubkey) -> Instruction {
+    let accounts = accounts::EndEditionSale {
+        seller,
+        edition_sale: edition_sale_pda(edition_mint).0,
+        edition_mint,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::EndEditionSale {}.data(),
+    )
+}
+
+// Build an unblacklist_mint instruction for the house authority or its whitelist manager to lift a ban.
+pub fn unblacklist_mint_ix(authority: Pubkey, mint: Pubkey) -> Instruction {
+    let accounts = accounts::UnblacklistMint {
+        authority,
+        auction_house: auction_house_pda().0,
+        blacklist_entry: blacklist_entry_pda(mint).0,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UnblacklistMint {}.data(),
+    )
+}
+
+// Build an update_house_approved_sellers instruction for the house authority to restrict listing to
+// approved sellers, or to lift the restriction.
+pub fn update_house_approved_sellers_ix(authority: Pubkey, enabled: bool) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseApprovedSellers { enabled };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an update_house_collection instruction for the house authority to only admit NFTs verified as
+// members of a collection; pass the default pubkey to admit any NFT.
+pub fn update_house_collection_ix(authority: Pubkey, collection: Pubkey) -> Instruction {
+    let accounts = accounts::UpdateHouse {
+        authority,
+        auction_house: auction_house_pda().0,
+    };
+    let data = instruction::UpdateHouseCollection { collection };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an add_seller instruction for the house authority or its whitelist manager to approve a seller.
+pub fn add_seller_ix(authority: Pubkey, seller: Pubkey) -> Instruction {
+    let accounts = accounts::AddSeller {
+        authority,
+        auction_house: auction_house_pda().0,
+        seller,
+        seller_allowlist: seller_allowlist_pda(seller).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AddSeller {}.data(),
+    )
+}
+
+// Build a remove_seller instruction for the house authority or its whitelist manager to withdraw a
+// seller's approval.
+pub fn remove_seller_ix(authority: Pubkey, seller: Pubkey) -> Instruction {
+    let accounts = accounts::RemoveSeller {
+        authority,
+        auction_house: auction_house_pda().0,
+        seller_allowlist: seller_allowlist_pda(seller).0,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RemoveSeller {}.data(),
+    )
+}
+
+// Build an add_partner instruction for the house authority or its whitelist manager to whitelist a
+// partner frontend.
+pub fn add_partner_ix(authority: Pubkey, partner: Pubkey, fee_share_bps: u16) -> Instruction {
+    let accounts = accounts::AddPartner {
+        authority,
+        auction_house: auction_house_pda().0,
+        partner,
+        partner_entry: partner_entry_pda(partner).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AddPartner { fee_share_bps }.data(),
+    )
+}
+
+// Build a remove_partner instruction for the house authority or its whitelist manager to take a partner
+// frontend off the whitelist.
+pub fn remove_partner_ix(authority: Pubkey, partner: Pubkey) -> Instruction {
+    let accounts = accounts::RemovePartner {
+        authority,
+        auction_house: auction_house_pda().0,
+        partner_entry: partner_entry_pda(partner).0,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RemovePartner {}.data(),
+    )
+}
+
+// Build an add_royalty_override instruction for the house authority to set the royalty of a verified
+// collection, or cap it when cap is set.
+pub fn add_royalty_override_ix(
+    authority: Pubkey,
+    collection: Pubkey,
+    royalty_bps: u16,
+    cap: bool,
+) -> Instruction {
+    let accounts = accounts::AddRoyaltyOverride {
+        authority,
+        auction_house: auction_house_pda().0,
+        collection,
+        royalty_override: royalty_override_pda(collection).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AddRoyaltyOverride { royalty_bps, cap }.data(),
+    )
+}
+
+// Build a create_collection_stats instruction to start recording a collection's sales in a payment mint.
+pub fn create_collection_stats_ix(
+    payer: Pubkey,
+    collection: Pubkey,
+    payment_mint: Pubkey,
+) -> Instruction {
+    let accounts = accounts::CreateCollectionStats {
+        payer,
+        collection,
+        payment_mint,
+        collection_stats: collection_stats_pda(collection, payment_mint).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateCollectionStats {}.data(),
+    )
+}
+
+// Build a create_house_stats instruction to start recording the house's sales in a payment mint.
+pub fn create_house_stats_ix(payer: Pubkey, payment_mint: Pubkey) -> Instruction {
+    let accounts = accounts::CreateHouseStats {
+        payer,
+        payment_mint,
+        house_stats: house_stats_pda(payment_mint).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateHouseStats {}.data(),
+    )
+}
+
+// Build a create_royalty_vault instruction for anyone to create the house's royalty vault of a payment
+// mint, which sales owing a royalty need to settle.
+pub fn create_royalty_vault_ix(payer: Pubkey, payment_mint: Pubkey) -> Instruction {
+    let accounts = accounts::CreateRoyaltyVault {
+        payer,
+        payment_mint,
+        royalty_vault: royalty_vault_pda(payment_mint).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateRoyaltyVault {}.data(),
+    )
+}
+
+// Build a create_affiliate_vault instruction for anyone to create the house's affiliate vault of a payment
+// mint, which sales whose winning bid has an affiliate need to settle.
+pub fn create_affiliate_vault_ix(payer: Pubkey, payment_mint: Pubkey) -> Instruction {
+    let accounts = accounts::CreateAffiliateVault {
+        payer,
+        payment_mint,
+        affiliate_vault: affiliate_vault_pda(payment_mint).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateAffiliateVault {}.data(),
+    )
+}
+
+// Build a claim_affiliate_earnings instruction for anyone to pay the affiliate of a settled sale the fee
+// it is still owed, to its associated token account of the payment mint, which must exist.
+pub fn claim_affiliate_earnings_ix(
+    purchase_receipt: Pubkey,
+    receipt: &PurchaseReceipt,
+) -> Instruction {
+    let accounts = accounts::ClaimAffiliateEarnings {
+        purchase_receipt,
+        affiliate_vault: affiliate_vault_pda(receipt.payment_mint).0,
+        affiliate_ft_account: get_associated_token_address(
+            &receipt.affiliate,
+            &receipt.payment_mint,
+        ),
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimAffiliateEarnings {}.data(),
+    )
+}
+
+// Build a distribute_royalties instruction for anyone to pay the creators still owed a royalty by a
+// settled sale, to their associated token accounts of the payment mint, which must exist. A sale with
+// five creators may be split across transactions by passing a subset of `creators`.
+pub fn distribute_royalties_ix(
+    purchase_receipt: Pubkey,
+    receipt: &PurchaseReceipt,
+    creators: &[Pubkey],
+) -> Instruction {
+    let accounts = accounts::DistributeRoyalties {
+        purchase_receipt,
+        royalty_vault: royalty_vault_pda(receipt.payment_mint).0,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    let creator_accounts = creators.iter().map(|creator| {
+        AccountMeta::new(
+            get_associated_token_address(creator, &receipt.payment_mint),
+            false,
+        )
+    });
+    build_ix(
+        accounts
+            .to_account_metas(None)
+            .into_iter()
+            .chain(creator_accounts)
+            .collect(),
+        instruction::DistributeRoyalties {}.data(),
+    )
+}
+
+// Build a remove_royalty_override instruction for the house authority to restore the metadata's royalty
+// for a collection.
+pub fn remove_royalty_override_ix(authority: Pubkey, collection: Pubkey) -> Instruction {
+    let accounts = accounts::RemoveRoyaltyOverride {
+        authority,
+        auction_house: auction_house_pda().0,
+        royalty_override: royalty_override_pda(collection).0,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RemoveRoyaltyOverride {}.data(),
+    )
+}
+
+// Build a migrate_house instruction to bring the house configuration up to the current layout.
+pub fn migrate_house_ix(payer: Pubkey) -> Instruction {
+    let accounts = accounts::MigrateHouse {
+        payer,
+        auction_house: auction_house_pda().0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::MigrateHouse {}.data(),
+    )
+}
+
+// Build an exhibit instruction, passing the payout and charity accounts as remaining accounts.
+pub fn exhibit_ix(exhibit_accounts: &ExhibitAccounts, params: ExhibitParams) -> Instruction {
+    let mut metas = to_exhibit_accounts(exhibit_accounts).to_account_metas(None);
+    metas.extend(exhibit_recipient_metas(&params));
+    let data = instruction::Exhibit {
+        initial_price: params.initial_price,
+        auction_duration_sec: params.auction_duration_sec,
+        payouts: params.payouts,
+        charity: params.charity,
+        charity_bps: params.charity_bps,
+        reserve_usd_cents: params.reserve_usd_cents,
+        reserve_price_feed: params.reserve_price_feed,
+        timing_mode: params.timing_mode,
+        title: params.title,
+        uri: params.uri,
+        listing_mode: params.listing_mode,
+        partner: params.partner.as_ref().map(|partner| partner.fee_account),
+        free_listing: params.free_listing,
+    };
+    build_ix(metas, data.data())
+}
+
+// Build an exhibit_many instruction listing several NFTs of one exhibitor with shared settings. Each
+// NFT's accounts must carry the consecutive auction IDs the registry will assign them, and the batch must
+// not exceed MAX_EXHIBIT_BATCH. Payouts, charity, reserve and metadata are not set.
+pub fn exhibit_many_ix(
+    exhibitor: Pubkey,
+    items: &[ExhibitAccounts],
+    initial_price: u64,
+    auction_duration_sec: u64,
+    timing_mode: TimingMode,
+    listing_mode: ListingMode,
+    free_listing: bool,
+) -> Instruction {
+    let mut metas = accounts::ExhibitMany { exhibitor }.to_account_metas(None);
+    metas.extend(
+        items
+            .iter()
+            .flat_map(|item| to_exhibit_accounts(item).to_account_metas(None)),
+    );
+    let data = instruction::ExhibitMany {
+        initial_price,
+        auction_duration_sec,
+        timing_mode,
+        listing_mode,
+        free_listing,
+    };
+    build_ix(metas, data.data())
+}
+
+// Build an exhibit_from_governance instruction for a DAO listing an NFT held by its native treasury.
+// The exhibitor is the treasury and the FT receiving account its ATA; the instruction is meant to be
+// inserted in a governance proposal, which signs for the treasury when executed.
+pub fn exhibit_from_governance_ix(
+    governance: Pubkey,
+    exhibit_accounts: &ExhibitAccounts,
+    params: ExhibitParams,
+) -> Instruction {
+    let accounts = accounts::ExhibitFromGovernance {
+        governance,
+        exhibit: to_exhibit_accounts(exhibit_accounts),
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(exhibit_recipient_metas(&params));
+    let data = instruction::ExhibitFromGovernance {
+        initial_price: params.initial_price,
+        auction_duration_sec: params.auction_duration_sec,
+        payouts: params.payouts,
+        charity: params.charity,
+        charity_bps: params.charity_bps,
+        reserve_usd_cents: params.reserve_usd_cents,
+        reserve_price_feed: params.reserve_price_feed,
+        timing_mode: params.timing_mode,
+        title: params.title,
+        uri: params.uri,
+        listing_mode: params.listing_mode,
+        partner: params.partner.as_ref().map(|partner| partner.fee_account),
+        free_listing: params.free_listing,
+    };
+    build_ix(metas, data.data())
+}
+
+// Build an exhibit_domain instruction listing a Solana Name Service domain the exhibitor owns. The escrow
+// account must carry the auction ID the registry will assign it. Payouts, charity and reserve are not set.
+pub fn exhibit_domain_ix(
+    exhibitor: Pubkey,
+    payer: Pubkey,
+    name_account: Pubkey,
+    exhibitor_ft_receiving_account: Pubkey,
+    payment_mint: Pubkey,
+    auction_id: u64,
+    treasury: Pubkey,
+    initial_price: u64,
+    auction_duration_sec: u64,
+    timing_mode: TimingMode,
+    title: String,
+    uri: String,
+    free_listing: bool,
+) -> Instruction {
+    let escrow_account = auction_pda(auction_id).0;
+    let accounts = accounts::ExhibitDomain {
+        exhibitor,
+        payer,
+        name_account,
+        exhibitor_ft_receiving_account,
+        registry: registry_pda().0,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, 0).0,
+        auction_house: auction_house_pda().0,
+        name_blacklist_entry: blacklist_entry_pda(name_account).0,
+        payment_blacklist_entry: blacklist_entry_pda(payment_mint).0,
+        seller_allowlist_entry: seller_allowlist_pda(exhibitor).0,
+        treasury,
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        name_service_program: NAME_SERVICE_PROGRAM_ID,
+        system_program: system_program::ID,
+    };
+    let data = instruction::ExhibitDomain {
+        initial_price,
+        auction_duration_sec,
+        timing_mode,
+        title,
+        uri,
+        free_listing,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Build an exhibit_stake instruction listing a stake account the exhibitor is the staker and withdrawer
+// of. The escrow
+// account must carry the auction ID the registry will assign it. Payouts, charity and reserve are not set.
+pub fn exhibit_stake_ix(
+    exhibitor: Pubkey,
+    payer: Pubkey,
+    stake_account: Pubkey,
+    exhibitor_ft_receiving_account: Pubkey,
+    payment_mint: Pubkey,
+    auction_id: u64,
+    treasury: Pubkey,
+    initial_price: u64,
+    auction_duration_sec: u64,
+    timing_mode: TimingMode,
+    title: String,
+    uri: String,
+    free_listing: bool,
+) -> Instruction {
+    let escrow_account = auction_pda(auction_id).0;
+    let accounts = accounts::ExhibitStake {
+        exhibitor,
+        payer,
+        stake_account,
+        exhibitor_ft_receiving_account,
+        registry: registry_pda().0,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, 0).0,
+        auction_house: auction_house_pda().0,
+        stake_blacklist_entry: blacklist_entry_pda(stake_account).0,
+        payment_blacklist_entry: blacklist_entry_pda(payment_mint).0,
+        seller_allowlist_entry: seller_allowlist_pda(exhibitor).0,
+        treasury,
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        stake_program: STAKE_PROGRAM_ID,
+        system_program: system_program::ID,
+    };
+    let data = instruction::ExhibitStake {
+        initial_price,
+        auction_duration_sec,
+        timing_mode,
+        title,
+        uri,
+        free_listing,
+    };
+    build_ix(accounts.to_account_metas(None), data.data())
+}
+
+// Map the accounts of a new auction to the exhibit accounts.
+fn to_exhibit_accounts(exhibit_accounts: &ExhibitAccounts) -> accounts::Exhibit {
+    accounts::Exhibit {
+        exhibitor: exhibit_accounts.exhibitor,
+        payer: exhibit_accounts.payer,
+        exhibitor_nft_token_account: exhibit_accounts.exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: exhibit_accounts.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: exhibit_accounts.exhibitor_ft_receiving_account,
+        registry: registry_pda().0,
+        escrow_account: auction_pda(exhibit_accounts.auction_id).0,
+        listing_receipt: listing_receipt_pda(auction_pda(exhibit_accounts.auction_id).0, 0).0,
+        auction_house: auction_house_pda().0,
+        nft_blacklist_entry: blacklist_entry_pda(exhibit_accounts.nft_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(exhibit_accounts.payment_mint).0,
+        seller_allowlist_entry: seller_allowlist_pda(exhibit_accounts.exhibitor).0,
+        nft_mint: exhibit_accounts.nft_mint,
+        nft_metadata: metadata_address(&exhibit_accounts.nft_mint),
+        treasury: exhibit_accounts.treasury,
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+// List the payout recipients' and the charity's FT receiving accounts, then the partner frontend's entry
+// and FT fee account, passed as remaining accounts.
+fn exhibit_recipient_metas(params: &ExhibitParams) -> impl Iterator<Item = AccountMeta> + '_ {
+    params
+        .payouts
+        .iter()
+        .map(|payout| payout.recipient_ft_pubkey)
+        .chain(params.charity)
+        .chain(
+            params
+                .partner
+                .iter()
+                .flat_map(|partner| [partner_entry_pda(partner.partner).0, partner.fee_account]),
+        )
+        .map(|recipient| AccountMeta::new_readonly(recipient, false))
+}
+
+// Build a reuse_escrow instruction listing another item in the escrow account of an auction the
+// exhibitor sold and kept with update_escrow_reuse. The payer must be the escrow account's rent payer.
+// Payouts, charity and reserve are not set.
+pub fn reuse_escrow_ix(
+    exhibit_accounts: &ExhibitAccounts,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    initial_price: u64,
+    auction_duration_sec: u64,
+    timing_mode: TimingMode,
+    title: String,
+    uri: String,
+    listing_mode: ListingMode,
+    free_listing: bool,
+) -> Instruction {
+    let accounts = accounts::ReuseEscrow {
+        exhibitor: exhibit_accounts.exhibitor,
+        payer: exhibit_accounts.payer,
+        exhibitor_nft_token_account: exhibit_accounts.exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: exhibit_accounts.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: exhibit_accounts.exhibitor_ft_receiving_account,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round + 1).0,
+        auction_house: auction_house_pda().0,
+        nft_blacklist_entry: blacklist_entry_pda(exhibit_accounts.nft_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(exhibit_accounts.payment_mint).0,
+        seller_allowlist_entry: seller_allowlist_pda(exhibit_accounts.exhibitor).0,
+        nft_mint: exhibit_accounts.nft_mint,
+        nft_metadata: metadata_address(&exhibit_accounts.nft_mint),
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ReuseEscrow {
+            initial_price,
+            auction_duration_sec,
+            timing_mode,
+            title,
+            uri,
+            listing_mode,
+            free_listing,
+        }
+        .data(),
+    )
+}
+
+// Build a migrate_auction instruction to bring an escrow account up to the current layout. Auctions
+// from before version 8 that still hold their NFT need their temporary NFT account.
+pub fn migrate_auction_ix(
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    nft_temp_account: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::MigrateAuction {
+        payer,
+        escrow_account,
+        system_program: system_program::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(nft_temp_account.map(|account| AccountMeta::new_readonly(account, false)));
+    build_ix(metas, instruction::MigrateAuction {}.data())
+}
+
+// Build an update_proceeds_account instruction for the exhibitor to take the proceeds of an auction in
+// another FT receiving account once the recorded one has been frozen.
+pub fn update_proceeds_account_ix(
+    exhibitor: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    new_ft_receiving_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::UpdateProceedsAccount {
+        exhibitor,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        new_ft_receiving_account,
+        escrow_account,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateProceedsAccount {}.data(),
+    )
+}
+
+// Build a set_operator instruction; pass the default pubkey to remove the operator.
+pub fn set_operator_ix(exhibitor: Pubkey, escrow_account: Pubkey, operator: Pubkey) -> Instruction {
+    let accounts = accounts::SetOperator {
+        exhibitor,
+        escrow_account,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::SetOperator { operator }.data(),
+    )
+}
+
+// Build a mint_listing_token instruction for the exhibitor to mint the listing token of the auction's
+// current round into their associated token account.
+pub fn mint_listing_token_ix(escrow_account: Pubkey, auction: &Auction) -> Instruction {
+    let listing_token_mint = listing_token_mint_pda(escrow_account, auction.round).0;
+    let accounts = accounts::MintListingToken {
+        exhibitor: auction.exhibitor,
+        escrow_account,
+        listing_token_mint,
+        exhibitor_listing_token_account: get_associated_token_address(
+            &auction.exhibitor,
+            &listing_token_mint,
+        ),
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::MintListingToken {}.data(),
+    )
+}
+
+// Build a burn_listing_token instruction to burn the listing token an exhibitor was minted in a round
+// that is over, which anyone may send.
+pub fn burn_listing_token_ix(escrow_account: Pubkey, round: u64, exhibitor: Pubkey) -> Instruction {
+    let listing_token_mint = listing_token_mint_pda(escrow_account, round).0;
+    let accounts = accounts::BurnListingToken {
+        escrow_account,
+        listing_token_mint,
+        exhibitor_listing_token_account: get_associated_token_address(
+            &exhibitor,
+            &listing_token_mint,
+        ),
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::BurnListingToken { _round: round }.data(),
+    )
+}
+
+// List the listing token mint and the exhibitor's listing token account that settlement and cancellation
+// burn the listing token from, if the exhibitor minted one.
+fn listing_token_accounts(auction: &Auction) -> Vec<AccountMeta> {
+    if auction.listing_token_mint == Pubkey::default() {
+        return Vec::new();
+    }
+    vec![
+        AccountMeta::new(auction.listing_token_mint, false),
+        AccountMeta::new(
+            get_associated_token_address(&auction.exhibitor, &auction.listing_token_mint),
+            false,
+        ),
+    ]
+}
+
+// Build an update_auction instruction, signed by the exhibitor or its operator.
+pub fn update_auction_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    initial_price: u64,
+    auction_duration_sec: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateAuction {
+            initial_price,
+            auction_duration_sec,
+        }
+        .data(),
+    )
+}
+
+// Build an update_auction_metadata instruction, signed by the exhibitor or its operator.
+pub fn update_auction_metadata_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    title: String,
+    uri: String,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateAuctionMetadata { title, uri }.data(),
+    )
+}
+
+// Build an update_bid_gate instruction to restrict bidding to holders of a gating mint. The default
+// pubkey removes the gate.
+pub fn update_bid_gate_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    gate_mint: Pubkey,
+    gate_amount: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateBidGate {
+            gate_mint,
+            gate_amount,
+        }
+        .data(),
+    )
+}
+
+// Build an update_dutch_curve instruction to turn an auction without bids into a Dutch auction decaying
+// to a floor price, or back into an English auction with DutchCurve::None.
+pub fn update_dutch_curve_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    curve: DutchCurve,
+    floor_price: u64,
+    curve_param: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateDutchCurve {
+            curve,
+            floor_price,
+            curve_param,
+        }
+        .data(),
+    )
+}
+
+// Build an update_collection_gate instruction to restrict bidding to holders of an NFT from a verified
+// collection. The default pubkey removes the gate.
+pub fn update_collection_gate_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    gate_collection: Pubkey,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateCollectionGate { gate_collection }.data(),
+    )
+}
+
+// Build an update_bid_phases instruction to open bidding to gated bidders at allowlist_at and to everyone
+// at public_at, in the auction's timing mode. A zero public_at keeps the gates until the end.
+pub fn update_bid_phases_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    allowlist_at: i64,
+    public_at: i64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateBidPhases {
+            allowlist_at,
+            public_at,
+        }
+        .data(),
+    )
+}
+
+// List the gate accounts proving a bidder holds an NFT from a gated collection: their token account
+// of the NFT, then the NFT's metadata.
+pub fn collection_gate_accounts(nft_account: Pubkey, nft_mint: Pubkey) -> [Pubkey; 2] {
+    [nft_account, metadata_address(&nft_mint)]
+}
+
+// Build an update_penny_auction instruction to turn an auction without bids into a penny auction, or
+// back into an English auction with a zero tick.
+pub fn update_penny_auction_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    tick: u64,
+    bid_fee: u64,
+    countdown: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdatePennyAuction {
+            tick,
+            bid_fee,
+            countdown,
+        }
+        .data(),
+    )
+}
+
+// Build an update_bid_bond instruction to make every bid on an auction without bids pay a non-refundable
+// bond to the treasury, either a flat amount or a share of the bid in basis points. Zero for both removes it.
+pub fn update_bid_bond_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    amount: u64,
+    bps: u16,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateBidBond { amount, bps }.data(),
+    )
+}
+
+// Build an update_print_sale instruction to sell a numbered print of an auction's escrowed master edition
+// instead of the NFT itself, claimed with claim_print. Zero sells the NFT itself again.
+pub fn update_print_sale_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    nft_mint: Pubkey,
+    edition: u64,
+) -> Instruction {
+    let accounts = accounts::UpdatePrintSale {
+        authority,
+        escrow_account,
+        master_edition: edition_address(&nft_mint),
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdatePrintSale { edition }.data(),
+    )
+}
+
+// Build an update_relist instruction to let an auction without bids be relisted if it ends without one,
+// by its exhibitor, its operator or the opted-in crank.
+pub fn update_relist_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    crank: Pubkey,
+    duration_sec: u64,
+    reduction_bps: u16,
+    max_relists: u16,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateRelist {
+            crank,
+            duration_sec,
+            reduction_bps,
+            max_relists,
+        }
+        .data(),
+    )
+}
+
+// Build an update_escrow_reuse instruction to keep the escrow account of an auction without bids open
+// once it is sold, for reuse_escrow.
+pub fn update_escrow_reuse_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    reuse: bool,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateEscrowReuse { reuse }.data(),
+    )
+}
+
+// Build an update_installments instruction to let the winner of an auction without bids pay the rest of
+// its purchase amount in installments after a down payment (zero installments to remove the plan).
+pub fn update_installments_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    installment_count: u8,
+    installment_interval_sec: u64,
+    down_payment_bps: u16,
+    default_forfeit_bps: u16,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateInstallments {
+            installment_count,
+            installment_interval_sec,
+            down_payment_bps,
+            default_forfeit_bps,
+        }
+        .data(),
+    )
+}
+
+// Build an update_tick_size instruction to make the English bids on an auction without bids multiples of
+// a tick size (zero to remove it). min_bid rounds up to it.
+pub fn update_tick_size_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    tick_size: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateTickSize { tick_size }.data(),
+    )
+}
+
+// Build an update_max_price instruction to give an auction without bids a maximum price, at which a bid
+// ends it at once (zero to remove it).
+pub fn update_max_price_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    max_price: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateMaxPrice { max_price }.data(),
+    )
+}
+
+// Build an update_hidden_reserve instruction to give an auction without bids a hidden reserve, committed
+// with reserve_commitment. Keep the reserve and salt to reveal it once the auction ends.
+pub fn update_hidden_reserve_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    commitment: [u8; 32],
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateHiddenReserve { commitment }.data(),
+    )
+}
+
+// Build an update_arbitration instruction to put an NFT auction without bids in arbitration mode, holding
+// its settlement for the dispute window after finalize, during which the arbiter may reverse the sale.
+pub fn update_arbitration_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    arbiter: Pubkey,
+    dispute_window_sec: u64,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::UpdateArbitration {
+            arbiter,
+            dispute_window_sec,
+        }
+        .data(),
+    )
+}
+
+// Build a reveal_reserve instruction to disclose the hidden reserve of an ended auction. Anyone holding
+// the reserve and salt may send it.
+pub fn reveal_reserve_ix(escrow_account: Pubkey, reserve: u64, salt: [u8; 32]) -> Instruction {
+    let accounts = accounts::RevealReserve {
+        escrow_account,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RevealReserve { reserve, salt }.data(),
+    )
+}
+
+// Build a relist instruction to restart an auction that ended without a bid, signed by its exhibitor,
+// its operator or the opted-in crank.
+pub fn relist_ix(authority: Pubkey, escrow_account: Pubkey) -> Instruction {
+    let accounts = accounts::Relist {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::Relist {}.data(),
+    )
+}
+
+// Build a relist_with_price instruction for the exhibitor or its operator to restart an auction without
+// bids, ended or not, at a new price and for a new duration, keeping the NFT where it is.
+pub fn relist_with_price_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    initial_price: u64,
+    auction_duration_sec: u64,
+) -> Instruction {
+    let accounts = accounts::RelistWithPrice {
+        authority,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RelistWithPrice {
+            initial_price,
+            auction_duration_sec,
+        }
+        .data(),
+    )
+}
+
+// Build a create_series instruction, signed by the series authority and the payer of the series account.
+pub fn create_series_ix(
+    authority: Pubkey,
+    payer: Pubkey,
+    series_id: u64,
+    start_at: i64,
+    lot_duration: u64,
+) -> Instruction {
+    let accounts = accounts::CreateSeries {
+        authority,
+        payer,
+        series: series_pda(authority, series_id).0,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateSeries {
+            series_id,
+            start_at,
+            lot_duration,
+        }
+        .data(),
+    )
+}
+
+// Build an add_series_lot instruction to append an auction to a series, signed by the series authority,
+// which must manage the auction.
+pub fn add_series_lot_ix(authority: Pubkey, series_id: u64, escrow_account: Pubkey) -> Instruction {
+    let accounts = accounts::AddSeriesLot {
+        authority,
+        series: series_pda(authority, series_id).0,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AddSeriesLot {}.data(),
+    )
+}
+
+// Build an open_next_lot instruction to open the next lot of a series, which anyone may send. The lot
+// before it is passed along; for the first lot any account stands in for it.
+pub fn open_next_lot_ix(series: Pubkey, series_account: &AuctionSeries) -> Option<Instruction> {
+    let index = series_account.next_lot as usize;
+    let lot = *series_account.lots.get(index)?;
+    let previous_lot = index
+        .checked_sub(1)
+        .map_or(lot, |previous| series_account.lots[previous]);
+    let accounts = accounts::OpenNextLot {
+        series,
+        previous_lot,
+        lot,
+        clock: sysvar::clock::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    Some(build_ix(
+        accounts.to_account_metas(None),
+        instruction::OpenNextLot {}.data(),
+    ))
+}
+
+// List the fee accounts of a bid paying a penny auction's fee or a bid bond to the treasury's FT account:
+// the auction house configuration, then the treasury's FT account. Other bids have none.
+fn treasury_fee_accounts(treasury_ft_account: Option<Pubkey>) -> Vec<AccountMeta> {
+    treasury_ft_account
+        .map(|treasury_ft_account| {
+            vec![
+                AccountMeta::new_readonly(auction_house_pda().0, false),
+                AccountMeta::new(treasury_ft_account, false),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+// Build an enable_vesting instruction to vest the exhibitor's proceeds of an auction without bids.
+pub fn enable_vesting_ix(
+    authority: Pubkey,
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    cliff_sec: u64,
+    duration_sec: u64,
+) -> Instruction {
+    let vesting_schedule = vesting_schedule_pda(escrow_account).0;
+    let accounts = accounts::EnableVesting {
+        authority,
+        payer,
+        escrow_account,
+        payment_mint: auction.payment_mint,
+        vesting_schedule,
+        vesting_vault: vesting_vault_pda(vesting_schedule).0,
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::EnableVesting {
+            cliff_sec,
+            duration_sec,
+        }
+        .data(),
+    )
+}
+
+// Build an enable_buyout instruction for a fractionalization vault exhibiting an NFT to settle its
+// auction as a buyout. The vault is a PDA, so its program signs for it when invoking the instruction.
+pub fn enable_buyout_ix(
+    vault: Pubkey,
+    escrow_account: Pubkey,
+    vault_program: Pubkey,
+) -> Instruction {
+    let accounts = accounts::UpdateAuction {
+        authority: vault,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::EnableBuyout { vault_program }.data(),
+    )
+}
+
+// Build an enable_rental instruction to auction the usage rights to an escrowed NFT without bids for a
+// rental duration, instead of the NFT itself.
+pub fn enable_rental_ix(
+    authority: Pubkey,
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    duration_sec: u64,
+) -> Instruction {
+    let accounts = accounts::EnableRental {
+        authority,
+        payer,
+        escrow_account,
+        rental: rental_pda(escrow_account).0,
+        clock: sysvar::clock::ID,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::EnableRental { duration_sec }.data(),
+    )
+}
+
+// Build an end_rental instruction to return a rented NFT to the owner's NFT account once the rental is
+// over, or close a rental that never started.
+pub fn end_rental_ix(rental: Pubkey, state: &Rental, owner_nft_account: Pubkey) -> Instruction {
+    let accounts = accounts::EndRental {
+        rental,
+        owner_nft_account,
+        nft_account: state.nft_account,
+        nft_rent_destination: state.nft_rent_destination,
+        escrow_account: state.auction,
+        rent_payer: state.rent_payer,
+        clock: sysvar::clock::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::EndRental {}.data(),
+    )
+}
+
+// Build a claim_vested instruction to release what has vested to the exhibitor's FT receiving account.
+pub fn claim_vested_ix(vesting_schedule: Pubkey, schedule: &VestingSchedule) -> Instruction {
+    let accounts = accounts::ClaimVested {
+        vesting_schedule,
+        vault: schedule.vault,
+        beneficiary_ft_account: schedule.beneficiary_ft_pubkey,
+        escrow_account: schedule.auction,
+        rent_payer: schedule.rent_payer,
+        clock: sysvar::clock::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimVested {}.data(),
+    )
+}
+
+// Build a get_auction_state instruction, meant to be simulated; decode its return data with
+// decode_auction_state.
+pub fn get_auction_state_ix(escrow_account: Pubkey) -> Instruction {
+    let accounts = accounts::GetAuctionState {
+        escrow_account,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::GetAuctionState {}.data(),
+    )
+}
+
+// Build a cancel instruction for an auction that has no bids, signed by the exhibitor or its operator,
+// which also burns the exhibitor's listing token.
+pub fn cancel_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    authority: Pubkey,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::Cancel {
+        authority,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(listing_token_accounts(auction));
+    build_ix(metas, instruction::Cancel {}.data())
+}
+
+// Build an expire_auction instruction for anyone to give the NFT of an auction that ended without a bid
+// back to the exhibitor, which also burns the exhibitor's listing token.
+pub fn expire_auction_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ExpireAuction {
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(listing_token_accounts(auction));
+    build_ix(metas, instruction::ExpireAuction {}.data())
+}
+
+// Build a cancel_domain instruction, signed by the exhibitor or its operator, to cancel a domain auction
+// without bids and give the domain back.
+pub fn cancel_domain_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    authority: Pubkey,
+) -> Instruction {
+    let accounts = accounts::CancelDomain {
+        authority,
+        name_account: auction.nft_mint,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        name_service_program: NAME_SERVICE_PROGRAM_ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(listing_token_accounts(auction));
+    build_ix(metas, instruction::CancelDomain {}.data())
+}
+
+// Build a cancel_stake instruction, signed by the exhibitor or its operator, to cancel a stake account
+// auction without bids and give the stake account's authorities back.
+pub fn cancel_stake_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    authority: Pubkey,
+) -> Instruction {
+    let accounts = accounts::CancelStake {
+        authority,
+        stake_account: auction.nft_mint,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        clock: sysvar::clock::ID,
+        stake_program: STAKE_PROGRAM_ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(listing_token_accounts(auction));
+    build_ix(metas, instruction::CancelStake {}.data())
+}
+
+// Define the MinBid struct to represent the lowest bid an auction accepts and what placing it costs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinBid {
+    // The lowest bid amount accepted.
+    pub price: u64,
+    // The buyer's premium escrowed on top of the bid, refunded with it when outbid.
+    pub premium: u64,
+    // The penny auction fee and bid bond paid to the treasury, never refunded.
+    pub treasury_fee: u64,
+    // The bond part of the treasury fee, as reported by the BidPlaced event.
+    pub bond: u64,
+    // Everything the bidder pays: the bid, the premium and the treasury fee.
+    pub total_cost: u64,
+}
+
+// Calculate the lowest bid an auction accepts at a point in time and what it costs the bidder, including
+// the non-refundable bid bond. A bid whose treasury fee is not zero needs the treasury's FT account. Higher
+// English bids must stay on the auction's tick size (see Auction::on_tick).
+pub fn min_bid(auction: &Auction, clock: &Clock) -> Result<MinBid> {
+    let price = auction.min_next_bid(clock)?;
+    Ok(MinBid {
+        price,
+        premium: auction.buyers_premium(price)?,
+        treasury_fee: auction.treasury_bid_fee(price)?,
+        bond: auction.bid_bond(price)?,
+        total_cost: auction.bid_cost(price)?,
+    })
+}
+
+// Build a bid instruction against the auction's current state. A gated auction also needs the bidder's
+// gate accounts until its public phase: their token account of the gating mint, then their
+// collection_gate_accounts. A penny auction or one with a bid bond also needs the treasury's FT account
+// its bid fee and bond are paid to (see min_bid).
+pub fn bid_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    bidder: Pubkey,
+    payer: Pubkey,
+    bidder_ft_temp_account: Pubkey,
+    bidder_ft_account: Pubkey,
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+    gate_accounts: &[Pubkey],
+    treasury_ft_account: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::Bid {
+        bidder,
+        payer,
+        bidder_ft_temp_account,
+        bidder_ft_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        bid_receipt: bid_receipt_pda(escrow_account, bidder, auction.bid_count).0,
+        nft_blacklist_entry: blacklist_entry_pda(auction.nft_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(auction.payment_mint).0,
+        auction_house: auction_house_pda().0,
+        instructions: sysvar::instructions::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(referrer.map(|referrer| AccountMeta::new_readonly(referrer, false)));
+    metas.extend(
+        gate_accounts
+            .iter()
+            .map(|gate_account| AccountMeta::new_readonly(*gate_account, false)),
+    );
+    metas.extend(treasury_fee_accounts(treasury_ft_account));
+    build_ix(
+        metas,
+        instruction::Bid {
+            price,
+            referrer,
+            memo,
+            affiliate,
+        }
+        .data(),
+    )
+}
+
+// Build a create_session instruction for a wallet to let a session key bid for it from one of its FT
+// accounts, up to a spending cap and until the session expires.
+pub fn create_session_ix(
+    wallet: Pubkey,
+    wallet_ft_account: Pubkey,
+    session_key: Pubkey,
+    spending_cap: u64,
+    expires_at: i64,
+) -> Instruction {
+    let accounts = accounts::CreateSession {
+        wallet,
+        wallet_ft_account,
+        session: bid_session_pda(wallet, session_key).0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateSession {
+            session_key,
+            spending_cap,
+            expires_at,
+        }
+        .data(),
+    )
+}
+
+// Build a close_session instruction for a wallet to end a bid session, revoking its approval.
+pub fn close_session_ix(wallet: Pubkey, session: &BidSession) -> Instruction {
+    let accounts = accounts::CloseSession {
+        wallet,
+        wallet_ft_account: session.ft_account,
+        session: bid_session_pda(wallet, session.session_key).0,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CloseSession {}.data(),
+    )
+}
+
+// Build a bid_with_session instruction for a session key to bid for the wallet of its session, with the
+// remaining accounts of bid_ix. The session key signs, and owns the bidder's temporary FT account until
+// the bid hands it to the PDA.
+pub fn bid_with_session_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    session: &BidSession,
+    payer: Pubkey,
+    bidder_ft_temp_account: Pubkey,
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+    gate_accounts: &[Pubkey],
+    treasury_ft_account: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::BidWithSession {
+        session_key: session.session_key,
+        session: bid_session_pda(session.wallet, session.session_key).0,
+        bidder: session.wallet,
+        payer,
+        bidder_ft_temp_account,
+        bidder_ft_account: session.ft_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        bid_receipt: bid_receipt_pda(escrow_account, session.wallet, auction.bid_count).0,
+        nft_blacklist_entry: blacklist_entry_pda(auction.nft_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(auction.payment_mint).0,
+        auction_house: auction_house_pda().0,
+        instructions: sysvar::instructions::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(referrer.map(|referrer| AccountMeta::new_readonly(referrer, false)));
+    metas.extend(
+        gate_accounts
+            .iter()
+            .map(|gate_account| AccountMeta::new_readonly(*gate_account, false)),
+    );
+    metas.extend(treasury_fee_accounts(treasury_ft_account));
+    build_ix(
+        metas,
+        instruction::BidWithSession {
+            price,
+            referrer,
+            memo,
+            affiliate,
+        }
+        .data(),
+    )
+}
+
+// Build a create_bid_pool instruction for an operator to pool funds for bidding on an auction, signed by
+// the operator and the payer of the pool and vault accounts.
+pub fn create_bid_pool_ix(
+    operator: Pubkey,
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+) -> Instruction {
+    let bid_pool = bid_pool_pda(escrow_account, operator).0;
+    let accounts = accounts::CreateBidPool {
+        operator,
+        payer,
+        escrow_account,
+        payment_mint: auction.payment_mint,
+        bid_pool,
+        vault: bid_pool_vault_pda(bid_pool).0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::CreateBidPool {}.data(),
+    )
+}
+
+// Build a contribute_to_pool instruction, signed by the contributor, whose receipt takes the pool's next
+// contribution index.
+pub fn contribute_to_pool_ix(
+    contributor: Pubkey,
+    contributor_ft_account: Pubkey,
+    bid_pool: Pubkey,
+    pool: &BidPool,
+    amount: u64,
+) -> Instruction {
+    let accounts = accounts::ContributeToPool {
+        contributor,
+        contributor_ft_account,
+        bid_pool,
+        vault: pool.vault,
+        escrow_account: pool.auction,
+        contribution: pool_contribution_pda(bid_pool, pool.contribution_count).0,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ContributeToPool { amount }.data(),
+    )
+}
+
+// Build a pool_bid instruction for a bid pool's operator to bid from the pool's vault, which also pays for
+// the bid receipt and the temporary FT account's rent. The temporary FT account must be owned by the pool.
+// Gate and treasury fee accounts are as for bid_ix.
+pub fn pool_bid_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    operator: Pubkey,
+    bidder_ft_temp_account: Pubkey,
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    gate_accounts: &[Pubkey],
+    treasury_ft_account: Option<Pubkey>,
+) -> Instruction {
+    let bid_pool = bid_pool_pda(escrow_account, operator).0;
+    let bid = bid_ix(
+        escrow_account,
+        auction,
+        bid_pool,
+        operator,
+        bidder_ft_temp_account,
+        bid_pool_vault_pda(bid_pool).0,
+        price,
+        referrer,
+        memo.clone(),
+        None,
+        gate_accounts,
+        treasury_ft_account,
+    );
+    let accounts = accounts::PoolBid {
+        operator,
+        bid_pool,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    // The pool signs the bid through the program, not in the transaction.
+    metas.extend(bid.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != bid_pool,
+        ..meta
+    }));
+    build_ix(
+        metas,
+        instruction::PoolBid {
+            price,
+            referrer,
+            memo,
+        }
+        .data(),
+    )
+}
+
+// Build a bid_with_swap instruction for a bidder to pay for a bid in another token, swapped into the
+// payment mint by `swap`, the aggregator instruction quoted by Jupiter's swap-instructions API, which must
+// pay into `bidder_ft_account`. Gate and treasury fee accounts are as for bid_ix. Look up tables the quote
+// came with still have to be added to the transaction.
+pub fn bid_with_swap_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    bidder: Pubkey,
+    payer: Pubkey,
+    bidder_ft_temp_account: Pubkey,
+    bidder_ft_account: Pubkey,
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+    gate_accounts: &[Pubkey],
+    treasury_ft_account: Option<Pubkey>,
+    swap: Instruction,
+    min_amount_out: u64,
+) -> Instruction {
+    let bid = bid_ix(
+        escrow_account,
+        auction,
+        bidder,
+        payer,
+        bidder_ft_temp_account,
+        bidder_ft_account,
+        price,
+        referrer,
+        memo.clone(),
+        affiliate,
+        gate_accounts,
+        treasury_ft_account,
+    );
+    let accounts = accounts::BidWithSwap {
+        bidder,
+        bidder_ft_account,
+        swap_program: swap.program_id,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(swap.accounts.iter().cloned());
+    metas.extend(bid.accounts);
+    build_ix(
+        metas,
+        instruction::BidWithSwap {
+            swap_account_count: swap.accounts.len() as u8,
+            swap_data: swap.data,
+            min_amount_out,
+            price,
+            referrer,
+            memo,
+            affiliate,
+        }
+        .data(),
+    )
+}
+
+// Build a settle_bid_pool instruction to lock in the refunds of a pool whose auction is over, which anyone
+// may send.
+pub fn settle_bid_pool_ix(bid_pool: Pubkey, pool: &BidPool) -> Instruction {
+    let accounts = accounts::SettleBidPool {
+        bid_pool,
+        vault: pool.vault,
+        escrow_account: pool.auction,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::SettleBidPool {}.data(),
+    )
+}
+
+// Build a claim_pool_refund instruction to refund a contribution's share of a settled pool to the
+// contributor's FT account, which anyone may send.
+pub fn claim_pool_refund_ix(
+    bid_pool: Pubkey,
+    pool: &BidPool,
+    contribution: Pubkey,
+    contributor_ft_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ClaimPoolRefund {
+        bid_pool,
+        vault: pool.vault,
+        contribution,
+        contributor_ft_account,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimPoolRefund {}.data(),
+    )
+}
+
+// Build a pool_claim_item instruction for anyone to claim the NFT a bid pool won into
+// `pool_nft_receiving_account`, an NFT account the pool owns. The pool signs the claim through the
+// program.
+pub fn pool_claim_item_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    operator: Pubkey,
+    pool_nft_receiving_account: Pubkey,
+) -> Instruction {
+    let bid_pool = bid_pool_pda(escrow_account, operator).0;
+    let claim = claim_item_ix(escrow_account, auction, pool_nft_receiving_account);
+    let accounts = accounts::PoolClaimItem {
+        bid_pool,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    // The pool signs the claim through the program, not in the transaction.
+    metas.extend(claim.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != bid_pool,
+        ..meta
+    }));
+    build_ix(metas, instruction::PoolClaimItem {}.data())
+}
+
+// Build a fractionalize_bid_pool instruction to create the fraction mint of a settled bid pool holding
+// the NFT it won in `item_account`.
+pub fn fractionalize_bid_pool_ix(
+    payer: Pubkey,
+    bid_pool: Pubkey,
+    pool: &BidPool,
+    item_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::FractionalizeBidPool {
+        payer,
+        bid_pool,
+        item_account,
+        payment_mint: pool.payment_mint,
+        fraction_mint: bid_pool_fractions_pda(bid_pool).0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::FractionalizeBidPool {}.data(),
+    )
+}
+
+// Build a claim_pool_fractions instruction to mint a contribution to a fractionalized bid pool its
+// fractions of the NFT, to the contributor's fraction account.
+pub fn claim_pool_fractions_ix(
+    bid_pool: Pubkey,
+    pool: &BidPool,
+    contribution: Pubkey,
+    contributor_fraction_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ClaimPoolFractions {
+        bid_pool,
+        fraction_mint: pool.fraction_mint,
+        contribution,
+        contributor_fraction_account,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimPoolFractions {}.data(),
+    )
+}
+
+// Build a redeem_pool_item instruction, signed by the holder of every fraction of a fractionalized bid
+// pool, to burn them and take the NFT into `redeemer_nft_account`.
+pub fn redeem_pool_item_ix(
+    redeemer: Pubkey,
+    bid_pool: Pubkey,
+    pool: &BidPool,
+    redeemer_fraction_account: Pubkey,
+    redeemer_nft_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::RedeemPoolItem {
+        redeemer,
+        bid_pool,
+        fraction_mint: pool.fraction_mint,
+        redeemer_fraction_account,
+        item_account: pool.item_account,
+        redeemer_nft_account,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::RedeemPoolItem {}.data(),
+    )
+}
+
+// Build a bid_with_sol instruction against the auction's current state. A gated auction also needs the
+// bidder's gate accounts until its public phase: their token account of the gating mint, then their
+// collection_gate_accounts. A penny auction or one with a bid bond also needs the treasury's wSOL account
+// its bid fee and bond are paid to.
+pub fn bid_with_sol_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    bidder: Pubkey,
+    payer: Pubkey,
+    bidder_ft_temp_account: Pubkey,
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+    gate_accounts: &[Pubkey],
+    treasury_ft_account: Option<Pubkey>,
+) -> Instruction {
+    let accounts = accounts::BidWithSol {
+        bidder,
+        payer,
+        bidder_ft_temp_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        bid_receipt: bid_receipt_pda(escrow_account, bidder, auction.bid_count).0,
+        nft_blacklist_entry: blacklist_entry_pda(auction.nft_mint).0,
+        payment_blacklist_entry: blacklist_entry_pda(auction.payment_mint).0,
+        auction_house: auction_house_pda().0,
+        instructions: sysvar::instructions::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(referrer.map(|referrer| AccountMeta::new_readonly(referrer, false)));
+    metas.extend(
+        gate_accounts
+            .iter()
+            .map(|gate_account| AccountMeta::new_readonly(*gate_account, false)),
+    );
+    metas.extend(treasury_fee_accounts(treasury_ft_account));
+    build_ix(
+        metas,
+        instruction::BidWithSol {
+            price,
+            referrer,
+            memo,
+            affiliate,
+        }
+        .data(),
+    )
+}
+
+// Build a close instruction, signed by the winning bidder.
+pub fn close_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    highest_bidder_nft_receiving_account: Pubkey,
+    treasury_ft_account: Pubkey,
+    royalties: &Royalties,
+) -> Instruction {
+    let accounts = accounts::Close {
+        winning_bidder: auction.highest_bidder,
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_nft_receiving_account,
+        auction_house: auction_house_pda().0,
+        treasury_ft_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        purchase_receipt: purchase_receipt_pda(escrow_account, auction.round).0,
+        system_program: system_program::ID,
+        payment_mint: auction.payment_mint,
+        price_feed: auction.reserve_price_feed,
+        nft_metadata: metadata_address(&auction.nft_mint),
+        royalty_override: royalties.royalty_override,
+        collection_stats: royalties.collection_stats(auction.payment_mint),
+        house_stats: house_stats_pda(auction.payment_mint).0,
+        clock: sysvar::clock::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(settlement_remaining_accounts(auction, royalties));
+    build_ix(metas, instruction::Close {}.data())
+}
+
+// Build a settle_if_ended instruction, which needs no signature from either party, only from the payer
+// of the purchase receipt.
+pub fn settle_if_ended_ix(
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    highest_bidder_nft_receiving_account: Pubkey,
+    treasury_ft_account: Pubkey,
+    royalties: &Royalties,
+) -> Instruction {
+    let accounts = accounts::SettleIfEnded {
+        payer,
+        winning_bidder: auction.highest_bidder,
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_nft_receiving_account,
+        auction_house: auction_house_pda().0,
+        treasury_ft_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        purchase_receipt: purchase_receipt_pda(escrow_account, auction.round).0,
+        system_program: system_program::ID,
+        payment_mint: auction.payment_mint,
+        price_feed: auction.reserve_price_feed,
+        nft_metadata: metadata_address(&auction.nft_mint),
+        royalty_override: royalties.royalty_override,
+        collection_stats: royalties.collection_stats(auction.payment_mint),
+        house_stats: house_stats_pda(auction.payment_mint).0,
+        clock: sysvar::clock::ID,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(settlement_remaining_accounts(auction, royalties));
+    build_ix(metas, instruction::SettleIfEnded {}.data())
+}
+
+// Combine up to MAX_CLOSE_BATCH settle_if_ended instructions, built by settle_if_ended_ix, into one
+// close_many instruction paid for by the crank.
+pub fn close_many_ix(payer: Pubkey, settle_ixs: &[Instruction]) -> Instruction {
+    let mut metas = accounts::CloseMany { payer }.to_account_metas(None);
+    metas.extend(settle_ixs.iter().flat_map(|ix| ix.accounts.iter().cloned()));
+    build_ix(metas, instruction::CloseMany {}.data())
+}
+
+// Build a finalize instruction to lock in the result of an ended auction.
+pub fn finalize_ix(escrow_account: Pubkey, auction: &Auction) -> Instruction {
+    let accounts = accounts::Finalize {
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        escrow_account,
+        payment_mint: auction.payment_mint,
+        price_feed: auction.reserve_price_feed,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::Finalize {}.data(),
+    )
+}
+
+// Build a claim_item instruction, signed by the winning bidder of a finalized auction. For a rented NFT
+// the rental starts instead, and the receiving account is unused.
+pub fn claim_item_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    highest_bidder_nft_receiving_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ClaimItem {
+        winning_bidder: auction.highest_bidder,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        highest_bidder_nft_receiving_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        clock: sysvar::clock::ID,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if auction.rental != Pubkey::default() {
+        metas.push(AccountMeta::new(auction.rental, false));
+    }
+    build_ix(metas, instruction::ClaimItem {}.data())
+}
+
+// Build a claim_print instruction, signed by the winning bidder of a finalized print sale, to print the
+// auction's edition to a new mint. The winning bidder must have created the new mint with themselves as
+// its mint authority and minted its single token, and update_authority is the master edition's metadata
+// update authority.
+pub fn claim_print_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    new_mint: Pubkey,
+    update_authority: Pubkey,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ClaimPrint {
+        winning_bidder: auction.highest_bidder,
+        new_mint,
+        new_metadata: metadata_address(&new_mint),
+        new_edition: edition_address(&new_mint),
+        master_edition: edition_address(&auction.nft_mint),
+        edition_marker: edition_marker_address(&auction.nft_mint, auction.print_edition),
+        nft_metadata: metadata_address(&auction.nft_mint),
+        update_authority,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_nft_token_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+        token_metadata_program: TOKEN_METADATA_PROGRAM_ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimPrint {}.data(),
+    )
+}
+
+// Build a claim_domain instruction transferring the domain of a finalized domain auction to its winning
+// bidder. It needs no signer beyond the fee payer.
+pub fn claim_domain_ix(escrow_account: Pubkey, auction: &Auction) -> Instruction {
+    let accounts = accounts::ClaimDomain {
+        name_account: auction.nft_mint,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        name_service_program: NAME_SERVICE_PROGRAM_ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimDomain {}.data(),
+    )
+}
+
+// Build a claim_stake instruction handing the stake account of a finalized stake account auction to its
+// winning bidder. It needs no signer beyond the fee payer.
+pub fn claim_stake_ix(escrow_account: Pubkey, auction: &Auction) -> Instruction {
+    let accounts = accounts::ClaimStake {
+        stake_account: auction.nft_mint,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        clock: sysvar::clock::ID,
+        stake_program: STAKE_PROGRAM_ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ClaimStake {}.data(),
+    )
+}
+
+// Build a claim_proceeds instruction, signed by the exhibitor of a finalized auction.
+pub fn claim_proceeds_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    treasury_ft_account: Pubkey,
+    royalties: &Royalties,
+) -> Instruction {
+    let accounts = accounts::ClaimProceeds {
+        exhibitor: auction.exhibitor,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        auction_house: auction_house_pda().0,
+        treasury_ft_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        purchase_receipt: purchase_receipt_pda(escrow_account, auction.round).0,
+        system_program: system_program::ID,
+        nft_metadata: metadata_address(&auction.nft_mint),
+        royalty_override: royalties.royalty_override,
+        collection_stats: royalties.collection_stats(auction.payment_mint),
+        house_stats: house_stats_pda(auction.payment_mint).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(settlement_remaining_accounts(auction, royalties));
+    build_ix(metas, instruction::ClaimProceeds {}.data())
+}
+
+// Build a settle_item instruction for anyone to deliver the NFT of a finalized auction to the winning
+// bidder's NFT receiving account, which must belong to the winning bidder.
+pub fn settle_item_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    highest_bidder_nft_receiving_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::SettleItem {
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        highest_bidder_nft_receiving_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        clock: sysvar::clock::ID,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    if auction.rental != Pubkey::default() {
+        metas.push(AccountMeta::new(auction.rental, false));
+    }
+    build_ix(metas, instruction::SettleItem {}.data())
+}
+
+// Build a settle_payment instruction for anyone to distribute the proceeds of a finalized auction, the
+// payer paying for the purchase receipt.
+pub fn settle_payment_ix(
+    payer: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    treasury_ft_account: Pubkey,
+    royalties: &Royalties,
+) -> Instruction {
+    let accounts = accounts::SettlePayment {
+        payer,
+        exhibitor: auction.exhibitor,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        auction_house: auction_house_pda().0,
+        treasury_ft_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        purchase_receipt: purchase_receipt_pda(escrow_account, auction.round).0,
+        system_program: system_program::ID,
+        nft_metadata: metadata_address(&auction.nft_mint),
+        royalty_override: royalties.royalty_override,
+        collection_stats: royalties.collection_stats(auction.payment_mint),
+        house_stats: house_stats_pda(auction.payment_mint).0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(settlement_remaining_accounts(auction, royalties));
+    build_ix(metas, instruction::SettlePayment {}.data())
+}
+
+// Build a reclaim_unclaimed instruction for a finalized auction whose winning bidder missed the claim
+// deadline.
+pub fn reclaim_unclaimed_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::ReclaimUnclaimed {
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        clock: sysvar::clock::ID,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ReclaimUnclaimed {}.data(),
+    )
+}
+
+// Build an accept_highest_bid instruction for the exhibitor or its operator to sell an ended auction at a
+// highest bid that falls short of its reserves. Settle it afterwards as usual.
+pub fn accept_highest_bid_ix(authority: Pubkey, escrow_account: Pubkey) -> Instruction {
+    let accounts = accounts::AcceptHighestBid {
+        authority,
+        escrow_account,
+        clock: sysvar::clock::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AcceptHighestBid {}.data(),
+    )
+}
+
+// Build a settle_unmet_reserve instruction for an ended auction whose reserve was not met, once its
+// acceptance period is over.
+pub fn settle_unmet_reserve_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::SettleUnmetReserve {
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        payment_mint: auction.payment_mint,
+        price_feed: auction.reserve_price_feed,
+        clock: sysvar::clock::ID,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::SettleUnmetReserve {}.data(),
+    )
+}
+
+// Build a pay_installment instruction for the winning bidder of an auction with an installment plan to pay
+// towards the rest of its purchase amount from one of its FT accounts.
+pub fn pay_installment_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    highest_bidder_ft_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = accounts::PayInstallment {
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_account,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        token_program: anchor_spl::token::ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::PayInstallment { amount }.data(),
+    )
+}
+
+// Build a default_installments instruction for anyone to unwind an auction whose winning bidder missed an
+// installment, returning the NFT to the exhibitor.
+pub fn default_installments_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+) -> Instruction {
+    let accounts = accounts::DefaultInstallments {
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        exhibitor_ft_receiving_account: auction.exhibitor_ft_receiving_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        clock: sysvar::clock::ID,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::DefaultInstallments {}.data(),
+    )
+}
+
+// Build an admin_cancel instruction for the house authority or its emergency canceller to unwind an
+// auction, refunding its highest bid and returning the NFT to the exhibitor.
+pub fn admin_cancel_ix(
+    authority: Pubkey,
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+    reason: u16,
+) -> Instruction {
+    let accounts = accounts::AdminCancel {
+        authority,
+        auction_house: auction_house_pda().0,
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::AdminCancel { reason }.data(),
+    )
+}
+
+// Build a reverse_sale instruction for the arbiter of an auction in arbitration mode to reverse its sale
+// during the dispute window, refunding the winning bid and returning the NFT to the exhibitor.
+pub fn reverse_sale_ix(
+    escrow_account: Pubkey,
+    auction: &Auction,
+    exhibitor_nft_token_account: Pubkey,
+    reason: u16,
+) -> Instruction {
+    let accounts = accounts::ReverseSale {
+        arbiter: auction.arbiter,
+        exhibitor: auction.exhibitor,
+        exhibitor_nft_token_account,
+        exhibitor_nft_temp_account: auction.exhibitor_nft_temp_account,
+        highest_bidder: auction.highest_bidder,
+        highest_bidder_ft_temp_account: auction.highest_bidder_ft_temp_account,
+        highest_bidder_ft_returning_account: auction.highest_bidder_ft_returning_account,
+        exhibitor_rent_payer: auction.exhibitor_rent_payer,
+        highest_bidder_rent_payer: auction.highest_bidder_rent_payer,
+        escrow_account,
+        auction_house: auction_house_pda().0,
+        pda: escrow_authority_pda().0,
+        token_program: anchor_spl::token::ID,
+        listing_receipt: listing_receipt_pda(escrow_account, auction.round).0,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    };
+    build_ix(
+        accounts.to_account_metas(None),
+        instruction::ReverseSale { reason }.data(),
+    )
+}
+
+// Define the Royalties struct to hold the accounts settlement needs to pay an NFT's royalty and record
+// the sale in its collection's stats. While the house has FEATURE_ROYALTIES off no royalty is paid, so
+// settlement expects no royalty vault.
+#[derive(Default)]
+pub struct Royalties {
+    // The NFT's verified collection, or the default pubkey without one.
+    pub collection: Pubkey,
+    // The royalty override PDA of the NFT's verified collection, or the default pubkey without one.
+    pub royalty_override: Pubkey,
+    // The creators owed a share of the royalty, in metadata order. Settlement escrows their shares in the
+    // house's royalty vault of the payment mint, which must exist, for distribute_royalties_ix to pay.
+    pub creators: Vec<Pubkey>,
+}
+
+impl Royalties {
+    // Get the royalty accounts of an NFT from the data of its metadata account, or None when it has no
+    // metadata, following the program's royalties::load_royalty.
+    pub fn from_metadata(metadata: Option<&[u8]>) -> Result<Self> {
+        let metadata = match metadata {
+            Some(data) => decode_metadata(data)?,
+            None => return Ok(Self::default()),
+        };
+        let collection = metadata
+            .collection
+            .filter(|collection| collection.verified)
+            .map_or(Pubkey::default(), |collection| collection.key);
+        let royalty_override = if collection == Pubkey::default() {
+            Pubkey::default()
+        } else {
+            royalty_override_pda(collection).0
+        };
+        // No royalty is owed on the primary sale.
+        let creators = match metadata.creators {
+            Some(creators) if metadata.primary_sale_happened => creators
+                .into_iter()
+                .filter(|creator| creator.share > 0)
+                .map(|creator| creator.address)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            collection,
+            royalty_override,
+            creators,
+        })
+    }
+
+    // Get the stats PDA of the NFT's verified collection in a payment mint, or the default pubkey without
+    // one.
+    pub fn collection_stats(&self, payment_mint: Pubkey) -> Pubkey {
+        if self.collection == Pubkey::default() {
+            return Pubkey::default();
+        }
+        collection_stats_pda(self.collection, payment_mint).0
+    }
+}
+
+// List the remaining accounts settlement expects: the payout recipients, then the charity, the referrer
+// of the winning bid and the partner frontend when the auction has them, then the house's royalty vault
+// when a royalty is owed, then the vesting schedule and vault when its proceeds vest, or the vault
+// program and buyout authority of a buyout, the rental when the NFT is rented, and last the listing token
+// mint and the exhibitor's listing token account when the exhibitor minted one.
+pub fn settlement_remaining_accounts(auction: &Auction, royalties: &Royalties) -> Vec<AccountMeta> {
+    let payouts = auction.payouts[..auction.payout_count as usize]
+        .iter()
+        .map(|payout| payout.recipient_ft_pubkey);
+    let charity = (auction.charity_bps > 0).then_some(auction.charity_ft_pubkey);
+    let referrer = (auction.highest_bidder_referrer_ft_pubkey != Pubkey::default())
+        .then_some(auction.highest_bidder_referrer_ft_pubkey);
+    let partner = (auction.partner_fee_ft_pubkey != Pubkey::default())
+        .then_some(auction.partner_fee_ft_pubkey);
+    let royalty_vault =
+        (!royalties.creators.is_empty()).then(|| royalty_vault_pda(auction.payment_mint).0);
+    let affiliate_vault = (auction.highest_bidder_affiliate != Pubkey::default())
+        .then(|| affiliate_vault_pda(auction.payment_mint).0);
+    let vesting = (auction.vesting_schedule != Pubkey::default()).then(|| {
+        [
+            auction.vesting_schedule,
+            vesting_vault_pda(auction.vesting_schedule).0,
+        ]
+    });
+    payouts
+        .chain(charity)
+        .chain(referrer)
+        .chain(partner)
+        .chain(royalty_vault)
+        .chain(affiliate_vault)
+        .chain(vesting.into_iter().flatten())
+        .map(|recipient| AccountMeta::new(recipient, false))
+        .chain(
+            (auction.buyout_program != Pubkey::default())
+                .then(|| {
+                    [
+                        AccountMeta::new_readonly(auction.buyout_program, false),
+                        AccountMeta::new_readonly(buyout_authority_pda(auction.exhibitor).0, false),
+                    ]
+                })
+                .into_iter()
+                .flatten(),
+        )
+        .chain(
+            (auction.rental != Pubkey::default()).then(|| AccountMeta::new(auction.rental, false)),
+        )
+        .chain(listing_token_accounts(auction))
+        .collect()
+}
+
+// Define the NonceAccount struct to hold the state of an initialized durable nonce account.
+pub struct NonceAccount {
+    // The account that must sign to advance the nonce.
+    pub authority: Pubkey,
+    // The stored nonce, used as the recent blockhash of a durable nonce transaction.
+    pub nonce: Hash,
+    // The fee per signature recorded with the nonce.
+    pub lamports_per_signature: u64,
+}
+
+// Decode the raw data of a durable nonce account, which is bincode-encoded: a u32 version, a u32 state
+// that is 1 once initialized, then the authority, the nonce and the fee calculator.
+pub fn decode_nonce_account(data: &[u8]) -> Result<NonceAccount> {
+    let field =
+        |range: std::ops::Range<usize>| data.get(range).ok_or(ErrorCode::AccountDidNotDeserialize);
+    require!(
+        field(4..8)? == 1u32.to_le_bytes(),
+        ErrorCode::AccountNotInitialized
+    );
+    Ok(NonceAccount {
+        authority: Pubkey::new(field(8..40)?),
+        nonce: Hash::new(field(40..72)?),
+        lamports_per_signature: u64::from_le_bytes(field(72..80)?.try_into().unwrap()),
+    })
+}
+
+// Build the instructions to create and initialize a durable nonce account, paid for by the payer. Both
+// the payer and the new nonce account sign.
+pub fn create_nonce_account_ixs(
+    payer: Pubkey,
+    nonce_account: Pubkey,
+    nonce_authority: Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    system_instruction::create_nonce_account(&payer, &nonce_account, &nonce_authority, lamports)
+}
+
+// Build the message of a transaction whose fees are paid by a sponsor, such as a marketplace relaying a
+// gasless bid. Pass the sponsor as the payer of the auction instructions too (for example bid_ix's payer),
+// so the user signs only for their own authority. Every signer signs the serialized message
+// (Message::serialize) separately, and the signatures are put in the order of signer_keys.
+pub fn sponsored_message(
+    instructions: &[Instruction],
+    fee_payer: Pubkey,
+    recent_blockhash: Hash,
+) -> Message {
+    Message::new_with_blockhash(instructions, Some(&fee_payer), &recent_blockhash)
+}
+
+// Build the message of a sponsored transaction that stays valid until its durable nonce is advanced,
+// rather than for the lifetime of a recent blockhash, so it can be signed by the user well before the
+// sponsor sends it. The nonce is read from the nonce account with decode_nonce_account, and its
+// authority must sign too.
+pub fn durable_nonce_message(
+    instructions: &[Instruction],
+    fee_payer: Pubkey,
+    nonce_account: Pubkey,
+    nonce: &NonceAccount,
+) -> Message {
+    let mut message = Message::new_with_nonce(
+        instructions.to_vec(),
+        Some(&fee_payer),
+        &nonce_account,
+        &nonce.authority,
+    );
+    message.recent_blockhash = nonce.nonce;
+    message
+}
+
+// List the keys that must sign a message, in the order of the transaction's signatures. The fee payer
+// always comes first.
+pub fn signer_keys(message: &Message) -> &[Pubkey] {
+    &message.account_keys[..message.header.num_required_signatures as usize]
+}
+
+// Get the position of a signer's signature in a transaction of the message, for adding the signatures
+// of a partially signed transaction one signer at a time. Returns None if the key does not sign.
+pub fn signer_index(message: &Message, signer: &Pubkey) -> Option<usize> {
+    signer_keys(message).iter().position(|key| key == signer)
+}
+
+// Build an instruction for the auction program.
+fn build_ix(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build an auction on its second reuse, with three bids placed in the current round.
+    fn auction() -> Auction {
+        let mut auction: Auction = bytemuck::Zeroable::zeroed();
+        auction.version = AUCTION_VERSION;
+        auction.round = 2;
+        auction.bid_count = 3;
+        auction
+    }
+
+    #[test]
+    fn bid_receipt_pda_matches_the_program_seeds() {
+        let (escrow_account, bidder) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let auction = auction();
+        // The seeds of Bid::bid_receipt.
+        let seeds: &[&[u8]] = &[
+            BID_RECEIPT_SEED,
+            escrow_account.as_ref(),
+            bidder.as_ref(),
+            &auction.bid_count.to_le_bytes(),
+        ];
+        assert_eq!(
+            bid_receipt_pda(escrow_account, bidder, auction.bid_count),
+            Pubkey::find_program_address(seeds, &ID)
+        );
+    }
+
+    #[test]
+    fn listing_receipt_pda_matches_the_program_seeds() {
+        let escrow_account = Pubkey::new_unique();
+        // The seeds of Exhibit::listing_receipt, in the escrow account's first round.
+        let seeds: &[&[u8]] = &[LISTING_RECEIPT_SEED, escrow_account.as_ref()];
+        assert_eq!(
+            listing_receipt_pda(escrow_account, 0),
+            Pubkey::find_program_address(seeds, &ID)
+        );
+        // The seeds of the receipts of later rounds, such as Cancel::listing_receipt.
+        let auction = auction();
+        let round_seed = auction.round_seed();
+        let seeds: &[&[u8]] = &[LISTING_RECEIPT_SEED, escrow_account.as_ref(), &round_seed];
+        assert_eq!(
+            listing_receipt_pda(escrow_account, auction.round),
+            Pubkey::find_program_address(seeds, &ID)
+        );
+    }
+
+    // Serialize an auction as its account data.
+    fn auction_data(auction: &Auction) -> Vec<u8> {
+        let mut data = Auction::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(auction));
+        data
+    }
+
+    // Build a live auction of an exhibitor and an NFT mint.
+    fn listed_auction(exhibitor: Pubkey, nft_mint: Pubkey) -> Auction {
+        let mut auction = auction();
+        auction.exhibitor = exhibitor;
+        auction.nft_mint = nft_mint;
+        auction.set_status(AuctionStatus::Live);
+        auction
+    }
+
+    #[test]
+    fn auction_filters_match_the_serialized_fields() {
+        let (exhibitor, nft_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = auction_data(&listed_auction(exhibitor, nft_mint));
+        assert_eq!(data.len(), Auction::LEN);
+        for filter in [
+            Memcmp::auction(),
+            Memcmp::auction_exhibitor(exhibitor),
+            Memcmp::auction_nft_mint(nft_mint),
+            Memcmp::auction_status(AuctionStatus::Live),
+        ] {
+            assert_eq!(
+                data[filter.offset..filter.offset + filter.bytes.len()],
+                filter.bytes
+            );
+        }
+        // Another auction's fields do not match.
+        let other = Memcmp::auction_exhibitor(Pubkey::new_unique());
+        assert_ne!(
+            data[other.offset..other.offset + other.bytes.len()],
+            other.bytes
+        );
+        let other = Memcmp::auction_status(AuctionStatus::Ended);
+        assert_ne!(
+            data[other.offset..other.offset + other.bytes.len()],
+            other.bytes
+        );
+    }
+
+    // Define the FakeRpc struct, which serves program accounts the way an RPC node filters them and records
+    // the queries it was sent.
+    struct FakeRpc {
+        accounts: Vec<(Pubkey, Vec<u8>)>,
+        queries: std::cell::RefCell<Vec<(usize, Vec<Memcmp>)>>,
+    }
+
+    // Implement the ProgramAccounts trait for the FakeRpc struct.
+    impl ProgramAccounts for FakeRpc {
+        type Error = std::convert::Infallible;
+
+        fn get_program_accounts(
+            &self,
+            program_id: &Pubkey,
+            data_size: usize,
+            filters: &[Memcmp],
+        ) -> std::result::Result<Vec<(Pubkey, Vec<u8>)>, Self::Error> {
+            assert_eq!(*program_id, ID);
+            self.queries
+                .borrow_mut()
+                .push((data_size, filters.to_vec()));
+            Ok(self
+                .accounts
+                .iter()
+                .filter(|(_, data)| {
+                    data.len() == data_size
+                        && filters.iter().all(|filter| {
+                            data.get(filter.offset..filter.offset + filter.bytes.len())
+                                == Some(&filter.bytes[..])
+                        })
+                })
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[test]
+    fn fetch_auctions_filters_on_the_discriminator_and_the_current_size() {
+        let (exhibitor, nft_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let listed = Pubkey::new_unique();
+        let data = auction_data(&listed_auction(exhibitor, nft_mint));
+        // An auction still to be migrated to the current layout.
+        let mut unmigrated = data.clone();
+        unmigrated.truncate(Auction::LEN - 8);
+        // Another account of the same size as an auction.
+        let mut other_account = data.clone();
+        other_account[..8].copy_from_slice(&ListingReceipt::discriminator());
+        let client = FakeRpc {
+            accounts: vec![
+                (listed, data),
+                (Pubkey::new_unique(), unmigrated),
+                (Pubkey::new_unique(), other_account),
+            ],
+            queries: Default::default(),
+        };
+
+        let auctions = fetch_auctions_by_exhibitor(&client, exhibitor).unwrap();
+        assert_eq!(auctions.len(), 1);
+        assert_eq!(auctions[0].0, listed);
+        assert_eq!(auctions[0].1.nft_mint, nft_mint);
+        assert_eq!(
+            client.queries.take(),
+            vec![(
+                Auction::LEN,
+                vec![Memcmp::auction(), Memcmp::auction_exhibitor(exhibitor)]
+            )]
+        );
+
+        assert_eq!(fetch_auctions_for_mint(&client, nft_mint).unwrap().len(), 1);
+        assert!(fetch_auctions_for_mint(&client, Pubkey::new_unique())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn fetch_live_auctions_leaves_out_auctions_past_their_end() {
+        let mut auction = listed_auction(Pubkey::new_unique(), Pubkey::new_unique());
+        auction.end_at = 1_000;
+        let client = FakeRpc {
+            accounts: vec![(Pubkey::new_unique(), auction_data(&auction))],
+            queries: Default::default(),
+        };
+        let at = |unix_timestamp| Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        assert_eq!(fetch_live_auctions(&client, &at(999)).unwrap().len(), 1);
+        assert!(fetch_live_auctions(&client, &at(1_000)).unwrap().is_empty());
+    }
+}
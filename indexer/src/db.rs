@@ -0,0 +1,165 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::Result;
+// Import the Postgres client from the tokio_postgres library.
+use tokio_postgres::Client;
+// Import the decoded updates.
+use crate::rpc::Update;
+
+// Define the schema. Token amounts are u64, so they are stored as numeric and bound as text.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS auctions (
+    address TEXT PRIMARY KEY,
+    auction_id BIGINT NOT NULL,
+    status SMALLINT NOT NULL,
+    exhibitor TEXT NOT NULL,
+    nft_mint TEXT NOT NULL,
+    payment_mint TEXT NOT NULL,
+    price NUMERIC NOT NULL,
+    highest_bidder TEXT NOT NULL,
+    timing_mode SMALLINT NOT NULL,
+    end_at BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    slot BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS auctions_nft_mint ON auctions (nft_mint);
+CREATE INDEX IF NOT EXISTS auctions_status ON auctions (status);
+
+CREATE TABLE IF NOT EXISTS bids (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    auction TEXT NOT NULL,
+    bidder TEXT NOT NULL,
+    price NUMERIC NOT NULL,
+    referrer TEXT,
+    slot BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS memo TEXT;
+ALTER TABLE bids ADD COLUMN IF NOT EXISTS affiliate TEXT;
+CREATE INDEX IF NOT EXISTS bids_auction ON bids (auction);
+
+CREATE TABLE IF NOT EXISTS settlements (
+    auction TEXT PRIMARY KEY,
+    signature TEXT NOT NULL,
+    winner TEXT,
+    price NUMERIC NOT NULL,
+    sold BOOLEAN NOT NULL,
+    slot BIGINT NOT NULL
+);
+";
+
+// Create the tables and indexes if they do not exist yet.
+pub async fn migrate(client: &Client) -> Result<()> {
+    client.batch_execute(SCHEMA).await?;
+    Ok(())
+}
+
+// Write an update. Auction rows are only replaced by newer slots, and events are idempotent, so
+// notifications may be replayed after a reconnection.
+pub async fn apply(client: &Client, update: Update) -> Result<()> {
+    match update {
+        Update::Auction {
+            address,
+            auction,
+            slot,
+        } => {
+            client
+                .execute(
+                    "INSERT INTO auctions (address, auction_id, status, exhibitor, nft_mint, payment_mint,
+                         price, highest_bidder, timing_mode, end_at, title, uri, slot)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7::TEXT::NUMERIC, $8, $9, $10, $11, $12, $13)
+                     ON CONFLICT (address) DO UPDATE SET
+                         status = EXCLUDED.status,
+                         price = EXCLUDED.price,
+                         highest_bidder = EXCLUDED.highest_bidder,
+                         end_at = EXCLUDED.end_at,
+                         title = EXCLUDED.title,
+                         uri = EXCLUDED.uri,
+                         slot = EXCLUDED.slot
+                     WHERE auctions.slot <= EXCLUDED.slot",
+                    &[
+                        &address,
+                        &(auction.auction_id as i64),
+                        &(auction.status as i16),
+                        &auction.exhibitor.to_string(),
+                        &auction.nft_mint.to_string(),
+                        &auction.payment_mint.to_string(),
+                        &auction.price.to_string(),
+                        &auction.highest_bidder.to_string(),
+                        &(auction.timing_mode as i16),
+                        &auction.end_at,
+                        &auction.title(),
+                        &auction.uri(),
+                        &(slot as i64),
+                    ],
+                )
+                .await?;
+        }
+        Update::Bid {
+            signature,
+            index,
+            event,
+            slot,
+        } => {
+            let referrer =
+                (event.referrer != Default::default()).then(|| event.referrer.to_string());
+            let memo = (!event.memo.is_empty()).then_some(&event.memo);
+            let affiliate =
+                (event.affiliate != Default::default()).then(|| event.affiliate.to_string());
+            client
+                .execute(
+                    "INSERT INTO bids (signature, event_index, auction, bidder, price, referrer, memo,
+                         affiliate, slot)
+                     VALUES ($1, $2, $3, $4, $5::TEXT::NUMERIC, $6, $7, $8, $9)
+                     ON CONFLICT DO NOTHING",
+                    &[
+                        &signature,
+                        &index,
+                        &event.auction.to_string(),
+                        &event.bidder.to_string(),
+                        &event.price.to_string(),
+                        &referrer,
+                        &memo,
+                        &affiliate,
+                        &(slot as i64),
+                    ],
+                )
+                .await?;
+        }
+        Update::Settlement {
+            signature,
+            event,
+            slot,
+        } => {
+            let winner = event.sold.then(|| event.winner.to_string());
+            client
+                .execute(
+                    "INSERT INTO settlements (auction, signature, winner, price, sold, slot)
+                     VALUES ($1, $2, $3, $4::TEXT::NUMERIC, $5, $6)
+                     ON CONFLICT DO NOTHING",
+                    &[
+                        &event.auction.to_string(),
+                        &signature,
+                        &winner,
+                        &event.price.to_string(),
+                        &event.sold,
+                        &(slot as i64),
+                    ],
+                )
+                .await?;
+            // The escrow account is closed on settlement, so record the final status on the auction row.
+            client
+                .execute(
+                    "UPDATE auctions SET status = $2, slot = GREATEST(slot, $3) WHERE address = $1",
+                    &[
+                        &event.auction.to_string(),
+                        &(wba_auction_client::AuctionStatus::Settled as i16),
+                        &(slot as i64),
+                    ],
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// Indexer for the WBA auction house program: follows program accounts and event CPIs over the RPC
+// websocket and writes normalized auction, bid and settlement rows into Postgres.
+//
+// Configuration comes from the environment:
+// - RPC_WS_URL: the RPC websocket endpoint (blockSubscribe must be enabled on the node).
+// - DATABASE_URL: the Postgres connection string.
+// - METRICS_ADDR: the address to serve Prometheus metrics on (defaults to 0.0.0.0:9101).
+
+// Declare the Postgres schema and writers.
+mod db;
+// Declare the Prometheus metrics.
+mod metrics;
+// Declare the websocket subscriptions and notification decoding.
+mod rpc;
+
+// Import the error handling helpers from the anyhow library.
+use anyhow::{Context, Result};
+// Import the stream and sink extensions used on the websocket.
+use futures_util::{SinkExt, StreamExt};
+// Import the duration and wall clock types.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+// Import the decoded updates.
+use rpc::Update;
+
+// Define how long to wait before reconnecting after the websocket drops.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let ws_url = std::env::var("RPC_WS_URL").context("RPC_WS_URL is not set")?;
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL is not set")?;
+    let metrics_addr = std::env::var("METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9101".to_string());
+
+    metrics::serve(&metrics_addr)?;
+
+    // Connect to Postgres and make sure the schema exists.
+    let (client, connection) = tokio_postgres::connect(&database_url, tokio_postgres::NoTls)
+        .await
+        .context("connecting to Postgres")?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            eprintln!("postgres connection error: {err}");
+        }
+    });
+    db::migrate(&client).await?;
+
+    // Follow the program until the process is stopped, reconnecting whenever the websocket drops.
+    loop {
+        if let Err(err) = follow(&ws_url, &client).await {
+            eprintln!("websocket error: {err:#}");
+            metrics::RPC_ERRORS.with_label_values(&["websocket"]).inc();
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+// Subscribe to the program's accounts and blocks and index every notification until the websocket
+// closes.
+async fn follow(ws_url: &str, client: &tokio_postgres::Client) -> Result<()> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(ws_url)
+        .await
+        .context("connecting to the RPC websocket")?;
+    for request in rpc::subscriptions() {
+        socket.send(request.to_string().into()).await?;
+    }
+    println!("subscribed to {}", wba_auction_client::ID);
+
+    while let Some(message) = socket.next().await {
+        let text = match message? {
+            tokio_tungstenite::tungstenite::Message::Text(text) => text,
+            tokio_tungstenite::tungstenite::Message::Close(_) => break,
+            _ => continue,
+        };
+        let notification: serde_json::Value = serde_json::from_str(&text)?;
+        match rpc::decode_notification(&notification) {
+            Ok(updates) => {
+                for update in updates {
+                    let counter = match &update {
+                        Update::Auction { .. } => &metrics::AUCTION_UPDATES,
+                        Update::Bid { .. } => &metrics::BIDS_OBSERVED,
+                        Update::Settlement { .. } => &metrics::AUCTIONS_SETTLED,
+                    };
+                    db::apply(client, update).await?;
+                    counter.inc();
+                }
+            }
+            // A notification we cannot decode is logged and skipped rather than stopping the indexer.
+            Err(err) => {
+                eprintln!("skipping notification: {err:#}");
+                metrics::RPC_ERRORS.with_label_values(&["decode"]).inc();
+            }
+        }
+
+        // Record how far behind the chain the indexer is, once a block has been fully indexed.
+        if let Some((slot, block_time)) = rpc::block_time(&notification) {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            metrics::LAST_SLOT.set(slot as i64);
+            metrics::LAG_SECONDS.set(now - block_time);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::{anyhow, Result};
+// Import the metric types and registration macros from the prometheus library.
+use prometheus::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, Encoder, IntCounter,
+    IntCounterVec, IntGauge, TextEncoder,
+};
+// Import the lazy initializer used for the metric statics.
+use std::sync::LazyLock;
+
+// Count the auction account updates indexed.
+pub static AUCTION_UPDATES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_auction_updates_total",
+        "Auction account updates indexed"
+    )
+    .unwrap()
+});
+// Count the BidPlaced events indexed.
+pub static BIDS_OBSERVED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("indexer_bids_observed_total", "BidPlaced events indexed").unwrap()
+});
+// Count the AuctionSettled events indexed.
+pub static AUCTIONS_SETTLED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_auctions_settled_total",
+        "AuctionSettled events indexed"
+    )
+    .unwrap()
+});
+// Count the RPC errors, by kind: websocket failures and notifications that could not be decoded.
+pub static RPC_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!("indexer_rpc_errors_total", "RPC errors, by kind", &["kind"]).unwrap()
+});
+// Track the slot of the last indexed block.
+pub static LAST_SLOT: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("indexer_last_slot", "Slot of the last indexed block").unwrap()
+});
+// Track how far the last indexed block's time is behind the indexer's own clock.
+pub static LAG_SECONDS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "indexer_lag_seconds",
+        "Seconds between now and the block time of the last indexed block"
+    )
+    .unwrap()
+});
+
+// Serve the metrics in the Prometheus text format on a background thread.
+pub fn serve(addr: &str) -> Result<()> {
+    let server = tiny_http::Server::http(addr).map_err(|err| anyhow!("binding {addr}: {err}"))?;
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let mut body = Vec::new();
+            let response = match TextEncoder::new().encode(&prometheus::gather(), &mut body) {
+                Ok(()) => tiny_http::Response::from_data(body),
+                Err(err) => tiny_http::Response::from_string(err.to_string()).with_status_code(500),
+            };
+            if let Err(err) = request.respond(response) {
+                eprintln!("metrics response failed: {err}");
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,382 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::{anyhow, Context, Result};
+// Import the JSON value type and constructor from the serde_json library.
+use serde_json::{json, Value};
+// Import the program's account and event types.
+use wba_auction_client::{
+    decode_auction, decode_event_cpi, Auction, AuctionEvent, AuctionSettled, BidPlaced, ID,
+};
+
+// Define the Update enum to represent a change decoded from a notification.
+pub enum Update {
+    // An auction account was written.
+    Auction {
+        address: String,
+        auction: Box<Auction>,
+        slot: u64,
+    },
+    // A bid was placed, as recorded by a BidPlaced event.
+    Bid {
+        signature: String,
+        index: i32,
+        event: BidPlaced,
+        slot: u64,
+    },
+    // An auction was settled, as recorded by an AuctionSettled event.
+    Settlement {
+        signature: String,
+        event: AuctionSettled,
+        slot: u64,
+    },
+}
+
+// Build the subscription requests: one for auction accounts and one for blocks with transactions that
+// mention the program, whose inner instructions carry the events.
+pub fn subscriptions() -> Vec<Value> {
+    vec![
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "programSubscribe",
+            "params": [
+                ID.to_string(),
+                {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "filters": [{ "dataSize": Auction::LEN }]
+                }
+            ]
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "blockSubscribe",
+            "params": [
+                { "mentionsAccountOrProgram": ID.to_string() },
+                {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "transactionDetails": "full",
+                    "showRewards": false,
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]
+        }),
+    ]
+}
+
+// Decode a websocket message into the updates it carries. Subscription confirmations carry none.
+pub fn decode_notification(message: &Value) -> Result<Vec<Update>> {
+    let params = &message["params"];
+    match message["method"].as_str() {
+        Some("programNotification") => decode_account(&params["result"]).map(|update| vec![update]),
+        Some("blockNotification") => decode_block(&params["result"]["value"]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Read the slot and block time of a block notification, if the message is one.
+pub fn block_time(message: &Value) -> Option<(u64, i64)> {
+    if message["method"].as_str() != Some("blockNotification") {
+        return None;
+    }
+    let value = &message["params"]["result"]["value"];
+    Some((
+        value["slot"].as_u64()?,
+        value["block"]["blockTime"].as_i64()?,
+    ))
+}
+
+// Decode a program account notification into an auction update.
+fn decode_account(result: &Value) -> Result<Update> {
+    let slot = result["context"]["slot"].as_u64().context("missing slot")?;
+    let value = &result["value"];
+    let address = value["pubkey"]
+        .as_str()
+        .context("missing pubkey")?
+        .to_string();
+    let data = value["account"]["data"][0]
+        .as_str()
+        .context("missing data")?;
+    let data = base64::decode(data)?;
+    let auction = decode_auction(&data).map_err(|err| anyhow!("decoding {address}: {err}"))?;
+    Ok(Update::Auction {
+        address,
+        auction: Box::new(auction),
+        slot,
+    })
+}
+
+// Decode the events of every successful transaction in a block notification.
+fn decode_block(value: &Value) -> Result<Vec<Update>> {
+    let slot = value["slot"].as_u64().context("missing slot")?;
+    let mut updates = Vec::new();
+    let transactions = value["block"]["transactions"].as_array();
+    for transaction in transactions.into_iter().flatten() {
+        // Failed transactions emitted nothing.
+        if !transaction["meta"]["err"].is_null() {
+            continue;
+        }
+        let signature = transaction["transaction"]["signatures"][0]
+            .as_str()
+            .context("missing signature")?;
+        let account_keys = account_keys(transaction);
+
+        let mut index = 0;
+        let inner = transaction["meta"]["innerInstructions"].as_array();
+        for instruction in inner
+            .into_iter()
+            .flatten()
+            .filter_map(|inner| inner["instructions"].as_array())
+            .flatten()
+        {
+            // Only this program's emit_event instructions carry events.
+            let program_id = instruction["programIdIndex"]
+                .as_u64()
+                .and_then(|program_index| account_keys.get(program_index as usize));
+            if program_id != Some(&ID.to_string()) {
+                continue;
+            }
+            let data = instruction["data"].as_str().unwrap_or_default();
+            let Ok(data) = bs58::decode(data).into_vec() else {
+                continue;
+            };
+            match decode_event_cpi(&data) {
+                Some(AuctionEvent::BidPlaced(event)) => {
+                    updates.push(Update::Bid {
+                        signature: signature.to_string(),
+                        index,
+                        event,
+                        slot,
+                    });
+                    index += 1;
+                }
+                Some(AuctionEvent::AuctionSettled(event)) => updates.push(Update::Settlement {
+                    signature: signature.to_string(),
+                    event,
+                    slot,
+                }),
+                Some(AuctionEvent::Outbid(_))
+                | Some(AuctionEvent::AuctionCancelledByAdmin(_))
+                | Some(AuctionEvent::SaleReversed(_))
+                | Some(AuctionEvent::InstallmentsDefaulted(_))
+                | Some(AuctionEvent::EditionPurchased(_))
+                | Some(AuctionEvent::AuctionRelisted(_))
+                | Some(AuctionEvent::SeriesLotOpened(_))
+                | None => {}
+            }
+        }
+    }
+    Ok(updates)
+}
+
+// List a transaction's account keys, including those loaded from address lookup tables.
+fn account_keys(transaction: &Value) -> Vec<String> {
+    let loaded = &transaction["meta"]["loadedAddresses"];
+    [
+        &transaction["transaction"]["message"]["accountKeys"],
+        &loaded["writable"],
+        &loaded["readonly"],
+    ]
+    .into_iter()
+    .filter_map(Value::as_array)
+    .flatten()
+    .filter_map(|key| key.as_str().map(str::to_string))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{Discriminator, Event, InstructionData};
+    use wba_auction_client::{AuctionStatus, Outbid, AUCTION_VERSION};
+    use wba_auction_house::instruction::EmitEvent;
+
+    // Build a program account notification of an account's data at a slot.
+    fn account_notification(address: &str, data: &[u8], slot: u64) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "programNotification",
+            "params": {
+                "result": {
+                    "context": { "slot": slot },
+                    "value": {
+                        "pubkey": address,
+                        "account": { "data": [base64::encode(data), "base64"] }
+                    }
+                }
+            }
+        })
+    }
+
+    // Build the data of the emit_event instruction recording an event, as the program sends it.
+    fn event_cpi<E: Event>(event: &E) -> String {
+        bs58::encode(
+            EmitEvent {
+                _event: event.data(),
+            }
+            .data(),
+        )
+        .into_string()
+    }
+
+    // Build a transaction of a signature whose inner instructions call programs by account index.
+    fn transaction(signature: &str, err: Value, instructions: &[(u64, String)]) -> Value {
+        let instructions = instructions
+            .iter()
+            .map(|(program_index, data)| json!({ "programIdIndex": program_index, "data": data }))
+            .collect::<Vec<_>>();
+        json!({
+            "transaction": {
+                "signatures": [signature],
+                "message": { "accountKeys": ["11111111111111111111111111111111"] }
+            },
+            "meta": {
+                "err": err,
+                "innerInstructions": [{ "index": 0, "instructions": instructions }],
+                // The program is loaded from an address lookup table, at account index 1.
+                "loadedAddresses": { "writable": [], "readonly": [ID.to_string()] }
+            }
+        })
+    }
+
+    // Build a block notification of some transactions at a slot.
+    fn block_notification(transactions: Vec<Value>, slot: u64) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "blockNotification",
+            "params": {
+                "result": {
+                    "value": {
+                        "slot": slot,
+                        "block": { "blockTime": 1_700_000_000, "transactions": transactions }
+                    }
+                }
+            }
+        })
+    }
+
+    fn bid_placed(price: u64) -> BidPlaced {
+        BidPlaced {
+            auction: Default::default(),
+            bidder: Default::default(),
+            price,
+            bond: 0,
+            referrer: Default::default(),
+            memo: "lot 7".to_string(),
+            affiliate: Default::default(),
+        }
+    }
+
+    #[test]
+    fn decode_notification_decodes_auction_accounts() {
+        let mut auction: Auction = bytemuck::Zeroable::zeroed();
+        auction.version = AUCTION_VERSION;
+        auction.auction_id = 42;
+        auction.price = 1_500;
+        auction.set_status(AuctionStatus::Live);
+        let mut data = Auction::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&auction));
+
+        let updates = decode_notification(&account_notification("auction", &data, 7)).unwrap();
+        let [Update::Auction {
+            address,
+            auction,
+            slot,
+        }] = &updates[..]
+        else {
+            panic!("expected one auction update");
+        };
+        assert_eq!((address.as_str(), *slot), ("auction", 7));
+        assert_eq!({ auction.auction_id }, 42);
+        assert_eq!({ auction.price }, 1_500);
+        assert_eq!(auction.stored_status().unwrap(), AuctionStatus::Live);
+
+        // An account that is not an auction in the current layout is an error, which the indexer skips.
+        assert!(
+            decode_notification(&account_notification("auction", &data[..data.len() - 1], 7))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn decode_notification_decodes_the_events_of_successful_transactions() {
+        let outbid = Outbid {
+            auction: Default::default(),
+            previous_bidder: Default::default(),
+            previous_price: 100,
+            new_price: 200,
+        };
+        let settled = AuctionSettled {
+            auction: Default::default(),
+            winner: Default::default(),
+            price: 300,
+            sold: true,
+        };
+        let notification = block_notification(
+            vec![
+                // A failed transaction emitted nothing.
+                transaction(
+                    "failed",
+                    json!({ "InstructionError": [0, "Custom"] }),
+                    &[(1, event_cpi(&bid_placed(50)))],
+                ),
+                transaction(
+                    "ok",
+                    Value::Null,
+                    &[
+                        (1, event_cpi(&bid_placed(100))),
+                        (1, event_cpi(&outbid)),
+                        // Another program's instruction is not an event, whatever its data.
+                        (0, event_cpi(&bid_placed(150))),
+                        (1, event_cpi(&bid_placed(200))),
+                        (1, event_cpi(&settled)),
+                        (1, "not base58!".to_string()),
+                    ],
+                ),
+            ],
+            9,
+        );
+
+        let updates = decode_notification(&notification).unwrap();
+        assert_eq!(updates.len(), 3);
+        for (update, (expected_index, expected_price)) in
+            updates[..2].iter().zip([(0, 100), (1, 200)])
+        {
+            let Update::Bid {
+                signature,
+                index,
+                event,
+                slot,
+            } = update
+            else {
+                panic!("expected a bid update");
+            };
+            assert_eq!(
+                (signature.as_str(), *index, *slot),
+                ("ok", expected_index, 9)
+            );
+            assert_eq!(event.price, expected_price);
+            assert_eq!(event.memo, "lot 7");
+        }
+        let Update::Settlement {
+            signature,
+            event,
+            slot,
+        } = &updates[2]
+        else {
+            panic!("expected a settlement update");
+        };
+        assert_eq!((signature.as_str(), *slot), ("ok", 9));
+        assert_eq!((event.price, event.sold), (300, true));
+
+        assert_eq!(block_time(&notification), Some((9, 1_700_000_000)));
+    }
+
+    #[test]
+    fn decode_notification_ignores_other_messages() {
+        let confirmation = json!({ "jsonrpc": "2.0", "result": 5, "id": 1 });
+        assert!(decode_notification(&confirmation).unwrap().is_empty());
+        assert_eq!(block_time(&confirmation), None);
+    }
+}
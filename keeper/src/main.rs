@@ -0,0 +1,273 @@
+// Keeper bot for the WBA auction house program: scans the program's auctions and sends the
+// permissionless instructions that move them along (settling, refunding and expiring, and opening the
+// next lot of a series).
+//
+// Configuration comes from the environment:
+// - RPC_URL: the RPC HTTP endpoint.
+// - KEEPER_KEYPAIR: the path of the keeper's keypair file, which pays for every transaction.
+// - POLL_INTERVAL_SEC: the time between two scans (defaults to 30).
+// - METRICS_ADDR: the address to serve Prometheus metrics on (defaults to 0.0.0.0:9100).
+
+// Declare the Prometheus metrics.
+mod metrics;
+// Declare the JSON-RPC client.
+mod rpc;
+// Declare the planning of the keeper's work.
+mod tasks;
+// Declare the keeper's hot key.
+mod wallet;
+
+// Import the error handling helpers from the anyhow library.
+use anyhow::{Context, Result};
+// Import the instruction, message and public key types from the solana_program library.
+use anchor_lang::solana_program::{instruction::Instruction, message::Message, pubkey::Pubkey};
+// Import the hash map, sleep, duration and wall clock types.
+use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+// Import the account decoders of the auction program.
+use wba_auction_client::{
+    auction_house_pda, decode_auction_house, decode_series, fetch_auctions, metadata_address,
+    Auction, AuctionSeries, AuctionStatus, Royalties, FEATURE_ROYALTIES, ID,
+};
+// Import the keeper's modules.
+use rpc::RpcClient;
+use tasks::Action;
+use wallet::Wallet;
+
+// Define the most accounts a getMultipleAccounts request may fetch.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+// Define the largest serialized transaction the network accepts.
+const MAX_TRANSACTION_SIZE: usize = 1232;
+// Define the balance below which the keeper warns that it needs funding (0.1 SOL).
+const LOW_BALANCE_LAMPORTS: u64 = 100_000_000;
+
+fn main() -> Result<()> {
+    let rpc_url = std::env::var("RPC_URL").context("RPC_URL is not set")?;
+    let keypair_path = std::env::var("KEEPER_KEYPAIR").context("KEEPER_KEYPAIR is not set")?;
+    let poll_interval = match std::env::var("POLL_INTERVAL_SEC") {
+        Ok(seconds) => Duration::from_secs(seconds.parse().context("invalid POLL_INTERVAL_SEC")?),
+        Err(_) => Duration::from_secs(30),
+    };
+
+    let metrics_addr = std::env::var("METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9100".to_string());
+
+    metrics::serve(&metrics_addr)?;
+    let rpc = RpcClient::new(rpc_url);
+    let wallet = Wallet::load(&keypair_path)?;
+    println!("keeper {} watching {}", wallet.pubkey(), ID);
+
+    // Scan until the process is stopped. A failed scan is logged and retried at the next interval.
+    loop {
+        if let Err(err) = scan(&rpc, &wallet) {
+            eprintln!("scan failed: {err:#}");
+        }
+        sleep(poll_interval);
+    }
+}
+
+// Scan the program's auctions once and carry out the resulting actions.
+fn scan(rpc: &RpcClient, wallet: &Wallet) -> Result<()> {
+    let balance = rpc.get_balance(&wallet.pubkey())?;
+    metrics::BALANCE_LAMPORTS.set(balance as i64);
+    if balance < LOW_BALANCE_LAMPORTS {
+        eprintln!("keeper balance is low: {balance} lamports");
+    }
+
+    let clock = rpc.get_clock()?;
+    let house = decode_auction_house(&rpc.get_account_data(&auction_house_pda().0)?)
+        .map_err(|err| anyhow::anyhow!("decoding the auction house: {err}"))?;
+    let auctions = fetch_auctions(rpc, &[])?;
+
+    let series = rpc
+        .get_program_accounts(&ID, AuctionSeries::LEN, &[])?
+        .into_iter()
+        .filter_map(|(address, data)| Some((address, decode_series(&data).ok()?)))
+        .collect::<Vec<_>>();
+
+    // Settlement escrows no royalty, and takes no royalty vault, while the house has royalties off.
+    let royalties = if house.enabled(FEATURE_ROYALTIES) {
+        load_royalties(rpc, &auctions)?
+    } else {
+        HashMap::new()
+    };
+    let mut actions = tasks::plan(&auctions, &royalties, &clock, &house, &wallet.pubkey());
+    actions.extend(tasks::plan_series(&series, &auctions, &clock));
+    metrics::PENDING_ACTIONS.set(actions.len() as i64);
+    for action in &mut actions {
+        tasks::create_missing_accounts(rpc, action, &wallet.pubkey())?;
+    }
+    for batch in batches(actions, &wallet.pubkey()) {
+        send_batch(rpc, wallet, batch)?;
+    }
+
+    // Record how far behind the chain this scan was.
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    metrics::LAST_SCAN_SLOT.set(clock.slot as i64);
+    metrics::LAG_SECONDS.set(now - clock.unix_timestamp);
+    Ok(())
+}
+
+// Load the royalty accounts of the live auctions, which settlement needs, from their NFTs' metadata. An
+// auction whose metadata cannot be decoded is left out, and its settlement fails like it would on-chain.
+fn load_royalties(
+    rpc: &RpcClient,
+    auctions: &[(Pubkey, Auction)],
+) -> Result<HashMap<Pubkey, Royalties>> {
+    let live = auctions
+        .iter()
+        .filter(|(_, auction)| matches!(auction.stored_status(), Ok(AuctionStatus::Live)))
+        .collect::<Vec<_>>();
+    let mut royalties = HashMap::new();
+    for chunk in live.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let addresses = chunk
+            .iter()
+            .map(|(_, auction)| metadata_address(&auction.nft_mint))
+            .collect::<Vec<_>>();
+        for ((address, _), metadata) in chunk.iter().zip(rpc.get_multiple_accounts(&addresses)?) {
+            if let Ok(royalty) = Royalties::from_metadata(metadata.as_deref()) {
+                royalties.insert(*address, royalty);
+            }
+        }
+    }
+    Ok(royalties)
+}
+
+// Group actions into as few transactions paid for by a payer as fit the size limit, keeping each action
+// whole.
+fn batches(actions: Vec<Action>, payer: &Pubkey) -> Vec<Vec<Action>> {
+    let mut batches: Vec<Vec<Action>> = Vec::new();
+    for action in actions {
+        let fits = batches.last().is_some_and(|batch| {
+            let instructions = batch
+                .iter()
+                .chain([&action])
+                .flat_map(|action| action.instructions.iter().cloned())
+                .collect::<Vec<_>>();
+            transaction_size(&instructions, payer) <= MAX_TRANSACTION_SIZE
+        });
+        match batches.last_mut() {
+            Some(batch) if fits => batch.push(action),
+            _ => batches.push(vec![action]),
+        }
+    }
+    batches
+}
+
+// Send a batch of actions in one transaction. If it fails, each action is sent on its own, so one
+// failing action does not hold back the others, and a failing action falls back to its alternative.
+fn send_batch(rpc: &RpcClient, wallet: &Wallet, batch: Vec<Action>) -> Result<()> {
+    if batch.len() > 1 {
+        let instructions = batch
+            .iter()
+            .flat_map(|action| action.instructions.iter().cloned())
+            .collect::<Vec<_>>();
+        match send(rpc, wallet, &instructions) {
+            Ok(signature) => {
+                for action in &batch {
+                    println!("{}: {signature}", action.description);
+                    metrics::ACTIONS
+                        .with_label_values(&[action.kind, "ok"])
+                        .inc();
+                }
+                return Ok(());
+            }
+            Err(err) => eprintln!("batch of {} failed: {err:#}", batch.len()),
+        }
+    }
+    for action in batch {
+        let mut next = Some(Box::new(action));
+        while let Some(action) = next {
+            match send(rpc, wallet, &action.instructions) {
+                Ok(signature) => {
+                    println!("{}: {signature}", action.description);
+                    metrics::ACTIONS
+                        .with_label_values(&[action.kind, "ok"])
+                        .inc();
+                    break;
+                }
+                Err(err) => {
+                    eprintln!("{} failed: {err:#}", action.description);
+                    metrics::ACTIONS
+                        .with_label_values(&[action.kind, "failed"])
+                        .inc();
+                }
+            }
+            next = action.fallback;
+        }
+    }
+    Ok(())
+}
+
+// Sign and send a transaction made of some instructions.
+fn send(rpc: &RpcClient, wallet: &Wallet, instructions: &[Instruction]) -> Result<String> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let message = Message::new_with_blockhash(instructions, Some(&wallet.pubkey()), &blockhash);
+    rpc.send_transaction(&wallet.sign_transaction(&message))
+}
+
+// Measure the serialized size of a transaction made of some instructions, paid for by a payer.
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    1 + 64 * message.header.num_required_signatures as usize + message.serialize().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    // Build an action of one instruction with some data and a few new accounts.
+    fn action(data_len: usize) -> Action {
+        let accounts = (0..3)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect();
+        Action {
+            kind: "settle",
+            description: String::new(),
+            instructions: vec![Instruction::new_with_bytes(
+                ID,
+                &vec![0; data_len],
+                accounts,
+            )],
+            required_accounts: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    #[test]
+    fn batches_fill_transactions_up_to_the_size_limit() {
+        let payer = Pubkey::new_unique();
+        let batches = batches((0..20).map(|_| action(100)).collect(), &payer);
+        // Every action is sent once, in order.
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 20);
+        assert!(batches.len() > 1);
+        for (batch, next) in batches.iter().zip(&batches[1..]) {
+            let instructions = |batch: &[Action]| {
+                batch
+                    .iter()
+                    .flat_map(|action| action.instructions.iter().cloned())
+                    .collect::<Vec<_>>()
+            };
+            // Each batch fits, and could not also take the next batch's first action.
+            assert!(transaction_size(&instructions(batch), &payer) <= MAX_TRANSACTION_SIZE);
+            let mut grown = instructions(batch);
+            grown.extend(next[0].instructions.iter().cloned());
+            assert!(transaction_size(&grown, &payer) > MAX_TRANSACTION_SIZE);
+        }
+    }
+
+    #[test]
+    fn batches_send_an_oversized_action_on_its_own() {
+        let payer = Pubkey::new_unique();
+        let sizes = batches(
+            vec![action(10), action(MAX_TRANSACTION_SIZE), action(10)],
+            &payer,
+        )
+        .iter()
+        .map(Vec::len)
+        .collect::<Vec<_>>();
+        assert_eq!(sizes, [1, 1, 1]);
+        assert!(batches(Vec::new(), &payer).is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::{anyhow, Result};
+// Import the metric types and registration macros from the prometheus library.
+use prometheus::{
+    register_int_counter_vec, register_int_gauge, Encoder, IntCounterVec, IntGauge, TextEncoder,
+};
+// Import the lazy initializer used for the metric statics.
+use std::sync::LazyLock;
+
+// Count the actions sent by the keeper, by kind and result.
+pub static ACTIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "keeper_actions_total",
+        "Actions sent by the keeper, by kind and result",
+        &["action", "result"]
+    )
+    .unwrap()
+});
+// Count the failed RPC calls, by method, including those that were retried.
+pub static RPC_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "keeper_rpc_errors_total",
+        "Failed RPC calls, by method",
+        &["method"]
+    )
+    .unwrap()
+});
+// Track the number of actions found by the last scan.
+pub static PENDING_ACTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("keeper_pending_actions", "Actions found by the last scan").unwrap()
+});
+// Track the chain's slot as of the last completed scan.
+pub static LAST_SCAN_SLOT: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("keeper_last_scan_slot", "Slot of the last completed scan").unwrap()
+});
+// Track how far the last completed scan's cluster time is behind the keeper's own clock.
+pub static LAG_SECONDS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "keeper_lag_seconds",
+        "Seconds between now and the cluster time of the last completed scan"
+    )
+    .unwrap()
+});
+// Track the keeper's balance, which pays for every transaction.
+pub static BALANCE_LAMPORTS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("keeper_balance_lamports", "Lamport balance of the keeper").unwrap()
+});
+
+// Serve the metrics in the Prometheus text format on a background thread.
+pub fn serve(addr: &str) -> Result<()> {
+    let server = tiny_http::Server::http(addr).map_err(|err| anyhow!("binding {addr}: {err}"))?;
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let mut body = Vec::new();
+            let response = match TextEncoder::new().encode(&prometheus::gather(), &mut body) {
+                Ok(()) => tiny_http::Response::from_data(body),
+                Err(err) => tiny_http::Response::from_string(err.to_string()).with_status_code(500),
+            };
+            if let Err(err) = request.respond(response) {
+                eprintln!("metrics response failed: {err}");
+            }
+        }
+    });
+    Ok(())
+}
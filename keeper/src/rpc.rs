@@ -0,0 +1,226 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::{anyhow, bail, Context, Result};
+// Import the JSON value type and constructor from the serde_json library.
+use serde_json::{json, Value};
+// Import the sleep and duration types used for backoff.
+use std::thread::sleep;
+use std::time::Duration;
+// Import the clock, hash and public key types from the solana_program library.
+use anchor_lang::solana_program::{clock::Clock, hash::Hash, pubkey::Pubkey, sysvar};
+// Import the getProgramAccounts filter and fetching trait of the client.
+use wba_auction_client::{Memcmp, ProgramAccounts};
+// Import the keeper's metrics.
+use crate::metrics;
+
+// Define the number of attempts made for a read before giving up.
+const MAX_ATTEMPTS: u32 = 6;
+// Define the delay before the first retry, doubled after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+// Define the longest delay between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// Define the RpcClient struct to call the JSON-RPC API of a node.
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+// Implement the RpcClient struct.
+impl RpcClient {
+    // Create a client for a node's HTTP endpoint.
+    pub fn new(url: String) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self { url, agent }
+    }
+
+    // Call a method once, returning its result or its JSON-RPC error.
+    fn call_once(&self, method: &str, params: &Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} request failed"))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        Ok(response["result"].clone())
+    }
+
+    // Call a read method, retrying with exponential backoff on any error, since reads are safe to repeat.
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let result = self.call_once(method, &params);
+            if result.is_err() {
+                metrics::RPC_ERRORS.with_label_values(&[method]).inc();
+            }
+            match result {
+                Ok(result) => return Ok(result),
+                Err(err) if attempt < MAX_ATTEMPTS => {
+                    eprintln!("{err:#}; retrying in {backoff:?}");
+                    sleep(backoff);
+                    backoff = next_backoff(backoff);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    // Fetch every program account of a given size matching the filters, returning each address and its data.
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        data_size: usize,
+        filters: &[Memcmp],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let mut filters = filters
+            .iter()
+            .map(|filter| {
+                let bytes = bs58::encode(&filter.bytes).into_string();
+                json!({ "memcmp": { "offset": filter.offset, "bytes": bytes } })
+            })
+            .collect::<Vec<_>>();
+        filters.insert(0, json!({ "dataSize": data_size }));
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                program_id.to_string(),
+                {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "filters": filters
+                }
+            ]),
+        )?;
+        result
+            .as_array()
+            .context("unexpected getProgramAccounts result")?
+            .iter()
+            .map(|account| {
+                let address = account["pubkey"].as_str().context("missing pubkey")?;
+                let data = account["account"]["data"][0]
+                    .as_str()
+                    .context("missing data")?;
+                Ok((address.parse()?, base64::decode(data)?))
+            })
+            .collect()
+    }
+
+    // Fetch the data of several accounts, or None for those that do not exist.
+    pub fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let keys = addresses.iter().map(Pubkey::to_string).collect::<Vec<_>>();
+        let result = self.call(
+            "getMultipleAccounts",
+            json!([keys, { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        result["value"]
+            .as_array()
+            .context("unexpected getMultipleAccounts result")?
+            .iter()
+            .map(|account| match account["data"][0].as_str() {
+                Some(data) => Ok(Some(base64::decode(data)?)),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    // Fetch the data of an account, failing if it does not exist.
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Vec<u8>> {
+        self.get_multiple_accounts(&[*address])?
+            .pop()
+            .flatten()
+            .ok_or_else(|| anyhow!("account {address} not found"))
+    }
+
+    // Fetch the cluster's clock from the clock sysvar.
+    pub fn get_clock(&self) -> Result<Clock> {
+        let data = self.get_account_data(&sysvar::clock::ID)?;
+        let field = |index: usize| -> Result<[u8; 8]> {
+            Ok(data
+                .get(index * 8..index * 8 + 8)
+                .context("clock sysvar too short")?
+                .try_into()?)
+        };
+        Ok(Clock {
+            slot: u64::from_le_bytes(field(0)?),
+            epoch_start_timestamp: i64::from_le_bytes(field(1)?),
+            epoch: u64::from_le_bytes(field(2)?),
+            leader_schedule_epoch: u64::from_le_bytes(field(3)?),
+            unix_timestamp: i64::from_le_bytes(field(4)?),
+        })
+    }
+
+    // Fetch a recent blockhash to build transactions with.
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"]
+            .as_str()
+            .context("missing blockhash")?;
+        Ok(blockhash.parse()?)
+    }
+
+    // Fetch the lamport balance of an account.
+    pub fn get_balance(&self, address: &Pubkey) -> Result<u64> {
+        let result = self.call(
+            "getBalance",
+            json!([address.to_string(), { "commitment": "confirmed" }]),
+        )?;
+        result["value"].as_u64().context("missing balance")
+    }
+
+    // Send a signed transaction, returning its signature. It is not retried here: a failed preflight
+    // means the transaction would fail on chain, and the next scan rebuilds it against fresh state.
+    pub fn send_transaction(&self, transaction: &[u8]) -> Result<String> {
+        let result = self.call_once(
+            "sendTransaction",
+            &json!([
+                base64::encode(transaction),
+                { "encoding": "base64", "preflightCommitment": "confirmed" }
+            ]),
+        )?;
+        Ok(result.as_str().context("missing signature")?.to_string())
+    }
+}
+
+// Get the delay before the next retry after a failed attempt: double the last one, up to MAX_BACKOFF.
+fn next_backoff(backoff: Duration) -> Duration {
+    (backoff * 2).min(MAX_BACKOFF)
+}
+
+// Implement the ProgramAccounts trait for the RpcClient struct, to fetch auctions with the client's helpers.
+impl ProgramAccounts for RpcClient {
+    type Error = anyhow::Error;
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        data_size: usize,
+        filters: &[Memcmp],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        RpcClient::get_program_accounts(self, program_id, data_size, filters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let delays = std::iter::successors(Some(INITIAL_BACKOFF), |&backoff| {
+            Some(next_backoff(backoff))
+        })
+        .take(MAX_ATTEMPTS as usize + 2)
+        .collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [500, 1_000, 2_000, 4_000, 8_000, 16_000, 30_000, 30_000].map(Duration::from_millis)
+        );
+    }
+}
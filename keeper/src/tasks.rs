@@ -0,0 +1,316 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::Result;
+// Import the hash map type.
+use std::collections::HashMap;
+// Import the clock, instruction and public key types from the solana_program library.
+use anchor_lang::solana_program::{clock::Clock, instruction::Instruction, pubkey::Pubkey};
+// Import the associated token account address derivation from the anchor_spl library.
+use anchor_spl::associated_token::get_associated_token_address;
+// Import the instruction builders and state types of the auction program.
+use wba_auction_client::{
+    default_installments_ix, expire_auction_ix, open_next_lot_ix, reclaim_unclaimed_ix,
+    settle_if_ended_ix, settle_unmet_reserve_ix, Auction, AuctionHouse, AuctionSeries,
+    AuctionStatus, ItemType, Royalties,
+};
+// Import the RPC client.
+use crate::rpc::RpcClient;
+
+// Define the Action struct to represent one unit of work for an auction.
+pub struct Action {
+    // The kind of the action, used to label metrics.
+    pub kind: &'static str,
+    // A description for the logs.
+    pub description: String,
+    // The instructions of the action, sent together in one transaction.
+    pub instructions: Vec<Instruction>,
+    // The associated token accounts (owner and mint) the instructions need to exist.
+    pub required_accounts: Vec<(Pubkey, Pubkey)>,
+    // An action to try instead if this one fails.
+    pub fallback: Option<Box<Action>>,
+}
+
+// Decide what the keeper should do for each auction:
+// - settle auctions that have ended with a bid, or refund the bidder if the USD reserve was not met;
+// - settle auctions paid in installments once fully paid, or default them once an installment is missed;
+// - unwind finalized auctions whose winner did not claim the NFT in time, refunding the rest of the bid;
+// - expire ended NFT auctions without bids, giving the NFT back to the exhibitor.
+// Outbid bidders are refunded by the bid that outbids them, so there are no outbid balances to return.
+// The royalty accounts of the auctions to settle are looked up by auction address.
+pub fn plan(
+    auctions: &[(Pubkey, Auction)],
+    royalties: &HashMap<Pubkey, Royalties>,
+    clock: &Clock,
+    house: &AuctionHouse,
+    keeper: &Pubkey,
+) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let no_royalties = Royalties::default();
+    for (address, auction) in auctions {
+        let (Ok(stored), Ok(status)) = (auction.stored_status(), auction.status(clock)) else {
+            continue;
+        };
+        let winner_nft_account = (auction.highest_bidder, auction.nft_mint);
+        let exhibitor_nft_account = (auction.exhibitor, auction.nft_mint);
+        let treasury_ft_account = (house.treasury, auction.payment_mint);
+        let royalties = royalties.get(address).unwrap_or(&no_royalties);
+        match (stored, status) {
+            (AuctionStatus::Live, AuctionStatus::Ended) if auction.has_installments() => actions
+                .push(Action {
+                    kind: "settle",
+                    description: format!("settle {address}"),
+                    instructions: vec![settle_if_ended_ix(
+                        *keeper,
+                        *address,
+                        auction,
+                        ata(winner_nft_account),
+                        ata(treasury_ft_account),
+                        royalties,
+                    )],
+                    required_accounts: vec![winner_nft_account, treasury_ft_account],
+                    fallback: Some(Box::new(Action {
+                        kind: "default_installments",
+                        description: format!("default installments of {address}"),
+                        instructions: vec![default_installments_ix(
+                            *address,
+                            auction,
+                            ata(exhibitor_nft_account),
+                        )],
+                        required_accounts: vec![exhibitor_nft_account],
+                        fallback: None,
+                    })),
+                }),
+            (AuctionStatus::Live, AuctionStatus::Ended) => actions.push(Action {
+                kind: "settle",
+                description: format!("settle {address}"),
+                instructions: vec![settle_if_ended_ix(
+                    *keeper,
+                    *address,
+                    auction,
+                    ata(winner_nft_account),
+                    ata(treasury_ft_account),
+                    royalties,
+                )],
+                required_accounts: vec![winner_nft_account, treasury_ft_account],
+                fallback: Some(Box::new(Action {
+                    kind: "refund_unmet_reserve",
+                    description: format!("refund unmet reserve of {address}"),
+                    instructions: vec![settle_unmet_reserve_ix(
+                        *address,
+                        auction,
+                        ata(exhibitor_nft_account),
+                    )],
+                    required_accounts: vec![exhibitor_nft_account],
+                    fallback: None,
+                })),
+            }),
+            (AuctionStatus::Ended, AuctionStatus::Ended)
+                if auction.claim_deadline <= clock.unix_timestamp
+                    && auction.item_claimed == 0
+                    && auction.proceeds_claimed == 0 =>
+            {
+                actions.push(Action {
+                    kind: "reclaim_unclaimed",
+                    description: format!("reclaim unclaimed {address}"),
+                    instructions: vec![reclaim_unclaimed_ix(
+                        *address,
+                        auction,
+                        ata(exhibitor_nft_account),
+                    )],
+                    required_accounts: vec![exhibitor_nft_account],
+                    fallback: None,
+                })
+            }
+            (AuctionStatus::Created, AuctionStatus::Ended)
+                if auction.item_type().ok() == Some(ItemType::Nft) =>
+            {
+                actions.push(Action {
+                    kind: "expire",
+                    description: format!("expire {address}"),
+                    instructions: vec![expire_auction_ix(
+                        *address,
+                        auction,
+                        ata(exhibitor_nft_account),
+                    )],
+                    required_accounts: vec![exhibitor_nft_account],
+                    fallback: None,
+                })
+            }
+            _ => {}
+        }
+    }
+    actions
+}
+
+// Open the next lot of every series whose current lot is done: settled, cancelled, ended without a bid or
+// no longer in the series. The first lot opens once the series starts.
+pub fn plan_series(
+    series: &[(Pubkey, AuctionSeries)],
+    auctions: &[(Pubkey, Auction)],
+    clock: &Clock,
+) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for (address, series_account) in series {
+        let ready = match (series_account.next_lot as usize).checked_sub(1) {
+            None => clock.unix_timestamp >= series_account.start_at,
+            Some(previous) => auctions
+                .iter()
+                .find(|(lot, _)| *lot == series_account.lots[previous])
+                .is_none_or(|(_, lot)| lot.lot_done(*address, clock).unwrap_or(false)),
+        };
+        let Some(instruction) = open_next_lot_ix(*address, series_account).filter(|_| ready) else {
+            continue;
+        };
+        actions.push(Action {
+            kind: "open_next_lot",
+            description: format!("open lot {} of {address}", series_account.next_lot),
+            instructions: vec![instruction],
+            required_accounts: Vec::new(),
+            fallback: None,
+        });
+    }
+    actions
+}
+
+// Prepend the creation of any associated token account an action needs that does not exist yet,
+// paid for by the keeper.
+pub fn create_missing_accounts(rpc: &RpcClient, action: &mut Action, payer: &Pubkey) -> Result<()> {
+    let addresses = action
+        .required_accounts
+        .iter()
+        .map(|&account| ata(account))
+        .collect::<Vec<_>>();
+    let existing = rpc.get_multiple_accounts(&addresses)?;
+    let mut creates = Vec::new();
+    for (&(owner, mint), account) in action.required_accounts.iter().zip(existing) {
+        if account.is_none() && !creates.iter().any(|(o, m)| (o, m) == (&owner, &mint)) {
+            creates.push((owner, mint));
+        }
+    }
+    let instructions = creates.into_iter().map(|(owner, mint)| {
+        spl_associated_token_account::instruction::create_associated_token_account(
+            payer, &owner, &mint,
+        )
+    });
+    action.instructions.splice(0..0, instructions);
+    if let Some(fallback) = action.fallback.as_mut() {
+        create_missing_accounts(rpc, fallback, payer)?;
+    }
+    Ok(())
+}
+
+// Derive the associated token account of an owner and mint.
+fn ata((owner, mint): (Pubkey, Pubkey)) -> Pubkey {
+    get_associated_token_address(&owner, &mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountDeserialize;
+    use wba_auction_client::AUCTION_VERSION;
+
+    // Build an auction ending at 1000 with a stored status.
+    fn auction(status: AuctionStatus) -> Auction {
+        let mut auction: Auction = bytemuck::Zeroable::zeroed();
+        auction.version = AUCTION_VERSION;
+        auction.exhibitor = Pubkey::new_unique();
+        auction.nft_mint = Pubkey::new_unique();
+        auction.payment_mint = Pubkey::new_unique();
+        auction.end_at = 1_000;
+        auction.set_status(status);
+        if status != AuctionStatus::Created {
+            auction.highest_bidder = Pubkey::new_unique();
+        }
+        auction
+    }
+
+    // Plan the keeper's work for some auctions at a UNIX timestamp, returning the kinds of each action
+    // and its fallbacks.
+    fn plan_at(auctions: &[Auction], unix_timestamp: i64) -> Vec<Vec<&'static str>> {
+        let auctions = auctions
+            .iter()
+            .map(|auction| (Pubkey::new_unique(), *auction))
+            .collect::<Vec<_>>();
+        let house =
+            AuctionHouse::try_deserialize_unchecked(&mut &[0; AuctionHouse::LEN][..]).unwrap();
+        let clock = Clock {
+            unix_timestamp,
+            ..Clock::default()
+        };
+        plan(
+            &auctions,
+            &HashMap::new(),
+            &clock,
+            &house,
+            &Pubkey::new_unique(),
+        )
+        .into_iter()
+        .map(|action| {
+            let mut kinds = vec![action.kind];
+            let mut next = action.fallback;
+            while let Some(fallback) = next {
+                kinds.push(fallback.kind);
+                next = fallback.fallback;
+            }
+            kinds
+        })
+        .collect()
+    }
+
+    #[test]
+    fn plan_settles_ended_auctions_with_a_bid() {
+        let live = auction(AuctionStatus::Live);
+        assert!(plan_at(&[live], 999).is_empty());
+        assert_eq!(
+            plan_at(&[live], 1_000),
+            [["settle", "refund_unmet_reserve"]]
+        );
+
+        let mut installments = live;
+        installments.installment_count = 3;
+        assert_eq!(
+            plan_at(&[installments], 1_000),
+            [["settle", "default_installments"]]
+        );
+    }
+
+    #[test]
+    fn plan_reclaims_unclaimed_auctions_past_their_claim_deadline() {
+        let mut ended = auction(AuctionStatus::Ended);
+        ended.claim_deadline = 2_000;
+        assert!(plan_at(&[ended], 1_999).is_empty());
+        assert_eq!(plan_at(&[ended], 2_000), [["reclaim_unclaimed"]]);
+
+        // Once either side has claimed, there is nothing to unwind.
+        let mut claimed = ended;
+        claimed.item_claimed = 1;
+        assert!(plan_at(&[claimed], 2_000).is_empty());
+        let mut claimed = ended;
+        claimed.proceeds_claimed = 1;
+        assert!(plan_at(&[claimed], 2_000).is_empty());
+    }
+
+    #[test]
+    fn plan_expires_ended_nft_auctions_without_a_bid() {
+        let created = auction(AuctionStatus::Created);
+        assert!(plan_at(&[created], 999).is_empty());
+        assert_eq!(plan_at(&[created], 1_000), [["expire"]]);
+
+        // Domains and stake accounts are not expired by the keeper.
+        let mut domain = created;
+        domain.item_type = ItemType::Domain as u8;
+        assert!(plan_at(&[domain], 1_000).is_empty());
+    }
+
+    #[test]
+    fn plan_skips_settled_and_undecodable_auctions() {
+        let settled = auction(AuctionStatus::Settled);
+        let mut unknown = auction(AuctionStatus::Live);
+        unknown.status = u8::MAX;
+        let live = auction(AuctionStatus::Live);
+        assert_eq!(
+            plan_at(&[settled, unknown, live], 1_000),
+            [["settle", "refund_unmet_reserve"]]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+// Import the error handling helpers from the anyhow library.
+use anyhow::{bail, Context, Result};
+// Import the keypair and signing trait from the ed25519_dalek library.
+use ed25519_dalek::{Keypair, Signer};
+// Import the message and public key types from the solana_program library.
+use anchor_lang::solana_program::{message::Message, pubkey::Pubkey};
+
+// Define the Wallet struct to hold the keeper's hot key, which pays for and signs every transaction.
+pub struct Wallet {
+    keypair: Keypair,
+}
+
+// Implement the Wallet struct.
+impl Wallet {
+    // Load a keypair file in the Solana CLI format (a JSON array of 64 bytes). The keeper holds a hot
+    // key, so the file must not be readable by other users.
+    pub fn load(path: &str) -> Result<Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path)
+                .with_context(|| format!("reading {path}"))?
+                .permissions()
+                .mode();
+            if mode & 0o077 != 0 {
+                bail!("{path} is accessible by other users; restrict it with chmod 600");
+            }
+        }
+        let contents = std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+        let bytes: Vec<u8> = serde_json::from_str(&contents).context("parsing the keypair file")?;
+        let keypair = Keypair::from_bytes(&bytes).context("invalid keypair")?;
+        Ok(Self { keypair })
+    }
+
+    // Get the wallet's public key.
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.keypair.public.to_bytes())
+    }
+
+    // Sign a message paid for by the wallet and serialize it as a legacy transaction.
+    pub fn sign_transaction(&self, message: &Message) -> Vec<u8> {
+        let message_bytes = message.serialize();
+        let signature = self.keypair.sign(&message_bytes);
+        // A transaction is a compact array of signatures followed by the message; the wallet is the
+        // only signer, so the array length fits in a single byte.
+        let mut transaction = vec![1u8];
+        transaction.extend_from_slice(&signature.to_bytes());
+        transaction.extend_from_slice(&message_bytes);
+        transaction
+    }
+}
@@ -0,0 +1,146 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction type from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+// Import the trait encoding instruction arguments.
+use anchor_lang::InstructionData;
+// Import the token account type from the anchor_spl library.
+use anchor_spl::token::TokenAccount;
+// Import the error type and the generated instructions of the auction program.
+use crate::{instruction, AuctionError};
+
+// Define the positions of the bidder, the bidder's FT account and the escrow account among the accounts
+// of the bid instruction (see Bid).
+pub const BID_BIDDER_INDEX: usize = 0;
+pub const BID_BIDDER_FT_ACCOUNT_INDEX: usize = 3;
+pub const BID_ESCROW_ACCOUNT_INDEX: usize = 8;
+
+// Define the positions of the winning bidder, the NFT receiving account and the escrow account among the
+// accounts of the claim_item instruction (see ClaimItem).
+pub const CLAIM_WINNING_BIDDER_INDEX: usize = 0;
+pub const CLAIM_NFT_RECEIVING_ACCOUNT_INDEX: usize = 2;
+pub const CLAIM_ESCROW_ACCOUNT_INDEX: usize = 5;
+
+// Build the bid instruction a pool places through a self-CPI from the bid's accounts, passed to pool_bid
+// as remaining accounts. The pool must be the bidder, its vault the bidder's FT account and its auction
+// the one bid on, so the pool's signature only ever spends its vault on its own auction.
+pub fn pool_bid_ix(
+    pool: &Pubkey,
+    vault: &Pubkey,
+    auction: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+) -> Result<Instruction> {
+    require!(accounts.len() > BID_ESCROW_ACCOUNT_INDEX, AuctionError::BidPoolMismatch);
+    require_keys_eq!(accounts[BID_BIDDER_INDEX].key(), *pool, AuctionError::BidPoolMismatch);
+    require_keys_eq!(accounts[BID_BIDDER_FT_ACCOUNT_INDEX].key(), *vault, AuctionError::BidPoolMismatch);
+    require_keys_eq!(accounts[BID_ESCROW_ACCOUNT_INDEX].key(), *auction, AuctionError::BidPoolMismatch);
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: pool_signed_metas(pool, accounts),
+        data: instruction::Bid { price, referrer, memo, affiliate: None }.data(),
+    })
+}
+
+// Build the claim_item instruction a pool that won its auction claims the NFT with through a self-CPI,
+// from the claim's accounts, passed to pool_claim_item as remaining accounts. The pool must be the winning
+// bidder, the NFT receiving account one of the pool's and its auction the one claimed, so the pool's
+// signature only ever moves the NFT into the pool.
+pub fn pool_claim_item_ix(pool: &Pubkey, auction: &Pubkey, accounts: &[AccountInfo]) -> Result<Instruction> {
+    require!(accounts.len() > CLAIM_ESCROW_ACCOUNT_INDEX, AuctionError::BidPoolMismatch);
+    require_keys_eq!(accounts[CLAIM_WINNING_BIDDER_INDEX].key(), *pool, AuctionError::BidPoolMismatch);
+    require_keys_eq!(accounts[CLAIM_ESCROW_ACCOUNT_INDEX].key(), *auction, AuctionError::BidPoolMismatch);
+    let receiving_account = Account::<TokenAccount>::try_from(&accounts[CLAIM_NFT_RECEIVING_ACCOUNT_INDEX])?;
+    require_keys_eq!(receiving_account.owner, *pool, AuctionError::BidPoolMismatch);
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: pool_signed_metas(pool, accounts),
+        data: instruction::ClaimItem {}.data(),
+    })
+}
+
+// Get the account metas of an instruction the pool signs through a self-CPI.
+fn pool_signed_metas(pool: &Pubkey, accounts: &[AccountInfo]) -> Vec<AccountMeta> {
+    accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer || account.key() == *pool,
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    // Build the data of a token account of an owner.
+    fn token_account_data(owner: &Pubkey) -> Vec<u8> {
+        let account = spl_token::state::Account {
+            mint: Pubkey::new_unique(),
+            owner: *owner,
+            amount: 0,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn pool_claims_the_item_only_into_its_own_account() {
+        let (pool, auction) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let keys: Vec<Pubkey> = (0..=CLAIM_ESCROW_ACCOUNT_INDEX)
+            .map(|index| match index {
+                CLAIM_WINNING_BIDDER_INDEX => pool,
+                CLAIM_ESCROW_ACCOUNT_INDEX => auction,
+                _ => Pubkey::new_unique(),
+            })
+            .collect();
+        let mut lamports = vec![0; keys.len()];
+        let mut datas: Vec<Vec<u8>> = keys.iter().map(|_| Vec::new()).collect();
+        datas[CLAIM_NFT_RECEIVING_ACCOUNT_INDEX] = token_account_data(&pool);
+        let mut other_data = token_account_data(&Pubkey::new_unique());
+        let (token_program, system_program) = (anchor_spl::token::ID, Pubkey::default());
+        let mut accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(datas.iter_mut())
+            .enumerate()
+            .map(|(index, ((key, lamports), data))| {
+                let owner = if index == CLAIM_NFT_RECEIVING_ACCOUNT_INDEX {
+                    &token_program
+                } else {
+                    &system_program
+                };
+                AccountInfo::new(key, false, true, lamports, data, owner, false, 0)
+            })
+            .collect();
+
+        // The pool signs the claim of its own auction into its own account.
+        let ix = pool_claim_item_ix(&pool, &auction, &accounts).unwrap();
+        assert!(ix.accounts[CLAIM_WINNING_BIDDER_INDEX].is_signer);
+        assert_eq!(ix.data, instruction::ClaimItem {}.data());
+        // Another auction cannot be claimed with the pool's signature.
+        assert!(pool_claim_item_ix(&pool, &Pubkey::new_unique(), &accounts).is_err());
+        // Nor can the NFT go to an account someone else owns.
+        let (other_key, mut other_lamports) = (Pubkey::new_unique(), 0);
+        let other_account = AccountInfo::new(
+            &other_key,
+            false,
+            true,
+            &mut other_lamports,
+            &mut other_data,
+            &token_program,
+            false,
+            0,
+        );
+        accounts[CLAIM_NFT_RECEIVING_ACCOUNT_INDEX] = other_account;
+        assert!(pool_claim_item_ix(&pool, &auction, &accounts).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the Metaplex metadata reader.
+use crate::metadata::{self, TOKEN_METADATA_PROGRAM_ID};
+// Import the error type, the collection stats account and its seed of the auction program.
+use crate::{AuctionError, CollectionStats, COLLECTION_STATS_SEED};
+
+// Derive the stats PDA of a collection's sales in a payment mint.
+pub fn collection_stats_address(collection: &Pubkey, payment_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[COLLECTION_STATS_SEED, collection.as_ref(), payment_mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+// Record the sale of an NFT in the stats of its verified collection in the payment mint. The stats account
+// must be the PDA of the NFT's verified collection, and is only updated once created with
+// create_collection_stats; for an NFT without metadata or outside a verified collection it is not read.
+pub fn record_collection_sale(
+    nft_metadata: &AccountInfo,
+    nft_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    collection_stats: &AccountInfo,
+    price: u64,
+    now: i64,
+) -> Result<()> {
+    if *nft_metadata.owner != TOKEN_METADATA_PROGRAM_ID {
+        return Ok(());
+    }
+    let metadata = metadata::load_metadata(nft_metadata, nft_mint)?;
+    let collection = match metadata.collection.filter(|collection| collection.verified) {
+        Some(collection) => collection,
+        None => return Ok(()),
+    };
+    require_keys_eq!(
+        collection_stats.key(),
+        collection_stats_address(&collection.key, payment_mint),
+        AuctionError::CollectionStatsMismatch
+    );
+    if *collection_stats.owner == crate::ID {
+        let mut stats = Account::<CollectionStats>::try_from(collection_stats)?;
+        stats.record_sale(price, now);
+        stats.exit(&crate::ID)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,170 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction types and the signed invoke from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+// Import the traits used to serialize events and instruction data.
+use anchor_lang::{Event, InstructionData};
+
+// Define the seed of the event authority PDA, which signs the self-CPI that records events.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+// Define the BidPlaced event, recorded whenever a bid becomes the highest.
+#[event]
+pub struct BidPlaced {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The new highest bidder.
+    pub bidder: Pubkey,
+    // The bid amount.
+    pub price: u64,
+    // The bond the bid paid to the treasury, which is not refunded when outbid (zero if none).
+    pub bond: u64,
+    // The FT receiving account of the referrer who brought the bidder (default if none).
+    pub referrer: Pubkey,
+    // The bidder's reference for the bid (empty if none).
+    pub memo: String,
+    // The affiliate the bid is tagged with (default if none).
+    pub affiliate: Pubkey,
+}
+
+// Define the Outbid event, recorded alongside BidPlaced whenever a bid refunds the previous highest bidder.
+#[event]
+pub struct Outbid {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The displaced highest bidder, who has been refunded.
+    pub previous_bidder: Pubkey,
+    // The displaced bid amount.
+    pub previous_price: u64,
+    // The amount of the bid that displaced it.
+    pub new_price: u64,
+}
+
+// Define the AuctionSettled event, recorded when an auction reaches the Settled status.
+#[event]
+pub struct AuctionSettled {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The winning bidder (default if the NFT went back to the exhibitor).
+    pub winner: Pubkey,
+    // The winning bid amount (zero if the NFT went back to the exhibitor).
+    pub price: u64,
+    // Whether the NFT was sold.
+    pub sold: bool,
+}
+
+// Define the AuctionRelisted event, recorded when an auction without a bid is restarted, by relist or at a
+// new price by relist_with_price.
+#[event]
+pub struct AuctionRelisted {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The new initial price.
+    pub price: u64,
+    // The new auction end, in the auction's timing mode.
+    pub end_at: i64,
+    // The number of times the auction has been relisted by relist.
+    pub relist_count: u16,
+}
+
+// Define the SeriesLotOpened event, recorded when a series opens bidding on its next lot.
+#[event]
+pub struct SeriesLotOpened {
+    // The series account.
+    pub series: Pubkey,
+    // The escrow account of the lot.
+    pub auction: Pubkey,
+    // The index of the lot in the series.
+    pub lot_index: u16,
+    // The end of the lot, in its timing mode.
+    pub end_at: i64,
+}
+
+// Define the EditionPurchased event, recorded whenever an open edition is bought.
+#[event]
+pub struct EditionPurchased {
+    // The sale account.
+    pub sale: Pubkey,
+    // The buyer.
+    pub buyer: Pubkey,
+    // The index of the edition in the sale.
+    pub index: u64,
+    // The price paid.
+    pub price: u64,
+}
+
+// Define the reason codes of an admin cancellation. Houses may use other values for their own reasons.
+pub const CANCEL_REASON_OTHER: u16 = 0;
+pub const CANCEL_REASON_STOLEN_NFT: u16 = 1;
+pub const CANCEL_REASON_BROKEN_STATE: u16 = 2;
+
+// Define the AuctionCancelledByAdmin event, recorded when the house authority unwinds an auction.
+#[event]
+pub struct AuctionCancelledByAdmin {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The house authority that cancelled it.
+    pub authority: Pubkey,
+    // Why the auction was cancelled (see the CANCEL_REASON constants).
+    pub reason: u16,
+    // The highest bidder refunded (default if the auction had no bid).
+    pub refunded_bidder: Pubkey,
+    // The amount refunded to the highest bidder.
+    pub refunded_amount: u64,
+}
+
+// Define the reason codes of a sale reversal. Arbiters may use other values for their own reasons.
+pub const REVERSAL_REASON_OTHER: u16 = 0;
+pub const REVERSAL_REASON_NOT_DELIVERED: u16 = 1;
+pub const REVERSAL_REASON_NOT_AS_DESCRIBED: u16 = 2;
+
+// Define the SaleReversed event, recorded when the arbiter reverses a sale during its dispute window.
+#[event]
+pub struct SaleReversed {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The arbiter that reversed it.
+    pub arbiter: Pubkey,
+    // Why the sale was reversed (see the REVERSAL_REASON constants).
+    pub reason: u16,
+    // The winning bidder refunded.
+    pub refunded_bidder: Pubkey,
+    // The amount refunded to the winning bidder.
+    pub refunded_amount: u64,
+}
+
+// Define the InstallmentsDefaulted event, recorded when a winning bidder misses an installment and the NFT
+// goes back to the exhibitor.
+#[event]
+pub struct InstallmentsDefaulted {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The winning bidder who defaulted.
+    pub bidder: Pubkey,
+    // The part of the payments forfeited to the exhibitor.
+    pub forfeited_amount: u64,
+    // The rest of the payments, refunded to the winning bidder.
+    pub refunded_amount: u64,
+}
+
+// Record an event by invoking this program's emit_event instruction with the event as its data. Unlike
+// logs, inner instructions are never truncated, so indexers can recover every event from the transaction.
+pub fn emit_cpi<'info, E: Event>(
+    event: &E,
+    event_authority: &AccountInfo<'info>,
+    program: &AccountInfo<'info>,
+    bump: u8,
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: crate::ID,
+        accounts: vec![AccountMeta::new_readonly(*event_authority.key, true)],
+        data: crate::instruction::EmitEvent { _event: event.data() }.data(),
+    };
+    invoke_signed(
+        &ix,
+        &[event_authority.clone(), program.clone()],
+        &[&[EVENT_AUTHORITY_SEED, &[bump]]],
+    )?;
+    Ok(())
+}
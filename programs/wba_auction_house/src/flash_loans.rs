@@ -0,0 +1,37 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction introspection helpers from the solana_program library.
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Define the lending programs known to offer flash loans, which a guarded bid's transaction must not call.
+pub const FLASH_LOAN_PROGRAM_IDS: [Pubkey; 6] = [
+    // Solend.
+    solana_program::pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo"),
+    // Port Finance.
+    solana_program::pubkey!("Port7uDYB3wk6GJAw4KT1WpTeMtSu9bTcChBHkX2LfR"),
+    // Mango Markets v3.
+    solana_program::pubkey!("mv3ekLzLbnVPNxjSKvqBpU3ZeZXPQdEC3bp5MDEBG68"),
+    // Mango Markets v4.
+    solana_program::pubkey!("4MangoMjqJ2firMokCjjGgoK8d4MXcrgL7XJaL3w6fVg"),
+    // marginfi v2.
+    solana_program::pubkey!("MFv2hWf31Z9kbCa1snEPYctwafyhdvnV7FZnsebVacA"),
+    // Kamino Lending.
+    solana_program::pubkey!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD"),
+];
+
+// Ensure no top-level instruction of the transaction calls a known flash loan program. The Instructions
+// sysvar only lists top-level instructions, so a flash loan taken through another program's CPI is not
+// caught; the guard raises the cost of manipulation rather than ruling it out.
+pub fn ensure_no_flash_loan(instructions: &AccountInfo) -> Result<()> {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions) {
+        require!(
+            !FLASH_LOAN_PROGRAM_IDS.contains(&instruction.program_id),
+            AuctionError::FlashLoanDetected
+        );
+        index += 1;
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction hashing and types from the solana_program library.
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+
+// Define the name of the instruction a fractionalization vault program implements to be told its NFT was
+// bought out. It is encoded the way Anchor encodes instructions, so a vault program written with Anchor
+// only needs an instruction of this name taking SettleBuyout's fields.
+pub const SETTLE_BUYOUT_IX_NAME: &str = "settle_buyout";
+
+// Define the seed of the buyout authority PDAs, which sign settle_buyout. Each vault has its own, derived
+// from the vault's address: the vault program a seller picks receives the signature, and could pass it on
+// to another vault program, so a buyout authority only vouches for its own vault.
+pub const BUYOUT_AUTHORITY_SEED: &[u8] = b"buyout_authority";
+
+// Derive the buyout authority PDA of a vault. A vault program must re-derive it from its own vault's
+// address and this program's ID, and require it to sign settle_buyout, rather than trust any signer.
+pub fn buyout_authority_address(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUYOUT_AUTHORITY_SEED, vault.as_ref()], &crate::ID)
+}
+
+// Define the SettleBuyout struct to hold the arguments of a vault program's settle_buyout instruction.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SettleBuyout {
+    // The ID of the auction that sold the vault's NFT.
+    pub auction_id: u64,
+    // The proceeds deposited to the vault's FT receiving account, to be redeemed pro rata by the fraction
+    // holders.
+    pub proceeds: u64,
+}
+
+// Build the settle_buyout instruction of a vault program. Its accounts are, in order, the vault (the
+// auction's exhibitor), the vault's FT receiving account holding the proceeds, and the vault's buyout
+// authority PDA, which signs so the vault program knows the call comes from the auction program.
+pub fn settle_buyout_ix(
+    vault_program: Pubkey,
+    vault: Pubkey,
+    vault_ft_account: Pubkey,
+    buyout_authority: Pubkey,
+    args: &SettleBuyout,
+) -> Result<Instruction> {
+    let mut data = hash(format!("global:{}", SETTLE_BUYOUT_IX_NAME).as_bytes()).to_bytes()[..8].to_vec();
+    args.serialize(&mut data)?;
+    Ok(Instruction {
+        program_id: vault_program,
+        accounts: vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(vault_ft_account, false),
+            AccountMeta::new_readonly(buyout_authority, true),
+        ],
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buyout_authority_is_derived_per_vault() {
+        let vault = Pubkey::new_unique();
+        let (authority, bump) = buyout_authority_address(&vault);
+        let seeds: &[&[u8]] = &[BUYOUT_AUTHORITY_SEED, vault.as_ref(), &[bump]];
+        assert_eq!(Pubkey::create_program_address(seeds, &crate::ID).unwrap(), authority);
+        // Another vault's authority cannot sign for this one, nor can a global authority.
+        assert_ne!(buyout_authority_address(&Pubkey::new_unique()).0, authority);
+        assert_ne!(Pubkey::find_program_address(&[BUYOUT_AUTHORITY_SEED], &crate::ID).0, authority);
+    }
+}
@@ -0,0 +1,25 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the associated token account address derivation from the anchor_spl library.
+use anchor_spl::associated_token::get_associated_token_address;
+
+// Define the SPL Governance program whose DAOs may list NFTs held by their treasuries.
+pub const SPL_GOVERNANCE_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+// Define the seed of a governance's native treasury PDA.
+pub const NATIVE_TREASURY_SEED: &[u8] = b"native-treasury";
+
+// Derive the native treasury of a governance, which only the governance program can sign for.
+pub fn native_treasury_address(governance: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[NATIVE_TREASURY_SEED, governance.as_ref()],
+        &SPL_GOVERNANCE_PROGRAM_ID,
+    )
+    .0
+}
+
+// Check that an account is the treasury ATA of a governance for a mint, so proceeds go to the DAO.
+pub fn is_treasury_ata(governance: &Pubkey, mint: &Pubkey, account: &Pubkey) -> bool {
+    get_associated_token_address(&native_treasury_address(governance), mint) == *account
+}
@@ -1,502 +1,13018 @@
+// Anchor's generated error type is larger than clippy's default threshold, and instruction
+// arguments are positional.
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 // Import necessary modules from the anchor_lang library.
 use anchor_lang::prelude::*;
+// Import the system program module from the anchor_lang library for lamport transfers.
+use anchor_lang::system_program;
+// Import the Discriminator trait to check account discriminators by hand.
+use anchor_lang::Discriminator;
+// Import the AccountsClose trait to close accounts whose closing depends on the handler.
+use anchor_lang::AccountsClose;
+// Import the target slot time from the solana_program library.
+use anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT;
 // Import necessary modules from the anchor_spl library for token operations.
-use anchor_spl::token::{self, CloseAccount, SetAuthority, TokenAccount, Transfer};
+use anchor_spl::token::{
+    self, Approve, Burn, CloseAccount, FreezeAccount, Mint, MintTo, Revoke, SetAuthority, ThawAccount, Token, TokenAccount,
+    Transfer,
+};
+// Import the associated token program from the anchor_spl library.
+use anchor_spl::associated_token::AssociatedToken;
 // Import the AuthorityType enum from the spl_token library.
 use spl_token::instruction::AuthorityType;
+// Import the wrapped SOL mint from the spl_token library.
+use spl_token::native_mint;
+// Import the optional type used by token account delegates.
+use anchor_lang::solana_program::program_option::COption;
+// Import the map type Anchor collects PDA bump seeds in.
+use std::collections::BTreeMap;
+
+// Declare the bid pools module, which lets a pool bid through a self-CPI.
+pub mod bid_pools;
+// Declare the collection stats module, which records the sales of verified collections on-chain.
+pub mod collection_stats;
+// Declare the events recorded through self-CPI.
+pub mod events;
+// Declare the instruction introspection guard against flash-loan-funded bids.
+pub mod flash_loans;
+// Declare the interface fractionalization vault programs implement to settle buyouts.
+pub mod fractions;
+// Declare the SPL Governance treasury helpers used for DAO listings.
+pub mod governance;
+// Declare the metadata module, which reads Metaplex token metadata.
+pub mod metadata;
+// Declare the pricing module, which computes Dutch auction and open edition prices for the program and
+// its clients.
+pub mod pricing;
+// Declare the prints module, which builds the metadata program instruction printing editions for print
+// sales.
+pub mod prints;
+// Declare the Pyth price account reader used for USD reserves.
+pub mod pyth;
+// Declare the royalties module, which works out the royalty owed to an NFT's creators on its sale.
+pub mod royalties;
+// Declare the SNS module, which reads and transfers Solana Name Service domains for domain auctions.
+pub mod sns;
+// Declare the stake module, which reads stake accounts and hands over their authorities for stake
+// account auctions.
+pub mod stake;
+// Declare the swaps module, which builds the aggregator swap and the bid of bid_with_swap.
+pub mod swaps;
+// Import the events and the self-CPI that records them.
+use pricing::{BondingCurve, DutchCurve, DutchPricing};
+use events::{
+    emit_cpi, AuctionCancelledByAdmin, AuctionRelisted, AuctionSettled, BidPlaced, EditionPurchased,
+    InstallmentsDefaulted, Outbid, SaleReversed, SeriesLotOpened,
+};
 
 // Declare the program ID.
 declare_id!("2gcFaJwn6AcRqgZdKSmTPjHJAXpwKu3EH67DFHThzpbP");
 
-// Define the anchor_auction module.
+// Define the maximum number of payout recipients an auction can split its proceeds between.
+pub const MAX_PAYOUT_RECIPIENTS: usize = 4;
+// Define the maximum number of creators an NFT's metadata can list, each of whom may be owed a royalty.
+pub const MAX_CREATORS: usize = 5;
+// Define the maximum number of NFTs exhibit_many lists in one instruction.
+pub const MAX_EXHIBIT_BATCH: usize = 8;
+// Define the maximum number of auctions close_many settles in one instruction.
+pub const MAX_CLOSE_BATCH: usize = 8;
+// Define the basis-point denominator (10,000 bps = 100%).
+pub const BASIS_POINTS: u64 = 10_000;
+// Define the current layout version of the Auction account.
+pub const AUCTION_VERSION: u8 = 38;
+// Define the feature flags of a house, which turn on the instructions and settlement behavior they name.
+// A feature added later gets the next bit and starts off, so houses can roll it out when they are ready.
+// Dutch auctions, through update_dutch_curve.
+pub const FEATURE_DUTCH: u32 = 1 << 0;
+// Bids paid in lamports, through bid_with_sol.
+pub const FEATURE_SOL_BIDS: u32 = 1 << 1;
+// Penny auctions, through update_penny_auction.
+pub const FEATURE_PENNY: u32 = 1 << 2;
+// Buyouts by fractionalization vaults, through enable_buyout.
+pub const FEATURE_BUYOUTS: u32 = 1 << 3;
+// Open edition sales, through buy_edition.
+pub const FEATURE_EDITIONS: u32 = 1 << 4;
+// Auction series, through add_series_lot.
+pub const FEATURE_SERIES: u32 = 1 << 5;
+// Royalties paid to the NFT's creators at settlement.
+pub const FEATURE_ROYALTIES: u32 = 1 << 6;
+// Solana Name Service domain auctions, through exhibit_domain.
+pub const FEATURE_DOMAINS: u32 = 1 << 7;
+// Stake account auctions, through exhibit_stake.
+pub const FEATURE_STAKE: u32 = 1 << 8;
+// Define every feature flag defined so far, which new and migrated houses start with.
+pub const FEATURES_ALL: u32 = FEATURE_DUTCH
+    | FEATURE_SOL_BIDS
+    | FEATURE_PENNY
+    | FEATURE_BUYOUTS
+    | FEATURE_EDITIONS
+    | FEATURE_SERIES
+    | FEATURE_ROYALTIES
+    | FEATURE_DOMAINS
+    | FEATURE_STAKE;
+// Define the longest auction duration any house allows (365 days).
+pub const MAX_AUCTION_DURATION_SEC: u64 = 365 * 24 * 60 * 60;
+// Define how long the winning bidder of a finalized auction has to claim the NFT (7 days).
+pub const CLAIM_PERIOD_SEC: i64 = 7 * 24 * 60 * 60;
+// Define the longest dispute window an auction in arbitration mode may hold its settlement for (30 days).
+pub const MAX_DISPUTE_WINDOW_SEC: u64 = 30 * 24 * 60 * 60;
+// Define the longest a bid session may last before its session key stops being accepted (7 days).
+pub const MAX_SESSION_DURATION_SEC: i64 = 7 * 24 * 60 * 60;
+// Define how long after its end the exhibitor of an auction with a hidden reserve has to reveal it before
+// the reserve is forfeited (2 days).
+pub const RESERVE_REVEAL_PERIOD_SEC: i64 = 2 * 24 * 60 * 60;
+// Define how long after its end the exhibitor of an auction whose highest bid falls short of a reserve may
+// still accept it, before the auction can be unwound (2 days).
+pub const ACCEPTANCE_PERIOD_SEC: i64 = 2 * 24 * 60 * 60;
+// Define how long after its end an auction with a USD reserve waits for a usable price feed before it can
+// be unwound without a price (7 days).
+pub const PRICE_FEED_GRACE_PERIOD_SEC: i64 = 7 * 24 * 60 * 60;
+// Define the share of the winning bid an unclaiming winner forfeits to the exhibitor in basis points.
+pub const UNCLAIMED_PENALTY_BPS: u16 = 1_000;
+// Define the maximum length of an auction title in bytes.
+pub const MAX_TITLE_LEN: usize = 32;
+// Define the maximum length of an auction metadata URI in bytes.
+pub const MAX_URI_LEN: usize = 200;
+// Define the maximum length of a bid memo in bytes.
+pub const MAX_MEMO_LEN: usize = 32;
+// Define the maximum number of lots in an auction series.
+pub const MAX_SERIES_LOTS: usize = 32;
+// Define a constant byte slice for the escrow PDA seed.
+pub const ESCROW_PDA_SEED: &[u8] = b"escrow";
+// Define a constant byte slice for the auction house PDA seed.
+pub const AUCTION_HOUSE_SEED: &[u8] = b"auction_house";
+// Define the seed of the auction registry PDA.
+pub const REGISTRY_SEED: &[u8] = b"registry";
+// Define the seed of the escrow account PDA of an auction, followed by its ID.
+pub const AUCTION_SEED: &[u8] = b"auction";
+// Define the seed of a bid receipt PDA, followed by the escrow account, the bidder and the bid index.
+pub const BID_RECEIPT_SEED: &[u8] = b"bid_receipt";
+// Define the seed of a listing receipt PDA, followed by the escrow account.
+pub const LISTING_RECEIPT_SEED: &[u8] = b"listing_receipt";
+// Define the seed of a purchase receipt PDA, followed by the escrow account.
+pub const PURCHASE_RECEIPT_SEED: &[u8] = b"purchase_receipt";
+// Define the seed of a blacklist entry PDA, followed by the blacklisted mint.
+pub const BLACKLIST_SEED: &[u8] = b"blacklist";
+// Define the seed of a seller allowlist PDA, followed by the approved seller's wallet.
+pub const SELLER_ALLOWLIST_SEED: &[u8] = b"seller_allowlist";
+// Define the seed of a partner entry PDA, followed by the partner frontend's wallet.
+pub const PARTNER_SEED: &[u8] = b"partner";
+// Define the seed of a royalty override PDA, followed by the collection mint.
+pub const ROYALTY_OVERRIDE_SEED: &[u8] = b"royalty_override";
+// Define the seed of a collection stats PDA, followed by the collection mint and the payment mint.
+pub const COLLECTION_STATS_SEED: &[u8] = b"collection_stats";
+// Define the seed of a house stats PDA, followed by the payment mint.
+pub const HOUSE_STATS_SEED: &[u8] = b"house_stats";
+// Define the seed of a vesting schedule PDA, followed by the escrow account.
+pub const VESTING_SEED: &[u8] = b"vesting";
+// Define the seed of a vesting vault PDA, followed by the vesting schedule.
+pub const VESTING_VAULT_SEED: &[u8] = b"vesting_vault";
+// Get the seed that follows the escrow account in the seeds of an auction's listing and purchase
+// receipts: nothing in the escrow account's first round, so receipts keep their addresses, then the
+// round, so every reuse of the escrow account gets its own receipts.
+pub fn round_seed(round: u64) -> Vec<u8> {
+    if round == 0 {
+        Vec::new()
+    } else {
+        round.to_le_bytes().to_vec()
+    }
+}
+
+// Get the commitment to a hidden reserve: the SHA-256 hash of the reserve in little-endian bytes followed
+// by a secret salt, which keeps small reserves from being guessed.
+pub fn reserve_commitment(reserve: u64, salt: &[u8; 32]) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hashv(&[&reserve.to_le_bytes(), salt]).to_bytes()
+}
+
+// Define the seed of a rental PDA, followed by the escrow account.
+pub const RENTAL_SEED: &[u8] = b"rental";
+// Define the seed of an open edition sale PDA, followed by the edition mint.
+pub const EDITION_SALE_SEED: &[u8] = b"edition_sale";
+// Define the seed of an edition receipt PDA, followed by the sale and the edition index.
+pub const EDITION_RECEIPT_SEED: &[u8] = b"edition_receipt";
+// Define the seed of an auction series PDA, followed by its authority and its series ID.
+pub const SERIES_SEED: &[u8] = b"series";
+// Define the seed of a listing token mint PDA, followed by the escrow account and its round seed.
+pub const LISTING_TOKEN_SEED: &[u8] = b"listing_token";
+// Define the seed of a bid pool PDA, followed by the escrow account and the pool's operator.
+pub const BID_POOL_SEED: &[u8] = b"bid_pool";
+// Define the seed of a bid pool's vault PDA, followed by the pool.
+pub const BID_POOL_VAULT_SEED: &[u8] = b"bid_pool_vault";
+// Define the seed of a bid pool's fraction mint PDA, followed by the pool.
+pub const BID_POOL_FRACTIONS_SEED: &[u8] = b"bid_pool_fractions";
+// Define the seed of a pool contribution PDA, followed by the pool and the contribution index.
+pub const POOL_CONTRIBUTION_SEED: &[u8] = b"pool_contribution";
+// Define the seed of a royalty vault PDA, followed by the payment mint.
+pub const ROYALTY_VAULT_SEED: &[u8] = b"royalty_vault";
+// Define the seed of an affiliate vault PDA, followed by the payment mint.
+pub const AFFILIATE_VAULT_SEED: &[u8] = b"affiliate_vault";
+// Define the seed of a bid session PDA, followed by the wallet and the session key.
+pub const BID_SESSION_SEED: &[u8] = b"bid_session";
+
+// Define the anchor_auction module. With the `cpi` feature enabled, Anchor also generates a public
+// `cpi` module with a function and an accounts struct for every instruction, so other programs can
+// exhibit, bid and close through `wba_auction_house::cpi`.
 #[program]
 pub mod anchor_auction {
-    // Import the Add trait to use the add method for i64.
-    use std::ops::Add;
     // Import everything from the parent module.
     use super::*;
 
-    // Define a constant byte slice for the escrow PDA seed.
-    const ESCROW_PDA_SEED: &[u8] = b"escrow";
+    // Define the initialize_registry function to create the registry that numbers auctions.
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+        // Start counting auctions from zero.
+        let registry = &mut ctx.accounts.registry;
+        registry.auction_count = 0;
+        registry.bump = *ctx.bumps.get("registry").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the initialize_house function for the program's upgrade authority to create the auction house
+    // configuration. The house is a singleton, and whoever initializes it controls every house setting.
+    pub fn initialize_house(
+        ctx: Context<InitializeHouse>, // Context for the InitializeHouse struct.
+        fee_bps: u16,                  // House fee taken from the proceeds in basis points.
+        referral_bps: u16,             // Share of the house fee paid to referrers in basis points.
+        min_duration_sec: u64,         // Shortest auction duration allowed in seconds (zero for no minimum).
+        max_duration_sec: u64,         // Longest auction duration allowed in seconds (zero for no maximum).
+    ) -> Result<()> {
+        // Ensure the fee and duration configuration is valid.
+        validate_house_fees(fee_bps, referral_bps)?;
+        validate_duration_limits(min_duration_sec, max_duration_sec)?;
+
+        // Set the house authority, treasury and fee configuration.
+        let auction_house = &mut ctx.accounts.auction_house;
+        auction_house.authority = ctx.accounts.authority.key();
+        auction_house.treasury = ctx.accounts.treasury.key();
+        auction_house.fee_bps = fee_bps;
+        auction_house.referral_bps = referral_bps;
+        auction_house.bump = *ctx.bumps.get("auction_house").unwrap();
+        auction_house.min_duration_sec = min_duration_sec;
+        auction_house.max_duration_sec = max_duration_sec;
+        auction_house.features = FEATURES_ALL;
+        auction_house.store_bumps();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_fees function for the house authority or its fee manager to change the house
+    // fee configuration.
+    pub fn update_house_fees(
+        ctx: Context<UpdateHouseFees>, // Context for the UpdateHouseFees struct.
+        fee_bps: u16,              // House fee taken from the proceeds in basis points.
+        referral_bps: u16,         // Share of the house fee paid to referrers in basis points.
+    ) -> Result<()> {
+        // Ensure the fee configuration is valid.
+        validate_house_fees(fee_bps, referral_bps)?;
+
+        // Update the fee configuration.
+        ctx.accounts.auction_house.fee_bps = fee_bps;
+        ctx.accounts.auction_house.referral_bps = referral_bps;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_durations function to change the auction duration limits.
+    pub fn update_house_durations(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        min_duration_sec: u64,     // Shortest auction duration allowed in seconds (zero for no minimum).
+        max_duration_sec: u64,     // Longest auction duration allowed in seconds (zero for no maximum).
+    ) -> Result<()> {
+        // Ensure the duration configuration is valid.
+        validate_duration_limits(min_duration_sec, max_duration_sec)?;
+
+        // Update the duration limits.
+        ctx.accounts.auction_house.min_duration_sec = min_duration_sec;
+        ctx.accounts.auction_house.max_duration_sec = max_duration_sec;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_flash_loan_guard function to turn the flash loan guard on bids on or off.
+    // While it is on, bids are rejected in transactions that call a known flash loan program.
+    pub fn update_house_flash_loan_guard(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        enabled: bool,             // Whether bids are checked for flash loans.
+    ) -> Result<()> {
+        // Update the flash loan guard.
+        ctx.accounts.auction_house.flash_loan_guard = enabled;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_listing_fee function for the house authority or its fee manager to charge a
+    // flat fee in lamports for every auction exhibited, paid to the treasury by the listing's payer, to make
+    // flooding the registry with junk listings costly. Approved sellers list for free.
+    pub fn update_house_listing_fee(
+        ctx: Context<UpdateHouseFees>, // Context for the UpdateHouseFees struct.
+        listing_fee_lamports: u64,     // Fee charged per listing in lamports (zero for none).
+    ) -> Result<()> {
+        // Update the listing fee.
+        ctx.accounts.auction_house.listing_fee_lamports = listing_fee_lamports;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_affiliate_fee function for the house authority or its fee manager to set the
+    // share of the house fee the affiliate tagged on a winning bid earns. Settlement escrows it in the
+    // house's affiliate vault of the payment mint, from which claim_affiliate_earnings pays it.
+    pub fn update_house_affiliate_fee(
+        ctx: Context<UpdateHouseFees>, // Context for the UpdateHouseFees struct.
+        affiliate_bps: u16,            // Share of the house fee earned by affiliates in basis points.
+    ) -> Result<()> {
+        // Ensure the share is at most the whole house fee.
+        require!(affiliate_bps as u64 <= BASIS_POINTS, AuctionError::InvalidFee);
+        // Update the affiliate share.
+        ctx.accounts.auction_house.affiliate_bps = affiliate_bps;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_buyers_premium function for the house authority or its fee manager to choose
+    // who pays the house fee: the exhibitor, out
+    // of the proceeds, or the winning bidder, as a buyer's premium on top of their bid. Auctions keep the
+    // mode in force when they were exhibited, since bids escrow the premium.
+    pub fn update_house_buyers_premium(
+        ctx: Context<UpdateHouseFees>, // Context for the UpdateHouseFees struct.
+        enabled: bool,             // Whether the winning bidder pays the house fee on top of the bid.
+    ) -> Result<()> {
+        // Update the fee mode.
+        ctx.accounts.auction_house.buyers_premium = enabled;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_features function to turn the house's features on or off, as a bitset of
+    // the FEATURE_ flags. Auctions already set up with a feature keep it; only the instructions and
+    // settlements gated by a flag check it.
+    pub fn update_house_features(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        features: u32,             // The enabled features, as FEATURE_ flags.
+    ) -> Result<()> {
+        // Ensure only defined features are enabled.
+        require!(features & !FEATURES_ALL == 0, AuctionError::InvalidFeatures);
+        // Update the feature flags.
+        ctx.accounts.auction_house.features = features;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the set_house_role function for the house authority to give a role to a low-privilege key, or
+    // take it away by setting the default pubkey. The authority keeps every role's permissions itself.
+    pub fn set_house_role(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        role: HouseRole,           // The role to assign.
+        holder: Pubkey,            // The key holding the role (the default pubkey for none).
+    ) -> Result<()> {
+        // Record the role's holder.
+        let auction_house = &mut ctx.accounts.auction_house;
+        match role {
+            HouseRole::Pauser => auction_house.pauser = holder,
+            HouseRole::FeeManager => auction_house.fee_manager = holder,
+            HouseRole::WhitelistManager => auction_house.whitelist_manager = holder,
+            HouseRole::EmergencyCanceller => auction_house.emergency_canceller = holder,
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the pause_house function for the house authority or its pauser to stop or resume new
+    // listings, bids and edition purchases. Settlement, cancellation and refunds keep working while the
+    // house is paused, so no funds are locked in.
+    pub fn pause_house(
+        ctx: Context<PauseHouse>, // Context for the PauseHouse struct.
+        paused: bool,             // Whether the house is paused.
+    ) -> Result<()> {
+        // Update the pause.
+        ctx.accounts.auction_house.paused = paused;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the nominate_authority function for the house authority to hand control of the configuration
+    // to a new authority, which takes over once it accepts. Nominating the default pubkey withdraws a
+    // nomination, and a new nomination replaces the previous one.
+    pub fn nominate_authority(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        new_authority: Pubkey,     // The nominated authority (the default pubkey for none).
+    ) -> Result<()> {
+        // Record the nominated authority.
+        ctx.accounts.auction_house.pending_authority = new_authority;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the accept_authority function for the nominated authority to take control of the
+    // configuration, which proves the nominated key is one someone controls.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        // Hand control to the nominated authority and clear the nomination.
+        let auction_house = &mut ctx.accounts.auction_house;
+        auction_house.authority = ctx.accounts.pending_authority.key();
+        auction_house.pending_authority = Pubkey::default();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the migrate_house function to grow a configuration account created before the duration
+    // limits, the flash loan guard, the buyer's premium, the feature flags, authority nominations, roles,
+    // the approved-sellers mode, the listing fee, the collection scope, the stored PDA bumps or the affiliate
+    // share were added. The new limits start at zero, which means no limits, the guard starts off, the house
+    // fee is taken from the proceeds, every feature the house already offered stays enabled, no authority is
+    // nominated, the house is not paused, no role is given out, anyone may list, listing is free, any NFT may
+    // be listed and affiliates earn nothing.
+    pub fn migrate_house(ctx: Context<MigrateHouse>) -> Result<()> {
+        // Grow the account to the current layout, topping up its rent from the payer.
+        let new_len = AuctionHouse::LEN;
+        let data_len = ctx.accounts.auction_house.data_len();
+        if data_len < new_len {
+            let rent = Rent::get()?.minimum_balance(new_len);
+            let lamports = ctx.accounts.auction_house.lamports();
+            if rent > lamports {
+                system_program::transfer(ctx.accounts.to_transfer_rent_context(), rent - lamports)?;
+            }
+            ctx.accounts.auction_house.realloc(new_len, true)?;
+            let mut auction_house = Account::<AuctionHouse>::try_from(&ctx.accounts.auction_house)?;
+            // An account that ends before the feature flags predates them.
+            if data_len < AuctionHouse::FEATURES_END {
+                auction_house.features = FEATURES_ALL;
+            }
+            // The PDA bumps only depend on the program ID, so storing them again is harmless for an account
+            // that already has them.
+            auction_house.store_bumps();
+            auction_house.exit(ctx.program_id)?;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
 
     // Define the exhibit function to exhibit an item for auction.
+    // The FT receiving accounts of the payout recipients, followed by the charity's, are passed as
+    // remaining accounts so their mint can be checked. A listing through a partner frontend passes the
+    // partner's entry and FT fee account after them (see check_partner).
     pub fn exhibit(
         ctx: Context<Exhibit>, // Context for the Exhibit struct.
         initial_price: u64,    // Initial price for the auction.
-        auction_duration_sec: u64, // Duration of the auction in seconds.
+        auction_duration_sec: u64, // Duration of the auction in seconds or slots.
+        payouts: Vec<Payout>,  // Additional recipients of the proceeds and their shares.
+        charity: Option<Pubkey>, // Optional FT receiving account of a charity.
+        charity_bps: u16,      // The charity's share of the proceeds in basis points.
+        reserve_usd_cents: u64, // Optional reserve price in US cents (zero for no reserve).
+        reserve_price_feed: Pubkey, // Pyth price feed of the payment token in USD.
+        timing_mode: TimingMode, // Whether the duration and end of the auction are in seconds or slots.
+        title: String,         // Optional title of the lot (empty for none).
+        uri: String,           // Optional URI of the lot's description (empty for none).
+        listing_mode: ListingMode, // Whether the NFT moves into escrow or stays in the exhibitor's wallet.
+        partner: Option<Pubkey>, // Optional FT account of the partner frontend sharing the house fee.
+        free_listing: bool,    // Whether the auction may start at a zero price.
     ) -> Result<()> {
+        // Ensure the initial price is set unless the listing is free.
+        validate_initial_price(initial_price, free_listing)?;
+        // Ensure the duration is within the house limits.
+        validate_duration(&ctx.accounts.auction_house, timing_mode, auction_duration_sec)?;
+        // Ensure the NFT belongs to the house's collection, if the house is scoped to one.
+        check_house_collection(
+            &ctx.accounts.auction_house,
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.exhibitor_nft_token_account.mint,
+        )?;
+        // Charge the house's listing fee to the payer, unless the exhibitor is an approved seller.
+        let listing_fee = ctx.accounts.auction_house.listing_fee_lamports;
+        if listing_fee > 0 && *ctx.accounts.seller_allowlist_entry.owner != crate::ID {
+            system_program::transfer(ctx.accounts.to_transfer_listing_fee_context(), listing_fee)?;
+        }
+        // Ensure a charity share is only given together with a charity.
+        require!(
+            charity.is_some() || charity_bps == 0,
+            AuctionError::CharityMissing
+        );
+        // Ensure the payout recipients fit in the escrow account and their and the charity's shares do not
+        // exceed the whole of the proceeds.
+        check_payout_shares(&payouts, charity_bps)?;
+
+        // The auction is paid in the mint of the exhibitor's FT receiving account.
+        let payment_mint = ctx.accounts.exhibitor_ft_receiving_account.mint;
+        // Ensure the payout recipients' and the charity's FT receiving accounts are passed as remaining
+        // accounts and hold the payment mint, so close can always pay them.
+        let expected_recipients = payouts
+            .iter()
+            .map(|payout| payout.recipient_ft_pubkey)
+            .chain(charity)
+            .collect::<Vec<_>>();
+        require!(
+            ctx.remaining_accounts.len() >= expected_recipients.len(),
+            AuctionError::PayoutRecipientMissing
+        );
+        for (expected, recipient) in expected_recipients.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(recipient.key(), *expected, AuctionError::PayoutRecipientMismatch);
+            check_payment_account(recipient, &payment_mint)?;
+        }
+        // Ensure a partner frontend sharing the house fee is whitelisted, and get its share.
+        let partner_fee_bps = match partner {
+            Some(partner) => check_partner(&ctx.remaining_accounts[expected_recipients.len()..], &partner, &payment_mint)?,
+            None => 0,
+        };
+
+        // Assign the auction the next ID, which its escrow account address is derived from.
+        let auction_id = ctx.accounts.registry.next_auction_id()?;
+        ctx.accounts.registry.auction_count = auction_id;
+
+        // Initialize the zero-copy escrow account.
+        let mut escrow_account = ctx.accounts.escrow_account.load_init()?;
+        // Record the layout version of the escrow account.
+        escrow_account.version = AUCTION_VERSION;
+        // Record the auction's ID in the escrow account.
+        escrow_account.auction_id = auction_id;
+        // The auction starts without bids.
+        escrow_account.set_status(AuctionStatus::Created);
         // Set the exhibitor's public key in the escrow account.
-        ctx.accounts.escrow_account.exhibitor_pubkey = ctx.accounts.exhibitor.key();
+        escrow_account.exhibitor = ctx.accounts.exhibitor.key();
+        // Set the rent payer of the escrow and temporary NFT accounts in the escrow account.
+        escrow_account.exhibitor_rent_payer = ctx.accounts.payer.key();
+        // Initially, set the highest bidder's rent payer to the exhibitor's rent payer in the escrow account.
+        escrow_account.highest_bidder_rent_payer = ctx.accounts.payer.key();
         // Set the exhibitor's fungible token (FT) receiving account public key in the escrow account.
-        ctx.accounts.escrow_account.exhibitor_ft_receiving_pubkey = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.exhibitor_ft_receiving_account = ctx.accounts.exhibitor_ft_receiving_account.key();
         // Set the exhibitor's non-fungible token (NFT) temporary account public key in the escrow account.
-        ctx.accounts.escrow_account.exhibiting_nft_temp_pubkey = ctx.accounts.exhibitor_nft_temp_account.key();
+        escrow_account.exhibitor_nft_temp_account = ctx.accounts.exhibitor_nft_temp_account.key();
         // Initially, set the highest bidder's public key to the exhibitor's public key in the escrow account.
-        ctx.accounts.escrow_account.highest_bidder_pubkey = ctx.accounts.exhibitor.key();
+        escrow_account.highest_bidder = ctx.accounts.exhibitor.key();
         // Set the highest bidder's FT temporary account public key to the exhibitor's FT receiving account public key.
-        ctx.accounts.escrow_account.highest_bidder_ft_temp_pubkey = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.highest_bidder_ft_temp_account = ctx.accounts.exhibitor_ft_receiving_account.key();
         // Set the highest bidder's FT returning account public key to the exhibitor's FT receiving account public key.
-        ctx.accounts.escrow_account.highest_bidder_ft_returning_pubkey = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.highest_bidder_ft_returning_account = ctx.accounts.exhibitor_ft_receiving_account.key();
+        // Set the auctioned NFT's mint and the payment mint in the escrow account.
+        escrow_account.nft_mint = ctx.accounts.exhibitor_nft_token_account.mint;
+        escrow_account.payment_mint = payment_mint;
         // Set the initial price for the auction in the escrow account.
-        ctx.accounts.escrow_account.price = initial_price;
-        // Calculate and set the auction end time in the escrow account.
-        ctx.accounts.escrow_account.end_at = ctx.accounts.clock.unix_timestamp.add(auction_duration_sec as i64);
+        escrow_account.price = initial_price;
+        escrow_account.free_listing = free_listing as u8;
+        // Record the listing mode in the escrow account.
+        escrow_account.listing_mode = listing_mode as u8;
+        // Record the timing mode, then calculate and set the auction end in the escrow account.
+        escrow_account.timing_mode = timing_mode as u8;
+        escrow_account.end_at = escrow_account.end_after(&ctx.accounts.clock, auction_duration_sec)?;
+        // Record the payout recipients and their shares in the escrow account.
+        escrow_account.payout_count = payouts.len() as u8;
+        for (slot, payout) in escrow_account.payouts.iter_mut().zip(payouts) {
+            *slot = payout;
+        }
+        // Record the charity and its share in the escrow account.
+        escrow_account.charity_ft_pubkey = charity.unwrap_or_default();
+        escrow_account.charity_bps = charity_bps;
+        // Record the title and URI of the lot in the escrow account.
+        escrow_account.title = to_fixed_bytes(&title)?;
+        escrow_account.uri = to_fixed_bytes(&uri)?;
+        // Record the USD reserve and the price feed used to check it in the escrow account.
+        escrow_account.reserve_usd_cents = reserve_usd_cents;
+        if reserve_usd_cents > 0 {
+            escrow_account.reserve_price_feed = reserve_price_feed;
+        }
+        // Record the partner frontend's FT fee account and its share of the house fee in the escrow account.
+        escrow_account.partner_fee_ft_pubkey = partner.unwrap_or_default();
+        escrow_account.partner_fee_bps = partner_fee_bps;
+        // Record the buyer's premium bids pay on top, if the house charges its fee to the winning bidder.
+        escrow_account.buyers_premium_bps = ctx.accounts.auction_house.buyers_premium_bps();
 
-        // Find the Program Derived Address (PDA) for the escrow account.
-        let (pda, _bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
-        // Set the authority of the NFT to the PDA.
-        token::set_authority(
-            ctx.accounts.to_set_authority_context(),
-            AuthorityType::AccountOwner,
-            Some(pda)
-        )?;
+        // Record the listing in its receipt, which outlives the escrow account.
+        let listing_receipt = &mut ctx.accounts.listing_receipt;
+        listing_receipt.auction = ctx.accounts.escrow_account.key();
+        listing_receipt.auction_id = auction_id;
+        listing_receipt.exhibitor = ctx.accounts.exhibitor.key();
+        listing_receipt.nft_mint = escrow_account.nft_mint;
+        listing_receipt.payment_mint = payment_mint;
+        listing_receipt.initial_price = initial_price;
+        listing_receipt.created_at = ctx.accounts.clock.unix_timestamp;
+        listing_receipt.set_status(AuctionStatus::Created);
+        listing_receipt.bump = *ctx.bumps.get("listing_receipt").unwrap();
 
-        // Transfer the NFT to the PDA-controlled escrow account.
-        token::transfer(
-            ctx.accounts.to_transfer_to_pda_context(),
-            1
-        )?;
+        match listing_mode {
+            ListingMode::Escrow => {
+                // Find the Program Derived Address (PDA) for the escrow account.
+                let (pda, _bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+                // Set the authority of the NFT to the PDA.
+                token::set_authority(
+                    ctx.accounts.to_set_authority_context(),
+                    AuthorityType::AccountOwner,
+                    Some(pda)
+                )?;
+
+                // Transfer the NFT to the PDA-controlled escrow account.
+                token::transfer(
+                    ctx.accounts.to_transfer_to_pda_context(),
+                    1
+                )?;
+            }
+            ListingMode::Delegate => {
+                // The NFT stays in the exhibitor's NFT account, which stands in for the temporary account.
+                require_keys_eq!(
+                    ctx.accounts.exhibitor_nft_temp_account.key(),
+                    ctx.accounts.exhibitor_nft_token_account.key(),
+                    AuctionError::InvalidDelegateListing
+                );
+                // Let the PDA transfer the NFT at settlement.
+                token::approve(ctx.accounts.to_approve_context(), 1)?;
+            }
+        }
 
         // Return an Ok result.
         Ok(())
     }
 
-    // Define the cancel function to cancel an ongoing auction.
-    pub fn cancel(ctx: Context<Cancel> ) -> Result<()> {
-        // Find the PDA for the escrow account.
-        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
-        // Create the seeds for the signer.
-        let signers_seeds: &[&[&[u8]]] = &[&[&ESCROW_PDA_SEED[..], &[bump_seed]]];
-
-        // Transfer the NFT back to the exhibitor.
-        token::transfer(
-            ctx.accounts
-                .to_transfer_to_exhibitor_context()
-                .with_signer(signers_seeds),
-            ctx.accounts.exhibitor_nft_temp_account.amount
+    // Define the reuse_escrow function for an exhibitor to list another item in the escrow account of an
+    // auction they sold and kept open with update_escrow_reuse. The account keeps its rent, auction ID and
+    // bid count, so bid receipts stay unique, and starts a new round with its own listing and purchase
+    // receipts. Every other setting starts over, as if exhibited without payouts, charity or reserve.
+    pub fn reuse_escrow(
+        ctx: Context<ReuseEscrow>, // Context for the ReuseEscrow struct.
+        initial_price: u64,        // Initial price for the auction.
+        auction_duration_sec: u64, // Duration of the auction in seconds or slots.
+        timing_mode: TimingMode,   // Whether the duration and end of the auction are in seconds or slots.
+        title: String,             // Optional title of the lot (empty for none).
+        uri: String,               // Optional URI of the lot's description (empty for none).
+        listing_mode: ListingMode, // Whether the NFT moves into escrow or stays in the exhibitor's wallet.
+        free_listing: bool,        // Whether the auction may start at a zero price.
+    ) -> Result<()> {
+        // Ensure the initial price is set unless the listing is free.
+        validate_initial_price(initial_price, free_listing)?;
+        // Ensure the duration is within the house limits.
+        validate_duration(&ctx.accounts.auction_house, timing_mode, auction_duration_sec)?;
+        // Ensure the NFT belongs to the house's collection, if the house is scoped to one.
+        check_house_collection(
+            &ctx.accounts.auction_house,
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.exhibitor_nft_token_account.mint,
         )?;
 
-        // Close the PDA-controlled escrow account.
-        token::close_account(
-            ctx.accounts
-                .to_close_context()
-                .with_signer(signers_seeds)
-        )?;
+        // Keep what outlives a round, then clear the previous round's state.
+        let (auction_id, bid_count, round, exhibitor_rent_payer) = {
+            let escrow_account = ctx.accounts.escrow_account.load()?;
+            (escrow_account.auction_id, escrow_account.bid_count, escrow_account.round, escrow_account.exhibitor_rent_payer)
+        };
+        ctx.accounts.escrow_account.to_account_info().try_borrow_mut_data()?[8..].fill(0);
+
+        // Set up the escrow account for the new item, as exhibit does.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        escrow_account.version = AUCTION_VERSION;
+        escrow_account.auction_id = auction_id;
+        escrow_account.bid_count = bid_count;
+        escrow_account.round = next_round(round)?;
+        escrow_account.reuse_escrow = 1;
+        escrow_account.set_status(AuctionStatus::Created);
+        escrow_account.exhibitor = ctx.accounts.exhibitor.key();
+        escrow_account.exhibitor_rent_payer = exhibitor_rent_payer;
+        escrow_account.highest_bidder_rent_payer = exhibitor_rent_payer;
+        escrow_account.exhibitor_ft_receiving_account = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.exhibitor_nft_temp_account = ctx.accounts.exhibitor_nft_temp_account.key();
+        escrow_account.highest_bidder = ctx.accounts.exhibitor.key();
+        escrow_account.highest_bidder_ft_temp_account = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.highest_bidder_ft_returning_account = ctx.accounts.exhibitor_ft_receiving_account.key();
+        escrow_account.nft_mint = ctx.accounts.exhibitor_nft_token_account.mint;
+        escrow_account.payment_mint = ctx.accounts.exhibitor_ft_receiving_account.mint;
+        escrow_account.price = initial_price;
+        escrow_account.free_listing = free_listing as u8;
+        escrow_account.listing_mode = listing_mode as u8;
+        escrow_account.timing_mode = timing_mode as u8;
+        escrow_account.end_at = escrow_account.end_after(&ctx.accounts.clock, auction_duration_sec)?;
+        escrow_account.title = to_fixed_bytes(&title)?;
+        escrow_account.uri = to_fixed_bytes(&uri)?;
+        escrow_account.buyers_premium_bps = ctx.accounts.auction_house.buyers_premium_bps();
+
+        // Record the new listing in the round's receipt.
+        let listing_receipt = &mut ctx.accounts.listing_receipt;
+        listing_receipt.auction = ctx.accounts.escrow_account.key();
+        listing_receipt.auction_id = auction_id;
+        listing_receipt.exhibitor = ctx.accounts.exhibitor.key();
+        listing_receipt.nft_mint = escrow_account.nft_mint;
+        listing_receipt.payment_mint = escrow_account.payment_mint;
+        listing_receipt.initial_price = initial_price;
+        listing_receipt.created_at = ctx.accounts.clock.unix_timestamp;
+        listing_receipt.set_status(AuctionStatus::Created);
+        listing_receipt.bump = *ctx.bumps.get("listing_receipt").unwrap();
+
+        match listing_mode {
+            ListingMode::Escrow => {
+                // Set the authority of the NFT to the PDA, then transfer the NFT to the PDA-controlled
+                // escrow account.
+                token::set_authority(
+                    ctx.accounts.to_set_authority_context(),
+                    AuthorityType::AccountOwner,
+                    Some(ctx.accounts.pda.key())
+                )?;
+                token::transfer(ctx.accounts.to_transfer_to_pda_context(), 1)?;
+            }
+            ListingMode::Delegate => {
+                // The NFT stays in the exhibitor's NFT account, which stands in for the temporary account.
+                require_keys_eq!(
+                    ctx.accounts.exhibitor_nft_temp_account.key(),
+                    ctx.accounts.exhibitor_nft_token_account.key(),
+                    AuctionError::InvalidDelegateListing
+                );
+                // Let the PDA transfer the NFT at settlement.
+                token::approve(ctx.accounts.to_approve_context(), 1)?;
+            }
+        }
 
         // Return an Ok result.
         Ok(())
     }
 
-    // Define the bid function for users to place bids.
-    pub fn bid(ctx: Context<Bid>, price: u64) -> Result<()> {
-        // Find the PDA for the escrow account.
-        let (pda, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
-        // Create the seeds for the signer.
-        let signers_seeds: &[&[&[u8]]] = &[&[&ESCROW_PDA_SEED[..], &[bump_seed]]];
+    // Define the migrate_auction function to bring an escrow account written by an older program
+    // version up to the current Auction layout. Fields are only ever appended, so migrating grows the
+    // account to the current size, zero-fills the new fields and bumps the version. Anyone may pay for it.
+    pub fn migrate_auction(ctx: Context<MigrateAuction>) -> Result<()> {
+        // Ensure the account is an Auction account.
+        {
+            let data = ctx.accounts.escrow_account.try_borrow_data()?;
+            require!(data.len() > 8, ErrorCode::AccountDiscriminatorNotFound);
+            require!(data[..8] == Auction::discriminator(), ErrorCode::AccountDiscriminatorMismatch);
+            // Ensure the account was not written by a newer program version.
+            require!(data[8] <= AUCTION_VERSION, AuctionError::UnsupportedAuctionVersion);
+        }
 
-        // Check if the current highest bidder is not the exhibitor.
-        if ctx.accounts.escrow_account.highest_bidder_pubkey != ctx.accounts.escrow_account.exhibitor_pubkey {
-            // Transfer the current highest bid amount back to the previous highest bidder.
-            token::transfer(
-                ctx.accounts
-                    .to_transfer_to_previous_bidder_context()
-                    .with_signer(signers_seeds),
-                ctx.accounts.escrow_account.price
-            )?;
+        // Grow the account to the current layout, topping up its rent from the payer.
+        let new_len = Auction::LEN;
+        if ctx.accounts.escrow_account.data_len() < new_len {
+            let rent = Rent::get()?.minimum_balance(new_len);
+            let lamports = ctx.accounts.escrow_account.lamports();
+            if rent > lamports {
+                system_program::transfer(ctx.accounts.to_transfer_rent_context(), rent - lamports)?;
+            }
+            ctx.accounts.escrow_account.realloc(new_len, true)?;
+        }
 
-            // Close the previous highest bidder's temporary FT account.
-            token::close_account(
-                ctx.accounts
-                    .to_close_context()
-                    .with_signer(signers_seeds)
-            )?;
+        // Fill in the fields added since the account's version and record the current version.
+        let escrow_account = AccountLoader::<Auction>::try_from(&ctx.accounts.escrow_account)?;
+        let mut escrow_account = escrow_account.load_mut()?;
+        if escrow_account.version < 2 {
+            // Version 2 added the status, which version 1 implied by the highest bidder.
+            let status = if escrow_account.highest_bidder == escrow_account.exhibitor {
+                AuctionStatus::Created
+            } else {
+                AuctionStatus::Live
+            };
+            escrow_account.set_status(status);
         }
+        if escrow_account.version < 4 && escrow_account.status == AuctionStatus::Ended as u8 {
+            // Version 4 added the claim deadline, so auctions finalized before get a full claim period.
+            escrow_account.claim_deadline = Clock::get()?
+                .unix_timestamp
+                .checked_add(CLAIM_PERIOD_SEC)
+                .ok_or(AuctionError::NumericalOverflow)?;
+        }
+        if escrow_account.version < 5 {
+            // Version 5 added the rent payers, which older versions assumed to be the exhibitor and the
+            // highest bidder.
+            escrow_account.exhibitor_rent_payer = escrow_account.exhibitor;
+            escrow_account.highest_bidder_rent_payer = escrow_account.highest_bidder;
+        }
+        // The fields added by these versions stay zeroed, which matches how older auctions behave:
+        // v6: operator (none)
+        // v7: timing mode (0 = UNIX timestamps)
+        // v9: title and URI (empty)
+        // v10: auction ID (0)
+        // v11: bid count (receipts numbered from 0)
+        // v12: listing mode (0 = escrow)
+        // v13: bid gate (none)
+        // v14: collection gate (none)
+        // v15: Dutch curve (0 = English auction)
+        // v16: penny auction settings (none)
+        // v17: vesting schedule (none, proceeds paid at once)
+        // v18: buyout program (none)
+        // v19: rental (0 = sale)
+        // v20: relist settings (no relisting)
+        // v21: escrow reuse (first round, closed at settlement)
+        // v22: partner fee (no partner)
+        // v23: free listing (0 = priced listing)
+        // v24: hidden reserve (none)
+        // v25: bidding phases (gates apply throughout)
+        // v26: reserve waiver (reserves enforced)
+        // v27: buyer's premium (0 = house fee taken from the proceeds)
+        // v28: series (standalone auction)
+        // v29: listing token (none)
+        // v30: bid bond (none)
+        // v31: print edition (0 = the NFT itself sold)
+        // v32: item type (0 = NFT)
+        // v33: arbitration settings (none)
+        // v34: bid affiliate (none)
+        // v35: maximum price (none)
+        // v36: tick size (any bid amount)
+        // v37: bidder bitmap (no bidders seen)
+        // v38: installment plan (paid in full)
+        if escrow_account.version < 8 {
+            // Version 8 added the NFT mint, read from the temporary NFT account (the first remaining
+            // account) while it still holds the NFT. Auctions whose NFT has left escrow do not need it.
+            let status = AuctionStatus::try_from(escrow_account.status)?;
+            let holds_nft = matches!(status, AuctionStatus::Created | AuctionStatus::Live | AuctionStatus::Ended)
+                && escrow_account.item_claimed == 0;
+            if holds_nft {
+                let nft_temp_account = ctx.remaining_accounts.first().ok_or(AuctionError::NftTempAccountMissing)?;
+                require_keys_eq!(
+                    nft_temp_account.key(),
+                    escrow_account.exhibitor_nft_temp_account,
+                    AuctionError::NftTempAccountMissing
+                );
+                escrow_account.nft_mint = Account::<TokenAccount>::try_from(nft_temp_account)?.mint;
+            }
+        }
+        escrow_account.version = AUCTION_VERSION;
 
-        // Set the authority of the bidder's FT account to the PDA.
-        token::set_authority(
-            ctx.accounts.to_set_authority_context(),
-            AuthorityType::AccountOwner,
-            Some(pda)
-        )?;
-        // Transfer the bid amount from the bidder's FT account to the PDA-controlled escrow account.
-        token::transfer(
-            ctx.accounts.to_transfer_to_pda_context(),
-            price,
-        )?;
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_proceeds_account function for the exhibitor to move the proceeds of an auction to
+    // another FT receiving account of theirs once the recorded one has been frozen by the payment mint's
+    // freeze authority, which would otherwise block settlement.
+    pub fn update_proceeds_account(ctx: Context<UpdateProceedsAccount>) -> Result<()> {
+        // Record the new FT receiving account in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.exhibitor_ft_receiving_account =
+            ctx.accounts.new_ft_receiving_account.key();
+
+        // Return an Ok result.
+        Ok(())
+    }
 
-        // Update the escrow account with the new highest bid amount.
-        ctx.accounts.escrow_account.price = price;
-        // Update the escrow account with the new highest bidder's public key.
-        ctx.accounts.escrow_account.highest_bidder_pubkey = ctx.accounts.bidder.key();
-        // Update the escrow account with the new highest bidder's FT temporary account public key.
-        ctx.accounts.escrow_account.highest_bidder_ft_temp_pubkey = ctx.accounts.bidder_ft_temp_account.key();
-        // Update the escrow account with the new highest bidder's FT returning account public key.
-        ctx.accounts.escrow_account.highest_bidder_ft_returning_pubkey = ctx.accounts.bidder_ft_account.key();
+    // Define the set_operator function for the exhibitor to let another account manage the auction
+    // (cancel and update it) on their behalf. The default pubkey removes the operator.
+    pub fn set_operator(ctx: Context<SetOperator>, operator: Pubkey) -> Result<()> {
+        // Record the operator in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.operator = operator;
 
         // Return an Ok result.
         Ok(())
     }
 
-    // Define the close function to close the auction and distribute the assets.
-    pub fn close(ctx: Context<Close>) -> Result<()> {
+    // Define the mint_listing_token function for the exhibitor to mint a listing token to their wallet
+    // while the auction takes bids, so wallets show the escrowed NFT as listed rather than missing. The
+    // token is frozen so it cannot be transferred, and it is burned at settlement or cancellation, or
+    // with burn_listing_token once the auction is otherwise over. Its mint and the exhibitor's token
+    // account are left empty.
+    pub fn mint_listing_token(ctx: Context<MintListingToken>) -> Result<()> {
         // Find the PDA for the escrow account.
         let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
         // Create the seeds for the signer.
-        let signers_seeds: &[&[&[u8]]] = &[&[&ESCROW_PDA_SEED[..], &[bump_seed]]];
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
 
-        // Transfer the NFT from the escrow account to the highest bidder.
-        token::transfer(
-            ctx.accounts
-                .to_transfer_to_highest_bidder_context()
-                .with_signer(signers_seeds),
-            ctx.accounts.exhibitor_nft_temp_account.amount,
-        )?;
+        // Mint the token, let the PDA burn it later as its delegate, then freeze it in the exhibitor's account.
+        token::mint_to(ctx.accounts.to_mint_to_context().with_signer(signers_seeds), 1)?;
+        token::approve(ctx.accounts.to_approve_context(), 1)?;
+        token::freeze_account(ctx.accounts.to_freeze_context().with_signer(signers_seeds))?;
+        // Record the listing token mint in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.listing_token_mint = ctx.accounts.listing_token_mint.key();
 
-        // Transfer the highest bid amount from the escrow account to the exhibitor.
-        token::transfer(
-            ctx.accounts
-                .to_transfer_to_exhibitor_context()
-                .with_signer(signers_seeds),
-            ctx.accounts.highest_bidder_ft_temp_account.amount,
-        )?;
+        // Return an Ok result.
+        Ok(())
+    }
 
-        // Close the highest bidder's temporary FT account.
-        token::close_account(
-            ctx.accounts.to_close_ft_context()
-                .with_signer(signers_seeds),
+    // Define the burn_listing_token function for anyone to burn the listing token of an auction round
+    // that is over without settle or cancel burning it, such as one unwound for an unmet reserve.
+    pub fn burn_listing_token(
+        ctx: Context<BurnListingToken>, // Context for the BurnListingToken struct.
+        _round: u64,                    // Round of the escrow account the token was minted in.
+    ) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        thaw_and_burn(
+            &ctx.accounts.listing_token_mint.to_account_info(),
+            &ctx.accounts.exhibitor_listing_token_account.to_account_info(),
+            &ctx.accounts.pda,
+            &ctx.accounts.token_program.to_account_info(),
+            signers_seeds,
         )?;
 
-        // Close the exhibitor's temporary NFT account.
-        token::close_account(
-            ctx.accounts.to_close_nft_context()
-                .with_signer(signers_seeds),
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_auction function for the exhibitor or its operator to change the starting price
+    // and restart the duration of an auction that has no bids yet.
+    pub fn update_auction(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        initial_price: u64,          // New initial price for the auction.
+        auction_duration_sec: u64,   // New duration of the auction in seconds or slots, counted from now.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the auction's end is not the series' to set.
+        require!(escrow_account.series == Pubkey::default(), AuctionError::AuctionInSeries);
+        // Ensure the new initial price is set unless the auction was listed for free.
+        validate_initial_price(initial_price, escrow_account.free_listing != 0)?;
+        // Ensure the new duration is within the house limits.
+        validate_duration(
+            &ctx.accounts.auction_house,
+            TimingMode::try_from(escrow_account.timing_mode)?,
+            auction_duration_sec,
         )?;
+        // Ensure a Dutch auction still starts at or above its floor price.
+        if escrow_account.is_dutch() {
+            require!(initial_price >= escrow_account.dutch_floor_price, AuctionError::InvalidDutchCurve);
+        }
+        // Set the new initial price for the auction in the escrow account.
+        escrow_account.price = initial_price;
+        // Calculate and set the new auction end in the escrow account, in the auction's timing mode.
+        escrow_account.end_at = escrow_account.end_after(&ctx.accounts.clock, auction_duration_sec)?;
+        // Restart the price decay of a Dutch auction along with its duration.
+        escrow_account.dutch_start_at = escrow_account.now(&ctx.accounts.clock)?;
 
         // Return an Ok result.
         Ok(())
     }
-}
 
-// Define the Exhibit struct with associated accounts and instructions.
-#[derive(Accounts)]
-#[instruction(initial_price: u64, auction_duration_sec: u64)]
-pub struct Exhibit<'info> {
-    // The exhibitor's account, which must be a signer.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    #[account(signer)]
-    pub exhibitor: AccountInfo<'info>,
-    // The exhibitor's NFT account, which must have an amount of 1.
-    #[account(
-        mut,
-        constraint = exhibitor_nft_token_account.amount == 1
-    )]
-    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
-    // The exhibitor's temporary NFT account.
-    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
-    // The exhibitor's FT receiving account.
-    pub exhibitor_ft_receiving_account:Account<'info, TokenAccount>,
-    // The escrow account, which must have a balance of zero.
-    #[account(zero)]
-    pub escrow_account: Box<Account<'info, Auction>>,
-    // The system clock account for getting the current UNIX timestamp.
-    pub clock: Sysvar<'info, Clock>,
-    // The SPL token program account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub token_program: AccountInfo<'info>,
-}
+    // Define the update_auction_metadata function for the exhibitor or its operator to change the title
+    // and URI of an auction that has no bids yet.
+    pub fn update_auction_metadata(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        title: String,               // New title of the lot (empty for none).
+        uri: String,                 // New URI of the lot's description (empty for none).
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Set the new title and URI in the escrow account.
+        escrow_account.title = to_fixed_bytes(&title)?;
+        escrow_account.uri = to_fixed_bytes(&uri)?;
 
-// Define the Cancel struct with associated accounts.
-#[derive(Accounts)]
-pub struct Cancel<'info> {
-    // The exhibitor's account, which must be a signer.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    #[account(signer)]
-    pub exhibitor: AccountInfo<'info>,
-    // The exhibitor's NFT account.
-    #[account(mut)]
-    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
-    // The exhibitor's temporary NFT account.
-    #[account(mut)]
-    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
-    // The escrow account with various constraints.
-    #[account(
-        mut,
-        constraint = escrow_account.exhibitor_pubkey == exhibitor.key(),
-        constraint = escrow_account.highest_bidder_pubkey == exhibitor.key(),
-        constraint = escrow_account.exhibiting_nft_temp_pubkey == exhibitor_nft_temp_account.key(),
-        close = exhibitor
-    )]
-    pub escrow_account: Box<Account<'info, Auction>>,
-    // The PDA account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub pda: AccountInfo<'info>,
-    // The SPL token program account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub token_program: AccountInfo<'info>,
-}
+        // Return an Ok result.
+        Ok(())
+    }
 
-// Define the Bid struct with associated accounts and instructions.
-#[derive(Accounts)]
-#[instruction(price: u64)]
-pub struct Bid<'info> {
-    // The bidder's account, which must be a signer.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    #[account(signer)]
-    pub bidder: AccountInfo<'info>,
-    // The bidder's temporary FT account.
-    #[account(mut)]
-    pub bidder_ft_temp_account: Account<'info, TokenAccount>,
-    // The bidder's FT account, which must have an amount greater than or equal to the bid price.
-    #[account(
-        mut,
-        constraint = bidder_ft_account.amount >= price
-    )]
-    pub bidder_ft_account: Account<'info, TokenAccount>,
-    // The highest bidder's account, which must not be the same as the current bidder.
+    // Define the update_bid_gate function for the exhibitor or its operator to restrict bidding on an
+    // auction that has no bids yet to holders of at least a given amount of a gating mint, such as a
+    // membership token. The default pubkey removes the gate.
+    pub fn update_bid_gate(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        gate_mint: Pubkey,           // Mint bidders must hold (default for no gate).
+        gate_amount: u64,            // Amount of the gating mint bidders must hold.
+    ) -> Result<()> {
+        // Ensure a gate requires holding some of its mint.
+        require!(
+            gate_mint == Pubkey::default() || gate_amount > 0,
+            AuctionError::InvalidBidGate
+        );
+
+        // Record the gate in the escrow account.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        escrow_account.gate_mint = gate_mint;
+        escrow_account.gate_amount = if gate_mint == Pubkey::default() { 0 } else { gate_amount };
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_collection_gate function for the exhibitor or its operator to restrict bidding on
+    // an auction that has no bids yet to holders of any NFT from a verified collection. The default pubkey
+    // removes the gate.
+    pub fn update_collection_gate(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        gate_collection: Pubkey,     // Mint of the collection NFT bidders must hold a member of (default for no gate).
+    ) -> Result<()> {
+        // Record the gate in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.gate_collection = gate_collection;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_bid_phases function for the exhibitor or its operator to split bidding on an
+    // auction that has no bids yet into two phases: from allowlist_at only bidders passing its bid gates may
+    // bid, and from public_at anyone may. Both are in the auction's timing mode. A zero public_at keeps the
+    // gates applying until the end, and a zero allowlist_at opens bidding at once.
+    pub fn update_bid_phases(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        allowlist_at: i64,           // When gated bidders may start bidding (zero for now).
+        public_at: i64,              // When anyone may start bidding (zero for never).
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure bidding does not open before the series opens the lot.
+        require!(escrow_account.series == Pubkey::default(), AuctionError::AuctionInSeries);
+        // Ensure the public phase follows the allowlist phase, and bidding opens before the auction ends.
+        require!(
+            public_at == 0 || allowlist_at <= public_at,
+            AuctionError::InvalidBidPhases
+        );
+        require!(allowlist_at < escrow_account.end_at, AuctionError::InvalidBidPhases);
+        // Record the phases in the escrow account.
+        escrow_account.allowlist_at = allowlist_at;
+        escrow_account.public_at = public_at;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_dutch_curve function for the exhibitor or its operator to turn an auction that has
+    // no bids yet into a Dutch auction, or back into an English one with the None curve. A Dutch auction's
+    // price decays from its initial price to the floor price from now until its end, and its first bid at
+    // or above the current price buys the NFT at that price and ends the auction.
+    pub fn update_dutch_curve(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        curve: DutchCurve,           // How the price decays (None for an English auction).
+        floor_price: u64,            // Lowest price the decay reaches.
+        curve_param: u64,            // Half-life of the exponential curve or step interval of the stepped curve.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Record the curve, starting the decay now. An English auction keeps no decay parameters.
+        let dutch = curve != DutchCurve::None;
+        // Ensure the house offers Dutch auctions.
+        if dutch {
+            ctx.accounts.auction_house.require_enabled(FEATURE_DUTCH)?;
+        }
+        // Ensure a penny auction is not turned into a Dutch auction.
+        require!(!dutch || !escrow_account.is_penny(), AuctionError::InvalidDutchCurve);
+        escrow_account.dutch_curve = curve as u8;
+        escrow_account.dutch_floor_price = if dutch { floor_price } else { 0 };
+        escrow_account.dutch_start_at = escrow_account.now(&ctx.accounts.clock)?;
+        escrow_account.dutch_curve_param = if dutch { curve_param } else { 0 };
+        // Ensure the decay is one the auction can follow.
+        escrow_account.dutch_pricing().validate()?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_penny_auction function for the exhibitor or its operator to turn an auction that
+    // has no bids yet into a penny auction, or back into an English one with a zero tick. Every bid on a
+    // penny auction pays a flat fee to the treasury, raises the price by exactly the tick, and resets the
+    // countdown to the end of the auction.
+    pub fn update_penny_auction(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        tick: u64,                   // Amount every bid raises the price by (zero for an English auction).
+        bid_fee: u64,                // Fee every bid pays to the treasury.
+        countdown: u64,              // Time left after a bid, in seconds or slots.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure a penny auction counts down after bids and is not also a Dutch auction.
+        let penny = tick > 0;
+        if penny {
+            ctx.accounts.auction_house.require_enabled(FEATURE_PENNY)?;
+        }
+        require!(!penny || countdown > 0, AuctionError::InvalidPennyAuction);
+        require!(!penny || !escrow_account.is_dutch(), AuctionError::InvalidPennyAuction);
+        // Record the settings. An English auction keeps none.
+        escrow_account.penny_tick = tick;
+        escrow_account.penny_bid_fee = if penny { bid_fee } else { 0 };
+        escrow_account.penny_countdown = if penny { countdown } else { 0 };
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_bid_bond function for the exhibitor or its operator to make every bid on an auction
+    // that has no bids yet pay a bond to the treasury, deterring spam and sniping bids. The bond is either a
+    // flat amount or a share of the bid, and is not refunded when the bidder is outbid. Zero for both
+    // removes it.
+    pub fn update_bid_bond(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        amount: u64,                 // Flat bond every bid pays (zero for a share of the bid or no bond).
+        bps: u16,                    // Share of the bid every bid pays in basis points (zero for a flat bond).
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the bond is either flat or a share of the bid, and the share not more than the bid.
+        require!(amount == 0 || bps == 0, AuctionError::InvalidBidBond);
+        require!(bps as u64 <= BASIS_POINTS, AuctionError::InvalidBidBond);
+        // Record the bond.
+        escrow_account.bid_bond = amount;
+        escrow_account.bid_bond_bps = bps;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_print_sale function for the exhibitor or its operator to sell a numbered print of
+    // an escrowed master edition that has no bids yet, instead of the master edition itself. The winning
+    // bidder claims the print with claim_print, which has the metadata program print the edition to a new
+    // mint of theirs, and the master edition goes back to the exhibitor. Zero sells the NFT itself again.
+    pub fn update_print_sale(
+        ctx: Context<UpdatePrintSale>, // Context for the UpdatePrintSale struct.
+        edition: u64,                  // Number of the edition to print (zero to sell the NFT itself).
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the NFT is a master edition held in escrow, since the PDA signs for the print as the owner
+        // of its token account, and that it is not rented out instead.
+        if edition > 0 {
+            require!(escrow_account.listing_mode()? == ListingMode::Escrow, AuctionError::InvalidPrintSale);
+            require!(escrow_account.rental == Pubkey::default(), AuctionError::InvalidPrintSale);
+            require!(
+                metadata::is_master_edition(&ctx.accounts.master_edition)?,
+                AuctionError::NotAMasterEdition
+            );
+        }
+        // Record the edition to print.
+        escrow_account.print_edition = edition;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_relist function for the exhibitor or its operator to let an auction that has no
+    // bids yet be restarted in place by relist if it ends without a bid, up to a number of times, each time
+    // for the relist duration and at a price reduced by a share of the previous one. An opted-in crank may
+    // relist it too; the default pubkey leaves relisting to the exhibitor and its operator. A zero
+    // maximum turns relisting off.
+    pub fn update_relist(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        crank: Pubkey,               // Account also allowed to relist the auction (default for none).
+        duration_sec: u64,           // Duration of every relisted auction in seconds or slots.
+        reduction_bps: u16,          // Share the price drops by on every relist, in basis points.
+        max_relists: u16,            // How many times the auction may be relisted (zero for never).
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure relisted auctions last within the house limits and the price does not drop below zero.
+        if max_relists > 0 {
+            validate_duration(
+                &ctx.accounts.auction_house,
+                TimingMode::try_from(escrow_account.timing_mode)?,
+                duration_sec,
+            )?;
+        }
+        require!(reduction_bps as u64 <= BASIS_POINTS, AuctionError::InvalidRelist);
+        // Record the settings. An auction that is never relisted keeps none.
+        let relisted = max_relists > 0;
+        escrow_account.relist_crank = if relisted { crank } else { Pubkey::default() };
+        escrow_account.relist_duration = if relisted { duration_sec } else { 0 };
+        escrow_account.relist_reduction_bps = if relisted { reduction_bps } else { 0 };
+        escrow_account.max_relists = max_relists;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_escrow_reuse function for the exhibitor or its operator to keep the escrow account
+    // of an auction that has no bids yet open once it is sold, so reuse_escrow can list another item in it
+    // without paying its rent again.
+    pub fn update_escrow_reuse(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        reuse: bool,                 // Whether to keep the escrow account for another item.
+    ) -> Result<()> {
+        // Record the choice in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.reuse_escrow = reuse as u8;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_hidden_reserve function for the exhibitor or its operator to give an auction that
+    // has no bids yet a reserve bidders cannot see, committed as reserve_commitment(reserve, salt). Once
+    // the auction ends, reveal_reserve discloses it and settlement enforces it. A zero commitment removes
+    // the hidden reserve.
+    pub fn update_hidden_reserve(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        commitment: [u8; 32],        // Hash of the reserve and a secret salt.
+    ) -> Result<()> {
+        // Ensure the auction can be unwound if the reserve is not met, which settle_unmet_reserve only does
+        // for NFTs.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        require!(escrow_account.item_type()? == ItemType::Nft, AuctionError::DomainUnsupported);
+        // Record the commitment in the escrow account.
+        escrow_account.hidden_reserve_commitment = commitment;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_max_price function for the exhibitor or its operator to give an auction that has no
+    // bids yet a maximum price, for fixed-value redemptions: a bid reaching it ends the auction at once, so
+    // the winner or the keeper can settle it, and a higher bid only pays the maximum price. A zero maximum
+    // price removes it.
+    pub fn update_max_price(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        max_price: u64,              // Price at which a bid wins outright (zero for none).
+    ) -> Result<()> {
+        // Ensure the maximum price is above the initial price, so the auction still takes bids.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        require!(max_price == 0 || max_price > escrow_account.price, AuctionError::InvalidMaxPrice);
+        // Record the maximum price in the escrow account.
+        escrow_account.max_price = max_price;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_tick_size function for the exhibitor or its operator to make the English bids on an
+    // auction that has no bids yet multiples of a tick size, such as one whole USDC, sparing bidders dust
+    // increments. A zero tick size removes it.
+    pub fn update_tick_size(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        tick_size: u64,              // Amount every bid must be a multiple of (zero for none).
+    ) -> Result<()> {
+        // Record the tick size in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.tick_size = tick_size;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_installments function for the exhibitor or its operator to let the winner of an
+    // NFT auction that has no bids yet pay in installments: bids only escrow the down payment, and after
+    // the end the winner pays the rest in equal installments with pay_installment, the NFT staying in
+    // escrow until the auction settles, which it may only once fully paid. If an installment is missed,
+    // default_installments sends the NFT back to the exhibitor, who keeps part of the payments. A zero
+    // installment count removes the plan.
+    pub fn update_installments(
+        ctx: Context<UpdateAuction>,   // Context for the UpdateAuction struct.
+        installment_count: u8,         // Number of installments after the down payment (zero for none).
+        installment_interval_sec: u64, // Time between installments, in seconds.
+        down_payment_bps: u16,         // Share of the purchase amount paid upfront in basis points.
+        default_forfeit_bps: u16,      // Share of the payments the exhibitor keeps on default in basis points.
+    ) -> Result<()> {
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        if installment_count > 0 {
+            // Ensure the NFT is escrowed, so a default can return it, and the installments fall due in
+            // seconds after a timestamp end.
+            require!(escrow_account.item_type()? == ItemType::Nft, AuctionError::DomainUnsupported);
+            require!(
+                escrow_account.listing_mode()? == ListingMode::Escrow
+                    && escrow_account.timing_mode == TimingMode::Timestamp as u8,
+                AuctionError::InvalidInstallmentPlan
+            );
+            // Ensure the down payment is part of the purchase amount and the plan has a schedule.
+            require!(
+                installment_interval_sec > 0
+                    && down_payment_bps > 0
+                    && (down_payment_bps as u64) < BASIS_POINTS
+                    && default_forfeit_bps as u64 <= BASIS_POINTS,
+                AuctionError::InvalidInstallmentPlan
+            );
+        }
+        // Record the installment plan in the escrow account.
+        let has_plan = installment_count > 0;
+        escrow_account.installment_count = installment_count;
+        escrow_account.installment_interval_sec = if has_plan { installment_interval_sec } else { 0 };
+        escrow_account.down_payment_bps = if has_plan { down_payment_bps } else { 0 };
+        escrow_account.default_forfeit_bps = if has_plan { default_forfeit_bps } else { 0 };
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_arbitration function for the exhibitor or its operator to put an NFT auction that
+    // has no bids yet in arbitration mode, for high-value sales backed by a physical item. Once finalized,
+    // its settlement is held for the dispute window, during which the arbiter may reverse the sale with
+    // reverse_sale: the NFT goes back to the exhibitor and the winning bid back to the bidder. Such auctions
+    // are settled with finalize and the claims rather than close. A zero window turns arbitration off.
+    pub fn update_arbitration(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        arbiter: Pubkey,             // Account allowed to reverse the sale.
+        dispute_window_sec: u64,     // How long settlement is held after finalize (zero for no arbitration).
+    ) -> Result<()> {
+        // Ensure the sale can be reversed, which reverse_sale only does for NFTs, and the window is bounded.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        require!(escrow_account.item_type()? == ItemType::Nft, AuctionError::DomainUnsupported);
+        require!(dispute_window_sec <= MAX_DISPUTE_WINDOW_SEC, AuctionError::InvalidDisputeWindow);
+        require!(
+            dispute_window_sec == 0 || arbiter != Pubkey::default(),
+            AuctionError::InvalidDisputeWindow
+        );
+        // Record the arbitration settings in the escrow account.
+        escrow_account.arbiter = if dispute_window_sec > 0 { arbiter } else { Pubkey::default() };
+        escrow_account.dispute_window_sec = dispute_window_sec;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the reveal_reserve function to disclose the hidden reserve of an ended auction. Anyone holding
+    // the reserve and salt may call it, since only they match the commitment. Settlement waits for the
+    // reveal for RESERVE_REVEAL_PERIOD_SEC after the end; a reserve still hidden then is forfeited and the
+    // highest bid wins. A revealed reserve the highest bid falls short of is unwound by
+    // settle_unmet_reserve.
+    pub fn reveal_reserve(
+        ctx: Context<RevealReserve>, // Context for the RevealReserve struct.
+        reserve: u64,                // The hidden reserve.
+        salt: [u8; 32],              // The salt the reserve was committed with.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the reserve is the committed one.
+        require!(
+            escrow_account.hidden_reserve_commitment != [0; 32]
+                && reserve_commitment(reserve, &salt) == escrow_account.hidden_reserve_commitment,
+            AuctionError::InvalidReserveReveal
+        );
+        // Record the revealed reserve in the escrow account.
+        escrow_account.hidden_reserve = reserve;
+        escrow_account.hidden_reserve_revealed = 1;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the accept_highest_bid function for the exhibitor or its operator to sell an ended auction at
+    // its highest bid even though it falls short of the USD or hidden reserve, rather than unwinding and
+    // relisting it. It must be called within ACCEPTANCE_PERIOD_SEC of the end, before which
+    // settle_unmet_reserve waits; the auction then settles as usual.
+    pub fn accept_highest_bid(ctx: Context<AcceptHighestBid>) -> Result<()> {
+        // Waive the reserves in the escrow account.
+        ctx.accounts.escrow_account.load_mut()?.reserve_waived = 1;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the enable_vesting function for the exhibitor or its operator to vest the exhibitor's proceeds
+    // of an auction that has no bids yet, instead of paying them at settlement. Settlement deposits them
+    // in a vault, from which claim_vested releases them to the exhibitor's FT receiving account: nothing
+    // before the cliff, then linearly until the vesting duration has passed since settlement.
+    pub fn enable_vesting(
+        ctx: Context<EnableVesting>, // Context for the EnableVesting struct.
+        cliff_sec: u64,              // Time after settlement before anything vests, in seconds.
+        duration_sec: u64,           // Time after settlement until everything has vested, in seconds.
+    ) -> Result<()> {
+        // Ensure the schedule ends, and not before its cliff.
+        require!(duration_sec > 0 && cliff_sec <= duration_sec, AuctionError::InvalidVestingSchedule);
+        // Ensure the proceeds do not already go to a fractionalization vault.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        require_keys_eq!(escrow_account.buyout_program, Pubkey::default(), AuctionError::InvalidVestingSchedule);
+
+        // Record the schedule, which starts when the auction is settled.
+        let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+        vesting_schedule.auction = ctx.accounts.escrow_account.key();
+        vesting_schedule.beneficiary_ft_pubkey = escrow_account.exhibitor_ft_receiving_account;
+        vesting_schedule.vault = ctx.accounts.vesting_vault.key();
+        vesting_schedule.rent_payer = ctx.accounts.payer.key();
+        vesting_schedule.cliff_sec = cliff_sec;
+        vesting_schedule.duration_sec = duration_sec;
+        vesting_schedule.start_at = 0;
+        vesting_schedule.total = 0;
+        vesting_schedule.claimed = 0;
+        vesting_schedule.bump = *ctx.bumps.get("vesting_schedule").unwrap();
+        escrow_account.vesting_schedule = vesting_schedule.key();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_vested function for anyone to release the vested proceeds of a settled auction to
+    // the exhibitor's FT receiving account. Once everything is claimed, or the auction ended without a sale
+    // and its escrow account is gone, the vault and schedule are closed to their rent payer.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_pda, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        let vesting_schedule = &ctx.accounts.vesting_schedule;
+        let funded = vesting_schedule.start_at != 0;
+        if funded {
+            // Release what has vested since the last claim.
+            let claimable = vesting_schedule
+                .vested(ctx.accounts.clock.unix_timestamp)?
+                .checked_sub(vesting_schedule.claimed)
+                .ok_or(AuctionError::NumericalOverflow)?;
+            require!(claimable > 0, AuctionError::NothingVested);
+            token::transfer(
+                ctx.accounts.to_transfer_context().with_signer(signers_seeds),
+                claimable,
+            )?;
+            ctx.accounts.vesting_schedule.claimed = ctx.accounts.vesting_schedule.claimed
+                .checked_add(claimable)
+                .ok_or(AuctionError::NumericalOverflow)?;
+        } else {
+            // A schedule is only left unfunded by an auction that closed without a sale.
+            require!(ctx.accounts.escrow_account.data_is_empty(), AuctionError::VestingNotStarted);
+        }
+
+        // Close the vault and the schedule once there is nothing left to release.
+        let vesting_schedule = &ctx.accounts.vesting_schedule;
+        if vesting_schedule.claimed == vesting_schedule.total {
+            token::close_account(ctx.accounts.to_close_context().with_signer(signers_seeds))?;
+            ctx.accounts.vesting_schedule.close(ctx.accounts.rent_payer.clone())?;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the enable_buyout function for a fractionalization vault PDA that exhibited its NFT, signing
+    // through its program, to have its auction settle as a buyout: once the proceeds are paid to the
+    // vault's FT receiving account, settlement calls the vault program's settle_buyout instruction (see
+    // the fractions module) so the fraction holders can redeem them pro rata.
+    pub fn enable_buyout(
+        ctx: Context<UpdateAuction>, // Context for the UpdateAuction struct.
+        vault_program: Pubkey,       // Program owning the vault, which implements settle_buyout.
+    ) -> Result<()> {
+        // Ensure the house offers buyouts.
+        ctx.accounts.auction_house.require_enabled(FEATURE_BUYOUTS)?;
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the vault itself, a PDA, opts in, and that its proceeds do not vest.
+        require_keys_eq!(ctx.accounts.authority.key(), escrow_account.exhibitor, AuctionError::Unauthorized);
+        require!(!escrow_account.exhibitor.is_on_curve(), AuctionError::InvalidBuyoutVault);
+        require_keys_eq!(escrow_account.vesting_schedule, Pubkey::default(), AuctionError::InvalidBuyoutVault);
+        // Record the vault program.
+        escrow_account.buyout_program = vault_program;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the enable_rental function for the exhibitor or its operator to auction time-limited usage
+    // rights to an escrowed NFT that has no bids yet, instead of the NFT itself. Settlement leaves the NFT
+    // in escrow and records the winning bidder as its renter in the rental until the rental duration has
+    // passed, after which end_rental returns the NFT to the exhibitor.
+    pub fn enable_rental(
+        ctx: Context<EnableRental>, // Context for the EnableRental struct.
+        duration_sec: u64,          // How long the winning bidder rents the NFT for, in seconds.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the rental ends, and that the NFT is held in escrow to be returned from.
+        require!(duration_sec > 0, AuctionError::InvalidRental);
+        require!(escrow_account.listing_mode()? == ListingMode::Escrow, AuctionError::InvalidRental);
+        require!(escrow_account.print_edition == 0, AuctionError::InvalidRental);
+        require!(escrow_account.item_type()? == ItemType::Nft, AuctionError::DomainUnsupported);
+
+        // Record the rental, which starts when the auction is settled.
+        let rental = &mut ctx.accounts.rental;
+        rental.auction = ctx.accounts.escrow_account.key();
+        rental.owner = escrow_account.exhibitor;
+        rental.nft_mint = escrow_account.nft_mint;
+        rental.nft_account = escrow_account.exhibitor_nft_temp_account;
+        rental.renter = Pubkey::default();
+        rental.rent_payer = ctx.accounts.payer.key();
+        rental.nft_rent_destination = Pubkey::default();
+        rental.duration_sec = duration_sec;
+        rental.ends_at = 0;
+        rental.bump = *ctx.bumps.get("rental").unwrap();
+        escrow_account.rental = rental.key();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the end_rental function for anyone to return a rented NFT to its owner once the rental is
+    // over, closing the temporary NFT account and the rental. A rental that never started, because its
+    // auction closed without a sale and the NFT went back already, is just closed.
+    pub fn end_rental(ctx: Context<EndRental>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_pda, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        let rental = &ctx.accounts.rental;
+        if rental.renter != Pubkey::default() {
+            // Ensure the rental is over and the accounts are the owner's and the recorded ones.
+            require!(ctx.accounts.clock.unix_timestamp >= rental.ends_at, AuctionError::RentalNotOver);
+            require_keys_eq!(
+                ctx.accounts.nft_rent_destination.key(),
+                rental.nft_rent_destination,
+                AuctionError::RentalAccountMismatch
+            );
+            let owner_nft_account = Account::<TokenAccount>::try_from(&ctx.accounts.owner_nft_account)?;
+            require_keys_eq!(owner_nft_account.owner, rental.owner, AuctionError::RentalAccountMismatch);
+            require_keys_eq!(owner_nft_account.mint, rental.nft_mint, AuctionError::NftMintMismatch);
+            let nft_account = Account::<TokenAccount>::try_from(&ctx.accounts.nft_account)?;
+
+            // Return the NFT to its owner and close the temporary NFT account.
+            token::transfer(
+                ctx.accounts.to_transfer_context().with_signer(signers_seeds),
+                nft_account.amount,
+            )?;
+            token::close_account(ctx.accounts.to_close_context().with_signer(signers_seeds))?;
+        } else {
+            // A rental is only left unstarted by an auction that closed without a sale.
+            require!(ctx.accounts.escrow_account.data_is_empty(), AuctionError::RentalNotOver);
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the exhibit_from_governance function for a DAO to list an NFT held by its governance's native
+    // treasury. The governance program signs for the treasury when it executes an approved proposal, and
+    // the proceeds go to the treasury's ATA.
+    pub fn exhibit_from_governance<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExhibitFromGovernance<'info>>, // Context for the ExhibitFromGovernance struct.
+        initial_price: u64,    // Initial price for the auction.
+        auction_duration_sec: u64, // Duration of the auction in seconds or slots.
+        payouts: Vec<Payout>,  // Additional recipients of the proceeds and their shares.
+        charity: Option<Pubkey>, // Optional FT receiving account of a charity.
+        charity_bps: u16,      // The charity's share of the proceeds in basis points.
+        reserve_usd_cents: u64, // Optional reserve price in US cents (zero for no reserve).
+        reserve_price_feed: Pubkey, // Pyth price feed of the payment token in USD.
+        timing_mode: TimingMode, // Whether the duration and end of the auction are in seconds or slots.
+        title: String,         // Optional title of the lot (empty for none).
+        uri: String,           // Optional URI of the lot's description (empty for none).
+        listing_mode: ListingMode, // Whether the NFT moves into escrow or stays in the treasury.
+        partner: Option<Pubkey>, // Optional FT account of the partner frontend sharing the house fee.
+        free_listing: bool,    // Whether the auction may start at a zero price.
+    ) -> Result<()> {
+        // The governance accounts are checked by the constraints, so list the NFT like any exhibitor.
+        exhibit(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.exhibit,
+                ctx.remaining_accounts,
+                ctx.bumps,
+            ),
+            initial_price,
+            auction_duration_sec,
+            payouts,
+            charity,
+            charity_bps,
+            reserve_usd_cents,
+            reserve_price_feed,
+            timing_mode,
+            title,
+            uri,
+            listing_mode,
+            partner,
+            free_listing,
+        )
+    }
+
+    // Define the exhibit_many function for an exhibitor to list several NFTs with shared settings in one
+    // transaction, such as when migrating a collection onto the marketplace. The exhibit accounts of each
+    // NFT are passed as a group of remaining accounts, in the order of the Exhibit struct, and validated
+    // and listed exactly as by exhibit, without payouts, charity, reserve or metadata. The auctions are
+    // assigned consecutive IDs.
+    pub fn exhibit_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExhibitMany<'info>>, // Context for the ExhibitMany struct.
+        initial_price: u64,        // Initial price for every auction.
+        auction_duration_sec: u64, // Duration of every auction in seconds or slots.
+        timing_mode: TimingMode,   // Whether the duration and end of the auctions are in seconds or slots.
+        listing_mode: ListingMode, // Whether the NFTs move into escrow or stay in the exhibitor's wallet.
+        free_listing: bool,        // Whether the auctions may start at a zero price.
+    ) -> Result<()> {
+        // The Exhibit struct reads its instruction arguments from the instruction data.
+        let ix_data = (initial_price, auction_duration_sec).try_to_vec()?;
+        let mut remaining_accounts = ctx.remaining_accounts;
+        let mut listed = 0;
+        while !remaining_accounts.is_empty() {
+            // Ensure the batch fits the limit.
+            require!(listed < MAX_EXHIBIT_BATCH, AuctionError::TooManyExhibits);
+            // Validate the next group of exhibit accounts, creating its escrow account and listing receipt.
+            let mut bumps = BTreeMap::new();
+            let mut accounts = Exhibit::try_accounts(ctx.program_id, &mut remaining_accounts, &ix_data, &mut bumps)?;
+            // Ensure every NFT is listed by the signing exhibitor.
+            require_keys_eq!(accounts.exhibitor.key(), ctx.accounts.exhibitor.key(), AuctionError::Unauthorized);
+            // List the NFT like any exhibitor, then write back the accounts, including the registry the
+            // next group is numbered from.
+            exhibit(
+                Context::new(ctx.program_id, &mut accounts, &[], bumps),
+                initial_price,
+                auction_duration_sec,
+                Vec::new(),
+                None,
+                0,
+                0,
+                Pubkey::default(),
+                timing_mode,
+                String::new(),
+                String::new(),
+                listing_mode,
+                None,
+                free_listing,
+            )?;
+            accounts.exit(ctx.program_id)?;
+            listed += 1;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the exhibit_domain function to exhibit a Solana Name Service domain for auction. The domain is
+    // escrowed by making the PDA its owner, and goes to the winning bidder through claim_domain once the
+    // auction is finalized (see init_authority_auction).
+    pub fn exhibit_domain(
+        ctx: Context<ExhibitDomain>, // Context for the ExhibitDomain struct.
+        initial_price: u64,          // Initial price for the auction.
+        auction_duration_sec: u64,   // Duration of the auction in seconds or slots.
+        timing_mode: TimingMode,     // Whether the duration and end of the auction are in seconds or slots.
+        title: String,               // Optional title of the lot (empty for none).
+        uri: String,                 // Optional URI of the lot's description (empty for none).
+        free_listing: bool,          // Whether the auction may start at a zero price.
+    ) -> Result<()> {
+        // Ensure the house auctions domains, and that it is not scoped to an NFT collection.
+        ctx.accounts.auction_house.require_enabled(FEATURE_DOMAINS)?;
+        require_keys_eq!(
+            ctx.accounts.auction_house.collection,
+            Pubkey::default(),
+            AuctionError::NotInHouseCollection
+        );
+        // Ensure the initial price is set unless the listing is free.
+        validate_initial_price(initial_price, free_listing)?;
+        // Ensure the duration is within the house limits.
+        validate_duration(&ctx.accounts.auction_house, timing_mode, auction_duration_sec)?;
+        // Ensure the exhibitor owns the domain.
+        require_keys_eq!(
+            sns::name_owner(&ctx.accounts.name_account)?,
+            ctx.accounts.exhibitor.key(),
+            AuctionError::Unauthorized
+        );
+        // Charge the house's listing fee to the payer, unless the exhibitor is an approved seller.
+        let listing_fee = ctx.accounts.auction_house.listing_fee_lamports;
+        if listing_fee > 0 && *ctx.accounts.seller_allowlist_entry.owner != crate::ID {
+            system_program::transfer(ctx.accounts.to_transfer_listing_fee_context(), listing_fee)?;
+        }
+
+        // Assign the auction the next ID, and set up its escrow account and listing receipt.
+        let auction_id = ctx.accounts.registry.next_auction_id()?;
+        ctx.accounts.registry.auction_count = auction_id;
+        init_authority_auction(
+            &mut *ctx.accounts.escrow_account.load_init()?,
+            &mut ctx.accounts.listing_receipt,
+            ctx.accounts.escrow_account.key(),
+            auction_id,
+            ItemType::Domain,
+            ctx.accounts.name_account.key(),
+            ctx.accounts.exhibitor.key(),
+            ctx.accounts.payer.key(),
+            &ctx.accounts.exhibitor_ft_receiving_account,
+            &ctx.accounts.auction_house,
+            &ctx.accounts.clock,
+            AuthorityListing { initial_price, auction_duration_sec, timing_mode, title, uri, free_listing },
+            *ctx.bumps.get("listing_receipt").unwrap(),
+        )?;
+
+        // Escrow the domain by making the PDA its owner, the exhibitor signing the transfer.
+        sns::transfer_name(
+            &ctx.accounts.name_account,
+            &ctx.accounts.exhibitor,
+            &ctx.accounts.pda.key(),
+            &ctx.accounts.name_service_program,
+            &[],
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the exhibit_stake function to exhibit a stake account for auction, delegated or not. The stake
+    // account is escrowed by making the PDA its staker and withdrawer, and goes to the winning bidder
+    // through claim_stake once the auction is finalized (see init_authority_auction). Its lockup must not
+    // be in force, since the withdrawer could then only change with the custodian's signature.
+    pub fn exhibit_stake(
+        ctx: Context<ExhibitStake>, // Context for the ExhibitStake struct.
+        initial_price: u64,         // Initial price for the auction.
+        auction_duration_sec: u64,  // Duration of the auction in seconds or slots.
+        timing_mode: TimingMode,    // Whether the duration and end of the auction are in seconds or slots.
+        title: String,              // Optional title of the lot (empty for none).
+        uri: String,                // Optional URI of the lot's description (empty for none).
+        free_listing: bool,         // Whether the auction may start at a zero price.
+    ) -> Result<()> {
+        // Ensure the house auctions stake accounts, and that it is not scoped to an NFT collection.
+        ctx.accounts.auction_house.require_enabled(FEATURE_STAKE)?;
+        require_keys_eq!(
+            ctx.accounts.auction_house.collection,
+            Pubkey::default(),
+            AuctionError::NotInHouseCollection
+        );
+        // Ensure the initial price is set unless the listing is free.
+        validate_initial_price(initial_price, free_listing)?;
+        // Ensure the duration is within the house limits.
+        validate_duration(&ctx.accounts.auction_house, timing_mode, auction_duration_sec)?;
+        // Ensure the exhibitor holds both authorities of the stake account, and its lockup is over.
+        let meta = stake::load_stake_meta(&ctx.accounts.stake_account)?;
+        require_keys_eq!(meta.authorized.staker, ctx.accounts.exhibitor.key(), AuctionError::Unauthorized);
+        require_keys_eq!(meta.authorized.withdrawer, ctx.accounts.exhibitor.key(), AuctionError::Unauthorized);
+        require!(!meta.lockup.is_in_force(&ctx.accounts.clock, None), AuctionError::StakeLockedUp);
+        // Charge the house's listing fee to the payer, unless the exhibitor is an approved seller.
+        let listing_fee = ctx.accounts.auction_house.listing_fee_lamports;
+        if listing_fee > 0 && *ctx.accounts.seller_allowlist_entry.owner != crate::ID {
+            system_program::transfer(ctx.accounts.to_transfer_listing_fee_context(), listing_fee)?;
+        }
+
+        // Assign the auction the next ID, and set up its escrow account and listing receipt.
+        let auction_id = ctx.accounts.registry.next_auction_id()?;
+        ctx.accounts.registry.auction_count = auction_id;
+        init_authority_auction(
+            &mut *ctx.accounts.escrow_account.load_init()?,
+            &mut ctx.accounts.listing_receipt,
+            ctx.accounts.escrow_account.key(),
+            auction_id,
+            ItemType::Stake,
+            ctx.accounts.stake_account.key(),
+            ctx.accounts.exhibitor.key(),
+            ctx.accounts.payer.key(),
+            &ctx.accounts.exhibitor_ft_receiving_account,
+            &ctx.accounts.auction_house,
+            &ctx.accounts.clock,
+            AuthorityListing { initial_price, auction_duration_sec, timing_mode, title, uri, free_listing },
+            *ctx.bumps.get("listing_receipt").unwrap(),
+        )?;
+
+        // Escrow the stake account by making the PDA its staker and withdrawer, the exhibitor signing.
+        stake::authorize_both(
+            &ctx.accounts.stake_account,
+            &ctx.accounts.exhibitor,
+            &ctx.accounts.pda.key(),
+            &ctx.accounts.clock.to_account_info(),
+            &ctx.accounts.stake_program,
+            &[],
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the cancel function to cancel an ongoing auction.
+    pub fn cancel<'info>(ctx: Context<'_, '_, '_, 'info, Cancel<'info>>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        // Burn the exhibitor's listing token, whose accounts are passed as remaining accounts.
+        let escrow_account = *ctx.accounts.escrow_account.load()?;
+        if escrow_account.listing_token_mint != Pubkey::default() {
+            burn_listing_token_of(
+                &escrow_account,
+                ctx.remaining_accounts,
+                &ctx.accounts.pda,
+                &ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        }
+
+        match ctx.accounts.escrow_account.load()?.listing_mode()? {
+            ListingMode::Escrow => {
+                // Transfer the NFT back to the exhibitor.
+                token::transfer(
+                    ctx.accounts
+                        .to_transfer_to_exhibitor_context()
+                        .with_signer(signers_seeds),
+                    ctx.accounts.exhibitor_nft_temp_account.amount
+                )?;
+
+                // Close the PDA-controlled escrow account.
+                token::close_account(
+                    ctx.accounts
+                        .to_close_context()
+                        .with_signer(signers_seeds)
+                )?;
+            }
+            // The NFT never left the exhibitor's wallet. Revoke the PDA's delegation when the owner signed;
+            // an operator cannot, and the owner can revoke it later.
+            ListingMode::Delegate => {
+                if ctx.accounts.exhibitor_nft_temp_account.owner == ctx.accounts.authority.key() {
+                    token::revoke(ctx.accounts.to_revoke_context())?;
+                }
+            }
+        }
+
+        // Mark the auction as cancelled, in its listing receipt too.
+        ctx.accounts.escrow_account.load_mut()?.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the expire_auction function for anyone to wind up an auction that ended without a bid, so the
+    // exhibitor need not sign cancel to get the NFT back. The NFT goes back to the exhibitor, in the delegate
+    // listing mode by leaving it where it is, and the escrow account closes to its recorded rent payer. The
+    // listing token's accounts are passed as remaining accounts, as for cancel.
+    pub fn expire_auction<'info>(ctx: Context<'_, '_, '_, 'info, ExpireAuction<'info>>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        // Burn the exhibitor's listing token, whose accounts are passed as remaining accounts.
+        let escrow_account = *ctx.accounts.escrow_account.load()?;
+        if escrow_account.listing_token_mint != Pubkey::default() {
+            burn_listing_token_of(
+                &escrow_account,
+                ctx.remaining_accounts,
+                &ctx.accounts.pda,
+                &ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        }
+
+        // The NFT of a delegate listing never left the exhibitor's wallet, and only its owner can revoke the
+        // PDA's delegation.
+        if escrow_account.listing_mode()? == ListingMode::Escrow {
+            // Transfer the NFT back to the exhibitor.
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_to_exhibitor_context()
+                    .with_signer(signers_seeds),
+                ctx.accounts.exhibitor_nft_temp_account.amount
+            )?;
+
+            // Close the PDA-controlled escrow account.
+            token::close_account(
+                ctx.accounts
+                    .to_close_context()
+                    .with_signer(signers_seeds)
+            )?;
+        }
+
+        // Mark the auction as cancelled, in its listing receipt too.
+        ctx.accounts.escrow_account.load_mut()?.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the cancel_domain function to cancel a domain auction without bids, before or after its end,
+    // giving the domain back to the exhibitor. The listing token's accounts are passed as remaining
+    // accounts, as for cancel.
+    pub fn cancel_domain<'info>(ctx: Context<'_, '_, '_, 'info, CancelDomain<'info>>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        // Burn the exhibitor's listing token, whose accounts are passed as remaining accounts.
+        let escrow_account = *ctx.accounts.escrow_account.load()?;
+        if escrow_account.listing_token_mint != Pubkey::default() {
+            burn_listing_token_of(
+                &escrow_account,
+                ctx.remaining_accounts,
+                &ctx.accounts.pda,
+                &ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        }
+
+        // Give the domain back to the exhibitor, the PDA signing as its owner.
+        sns::transfer_name(
+            &ctx.accounts.name_account,
+            &ctx.accounts.pda,
+            &escrow_account.exhibitor,
+            &ctx.accounts.name_service_program,
+            signers_seeds,
+        )?;
+
+        // Mark the auction as cancelled, in its listing receipt too.
+        ctx.accounts.escrow_account.load_mut()?.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the cancel_stake function to cancel a stake account auction without bids, before or after its
+    // end, giving the stake account's authorities back to the exhibitor. The listing token's accounts are
+    // passed as remaining accounts, as for cancel.
+    pub fn cancel_stake<'info>(ctx: Context<'_, '_, '_, 'info, CancelStake<'info>>) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (_, bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+        // Create the seeds for the signer.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[bump_seed]]];
+
+        // Burn the exhibitor's listing token, whose accounts are passed as remaining accounts.
+        let escrow_account = *ctx.accounts.escrow_account.load()?;
+        if escrow_account.listing_token_mint != Pubkey::default() {
+            burn_listing_token_of(
+                &escrow_account,
+                ctx.remaining_accounts,
+                &ctx.accounts.pda,
+                &ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        }
+
+        // Make the exhibitor the staker and withdrawer again, the PDA signing as both.
+        stake::authorize_both(
+            &ctx.accounts.stake_account,
+            &ctx.accounts.pda,
+            &escrow_account.exhibitor,
+            &ctx.accounts.clock.to_account_info(),
+            &ctx.accounts.stake_program,
+            signers_seeds,
+        )?;
+
+        // Mark the auction as cancelled, in its listing receipt too.
+        ctx.accounts.escrow_account.load_mut()?.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the relist function for the exhibitor, its operator or the opted-in crank to restart an
+    // auction that ended without a bid in place, leaving the NFT where it is. The price drops by the
+    // relist reduction, never below a Dutch auction's floor price, and the auction runs for the relist
+    // duration from now.
+    pub fn relist(ctx: Context<Relist>) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the auction may be relisted again, for a duration the house still allows.
+        require!(escrow_account.relist_count < escrow_account.max_relists, AuctionError::RelistLimitReached);
+        validate_duration(
+            &ctx.accounts.auction_house,
+            TimingMode::try_from(escrow_account.timing_mode)?,
+            escrow_account.relist_duration,
+        )?;
+
+        // Reduce the price, rounding the reduction down in the exhibitor's favour.
+        let reduction = share_of(escrow_account.price, escrow_account.relist_reduction_bps)?;
+        let price = escrow_account.price.checked_sub(reduction).ok_or(AuctionError::NumericalOverflow)?;
+        escrow_account.price = if escrow_account.is_dutch() {
+            price.max(escrow_account.dutch_floor_price)
+        } else {
+            price
+        };
+        // Restart the auction, and the price decay of a Dutch auction along with it.
+        escrow_account.end_at = escrow_account.end_after(&ctx.accounts.clock, escrow_account.relist_duration)?;
+        escrow_account.dutch_start_at = escrow_account.now(&ctx.accounts.clock)?;
+        escrow_account.relist_count = escrow_account.relist_count
+            .checked_add(1)
+            .ok_or(AuctionError::NumericalOverflow)?;
+        // Record the relist as an event.
+        emit_cpi(
+            &AuctionRelisted {
+                auction: ctx.accounts.escrow_account.key(),
+                price: escrow_account.price,
+                end_at: escrow_account.end_at,
+                relist_count: escrow_account.relist_count,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the relist_with_price function for the exhibitor or its operator to restart an auction that has
+    // no bids at a new starting price and for a new duration from now, leaving the NFT where it is. Unlike
+    // update_auction it also restarts an auction that has already ended without a bid, so changing the price
+    // takes neither a cancel and a new exhibit nor the rent of a new escrow account. It does not count
+    // towards the auction's relists.
+    pub fn relist_with_price(
+        ctx: Context<RelistWithPrice>, // Context for the RelistWithPrice struct.
+        initial_price: u64,            // New initial price for the auction.
+        auction_duration_sec: u64,     // New duration of the auction in seconds or slots, counted from now.
+    ) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        // Ensure the new initial price is set unless the auction was listed for free.
+        validate_initial_price(initial_price, escrow_account.free_listing != 0)?;
+        // Ensure the new duration is within the house limits.
+        validate_duration(
+            &ctx.accounts.auction_house,
+            TimingMode::try_from(escrow_account.timing_mode)?,
+            auction_duration_sec,
+        )?;
+        // Ensure a Dutch auction still starts at or above its floor price.
+        if escrow_account.is_dutch() {
+            require!(initial_price >= escrow_account.dutch_floor_price, AuctionError::InvalidDutchCurve);
+        }
+
+        // Restart the auction at the new price, and the price decay of a Dutch auction along with it.
+        escrow_account.price = initial_price;
+        escrow_account.end_at = escrow_account.end_after(&ctx.accounts.clock, auction_duration_sec)?;
+        escrow_account.dutch_start_at = escrow_account.now(&ctx.accounts.clock)?;
+        // Record the relist as an event.
+        emit_cpi(
+            &AuctionRelisted {
+                auction: ctx.accounts.escrow_account.key(),
+                price: escrow_account.price,
+                end_at: escrow_account.end_at,
+                relist_count: escrow_account.relist_count,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_series function for an exhibitor to run auctions as a series of lots sold one after
+    // the other. The first lot opens for bidding at start_at, and every later lot once the lot before it is
+    // settled, cancelled or has ended without a bid, each for the lot duration.
+    pub fn create_series(
+        ctx: Context<CreateSeries>, // Context for the CreateSeries struct.
+        series_id: u64,             // ID of the series, unique among its authority's series.
+        start_at: i64,              // When the first lot opens, in UNIX timestamp.
+        lot_duration: u64,          // Duration of every lot in seconds or slots, in the lot's timing mode.
+    ) -> Result<()> {
+        // Record the series.
+        let series = &mut ctx.accounts.series;
+        series.authority = ctx.accounts.authority.key();
+        series.series_id = series_id;
+        series.start_at = start_at;
+        series.lot_duration = lot_duration;
+        series.lots = Vec::new();
+        series.next_lot = 0;
+        series.created_at = Clock::get()?.unix_timestamp;
+        series.bump = *ctx.bumps.get("series").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the add_series_lot function for the series authority to append an auction it manages to a
+    // series. The auction must have no bids, and bidding on it stays closed until the series opens it.
+    pub fn add_series_lot(ctx: Context<AddSeriesLot>) -> Result<()> {
+        // Ensure the house offers auction series.
+        ctx.accounts.auction_house.require_enabled(FEATURE_SERIES)?;
+        let series = &mut ctx.accounts.series;
+        // Ensure the series has room for the lot.
+        require!(series.lots.len() < MAX_SERIES_LOTS, AuctionError::SeriesFull);
+        // Ensure the lot duration is within the house limits, in the auction's timing mode.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+        validate_duration(
+            &ctx.accounts.auction_house,
+            TimingMode::try_from(escrow_account.timing_mode)?,
+            series.lot_duration,
+        )?;
+
+        // Close bidding on the auction, and keep it from ending, until the series opens it.
+        escrow_account.series = series.key();
+        escrow_account.allowlist_at = i64::MAX;
+        escrow_account.end_at = i64::MAX;
+        series.lots.push(ctx.accounts.escrow_account.key());
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the open_next_lot function for anyone to crank a series along once its next lot may open. The
+    // lot opens for bidding for the lot duration from now, restarting the price decay of a Dutch auction. A
+    // lot that was cancelled, closed or taken out of the series is skipped instead, leaving the lot after it
+    // for the next call.
+    pub fn open_next_lot(ctx: Context<OpenNextLot>) -> Result<()> {
+        let series = &mut ctx.accounts.series;
+        let clock = &ctx.accounts.clock;
+        let index = series.next_lot as usize;
+        // Ensure the series has a lot left, and the one passed is it.
+        require!(index < series.lots.len(), AuctionError::SeriesComplete);
+        require_keys_eq!(ctx.accounts.lot.key(), series.lots[index], AuctionError::InvalidSeriesLot);
+        // Ensure the series has started, and the lot before this one is done.
+        if index == 0 {
+            require!(clock.unix_timestamp >= series.start_at, AuctionError::SeriesLotNotReady);
+        } else {
+            let previous_lot = &ctx.accounts.previous_lot;
+            require_keys_eq!(previous_lot.key(), series.lots[index - 1], AuctionError::InvalidSeriesLot);
+            if *previous_lot.owner == crate::ID {
+                let previous_lot = AccountLoader::<Auction>::try_from(previous_lot)?;
+                require!(previous_lot.load()?.lot_done(series.key(), clock)?, AuctionError::SeriesLotNotReady);
+            }
+        }
+        series.next_lot += 1;
+
+        // Skip a lot that is gone or no longer waiting in this series.
+        if *ctx.accounts.lot.owner != crate::ID {
+            return Ok(());
+        }
+        let lot_loader = AccountLoader::<Auction>::try_from(&ctx.accounts.lot)?;
+        let mut lot = lot_loader.load_mut()?;
+        if lot.series != series.key() || lot.stored_status()? != AuctionStatus::Created {
+            return Ok(());
+        }
+
+        // Open bidding on the lot for the lot duration, starting the price decay of a Dutch auction now.
+        lot.allowlist_at = lot.now(clock)?;
+        lot.end_at = lot.end_after(clock, series.lot_duration)?;
+        lot.dutch_start_at = lot.allowlist_at;
+        // Record the opening as an event.
+        emit_cpi(
+            &SeriesLotOpened {
+                series: series.key(),
+                auction: ctx.accounts.lot.key(),
+                lot_index: index as u16,
+                end_at: lot.end_at,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the bid function for users to place bids.
+    // On a gated auction, the bidder's gate accounts are passed as remaining accounts after the referrer's
+    // until its public phase opens, and on a penny auction or one with a bid bond the fee accounts follow
+    // them (see treasury_fee_account).
+    pub fn bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, Bid<'info>>, // Context for the Bid struct.
+        price: u64,                // The bid amount.
+        referrer: Option<Pubkey>,  // Optional FT receiving account of the referrer.
+        memo: String,              // Optional reference the bidder tags the bid with (empty for none).
+        affiliate: Option<Pubkey>, // Optional affiliate who earns a share of the house fee if the bid wins.
+    ) -> Result<()> {
+        // Place the bid, paid from the bidder's FT account with the bidder's signature.
+        let funding = BidFunding::Tokens {
+            ft_account: ctx.accounts.bidder_ft_account.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+            signers_seeds: &[],
+        };
+        let bid_receipt_bump = *ctx.bumps.get("bid_receipt").unwrap();
+        place_bid(
+            ctx.accounts.bid_placement(bid_receipt_bump),
+            funding,
+            ctx.remaining_accounts,
+            price,
+            referrer,
+            memo,
+            affiliate,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the bid_with_sol function for users to bid lamports on a wrapped SOL auction. The bid is
+    // wrapped into the bidder's temporary wSOL account, so the bidder needs no wSOL account of their own.
+    // On a gated auction, the bidder's gate accounts are passed as remaining accounts after the referrer's
+    // until its public phase opens, and on a penny auction or one with a bid bond the fee accounts follow
+    // them (see treasury_fee_account).
+    pub fn bid_with_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, BidWithSol<'info>>, // Context for the BidWithSol struct.
+        price: u64,                // The bid amount in lamports.
+        referrer: Option<Pubkey>,  // Optional FT receiving account of the referrer.
+        memo: String,              // Optional reference the bidder tags the bid with (empty for none).
+        affiliate: Option<Pubkey>, // Optional affiliate who earns a share of the house fee if the bid wins.
+    ) -> Result<()> {
+        // Ensure the house takes bids in lamports.
+        ctx.accounts.auction_house.require_enabled(FEATURE_SOL_BIDS)?;
+
+        // Place the bid, paid in lamports from the bidder and wrapped into the temporary wSOL account.
+        let bid_receipt_bump = *ctx.bumps.get("bid_receipt").unwrap();
+        place_bid(
+            ctx.accounts.bid_placement(bid_receipt_bump),
+            BidFunding::Lamports,
+            ctx.remaining_accounts,
+            price,
+            referrer,
+            memo,
+            affiliate,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_session function for a wallet to authorize an ephemeral session key, such as a
+    // trading bot's, to bid on its behalf with bid_with_session until the session expires, spending at
+    // most the cap from one FT account. The wallet approves the session PDA as the delegate of that
+    // account for the cap, so the session key itself can never move the wallet's tokens.
+    pub fn create_session(
+        ctx: Context<CreateSession>, // Context for the CreateSession struct.
+        session_key: Pubkey,         // The key allowed to bid for the wallet.
+        spending_cap: u64,           // Total the session's bids may cost, bonds and fees included.
+        expires_at: i64,             // When the session key stops being accepted, in UNIX timestamp.
+    ) -> Result<()> {
+        // Ensure the session expires in the future, within the longest session allowed.
+        let now = ctx.accounts.clock.unix_timestamp;
+        require!(expires_at > now, AuctionError::InvalidSession);
+        require!(expires_at - now <= MAX_SESSION_DURATION_SEC, AuctionError::InvalidSession);
+
+        // Let the session PDA spend up to the cap from the wallet's FT account.
+        token::approve(ctx.accounts.to_approve_context(), spending_cap)?;
+
+        // Record the session.
+        let session = &mut ctx.accounts.session;
+        session.wallet = ctx.accounts.wallet.key();
+        session.session_key = session_key;
+        session.ft_account = ctx.accounts.wallet_ft_account.key();
+        session.spending_cap = spending_cap;
+        session.spent = 0;
+        session.expires_at = expires_at;
+        session.bump = *ctx.bumps.get("session").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the close_session function for a wallet to end a bid session early or once it expired,
+    // revoking the session PDA's approval on its FT account and taking back the session's rent.
+    pub fn close_session(ctx: Context<CloseSession>) -> Result<()> {
+        // Revoke the session PDA's approval, unless the wallet has since approved another delegate.
+        if ctx.accounts.wallet_ft_account.delegate == COption::Some(ctx.accounts.session.key()) {
+            token::revoke(ctx.accounts.to_revoke_context())?;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the bid_with_session function for a session key to bid for the wallet that authorized it with
+    // create_session. It works as bid does, with the wallet as the bidder, except that the session key
+    // signs instead of the wallet and the bid is paid from the session's FT account through the session
+    // PDA's approval, within the session's spending cap and before it expires. The session key owns the
+    // bidder's temporary FT account until the bid hands it to the PDA.
+    pub fn bid_with_session<'info>(
+        ctx: Context<'_, '_, '_, 'info, BidWithSession<'info>>, // Context for the BidWithSession struct.
+        price: u64,                // The bid amount.
+        referrer: Option<Pubkey>,  // Optional FT receiving account of the referrer.
+        memo: String,              // Optional reference the bidder tags the bid with (empty for none).
+        affiliate: Option<Pubkey>, // Optional affiliate who earns a share of the house fee if the bid wins.
+    ) -> Result<()> {
+        // Create the seeds for the session PDA's signature, which spends from the wallet's FT account.
+        let (wallet, session_key) = (ctx.accounts.session.wallet, ctx.accounts.session.session_key);
+        let session_seeds: &[&[&[u8]]] = &[&[
+            BID_SESSION_SEED,
+            wallet.as_ref(),
+            session_key.as_ref(),
+            &[ctx.accounts.session.bump],
+        ]];
+
+        // Place the bid, paid from the session's FT account through the session PDA's approval.
+        let funding = BidFunding::Tokens {
+            ft_account: ctx.accounts.bidder_ft_account.to_account_info(),
+            authority: ctx.accounts.session.to_account_info(),
+            signers_seeds: session_seeds,
+        };
+        let bid_receipt_bump = *ctx.bumps.get("bid_receipt").unwrap();
+        place_bid(
+            ctx.accounts.bid_placement(bid_receipt_bump),
+            funding,
+            ctx.remaining_accounts,
+            price,
+            referrer,
+            memo,
+            affiliate,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_bid_pool function for an operator to open a pool that contributors fund to bid on
+    // an auction together. Only the operator bids from it, and if the pool wins the NFT goes to the pool.
+    // Wrapped SOL auctions are not supported, since their refunds are paid in lamports.
+    pub fn create_bid_pool(ctx: Context<CreateBidPool>) -> Result<()> {
+        // Record the pool.
+        let bid_pool = &mut ctx.accounts.bid_pool;
+        bid_pool.auction = ctx.accounts.escrow_account.key();
+        bid_pool.operator = ctx.accounts.operator.key();
+        bid_pool.payment_mint = ctx.accounts.payment_mint.key();
+        bid_pool.vault = ctx.accounts.vault.key();
+        bid_pool.total_contributed = 0;
+        bid_pool.contribution_count = 0;
+        bid_pool.settled = false;
+        bid_pool.settled_balance = 0;
+        bid_pool.item_mint = ctx.accounts.escrow_account.load()?.nft_mint;
+        bid_pool.item_account = Pubkey::default();
+        bid_pool.fraction_mint = Pubkey::default();
+        bid_pool.created_at = ctx.accounts.clock.unix_timestamp;
+        bid_pool.bump = *ctx.bumps.get("bid_pool").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the contribute_to_pool function for anyone to fund a bid pool while its auction takes bids.
+    // Each contribution gets its own receipt, the contributor's pro-rata claim on what the pool holds once
+    // the auction is over: the NFT if the pool won it, and the funds left in its vault.
+    pub fn contribute_to_pool(
+        ctx: Context<ContributeToPool>, // Context for the ContributeToPool struct.
+        amount: u64,                    // The amount of the payment mint contributed.
+    ) -> Result<()> {
+        // Ensure the contribution is not empty.
+        require!(amount > 0, AuctionError::EmptyContribution);
+        // Transfer the contribution to the pool's vault.
+        token::transfer(ctx.accounts.to_transfer_to_vault_context(), amount)?;
+
+        // Record the contribution in its receipt and in the pool.
+        let bid_pool = &mut ctx.accounts.bid_pool;
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.bid_pool = bid_pool.key();
+        contribution.contributor = ctx.accounts.contributor.key();
+        contribution.index = bid_pool.contribution_count;
+        contribution.amount = amount;
+        contribution.refunded = false;
+        contribution.refund = 0;
+        contribution.fractions_claimed = false;
+        contribution.created_at = ctx.accounts.clock.unix_timestamp;
+        contribution.bump = *ctx.bumps.get("contribution").unwrap();
+        bid_pool.total_contributed = bid_pool.total_contributed
+            .checked_add(amount)
+            .ok_or(AuctionError::NumericalOverflow)?;
+        bid_pool.contribution_count = bid_pool.contribution_count
+            .checked_add(1)
+            .ok_or(AuctionError::NumericalOverflow)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the pool_bid function for a bid pool's operator to bid on the pool's auction from its vault.
+    // The bid's accounts, as bid takes them with the pool as the bidder and its vault as the bidder's FT
+    // account, are passed as remaining accounts, and bid is called through a self-CPI the pool signs.
+    pub fn pool_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PoolBid<'info>>, // Context for the PoolBid struct.
+        price: u64,                // The bid amount.
+        referrer: Option<Pubkey>,  // Optional FT receiving account of the referrer.
+        memo: String,              // Optional reference the bid is tagged with (empty for none).
+    ) -> Result<()> {
+        let bid_pool = &ctx.accounts.bid_pool;
+        let bid_ix = bid_pools::pool_bid_ix(
+            &bid_pool.key(),
+            &bid_pool.vault,
+            &bid_pool.auction,
+            ctx.remaining_accounts,
+            price,
+            referrer,
+            memo,
+        )?;
+        // Create the seeds for the pool's signature.
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BID_POOL_SEED,
+            bid_pool.auction.as_ref(),
+            bid_pool.operator.as_ref(),
+            &[bid_pool.bump],
+        ]];
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.program.to_account_info());
+        anchor_lang::solana_program::program::invoke_signed(&bid_ix, &account_infos, signer_seeds)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the bid_with_swap function for a bidder to pay for a bid in any token: the aggregator swaps it
+    // into the auction's payment mint, into the bidder's FT account, then bid is called through a self-CPI,
+    // all in one instruction. The swap's accounts come first among the remaining accounts, followed by the
+    // bid's, as bid takes them. The swap must deliver at least min_amount_out, whatever the slippage limit
+    // of its route.
+    pub fn bid_with_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, BidWithSwap<'info>>, // Context for the BidWithSwap struct.
+        swap_account_count: u8,    // Number of remaining accounts taken by the swap.
+        swap_data: Vec<u8>,        // Route data of the swap, as quoted by the aggregator's API.
+        min_amount_out: u64,       // Least the swap must deliver in the payment mint.
+        price: u64,                // The bid amount.
+        referrer: Option<Pubkey>,  // Optional FT receiving account of the referrer.
+        memo: String,              // Optional reference the bidder tags the bid with (empty for none).
+        affiliate: Option<Pubkey>, // Optional affiliate who earns a share of the house fee if the bid wins.
+    ) -> Result<()> {
+        // Split the remaining accounts between the swap and the bid.
+        let swap_account_count = swap_account_count as usize;
+        require!(ctx.remaining_accounts.len() >= swap_account_count, AuctionError::SwapAccountMismatch);
+        let (swap_accounts, bid_accounts) = ctx.remaining_accounts.split_at(swap_account_count);
+
+        // Swap the bidder's token into the payment mint, and ensure enough of it arrived.
+        let balance_before = ctx.accounts.bidder_ft_account.amount;
+        let mut account_infos = swap_accounts.to_vec();
+        account_infos.push(ctx.accounts.swap_program.clone());
+        let swap_ix = swaps::swap_ix(swap_accounts, swap_data);
+        anchor_lang::solana_program::program::invoke(&swap_ix, &account_infos)?;
+        ctx.accounts.bidder_ft_account.reload()?;
+        let amount_out = ctx.accounts.bidder_ft_account.amount.saturating_sub(balance_before);
+        require!(amount_out >= min_amount_out, AuctionError::SwapSlippageExceeded);
+
+        // Place the bid from the swapped tokens.
+        let bid_ix = swaps::swap_bid_ix(
+            &ctx.accounts.bidder.key(),
+            &ctx.accounts.bidder_ft_account.key(),
+            bid_accounts,
+            price,
+            referrer,
+            memo,
+            affiliate,
+        )?;
+        let mut account_infos = bid_accounts.to_vec();
+        account_infos.push(ctx.accounts.program.to_account_info());
+        anchor_lang::solana_program::program::invoke(&bid_ix, &account_infos)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the settle_bid_pool function for anyone to lock in what a bid pool's contributors may claim
+    // once its auction is over: settled, cancelled or closed, ended with another highest bidder, or won by
+    // the pool with the NFT claimed (see pool_claim_item). The funds left in the vault are then refunded pro
+    // rata to the contributions.
+    pub fn settle_bid_pool(ctx: Context<SettleBidPool>) -> Result<()> {
+        let bid_pool = &mut ctx.accounts.bid_pool;
+        // Ensure the auction is over for the pool. A closed escrow account has been settled.
+        let escrow_account = &ctx.accounts.escrow_account;
+        if *escrow_account.owner == crate::ID {
+            let escrow_account = AccountLoader::<Auction>::try_from(escrow_account)?;
+            let over = pool_auction_over(&*escrow_account.load()?, &bid_pool.key(), &ctx.accounts.clock)?;
+            require!(over, AuctionError::PoolAuctionNotOver);
+        }
+
+        // Lock in the funds left to refund.
+        bid_pool.settled = true;
+        bid_pool.settled_balance = ctx.accounts.vault.amount;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_pool_refund function for anyone to refund a contribution to a settled bid pool its
+    // pro-rata share of the funds left, to the contributor's FT account. The contribution's receipt stays
+    // as the contributor's claim on the NFT, which claim_pool_fractions turns into fractions of it.
+    pub fn claim_pool_refund(ctx: Context<ClaimPoolRefund>) -> Result<()> {
+        let bid_pool = &ctx.accounts.bid_pool;
+        // Work out the contribution's share of the funds left, rounding down.
+        let refund = (bid_pool.settled_balance as u128)
+            .checked_mul(ctx.accounts.contribution.amount as u128)
+            .and_then(|amount| amount.checked_div(bid_pool.total_contributed as u128))
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(AuctionError::NumericalOverflow)?;
+
+        // Transfer the refund from the pool's vault, which the pool signs for.
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BID_POOL_SEED,
+            bid_pool.auction.as_ref(),
+            bid_pool.operator.as_ref(),
+            &[bid_pool.bump],
+        ]];
+        token::transfer(
+            ctx.accounts.to_transfer_refund_context().with_signer(signer_seeds),
+            refund,
+        )?;
+
+        // Record the refund in the receipt.
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.refunded = true;
+        contribution.refund = refund;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the pool_claim_item function for anyone to claim the NFT a bid pool won on an auction settled
+    // through finalize into an NFT account of the pool's. The claim's accounts, as claim_item takes them
+    // with the pool as the winning bidder, are passed as remaining accounts, and claim_item is called
+    // through a self-CPI the pool signs.
+    pub fn pool_claim_item<'info>(ctx: Context<'_, '_, '_, 'info, PoolClaimItem<'info>>) -> Result<()> {
+        let bid_pool = &ctx.accounts.bid_pool;
+        let claim_ix =
+            bid_pools::pool_claim_item_ix(&bid_pool.key(), &bid_pool.auction, ctx.remaining_accounts)?;
+        // Create the seeds for the pool's signature.
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BID_POOL_SEED,
+            bid_pool.auction.as_ref(),
+            bid_pool.operator.as_ref(),
+            &[bid_pool.bump],
+        ]];
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.program.to_account_info());
+        anchor_lang::solana_program::program::invoke_signed(&claim_ix, &account_infos, signer_seeds)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the fractionalize_bid_pool function for anyone to create the fraction mint of a settled bid
+    // pool holding the NFT it won. Each contribution's receipt is worth a fraction per unit contributed (see
+    // claim_pool_fractions), in the payment mint's decimals, and whoever holds every fraction may redeem
+    // them for the NFT (see redeem_pool_item).
+    pub fn fractionalize_bid_pool(ctx: Context<FractionalizeBidPool>) -> Result<()> {
+        // Record the account holding the NFT and the fraction mint.
+        let bid_pool = &mut ctx.accounts.bid_pool;
+        bid_pool.item_account = ctx.accounts.item_account.key();
+        bid_pool.fraction_mint = ctx.accounts.fraction_mint.key();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_pool_fractions function for anyone to mint a contribution to a fractionalized bid
+    // pool its fractions of the NFT, one per unit contributed, to the contributor's fraction account.
+    pub fn claim_pool_fractions(ctx: Context<ClaimPoolFractions>) -> Result<()> {
+        // Mint the fractions, which the pool signs for as the fraction mint's authority.
+        let bid_pool = &ctx.accounts.bid_pool;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BID_POOL_SEED,
+            bid_pool.auction.as_ref(),
+            bid_pool.operator.as_ref(),
+            &[bid_pool.bump],
+        ]];
+        token::mint_to(
+            ctx.accounts.to_mint_fractions_context().with_signer(signer_seeds),
+            ctx.accounts.contribution.amount,
+        )?;
+
+        // Record the claim in the receipt.
+        ctx.accounts.contribution.fractions_claimed = true;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the redeem_pool_item function for the holder of every fraction of a fractionalized bid pool to
+    // burn them and take the NFT out of the pool.
+    pub fn redeem_pool_item(ctx: Context<RedeemPoolItem>) -> Result<()> {
+        // Ensure the redeemer holds every fraction, which requires every contribution to have claimed its
+        // fractions.
+        let bid_pool = &ctx.accounts.bid_pool;
+        let fractions = ctx.accounts.redeemer_fraction_account.amount;
+        require!(
+            bid_pool.redeems_item(ctx.accounts.fraction_mint.supply, fractions),
+            AuctionError::IncompleteFractions
+        );
+
+        // Burn the fractions.
+        token::burn(ctx.accounts.to_burn_fractions_context(), fractions)?;
+
+        // Transfer the NFT to the redeemer, the pool signing as the owner of the account holding it.
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BID_POOL_SEED,
+            bid_pool.auction.as_ref(),
+            bid_pool.operator.as_ref(),
+            &[bid_pool.bump],
+        ]];
+        token::transfer(
+            ctx.accounts.to_transfer_item_context().with_signer(signer_seeds),
+            ctx.accounts.item_account.amount,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the close function for the winning bidder to close the auction and distribute the assets.
+    // The payout, charity and referrer FT receiving accounts are passed as remaining accounts (see settle).
+    pub fn close<'info>(ctx: Context<'_, '_, '_, 'info, Close<'info>>) -> Result<()> {
+        // Settle the auction and record the sale.
+        close_sale(
+            ctx.accounts.as_sale_closing(),
+            ctx.remaining_accounts,
+            *ctx.bumps.get("purchase_receipt").unwrap(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the settle_if_ended function so an automation service can settle an ended auction without the
+    // winning bidder being online. It needs no signer beyond the fee payer, fails on the escrow constraints
+    // before doing any work if the auction has not ended, and cannot settle twice since settling closes the
+    // escrow account. The remaining accounts are the same as for close.
+    pub fn settle_if_ended<'info>(ctx: Context<'_, '_, '_, 'info, SettleIfEnded<'info>>) -> Result<()> {
+        // Settle the auction and record the sale, as close does.
+        close_sale(
+            ctx.accounts.as_sale_closing(),
+            ctx.remaining_accounts,
+            *ctx.bumps.get("purchase_receipt").unwrap(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the close_many function for cranks to settle several ended auctions in one transaction. The
+    // settle_if_ended accounts of each auction are passed as a group of remaining accounts, in the order of
+    // the SettleIfEnded struct and followed by that auction's payout, charity and referrer accounts, and
+    // each auction is validated and settled exactly as by settle_if_ended.
+    pub fn close_many<'info>(ctx: Context<'_, '_, '_, 'info, CloseMany<'info>>) -> Result<()> {
+        let mut remaining_accounts = ctx.remaining_accounts;
+        let mut settled = 0;
+        while !remaining_accounts.is_empty() {
+            // Ensure the batch fits the limit.
+            require!(settled < MAX_CLOSE_BATCH, AuctionError::TooManyCloses);
+            // Validate the next group of settle_if_ended accounts.
+            let mut bumps = BTreeMap::new();
+            let mut accounts = SettleIfEnded::try_accounts(ctx.program_id, &mut remaining_accounts, &[], &mut bumps)?;
+            // Split off the auction's own settlement accounts, which follow its settle_if_ended accounts,
+            // including the house's royalty vault when a royalty is owed.
+            let royalty = royalties::load_royalty(
+                &accounts.auction_house,
+                &accounts.nft_metadata,
+                &accounts.escrow_account.load()?.nft_mint,
+                &accounts.royalty_override,
+            )?;
+            let settlement_account_count = accounts.escrow_account.load()?.settlement_account_count()
+                + royalty.is_some() as usize;
+            require!(
+                remaining_accounts.len() >= settlement_account_count,
+                AuctionError::PayoutRecipientMissing
+            );
+            let (settlement_accounts, rest) = remaining_accounts.split_at(settlement_account_count);
+            remaining_accounts = rest;
+            // Settle the auction, then write back the accounts, which closes its escrow account.
+            settle_if_ended(Context::new(ctx.program_id, &mut accounts, settlement_accounts, bumps))?;
+            accounts.exit(ctx.program_id)?;
+            settled += 1;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the finalize function to lock in the result of an ended auction whose highest bid meets the
+    // USD reserve. Anyone may call it; afterwards the winning bidder and the exhibitor each claim their side
+    // with claim_item and claim_proceeds, in any order and in separate transactions, or anyone settles each
+    // side with settle_item and settle_payment.
+    pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Ensure the winning bid meets the reserves, unless the exhibitor accepted it.
+        check_reserves(
+            &escrow_account,
+            &ctx.accounts.price_feed,
+            ctx.accounts.highest_bidder_ft_temp_account.amount,
+            ctx.accounts.payment_mint.decimals,
+            &ctx.accounts.clock,
+        )?;
+
+        // Mark the auction as ended, which no longer depends on the time.
+        escrow_account.set_status(AuctionStatus::Ended);
+        // Hold the sale for disputes in arbitration mode, during which neither side can be claimed.
+        escrow_account.dispute_deadline = if escrow_account.dispute_window_sec > 0 {
+            ctx.accounts.clock.unix_timestamp
+                .checked_add(escrow_account.dispute_window_sec as i64)
+                .ok_or(AuctionError::NumericalOverflow)?
+        } else {
+            0
+        };
+        // Give the winning bidder the claim period to take the NFT, once the dispute window closes.
+        escrow_account.claim_deadline = ctx.accounts.clock.unix_timestamp
+            .max(escrow_account.dispute_deadline)
+            .checked_add(CLAIM_PERIOD_SEC)
+            .ok_or(AuctionError::NumericalOverflow)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_item function for the winning bidder to take the NFT of a finalized auction, or
+    // start renting it. A rented auction's rental is passed as the remaining account. Print sales are
+    // claimed with claim_print instead.
+    pub fn claim_item<'info>(ctx: Context<'_, '_, '_, 'info, ClaimItem<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Deliver the NFT. For wrapped SOL the exhibitor receives the FT temporary account's rent when the
+        // proceeds are unwrapped, so the NFT temporary account's rent goes to the highest bidder's rent
+        // payer in exchange.
+        let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+            ctx.accounts.highest_bidder_rent_payer.clone()
+        } else {
+            ctx.accounts.exhibitor_rent_payer.clone()
+        };
+        if escrow_account.rental != Pubkey::default() {
+            // A rented NFT stays in escrow until the rental ends.
+            let rental = ctx.remaining_accounts.first().ok_or(AuctionError::RentalAccountMissing)?;
+            start_rental(rental, &escrow_account, &rent_destination.key(), ctx.accounts.clock.unix_timestamp)?;
+        } else {
+            deliver_item(ctx.accounts.to_item_delivery(), escrow_account.listing_mode()?, rent_destination, signers_seeds)?;
+        }
+
+        // Record the claim, and settle the auction once the proceeds have been claimed too.
+        escrow_account.item_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_print function for the winning bidder of a finalized print sale to take the print.
+    // The metadata program prints the auction's edition of the escrowed master edition to a new mint the
+    // winning bidder created with a supply of 1, and the master edition goes back to the exhibitor.
+    pub fn claim_print<'info>(ctx: Context<'_, '_, '_, 'info, ClaimPrint<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Ensure the new metadata's update authority is the master edition's, as the metadata program
+        // expects.
+        let master_metadata = metadata::load_metadata(&ctx.accounts.nft_metadata, &escrow_account.nft_mint)?;
+        require_keys_eq!(
+            ctx.accounts.update_authority.key(),
+            master_metadata.update_authority,
+            AuctionError::InvalidMetadata
+        );
+
+        // Print the edition, the PDA signing as the owner of the token account holding the master edition.
+        let ix = prints::print_edition_ix(
+            &prints::PrintEdition {
+                new_metadata: ctx.accounts.new_metadata.key(),
+                new_edition: ctx.accounts.new_edition.key(),
+                master_edition: ctx.accounts.master_edition.key(),
+                new_mint: ctx.accounts.new_mint.key(),
+                edition_marker: ctx.accounts.edition_marker.key(),
+                new_mint_authority: ctx.accounts.winning_bidder.key(),
+                payer: ctx.accounts.winning_bidder.key(),
+                master_token_owner: ctx.accounts.pda.key(),
+                master_token_account: ctx.accounts.exhibitor_nft_temp_account.key(),
+                new_update_authority: ctx.accounts.update_authority.key(),
+                master_metadata: ctx.accounts.nft_metadata.key(),
+            },
+            escrow_account.print_edition,
+        );
+        anchor_lang::solana_program::program::invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.new_metadata.clone(),
+                ctx.accounts.new_edition.clone(),
+                ctx.accounts.master_edition.clone(),
+                ctx.accounts.new_mint.to_account_info(),
+                ctx.accounts.edition_marker.clone(),
+                ctx.accounts.winning_bidder.to_account_info(),
+                ctx.accounts.pda.clone(),
+                ctx.accounts.exhibitor_nft_temp_account.to_account_info(),
+                ctx.accounts.update_authority.clone(),
+                ctx.accounts.nft_metadata.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+                ctx.accounts.token_metadata_program.clone(),
+            ],
+            signers_seeds,
+        )?;
+
+        // Return the master edition to the exhibitor. For wrapped SOL the exhibitor receives the FT
+        // temporary account's rent when the proceeds are unwrapped, so the NFT temporary account's rent goes
+        // to the highest bidder's rent payer in exchange.
+        let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+            ctx.accounts.highest_bidder_rent_payer.clone()
+        } else {
+            ctx.accounts.exhibitor_rent_payer.clone()
+        };
+        deliver_item(ctx.accounts.to_master_return(), ListingMode::Escrow, rent_destination, signers_seeds)?;
+
+        // Record the claim, and settle the auction once the proceeds have been claimed too.
+        escrow_account.item_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_domain function to transfer the domain of a finalized domain auction to its winning
+    // bidder. Anyone may call it, since the domain can only go to the winning bidder, so a domain auction
+    // is never left unclaimed.
+    pub fn claim_domain(ctx: Context<ClaimDomain>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Make the winning bidder the domain's owner, the PDA signing as its current owner.
+        sns::transfer_name(
+            &ctx.accounts.name_account,
+            &ctx.accounts.pda,
+            &escrow_account.highest_bidder,
+            &ctx.accounts.name_service_program,
+            signers_seeds,
+        )?;
+
+        // Record the claim, and settle the auction once the proceeds have been claimed too.
+        escrow_account.item_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_stake function to hand the stake account of a finalized stake account auction to its
+    // winning bidder, as its staker and withdrawer. Like claim_domain, anyone may call it.
+    pub fn claim_stake(ctx: Context<ClaimStake>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Make the winning bidder the staker and withdrawer, the PDA signing as both.
+        stake::authorize_both(
+            &ctx.accounts.stake_account,
+            &ctx.accounts.pda,
+            &escrow_account.highest_bidder,
+            &ctx.accounts.clock.to_account_info(),
+            &ctx.accounts.stake_program,
+            signers_seeds,
+        )?;
+
+        // Record the claim, and settle the auction once the proceeds have been claimed too.
+        escrow_account.item_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_proceeds function for the exhibitor to distribute the proceeds of a finalized
+    // auction. The remaining accounts are the same as for close.
+    pub fn claim_proceeds<'info>(ctx: Context<'_, '_, '_, 'info, ClaimProceeds<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Distribute the proceeds.
+        let sale_shares = distribute_proceeds(
+            ctx.accounts.to_proceeds_distribution(&escrow_account),
+            ctx.remaining_accounts,
+            signers_seeds,
+        )?;
+
+        // Record the sale in its purchase receipt.
+        record_purchase(
+            &mut ctx.accounts.purchase_receipt,
+            ctx.accounts.escrow_account.key(),
+            &escrow_account,
+            &sale_shares,
+            Clock::get()?.unix_timestamp,
+            *ctx.bumps.get("purchase_receipt").unwrap(),
+        )?;
+        // Record the sale in the house's and the NFT's collection's stats.
+        record_house_sale(&ctx.accounts.house_stats, &ctx.accounts.purchase_receipt, ctx.program_id)?;
+        collection_stats::record_collection_sale(
+            &ctx.accounts.nft_metadata,
+            &escrow_account.nft_mint,
+            &escrow_account.payment_mint,
+            &ctx.accounts.collection_stats,
+            escrow_account.price,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Record the claim, and settle the auction once the item has been claimed too.
+        escrow_account.proceeds_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the settle_item function for anyone to deliver the NFT of a finalized auction to the winning
+    // bidder, or start their rental. It is the NFT leg of close, with settle_payment the payment leg, so an
+    // automation service can settle a finalized auction in two small transactions, in any order, without
+    // either party online. The NFT goes to an account of the winning bidder's. A rented auction's rental is
+    // passed as the remaining account.
+    pub fn settle_item<'info>(ctx: Context<'_, '_, '_, 'info, SettleItem<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Deliver the NFT. For wrapped SOL the exhibitor receives the FT temporary account's rent when the
+        // proceeds are unwrapped, so the NFT temporary account's rent goes to the highest bidder's rent
+        // payer in exchange.
+        let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+            ctx.accounts.highest_bidder_rent_payer.clone()
+        } else {
+            ctx.accounts.exhibitor_rent_payer.clone()
+        };
+        if escrow_account.rental != Pubkey::default() {
+            // A rented NFT stays in escrow until the rental ends.
+            let rental = ctx.remaining_accounts.first().ok_or(AuctionError::RentalAccountMissing)?;
+            start_rental(rental, &escrow_account, &rent_destination.key(), ctx.accounts.clock.unix_timestamp)?;
+        } else {
+            deliver_item(ctx.accounts.to_item_delivery(), escrow_account.listing_mode()?, rent_destination, signers_seeds)?;
+        }
+
+        // Record the claim, and settle the auction once the proceeds have been claimed too.
+        escrow_account.item_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the settle_payment function for anyone to distribute the proceeds of a finalized auction, the
+    // payment leg of close (see settle_item). The payer pays for the purchase receipt. The remaining accounts
+    // are the same as for close.
+    pub fn settle_payment<'info>(ctx: Context<'_, '_, '_, 'info, SettlePayment<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Distribute the proceeds.
+        let sale_shares = distribute_proceeds(
+            ctx.accounts.to_proceeds_distribution(&escrow_account),
+            ctx.remaining_accounts,
+            signers_seeds,
+        )?;
+
+        // Record the sale in its purchase receipt.
+        record_purchase(
+            &mut ctx.accounts.purchase_receipt,
+            ctx.accounts.escrow_account.key(),
+            &escrow_account,
+            &sale_shares,
+            Clock::get()?.unix_timestamp,
+            *ctx.bumps.get("purchase_receipt").unwrap(),
+        )?;
+        // Record the sale in the house's and the NFT's collection's stats.
+        record_house_sale(&ctx.accounts.house_stats, &ctx.accounts.purchase_receipt, ctx.program_id)?;
+        collection_stats::record_collection_sale(
+            &ctx.accounts.nft_metadata,
+            &escrow_account.nft_mint,
+            &escrow_account.payment_mint,
+            &ctx.accounts.collection_stats,
+            escrow_account.price,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Record the claim, and settle the auction once the item has been claimed too.
+        escrow_account.proceeds_claimed = 1;
+        complete_claim(
+            &mut escrow_account,
+            ctx.accounts.to_claim_completion(),
+            *ctx.bumps.get("event_authority").unwrap(),
+            ctx.program_id,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the reclaim_unclaimed function to unwind a finalized auction whose winning bidder did not
+    // claim the NFT by the claim deadline, provided the exhibitor has not claimed the proceeds either.
+    // Anyone may call it: the NFT goes back to the exhibitor, who keeps a penalty from the winning bid,
+    // and the rest of the bid is refunded to the winning bidder.
+    pub fn reclaim_unclaimed(ctx: Context<ReclaimUnclaimed>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Transfer the NFT back to the exhibitor, unless it never left their wallet.
+        let escrowed = escrow_account.listing_mode()? == ListingMode::Escrow;
+        if escrowed {
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_nft_to_exhibitor_context()
+                    .with_signer(signers_seeds),
+                ctx.accounts.exhibitor_nft_temp_account.amount,
+            )?;
+        }
+
+        // Pay the exhibitor the penalty from the winning bid.
+        let bid = ctx.accounts.highest_bidder_ft_temp_account.amount;
+        let penalty = share_of(bid, UNCLAIMED_PENALTY_BPS)?;
+        token::transfer(
+            ctx.accounts
+                .to_transfer_penalty_to_exhibitor_context()
+                .with_signer(signers_seeds),
+            penalty,
+        )?;
+
+        if escrow_account.payment_mint != native_mint::id()
+            && ctx.accounts.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding the
+            // rest of the bid over to the winning bidder instead.
+            hand_back_bid(
+                ctx.accounts.highest_bidder_ft_temp_account.to_account_info(),
+                escrow_account.highest_bidder,
+                ctx.accounts.pda.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        } else {
+            // Refund the rest of the bid to the winning bidder. Wrapped SOL bids are instead refunded as
+            // lamports when the temporary account is closed, which unwraps it.
+            if escrow_account.payment_mint != native_mint::id() {
+                token::transfer(
+                    ctx.accounts
+                        .to_refund_highest_bidder_context()
+                        .with_signer(signers_seeds),
+                    bid.checked_sub(penalty).ok_or(AuctionError::NumericalOverflow)?,
+                )?;
+            }
+
+            // Close the highest bidder's temporary FT account, returning its rent to its payer. A wrapped SOL
+            // account holds the refund too, so it goes to the highest bidder instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                ctx.accounts.highest_bidder.clone()
+            } else {
+                ctx.accounts.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                ctx.accounts.to_close_ft_context(rent_destination)
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Close the exhibitor's temporary NFT account, if the NFT was escrowed in one.
+        if escrowed {
+            token::close_account(
+                ctx.accounts.to_close_nft_context()
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Mark the auction as settled without a sale and record it as an event.
+        escrow_account.set_status(AuctionStatus::Settled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Settled, Pubkey::default(), 0, ctx.program_id)?;
+        emit_cpi(
+            &AuctionSettled {
+                auction: ctx.accounts.escrow_account.key(),
+                winner: Pubkey::default(),
+                price: 0,
+                sold: false,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the pay_installment function for the winning bidder of an auction with an installment plan to
+    // pay towards the rest of the purchase amount, into the temporary FT account escrowing the down
+    // payment. Anything beyond what is still owed is not taken. Paying late is allowed until someone
+    // defaults the plan.
+    pub fn pay_installment(
+        ctx: Context<PayInstallment>, // Context for the PayInstallment struct.
+        amount: u64,                  // Amount to pay.
+    ) -> Result<()> {
+        // Cap the payment at what is still owed.
+        let escrow_account = ctx.accounts.escrow_account.load()?;
+        let owed = escrow_account
+            .purchase_amount(escrow_account.price)?
+            .saturating_sub(ctx.accounts.highest_bidder_ft_temp_account.amount);
+        let amount = amount.min(owed);
+        require!(amount > 0, AuctionError::InstallmentsPaid);
+
+        // Transfer the payment from the winning bidder to the temporary FT account.
+        token::transfer(ctx.accounts.to_transfer_to_temp_context(), amount)?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the default_installments function for anyone to unwind an auction whose winning bidder missed
+    // an installment: the NFT goes back to the exhibitor, who keeps the default forfeit share of what was
+    // paid, and the rest goes back to the winning bidder.
+    pub fn default_installments(ctx: Context<DefaultInstallments>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Transfer the NFT back to the exhibitor.
+        token::transfer(
+            ctx.accounts
+                .to_transfer_nft_to_exhibitor_context()
+                .with_signer(signers_seeds),
+            ctx.accounts.exhibitor_nft_temp_account.amount,
+        )?;
+
+        // Transfer the forfeited share of the payments to the exhibitor. A frozen account cannot take it, so
+        // the winning bidder gets it back instead.
+        let paid = ctx.accounts.highest_bidder_ft_temp_account.amount;
+        let forfeited_amount = if ctx.accounts.exhibitor_ft_receiving_account.is_frozen() {
+            0
+        } else {
+            share_of(paid, escrow_account.default_forfeit_bps)?
+        };
+        token::transfer(
+            ctx.accounts
+                .to_transfer_forfeit_context()
+                .with_signer(signers_seeds),
+            forfeited_amount,
+        )?;
+        let refunded_amount = paid.checked_sub(forfeited_amount).ok_or(AuctionError::NumericalOverflow)?;
+
+        if escrow_account.payment_mint != native_mint::id()
+            && ctx.accounts.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding the
+            // rest over to the winning bidder instead.
+            hand_back_bid(
+                ctx.accounts.highest_bidder_ft_temp_account.to_account_info(),
+                escrow_account.highest_bidder,
+                ctx.accounts.pda.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        } else {
+            // Refund the rest to the winning bidder. Wrapped SOL is instead refunded as lamports when the
+            // temporary account is closed, which unwraps it.
+            if escrow_account.payment_mint != native_mint::id() {
+                token::transfer(
+                    ctx.accounts
+                        .to_refund_highest_bidder_context()
+                        .with_signer(signers_seeds),
+                    refunded_amount,
+                )?;
+            }
+
+            // Close the winning bidder's temporary FT account, returning its rent to its payer. A wrapped SOL
+            // account holds the refund too, so it goes to the winning bidder instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                ctx.accounts.highest_bidder.clone()
+            } else {
+                ctx.accounts.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                ctx.accounts.to_close_ft_context(rent_destination)
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Close the exhibitor's temporary NFT account.
+        token::close_account(
+            ctx.accounts.to_close_nft_context()
+                .with_signer(signers_seeds),
+        )?;
+
+        // Mark the auction as settled without a sale and record it as events.
+        escrow_account.set_status(AuctionStatus::Settled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Settled, Pubkey::default(), 0, ctx.program_id)?;
+        let event_authority_bump = *ctx.bumps.get("event_authority").unwrap();
+        emit_cpi(
+            &InstallmentsDefaulted {
+                auction: ctx.accounts.escrow_account.key(),
+                bidder: escrow_account.highest_bidder,
+                forfeited_amount,
+                refunded_amount,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            event_authority_bump,
+        )?;
+        emit_cpi(
+            &AuctionSettled {
+                auction: ctx.accounts.escrow_account.key(),
+                winner: Pubkey::default(),
+                price: 0,
+                sold: false,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            event_authority_bump,
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the settle_unmet_reserve function to unwind an ended auction that cannot be sold: its highest
+    // bid did not meet the revealed hidden reserve or the USD reserve and the exhibitor did not accept it
+    // within the acceptance period, or the NFT of a delegate listing has left the exhibitor's account.
+    // Once the price feed grace period is over, a USD reserve whose feed is stale, halted or too uncertain
+    // counts as unmet. The NFT goes back to the exhibitor and the bid back to the highest bidder.
+    pub fn settle_unmet_reserve(ctx: Context<SettleUnmetReserve>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        let pda = ctx.accounts.pda.key();
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Ensure the NFT can no longer be delivered, or the highest bid really falls short of a reserve and
+        // the exhibitor neither accepted it nor may still do so.
+        let nft_temp_account = &ctx.accounts.exhibitor_nft_temp_account;
+        let listing_lost = escrow_account.listing_mode()? == ListingMode::Delegate
+            && (nft_temp_account.amount == 0
+                || nft_temp_account.delegate != COption::Some(pda)
+                || nft_temp_account.delegated_amount == 0);
+        require!(
+            listing_lost
+                || escrow_account.reserve_waived == 0
+                    && (!escrow_account.meets_hidden_reserve()
+                        || !usd_reserve_met_for_unwind(
+                            &escrow_account,
+                            &ctx.accounts.price_feed,
+                            ctx.accounts.highest_bidder_ft_temp_account.amount,
+                            ctx.accounts.payment_mint.decimals,
+                            &ctx.accounts.clock,
+                        )?),
+            AuctionError::ReserveMet
+        );
+        require!(
+            listing_lost || !escrow_account.in_acceptance_period(&ctx.accounts.clock)?,
+            AuctionError::AcceptancePeriodNotOver
+        );
+
+        // Transfer the NFT back to the exhibitor, unless it never left their wallet.
+        let escrowed = escrow_account.listing_mode()? == ListingMode::Escrow;
+        if escrowed {
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_nft_to_exhibitor_context()
+                    .with_signer(signers_seeds),
+                ctx.accounts.exhibitor_nft_temp_account.amount,
+            )?;
+        }
+
+        if escrow_account.payment_mint != native_mint::id()
+            && ctx.accounts.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding the
+            // bid over to the highest bidder instead.
+            hand_back_bid(
+                ctx.accounts.highest_bidder_ft_temp_account.to_account_info(),
+                escrow_account.highest_bidder,
+                ctx.accounts.pda.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        } else {
+            // Refund the highest bid to the highest bidder. Wrapped SOL bids are instead refunded as lamports
+            // when the temporary account is closed, which unwraps it.
+            if escrow_account.payment_mint != native_mint::id() {
+                token::transfer(
+                    ctx.accounts
+                        .to_refund_highest_bidder_context()
+                        .with_signer(signers_seeds),
+                    ctx.accounts.highest_bidder_ft_temp_account.amount,
+                )?;
+            }
+
+            // Close the highest bidder's temporary FT account, returning its rent to its payer. A wrapped SOL
+            // account holds the refund too, so it goes to the highest bidder instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                ctx.accounts.highest_bidder.clone()
+            } else {
+                ctx.accounts.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                ctx.accounts.to_close_ft_context(rent_destination)
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Close the exhibitor's temporary NFT account, if the NFT was escrowed in one.
+        if escrowed {
+            token::close_account(
+                ctx.accounts.to_close_nft_context()
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Mark the auction as settled without a sale and record it as an event.
+        escrow_account.set_status(AuctionStatus::Settled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Settled, Pubkey::default(), 0, ctx.program_id)?;
+        emit_cpi(
+            &AuctionSettled {
+                auction: ctx.accounts.escrow_account.key(),
+                winner: Pubkey::default(),
+                price: 0,
+                sold: false,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the admin_cancel function for the house authority or its emergency canceller to unwind an
+    // auction in an emergency, such as a stolen NFT takedown or broken state: the NFT goes back to the
+    // exhibitor and the highest bid, if any, back to the highest bidder. Finalized auctions can be unwound
+    // until either side has claimed.
+    pub fn admin_cancel(
+        ctx: Context<AdminCancel>, // Context for the AdminCancel struct.
+        reason: u16,               // Why the auction is cancelled (see the events::CANCEL_REASON constants).
+    ) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Transfer the NFT back to the exhibitor, unless it never left their wallet. An NFT frozen in escrow
+        // by its mint's freeze authority cannot move, so it is left there and only the bid is unwound.
+        let escrowed = escrow_account.listing_mode()? == ListingMode::Escrow
+            && !ctx.accounts.exhibitor_nft_temp_account.is_frozen();
+        if escrowed {
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_nft_to_exhibitor_context()
+                    .with_signer(signers_seeds),
+                ctx.accounts.exhibitor_nft_temp_account.amount,
+            )?;
+        }
+
+        // Refund the highest bid, if there is one. Without a bid the highest bidder accounts are the
+        // exhibitor's, so there is nothing to refund.
+        let has_bid = escrow_account.stored_status()? != AuctionStatus::Created;
+        let refunded_amount = if has_bid { ctx.accounts.highest_bidder_ft_temp_account.amount } else { 0 };
+        if has_bid
+            && escrow_account.payment_mint != native_mint::id()
+            && ctx.accounts.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding it
+            // over to the highest bidder instead.
+            hand_back_bid(
+                ctx.accounts.highest_bidder_ft_temp_account.to_account_info(),
+                escrow_account.highest_bidder,
+                ctx.accounts.pda.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        } else if has_bid {
+            // Wrapped SOL bids are instead refunded as lamports when the temporary account is closed,
+            // which unwraps it.
+            if escrow_account.payment_mint != native_mint::id() {
+                token::transfer(
+                    ctx.accounts
+                        .to_refund_highest_bidder_context()
+                        .with_signer(signers_seeds),
+                    refunded_amount,
+                )?;
+            }
+
+            // Close the highest bidder's temporary FT account, returning its rent to its payer. A wrapped
+            // SOL account holds the refund too, so it goes to the highest bidder instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                ctx.accounts.highest_bidder.clone()
+            } else {
+                ctx.accounts.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                ctx.accounts.to_close_ft_context(rent_destination)
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Close the exhibitor's temporary NFT account, if the NFT was escrowed in one.
+        if escrowed {
+            token::close_account(
+                ctx.accounts.to_close_nft_context()
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Mark the auction as cancelled, in its listing receipt too, and record it as an event.
+        escrow_account.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        emit_cpi(
+            &AuctionCancelledByAdmin {
+                auction: ctx.accounts.escrow_account.key(),
+                authority: ctx.accounts.authority.key(),
+                reason,
+                refunded_bidder: if has_bid { escrow_account.highest_bidder } else { Pubkey::default() },
+                refunded_amount,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the reverse_sale function for the arbiter of an auction in arbitration mode to reverse its sale
+    // during the dispute window, such as when the physical item backing the NFT is not delivered: the NFT
+    // goes back to the exhibitor and the winning bid back to the winning bidder, with a reason code.
+    pub fn reverse_sale(
+        ctx: Context<ReverseSale>, // Context for the ReverseSale struct.
+        reason: u16,               // Why the sale is reversed (see the events::REVERSAL_REASON constants).
+    ) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Load the zero-copy escrow account for updating.
+        let mut escrow_account = ctx.accounts.escrow_account.load_mut()?;
+
+        // Transfer the NFT back to the exhibitor, unless it never left their wallet. An NFT frozen in escrow
+        // by its mint's freeze authority cannot move, so it is left there and only the bid is unwound.
+        let escrowed = escrow_account.listing_mode()? == ListingMode::Escrow
+            && !ctx.accounts.exhibitor_nft_temp_account.is_frozen();
+        if escrowed {
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_nft_to_exhibitor_context()
+                    .with_signer(signers_seeds),
+                ctx.accounts.exhibitor_nft_temp_account.amount,
+            )?;
+        }
+
+        // Refund the winning bid.
+        let refunded_amount = ctx.accounts.highest_bidder_ft_temp_account.amount;
+        if escrow_account.payment_mint != native_mint::id()
+            && ctx.accounts.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding it
+            // over to the winning bidder instead.
+            hand_back_bid(
+                ctx.accounts.highest_bidder_ft_temp_account.to_account_info(),
+                escrow_account.highest_bidder,
+                ctx.accounts.pda.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                signers_seeds,
+            )?;
+        } else {
+            // Wrapped SOL bids are instead refunded as lamports when the temporary account is closed,
+            // which unwraps it.
+            if escrow_account.payment_mint != native_mint::id() {
+                token::transfer(
+                    ctx.accounts
+                        .to_refund_highest_bidder_context()
+                        .with_signer(signers_seeds),
+                    refunded_amount,
+                )?;
+            }
+
+            // Close the winning bidder's temporary FT account, returning its rent to its payer. A wrapped
+            // SOL account holds the refund too, so it goes to the winning bidder instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                ctx.accounts.highest_bidder.clone()
+            } else {
+                ctx.accounts.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                ctx.accounts.to_close_ft_context(rent_destination)
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Close the exhibitor's temporary NFT account, if the NFT was escrowed in one.
+        if escrowed {
+            token::close_account(
+                ctx.accounts.to_close_nft_context()
+                    .with_signer(signers_seeds),
+            )?;
+        }
+
+        // Mark the auction as cancelled, in its listing receipt too, and record the reversal as an event.
+        escrow_account.set_status(AuctionStatus::Cancelled);
+        tombstone_listing(&ctx.accounts.listing_receipt, AuctionStatus::Cancelled, Pubkey::default(), 0, ctx.program_id)?;
+        emit_cpi(
+            &SaleReversed {
+                auction: ctx.accounts.escrow_account.key(),
+                arbiter: ctx.accounts.arbiter.key(),
+                reason,
+                refunded_bidder: escrow_account.highest_bidder,
+                refunded_amount,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_edition_sale function for a seller to sell an open edition through the escrow PDA.
+    // The seller hands the mint authority of the edition mint to the PDA, which mints one edition to each
+    // buyer at a price following the bonding curve, up to the maximum supply.
+    pub fn create_edition_sale(
+        ctx: Context<CreateEditionSale>, // Context for the CreateEditionSale struct.
+        curve: BondingCurve,             // How the price grows with the editions sold.
+        base_price: u64,                 // Price of the first edition.
+        curve_param: u64,                // Price increase factor of the curve.
+        max_supply: u64,                 // Number of editions for sale (zero for no limit).
+    ) -> Result<()> {
+        // Find the PDA for the escrow account.
+        let (pda, _bump_seed) = Pubkey::find_program_address(&[ESCROW_PDA_SEED], ctx.program_id);
+
+        // Hand the mint authority of the edition mint to the PDA.
+        token::set_authority(
+            ctx.accounts.to_set_authority_context(),
+            AuthorityType::MintTokens,
+            Some(pda)
+        )?;
+
+        // Record the sale.
+        let edition_sale = &mut ctx.accounts.edition_sale;
+        edition_sale.seller = ctx.accounts.seller.key();
+        edition_sale.edition_mint = ctx.accounts.edition_mint.key();
+        edition_sale.payment_mint = ctx.accounts.seller_ft_account.mint;
+        edition_sale.seller_ft_pubkey = ctx.accounts.seller_ft_account.key();
+        edition_sale.curve = curve as u8;
+        edition_sale.base_price = base_price;
+        edition_sale.curve_param = curve_param;
+        edition_sale.max_supply = max_supply;
+        edition_sale.sold = 0;
+        edition_sale.created_at = Clock::get()?.unix_timestamp;
+        edition_sale.bump = *ctx.bumps.get("edition_sale").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the buy_edition function for a buyer to mint the next edition of an open edition sale at its
+    // current price, which max_price caps. The house fee goes to the treasury and the rest to the seller.
+    pub fn buy_edition(
+        ctx: Context<BuyEdition>, // Context for the BuyEdition struct.
+        max_price: u64,           // Highest price the buyer accepts.
+    ) -> Result<()> {
+        // Ensure the house offers open edition sales.
+        ctx.accounts.auction_house.require_enabled(FEATURE_EDITIONS)?;
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+
+        // Ensure an edition is left and its price is acceptable.
+        let edition_sale = &ctx.accounts.edition_sale;
+        require!(
+            edition_sale.max_supply == 0 || edition_sale.sold < edition_sale.max_supply,
+            AuctionError::EditionSoldOut
+        );
+        let price = edition_sale.current_price()?;
+        require!(price <= max_price, AuctionError::EditionPriceTooHigh);
+        // Calculate the house fee taken from the price.
+        let house_fee = share_of(price, ctx.accounts.auction_house.fee_bps)?;
+
+        // Pay the house fee to the treasury and the rest to the seller.
+        token::transfer(
+            ctx.accounts.to_transfer_context(ctx.accounts.treasury_ft_account.to_account_info()),
+            house_fee,
+        )?;
+        token::transfer(
+            ctx.accounts.to_transfer_context(ctx.accounts.seller_ft_account.to_account_info()),
+            price.checked_sub(house_fee).ok_or(AuctionError::NumericalOverflow)?,
+        )?;
+        // Mint the edition to the buyer.
+        token::mint_to(ctx.accounts.to_mint_to_context().with_signer(signers_seeds), 1)?;
+
+        // Record the purchase in its receipt and advance the editions sold.
+        let index = ctx.accounts.edition_sale.sold;
+        let edition_receipt = &mut ctx.accounts.edition_receipt;
+        edition_receipt.sale = ctx.accounts.edition_sale.key();
+        edition_receipt.buyer = ctx.accounts.buyer.key();
+        edition_receipt.index = index;
+        edition_receipt.price = price;
+        edition_receipt.house_fee = house_fee;
+        edition_receipt.created_at = Clock::get()?.unix_timestamp;
+        edition_receipt.bump = *ctx.bumps.get("edition_receipt").unwrap();
+        ctx.accounts.edition_sale.sold = index.checked_add(1).ok_or(AuctionError::NumericalOverflow)?;
+        // Record the purchase as an event.
+        emit_cpi(
+            &EditionPurchased {
+                sale: ctx.accounts.edition_sale.key(),
+                buyer: ctx.accounts.buyer.key(),
+                index,
+                price,
+            },
+            &ctx.accounts.event_authority,
+            &ctx.accounts.program.to_account_info(),
+            *ctx.bumps.get("event_authority").unwrap(),
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the end_edition_sale function for the seller to stop an open edition sale. The mint authority
+    // goes back to the seller and the sale account is closed by its constraints.
+    pub fn end_edition_sale(ctx: Context<EndEditionSale>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+
+        // Hand the mint authority of the edition mint back to the seller.
+        token::set_authority(
+            ctx.accounts.to_set_authority_context().with_signer(signers_seeds),
+            AuthorityType::MintTokens,
+            Some(ctx.accounts.seller.key())
+        )?;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the blacklist_mint function for the house authority or its whitelist manager to ban a mint,
+    // such as a stolen or compromised NFT or a sanctioned token, from being exhibited or bid in.
+    pub fn blacklist_mint(ctx: Context<BlacklistMint>) -> Result<()> {
+        // Record the mint and when it was banned.
+        let blacklist_entry = &mut ctx.accounts.blacklist_entry;
+        blacklist_entry.mint = ctx.accounts.mint.key();
+        blacklist_entry.created_at = Clock::get()?.unix_timestamp;
+        blacklist_entry.bump = *ctx.bumps.get("blacklist_entry").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the unblacklist_mint function for the house authority or its whitelist manager to lift a ban.
+    // The entry is closed by its constraints, so there is nothing left to do.
+    pub fn unblacklist_mint(_ctx: Context<UnblacklistMint>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_collection function for the house authority to scope the house to a
+    // collection: only NFTs verified as members of it may then be listed, which keeps out lookalike mints.
+    // The default pubkey opens the house to any NFT again. Auctions already listed are not affected.
+    pub fn update_house_collection(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        collection: Pubkey,        // Mint of the collection listed NFTs must belong to (default for any).
+    ) -> Result<()> {
+        // Update the house's collection.
+        ctx.accounts.auction_house.collection = collection;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the update_house_approved_sellers function for the house authority to restrict listing to the
+    // sellers on its allowlist, or to open it to everyone again. Auctions already listed are not affected.
+    pub fn update_house_approved_sellers(
+        ctx: Context<UpdateHouse>, // Context for the UpdateHouse struct.
+        enabled: bool,             // Whether only approved sellers may list.
+    ) -> Result<()> {
+        // Update the approved-sellers mode.
+        ctx.accounts.auction_house.approved_sellers_only = enabled;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the add_seller function for the house authority or its whitelist manager to approve a seller,
+    // who may then list while the house only admits approved sellers.
+    pub fn add_seller(ctx: Context<AddSeller>) -> Result<()> {
+        // Record the seller and when they were approved.
+        let seller_allowlist = &mut ctx.accounts.seller_allowlist;
+        seller_allowlist.seller = ctx.accounts.seller.key();
+        seller_allowlist.created_at = Clock::get()?.unix_timestamp;
+        seller_allowlist.bump = *ctx.bumps.get("seller_allowlist").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the remove_seller function for the house authority or its whitelist manager to withdraw a
+    // seller's approval. Auctions they already listed go on. The entry is closed by its constraints, so
+    // there is nothing left to do.
+    pub fn remove_seller(_ctx: Context<RemoveSeller>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the add_partner function for the house authority or its whitelist manager to whitelist a
+    // partner frontend, which may
+    // then list auctions with its own FT fee account and receive a share of their house fee.
+    pub fn add_partner(
+        ctx: Context<AddPartner>, // Context for the AddPartner struct.
+        fee_share_bps: u16,       // The partner's share of the house fee in basis points.
+    ) -> Result<()> {
+        // Ensure the share does not exceed the whole house fee.
+        require!(fee_share_bps as u64 <= BASIS_POINTS, AuctionError::InvalidPartnerShare);
+        // Record the partner, its share and when it was added.
+        let partner_entry = &mut ctx.accounts.partner_entry;
+        partner_entry.partner = ctx.accounts.partner.key();
+        partner_entry.fee_share_bps = fee_share_bps;
+        partner_entry.created_at = Clock::get()?.unix_timestamp;
+        partner_entry.bump = *ctx.bumps.get("partner_entry").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the remove_partner function for the house authority or its whitelist manager to take a partner
+    // frontend off the whitelist. Auctions it already listed keep paying its share. The entry is closed by
+    // its constraints, so there is nothing left to do.
+    pub fn remove_partner(_ctx: Context<RemovePartner>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the add_royalty_override function for the house authority to set the royalty paid to the
+    // creators of a verified collection's NFTs at settlement, in place of their metadata's seller fee, or to
+    // cap it. To change an override, remove it and add it again.
+    pub fn add_royalty_override(
+        ctx: Context<AddRoyaltyOverride>, // Context for the AddRoyaltyOverride struct.
+        royalty_bps: u16,                 // The royalty, or its cap, in basis points.
+        cap: bool,                        // Whether the royalty only caps the metadata's seller fee.
+    ) -> Result<()> {
+        // Ensure the royalty does not exceed the whole proceeds.
+        require!(royalty_bps as u64 <= BASIS_POINTS, AuctionError::InvalidRoyaltyOverride);
+        // Record the collection, its royalty and when it was added.
+        let royalty_override = &mut ctx.accounts.royalty_override;
+        royalty_override.collection = ctx.accounts.collection.key();
+        royalty_override.royalty_bps = royalty_bps;
+        royalty_override.cap = cap;
+        royalty_override.created_at = Clock::get()?.unix_timestamp;
+        royalty_override.bump = *ctx.bumps.get("royalty_override").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the remove_royalty_override function for the house authority to go back to the metadata's
+    // seller fee for a collection. The override is closed by its constraints, so there is nothing left to
+    // do.
+    pub fn remove_royalty_override(_ctx: Context<RemoveRoyaltyOverride>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_collection_stats function for anyone to start recording the sales of a collection
+    // in a payment mint, so lending protocols can read its volume and floor on-chain. Every sale of a
+    // verified member settled afterwards updates the stats.
+    pub fn create_collection_stats(ctx: Context<CreateCollectionStats>) -> Result<()> {
+        // Record the collection, the payment mint and when the stats were created.
+        let collection_stats = &mut ctx.accounts.collection_stats;
+        collection_stats.collection = ctx.accounts.collection.key();
+        collection_stats.payment_mint = ctx.accounts.payment_mint.key();
+        collection_stats.created_at = Clock::get()?.unix_timestamp;
+        collection_stats.bump = *ctx.bumps.get("collection_stats").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_house_stats function for anyone to start recording the house's sales and fee revenue
+    // in a payment mint, for dashboards and accounting. Every sale settled afterwards updates the stats.
+    pub fn create_house_stats(ctx: Context<CreateHouseStats>) -> Result<()> {
+        // Record the payment mint and when the stats were created.
+        let house_stats = &mut ctx.accounts.house_stats;
+        house_stats.payment_mint = ctx.accounts.payment_mint.key();
+        house_stats.created_at = Clock::get()?.unix_timestamp;
+        house_stats.bump = *ctx.bumps.get("house_stats").unwrap();
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_royalty_vault function for anyone to create the house's royalty vault of a payment
+    // mint, which settlement escrows the creators' royalties in. Sales owing a royalty cannot settle in a
+    // mint until its vault exists.
+    pub fn create_royalty_vault(_ctx: Context<CreateRoyaltyVault>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the distribute_royalties function for anyone to pay the NFT's creators the royalty escrowed in
+    // the house's royalty vault when a sale settled, as recorded in its purchase receipt. The creators' FT
+    // accounts are passed as remaining accounts, in any order, so they may be paid across several
+    // transactions; a creator already paid, or whose account is frozen, is skipped.
+    pub fn distribute_royalties<'info>(ctx: Context<'_, '_, '_, 'info, DistributeRoyalties<'info>>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+
+        for creator_account in ctx.remaining_accounts {
+            // Ensure the FT account holds the payment mint and belongs to one of the sale's creators.
+            let creator_token_account = Account::<TokenAccount>::try_from(creator_account)?;
+            let purchase_receipt = &ctx.accounts.purchase_receipt;
+            require_keys_eq!(
+                creator_token_account.mint,
+                purchase_receipt.payment_mint,
+                AuctionError::PaymentMintMismatch
+            );
+            require!(
+                purchase_receipt.royalty_creators.contains(&creator_token_account.owner),
+                AuctionError::CreatorAccountMismatch
+            );
+            // Find the creator's first share still owed, since metadata may list a creator twice.
+            let index = (0..MAX_CREATORS).find(|&index| {
+                purchase_receipt.royalty_creators[index] == creator_token_account.owner
+                    && purchase_receipt.royalty_owed[index] > 0
+            });
+            let index = match index {
+                Some(index) => index,
+                None => continue,
+            };
+            // A frozen account cannot take the creator's share, which stays in the vault until it is thawed.
+            if creator_token_account.is_frozen() {
+                continue;
+            }
+            // Transfer the share from the vault to the creator, and record it as paid.
+            let share = purchase_receipt.royalty_owed[index];
+            token::transfer(
+                ctx.accounts
+                    .to_transfer_to_creator_context(creator_account.clone())
+                    .with_signer(signers_seeds),
+                share,
+            )?;
+            ctx.accounts.purchase_receipt.royalty_owed[index] = 0;
+        }
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the create_affiliate_vault function for anyone to create the house's affiliate vault of a
+    // payment mint, which settlement escrows affiliate fees in. Sales whose winning bid has an affiliate
+    // cannot settle in a mint until its vault exists.
+    pub fn create_affiliate_vault(_ctx: Context<CreateAffiliateVault>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the claim_affiliate_earnings function for anyone to pay the affiliate of a sale the fee
+    // escrowed in the house's affiliate vault when it settled, as recorded in its purchase receipt.
+    pub fn claim_affiliate_earnings(ctx: Context<ClaimAffiliateEarnings>) -> Result<()> {
+        // Create the seeds for the signer from the escrow PDA's stored bump seed.
+        let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[ctx.accounts.auction_house.escrow_bump]]];
+        // Ensure the affiliate's FT account can take the fee, which stays in the vault until it is thawed.
+        require!(!ctx.accounts.affiliate_ft_account.is_frozen(), AuctionError::RecipientFrozen);
+
+        // Transfer the fee from the vault to the affiliate, and record it as paid.
+        let affiliate_owed = ctx.accounts.purchase_receipt.affiliate_owed;
+        token::transfer(
+            ctx.accounts
+                .to_transfer_to_affiliate_context()
+                .with_signer(signers_seeds),
+            affiliate_owed,
+        )?;
+        ctx.accounts.purchase_receipt.affiliate_owed = 0;
+
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the emit_event function, which does nothing but carry an event in its instruction data. Only
+    // the program itself can call it, through emit_cpi, since it must be signed by the event authority.
+    pub fn emit_event(_ctx: Context<EmitEvent>, _event: Vec<u8>) -> Result<()> {
+        // Return an Ok result.
+        Ok(())
+    }
+
+    // Define the get_auction_state function to report the values derived from an auction's state, so
+    // wallets can simulate it instead of re-implementing the rules. The AuctionState is written as return
+    // data.
+    pub fn get_auction_state(ctx: Context<GetAuctionState>) -> Result<()> {
+        let escrow_account = ctx.accounts.escrow_account.load()?;
+        let clock = &ctx.accounts.clock;
+        let status = escrow_account.status(clock)?;
+        let taking_bids = matches!(status, AuctionStatus::Created | AuctionStatus::Live);
+
+        // A bid must be strictly higher than the current price, or at least the current price of a Dutch
+        // auction.
+        let min_next_bid = if taking_bids {
+            escrow_account.min_next_bid(clock)?
+        } else {
+            0
+        };
+        // The time left is measured in the auction's timing mode.
+        let time_remaining = if taking_bids {
+            escrow_account.end_at.saturating_sub(escrow_account.now(clock)?).max(0) as u64
+        } else {
+            0
+        };
+
+        let state = AuctionState {
+            status,
+            min_next_bid,
+            time_remaining,
+            current_price: escrow_account.current_price(clock)?,
+            bid_count: escrow_account.bid_count,
+            unique_bidders: escrow_account.unique_bidders(),
+        };
+        anchor_lang::solana_program::program::set_return_data(&state.try_to_vec()?);
+
+        // Return an Ok result.
+        Ok(())
+    }
+}
+
+// Define the Exhibit struct with associated accounts and instructions.
+#[derive(Accounts)]
+#[instruction(initial_price: u64, auction_duration_sec: u64)]
+pub struct Exhibit<'info> {
+    // The exhibitor's account, which must be a signer.
+    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
+    #[account(signer)]
+    pub exhibitor: AccountInfo<'info>,
+    // The payer of the transaction and of the escrow and temporary NFT accounts, which gets their rent
+    // back. It may differ from the exhibitor so platforms can sponsor listings.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The exhibitor's NFT account, which must have an amount of 1.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.amount == 1
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the same mint as the NFT account.
+    #[account(
+        constraint = exhibitor_nft_temp_account.mint == exhibitor_nft_token_account.mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account.
+    pub exhibitor_ft_receiving_account:Account<'info, TokenAccount>,
+    // The registry, which assigns the auction its ID.
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    // The escrow account, created at the PDA of the auction's ID.
+    #[account(
+        init,
+        payer = payer,
+        space = Auction::LEN,
+        seeds = [AUCTION_SEED, registry.next_auction_id()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The receipt of the listing, created alongside the escrow account.
+    #[account(
+        init,
+        payer = payer,
+        space = ListingReceipt::LEN,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref()],
+        bump
+    )]
+    pub listing_receipt: Box<Account<'info, ListingReceipt>>,
+    // The auction house configuration, which limits the auction duration, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The blacklist entry of the NFT's mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_nft_token_account.mint.as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_ft_receiving_account.mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The exhibitor's seller allowlist entry, which must exist while the house only admits approved sellers.
+    /// CHECK: Checked by its seeds, and only its owner is used.
+    #[account(
+        seeds = [SELLER_ALLOWLIST_SEED, exhibitor.key().as_ref()],
+        bump,
+        constraint = !auction_house.approved_sellers_only
+            || *seller_allowlist_entry.owner == crate::ID @ AuctionError::SellerNotApproved
+    )]
+    pub seller_allowlist_entry: AccountInfo<'info>,
+    // The NFT's mint, which must be a one-of-one: a supply of 1 and no decimals, so a fungible token
+    // cannot be listed as an NFT.
+    #[account(
+        address = exhibitor_nft_token_account.mint @ AuctionError::NftMintMismatch,
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ AuctionError::NotAnNft
+    )]
+    pub nft_mint: Box<Account<'info, Mint>>,
+    // The NFT's Metaplex metadata, which must show it a verified member of the house's collection, if the
+    // house has one.
+    /// CHECK: Checked by its address, and only read if the house has a collection.
+    #[account(address = metadata::metadata_address(&exhibitor_nft_token_account.mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The house treasury wallet, which receives the listing fee.
+    /// CHECK: Checked against the auction house.
+    #[account(mut, address = auction_house.treasury)]
+    pub treasury: AccountInfo<'info>,
+    // The PDA account, which becomes the delegate of the NFT in the delegate listing mode.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the ReuseEscrow struct with associated accounts.
+#[derive(Accounts)]
+pub struct ReuseEscrow<'info> {
+    // The exhibitor of the sold auction, which must be a signer.
+    pub exhibitor: Signer<'info>,
+    // The payer of the escrow account, which keeps paying for it and the new temporary NFT account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The exhibitor's NFT account, which must have an amount of 1.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.amount == 1
+    )]
+    pub exhibitor_nft_token_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's temporary NFT account, which must hold the same mint as the NFT account.
+    #[account(
+        constraint = exhibitor_nft_temp_account.mint == exhibitor_nft_token_account.mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's FT receiving account.
+    pub exhibitor_ft_receiving_account: Box<Account<'info, TokenAccount>>,
+    // The escrow account, which must have been sold by the exhibitor and kept for reuse.
+    #[account(
+        mut,
+        has_one = exhibitor @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.exhibitor_rent_payer == payer.key() @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Settled @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.reuse_escrow != 0 @ AuctionError::EscrowNotReusable
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The receipt of the listing, created for the escrow account's new round.
+    #[account(
+        init,
+        payer = payer,
+        space = ListingReceipt::LEN,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &round_seed(next_round(escrow_account.load()?.round)?)],
+        bump
+    )]
+    pub listing_receipt: Box<Account<'info, ListingReceipt>>,
+    // The auction house configuration, which limits the auction duration, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The blacklist entry of the NFT's mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_nft_token_account.mint.as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_ft_receiving_account.mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The exhibitor's seller allowlist entry, which must exist while the house only admits approved sellers.
+    /// CHECK: Checked by its seeds, and only its owner is used.
+    #[account(
+        seeds = [SELLER_ALLOWLIST_SEED, exhibitor.key().as_ref()],
+        bump,
+        constraint = !auction_house.approved_sellers_only
+            || *seller_allowlist_entry.owner == crate::ID @ AuctionError::SellerNotApproved
+    )]
+    pub seller_allowlist_entry: AccountInfo<'info>,
+    // The NFT's mint, which must be a one-of-one: a supply of 1 and no decimals, so a fungible token
+    // cannot be listed as an NFT.
+    #[account(
+        address = exhibitor_nft_token_account.mint @ AuctionError::NftMintMismatch,
+        constraint = nft_mint.supply == 1 && nft_mint.decimals == 0 @ AuctionError::NotAnNft
+    )]
+    pub nft_mint: Box<Account<'info, Mint>>,
+    // The NFT's Metaplex metadata, which must show it a verified member of the house's collection, if the
+    // house has one.
+    /// CHECK: Checked by its address, and only read if the house has a collection.
+    #[account(address = metadata::metadata_address(&exhibitor_nft_token_account.mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The PDA account, which owns the escrowed NFT or becomes its delegate.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the ExhibitMany struct with associated accounts. The exhibit accounts of each NFT follow as
+// remaining accounts.
+#[derive(Accounts)]
+pub struct ExhibitMany<'info> {
+    // The exhibitor of every NFT in the batch, which must be a signer.
+    pub exhibitor: Signer<'info>,
+}
+
+// Define the ExhibitFromGovernance struct with associated accounts.
+#[derive(Accounts)]
+pub struct ExhibitFromGovernance<'info> {
+    // The governance whose native treasury holds the NFT.
+    /// CHECK: Only its owner and address are used, to derive the treasury.
+    #[account(owner = governance::SPL_GOVERNANCE_PROGRAM_ID)]
+    pub governance: AccountInfo<'info>,
+    // The exhibit accounts, with the governance's native treasury as the exhibitor and its ATA receiving
+    // the proceeds.
+    #[account(
+        constraint = exhibit.exhibitor.key() == governance::native_treasury_address(governance.key) @ AuctionError::InvalidGovernanceTreasury,
+        constraint = governance::is_treasury_ata(
+            governance.key,
+            &exhibit.exhibitor_ft_receiving_account.mint,
+            &exhibit.exhibitor_ft_receiving_account.key()
+        ) @ AuctionError::InvalidGovernanceTreasury
+    )]
+    pub exhibit: Exhibit<'info>,
+}
+
+// Define the ExhibitDomain struct with associated accounts.
+#[derive(Accounts)]
+pub struct ExhibitDomain<'info> {
+    // The exhibitor's account, which must be a signer and own the domain.
+    /// CHECK: Checked against the domain's owner.
+    #[account(signer)]
+    pub exhibitor: AccountInfo<'info>,
+    // The payer of the transaction and of the escrow account, which gets its rent back.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The domain's name account, whose owner becomes the PDA.
+    /// CHECK: Checked and transferred by the sns module.
+    #[account(mut)]
+    pub name_account: AccountInfo<'info>,
+    // The exhibitor's FT receiving account.
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The registry, which assigns the auction its ID.
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    // The escrow account, created at the PDA of the auction's ID.
+    #[account(
+        init,
+        payer = payer,
+        space = Auction::LEN,
+        seeds = [AUCTION_SEED, registry.next_auction_id()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The receipt of the listing, created alongside the escrow account.
+    #[account(
+        init,
+        payer = payer,
+        space = ListingReceipt::LEN,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref()],
+        bump
+    )]
+    pub listing_receipt: Box<Account<'info, ListingReceipt>>,
+    // The auction house configuration, which limits the auction duration, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The blacklist entry of the domain's name account, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, name_account.key().as_ref()],
+        bump,
+        constraint = name_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub name_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_ft_receiving_account.mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The exhibitor's seller allowlist entry, which must exist while the house only admits approved sellers.
+    /// CHECK: Checked by its seeds, and only its owner is used.
+    #[account(
+        seeds = [SELLER_ALLOWLIST_SEED, exhibitor.key().as_ref()],
+        bump,
+        constraint = !auction_house.approved_sellers_only
+            || *seller_allowlist_entry.owner == crate::ID @ AuctionError::SellerNotApproved
+    )]
+    pub seller_allowlist_entry: AccountInfo<'info>,
+    // The house treasury wallet, which receives the listing fee.
+    /// CHECK: Checked against the auction house.
+    #[account(mut, address = auction_house.treasury)]
+    pub treasury: AccountInfo<'info>,
+    // The PDA account, which becomes the domain's owner.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The Solana Name Service program, which transfers the domain.
+    /// CHECK: Checked by its address.
+    #[account(address = sns::NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: AccountInfo<'info>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the ExhibitStake struct with associated accounts.
+#[derive(Accounts)]
+pub struct ExhibitStake<'info> {
+    // The exhibitor's account, which must be a signer and hold both authorities of the stake account.
+    /// CHECK: Checked against the stake account's authorities.
+    #[account(signer)]
+    pub exhibitor: AccountInfo<'info>,
+    // The payer of the transaction and of the escrow account, which gets its rent back.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The stake account, whose staker and withdrawer become the PDA.
+    /// CHECK: Checked and authorized by the stake module.
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+    // The exhibitor's FT receiving account.
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The registry, which assigns the auction its ID.
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    // The escrow account, created at the PDA of the auction's ID.
+    #[account(
+        init,
+        payer = payer,
+        space = Auction::LEN,
+        seeds = [AUCTION_SEED, registry.next_auction_id()?.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The receipt of the listing, created alongside the escrow account.
+    #[account(
+        init,
+        payer = payer,
+        space = ListingReceipt::LEN,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref()],
+        bump
+    )]
+    pub listing_receipt: Box<Account<'info, ListingReceipt>>,
+    // The auction house configuration, which limits the auction duration, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The blacklist entry of the stake account, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, stake_account.key().as_ref()],
+        bump,
+        constraint = stake_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub stake_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, exhibitor_ft_receiving_account.mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The exhibitor's seller allowlist entry, which must exist while the house only admits approved sellers.
+    /// CHECK: Checked by its seeds, and only its owner is used.
+    #[account(
+        seeds = [SELLER_ALLOWLIST_SEED, exhibitor.key().as_ref()],
+        bump,
+        constraint = !auction_house.approved_sellers_only
+            || *seller_allowlist_entry.owner == crate::ID @ AuctionError::SellerNotApproved
+    )]
+    pub seller_allowlist_entry: AccountInfo<'info>,
+    // The house treasury wallet, which receives the listing fee.
+    /// CHECK: Checked against the auction house.
+    #[account(mut, address = auction_house.treasury)]
+    pub treasury: AccountInfo<'info>,
+    // The PDA account, which becomes the stake account's staker and withdrawer.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp and checking the lockup.
+    pub clock: Sysvar<'info, Clock>,
+    // The stake program, which changes the stake account's authorities.
+    /// CHECK: Checked by its address.
+    #[account(address = stake::STAKE_PROGRAM_ID)]
+    pub stake_program: AccountInfo<'info>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the Cancel struct with associated accounts.
+#[derive(Accounts)]
+pub struct Cancel<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == escrow_account.load()?.exhibitor,
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    #[account(mut)]
+    pub exhibitor_rent_payer: SystemAccount<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        has_one = exhibitor_nft_temp_account,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+}
+
+// Define the ExpireAuction struct with associated accounts.
+#[derive(Accounts)]
+pub struct ExpireAuction<'info> {
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == escrow_account.load()?.exhibitor,
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    #[account(mut)]
+    pub exhibitor_rent_payer: SystemAccount<'info>,
+    // The escrow account, which must be an NFT auction that ended with the exhibitor still its highest
+    // bidder, that is without a bid.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = exhibitor_nft_temp_account,
+        constraint = escrow_account.load()?.item_type()? == ItemType::Nft @ AuctionError::DomainUnsupported,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.highest_bidder == escrow_account.load()?.exhibitor @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.has_ended(&clock)? @ AuctionError::AuctionNotEnded,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+}
+
+// Define the CancelDomain struct with associated accounts.
+#[derive(Accounts)]
+pub struct CancelDomain<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The domain's name account, which goes back to the exhibitor.
+    /// CHECK: Checked against the escrow account, and transferred by the sns module.
+    #[account(mut, address = escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch)]
+    pub name_account: AccountInfo<'info>,
+    // The exhibitor's rent payer, which gets the rent of the escrow account back.
+    #[account(mut)]
+    pub exhibitor_rent_payer: SystemAccount<'info>,
+    // The escrow account, which must be a domain auction without bids.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.item_type()? == ItemType::Domain @ AuctionError::NotADomainAuction,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The PDA account, which owns the escrowed domain.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account, which burns the listing token.
+    pub token_program: Program<'info, Token>,
+    // The Solana Name Service program, which transfers the domain.
+    /// CHECK: Checked by its address.
+    #[account(address = sns::NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: AccountInfo<'info>,
+    // The listing receipt of the auction, tombstoned with its final status.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+}
+
+// Define the CancelStake struct with associated accounts.
+#[derive(Accounts)]
+pub struct CancelStake<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The stake account, whose authorities go back to the exhibitor.
+    /// CHECK: Checked against the escrow account, and authorized by the stake module.
+    #[account(mut, address = escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch)]
+    pub stake_account: AccountInfo<'info>,
+    // The exhibitor's rent payer, which gets the rent of the escrow account back.
+    #[account(mut)]
+    pub exhibitor_rent_payer: SystemAccount<'info>,
+    // The escrow account, which must be a stake account auction without bids.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.item_type()? == ItemType::Stake @ AuctionError::NotAStakeAuction,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The PDA account, which holds the escrowed stake account's authorities.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account, which burns the listing token.
+    pub token_program: Program<'info, Token>,
+    // The system clock account, which the stake program reads.
+    pub clock: Sysvar<'info, Clock>,
+    // The stake program, which changes the stake account's authorities.
+    /// CHECK: Checked by its address.
+    #[account(address = stake::STAKE_PROGRAM_ID)]
+    pub stake_program: AccountInfo<'info>,
+    // The listing receipt of the auction, tombstoned with its final status.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+}
+
+// Define the SetOperator struct with associated accounts.
+#[derive(Accounts)]
+pub struct SetOperator<'info> {
+    // The exhibitor's account, which must be a signer.
+    pub exhibitor: Signer<'info>,
+    // The escrow account, which must belong to the exhibitor.
+    #[account(
+        mut,
+        has_one = exhibitor @ AuctionError::Unauthorized
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+}
+
+// Define the UpdateProceedsAccount struct with associated accounts.
+#[derive(Accounts)]
+pub struct UpdateProceedsAccount<'info> {
+    // The exhibitor's account, which must be a signer.
+    pub exhibitor: Signer<'info>,
+    // The exhibitor's recorded FT receiving account, which must be frozen.
+    #[account(constraint = exhibitor_ft_receiving_account.is_frozen() @ AuctionError::RecipientNotFrozen)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The exhibitor's new FT receiving account, which must hold the payment mint and not be frozen.
+    #[account(
+        constraint = new_ft_receiving_account.owner == exhibitor.key() @ AuctionError::Unauthorized,
+        constraint = new_ft_receiving_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = !new_ft_receiving_account.is_frozen() @ AuctionError::RecipientFrozen
+    )]
+    pub new_ft_receiving_account: Account<'info, TokenAccount>,
+    // The escrow account, which must belong to the exhibitor and not have paid out the proceeds yet.
+    #[account(
+        mut,
+        has_one = exhibitor @ AuctionError::Unauthorized,
+        has_one = exhibitor_ft_receiving_account,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+}
+
+// Define the MintListingToken struct with associated accounts.
+#[derive(Accounts)]
+pub struct MintListingToken<'info> {
+    // The exhibitor, which must be a signer and pays for the listing token's mint and account.
+    #[account(mut)]
+    pub exhibitor: Signer<'info>,
+    // The escrow account, which must belong to the exhibitor, take bids and have no listing token yet.
+    #[account(
+        mut,
+        has_one = exhibitor @ AuctionError::Unauthorized,
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.listing_token_mint == Pubkey::default() @ AuctionError::ListingTokenMinted
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The listing token mint PDA of the escrow account's round, whose mint and freeze authority is the PDA.
+    #[account(
+        init,
+        payer = exhibitor,
+        mint::decimals = 0,
+        mint::authority = pda,
+        mint::freeze_authority = pda,
+        seeds = [LISTING_TOKEN_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_token_mint: Account<'info, Mint>,
+    // The exhibitor's associated token account of the listing token.
+    #[account(
+        init,
+        payer = exhibitor,
+        associated_token::mint = listing_token_mint,
+        associated_token::authority = exhibitor
+    )]
+    pub exhibitor_listing_token_account: Account<'info, TokenAccount>,
+    // The PDA account, which mints, freezes and later burns the listing token.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The associated token program account.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the mint and token account.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the BurnListingToken struct with associated accounts and instructions.
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct BurnListingToken<'info> {
+    // The escrow account, whose round must be over: settled, cancelled, closed or reused since.
+    /// CHECK: Only read if it still belongs to this program.
+    #[account(
+        constraint = listing_round_over(&escrow_account, round)? @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountInfo<'info>,
+    // The listing token mint PDA of the escrow account's round.
+    #[account(
+        mut,
+        seeds = [LISTING_TOKEN_SEED, escrow_account.key().as_ref(), &round_seed(round)],
+        bump
+    )]
+    pub listing_token_mint: Account<'info, Mint>,
+    // The exhibitor's token account holding the listing token.
+    #[account(
+        mut,
+        constraint = exhibitor_listing_token_account.mint == listing_token_mint.key() @ AuctionError::ListingTokenMismatch
+    )]
+    pub exhibitor_listing_token_account: Account<'info, TokenAccount>,
+    // The PDA account, which burns the listing token.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the UpdateAuction struct with associated accounts.
+#[derive(Accounts)]
+pub struct UpdateAuction<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The escrow account, which must not have bids or have ended yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which limits the auction duration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the UpdatePrintSale struct with associated accounts.
+#[derive(Accounts)]
+pub struct UpdatePrintSale<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The escrow account, which must not have bids or have ended yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The master edition PDA of the escrowed NFT, which must be a master edition to print from.
+    /// CHECK: Checked by its address, and read by the metadata module.
+    #[account(address = metadata::edition_address(&escrow_account.load()?.nft_mint) @ AuctionError::NotAMasterEdition)]
+    pub master_edition: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the Relist struct with associated accounts.
+#[derive(Accounts)]
+pub struct Relist<'info> {
+    // The exhibitor, its operator or the opted-in crank, which must be a signer.
+    pub authority: Signer<'info>,
+    // The escrow account, which must have ended without a bid.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.may_relist(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.has_ended(&clock)? @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.series == Pubkey::default() @ AuctionError::AuctionInSeries
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which limits the auction duration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the RelistWithPrice struct with associated accounts.
+#[derive(Accounts)]
+pub struct RelistWithPrice<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The escrow account, which must not have bids, whether it has ended or not.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.series == Pubkey::default() @ AuctionError::AuctionInSeries
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which limits the auction duration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the CreateSeries struct with associated accounts.
+#[derive(Accounts)]
+#[instruction(series_id: u64)]
+pub struct CreateSeries<'info> {
+    // The authority of the series, which must be a signer and manage every lot it adds.
+    pub authority: Signer<'info>,
+    // The payer of the transaction and of the series account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The series PDA of the authority and series ID.
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionSeries::LEN,
+        seeds = [SERIES_SEED, authority.key().as_ref(), &series_id.to_le_bytes()],
+        bump
+    )]
+    pub series: Account<'info, AuctionSeries>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the AddSeriesLot struct with associated accounts.
+#[derive(Accounts)]
+pub struct AddSeriesLot<'info> {
+    // The authority of the series, which must be a signer.
+    pub authority: Signer<'info>,
+    // The series account.
+    #[account(
+        mut,
+        seeds = [SERIES_SEED, authority.key().as_ref(), &series.series_id.to_le_bytes()],
+        bump = series.bump,
+        has_one = authority
+    )]
+    pub series: Account<'info, AuctionSeries>,
+    // The escrow account of the lot, which the authority must manage and which must have no bids, not have
+    // ended and not be in a series yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.series == Pubkey::default() @ AuctionError::AuctionInSeries
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which limits the lot duration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the OpenNextLot struct with associated accounts.
+#[derive(Accounts)]
+pub struct OpenNextLot<'info> {
+    // The series account.
+    #[account(
+        mut,
+        seeds = [SERIES_SEED, series.authority.as_ref(), &series.series_id.to_le_bytes()],
+        bump = series.bump
+    )]
+    pub series: Account<'info, AuctionSeries>,
+    // The escrow account of the lot before the next one, unused when the next lot is the first.
+    /// CHECK: Checked against the series' lots, and only read if it still belongs to this program.
+    pub previous_lot: AccountInfo<'info>,
+    // The escrow account of the next lot.
+    /// CHECK: Checked against the series' lots, and only written if it still belongs to this program.
+    #[account(mut)]
+    pub lot: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the RevealReserve struct with associated accounts.
+#[derive(Accounts)]
+pub struct RevealReserve<'info> {
+    // The escrow account, which must have ended and not been settled yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the AcceptHighestBid struct with associated accounts.
+#[derive(Accounts)]
+pub struct AcceptHighestBid<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The escrow account, which must have ended with a bid less than the acceptance period ago.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = escrow_account.load()?.in_acceptance_period(&clock)? @ AuctionError::AcceptancePeriodOver
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the EnableVesting struct with associated accounts.
+#[derive(Accounts)]
+pub struct EnableVesting<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The payer of the vesting schedule and vault, which gets their rent back once they are closed.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The escrow account, which must not have bids or have ended yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The mint the auction is paid in.
+    #[account(
+        constraint = payment_mint.key() == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub payment_mint: Account<'info, Mint>,
+    // The vesting schedule PDA of the auction.
+    #[account(
+        init,
+        payer = payer,
+        space = VestingSchedule::LEN,
+        seeds = [VESTING_SEED, escrow_account.key().as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    // The vault PDA holding the proceeds while they vest, owned by the escrow PDA.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = payment_mint,
+        token::authority = pda,
+        seeds = [VESTING_VAULT_SEED, vesting_schedule.key().as_ref()],
+        bump
+    )]
+    pub vesting_vault: Account<'info, TokenAccount>,
+    // The PDA account.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the vault.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the ClaimVested struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    // The vesting schedule of the auction.
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, vesting_schedule.auction.as_ref()],
+        bump = vesting_schedule.bump,
+        has_one = vault,
+        has_one = rent_payer
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    // The vault holding the proceeds while they vest.
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account recorded in the schedule.
+    #[account(
+        mut,
+        constraint = beneficiary_ft_account.key() == vesting_schedule.beneficiary_ft_pubkey @ AuctionError::VestingAccountMismatch
+    )]
+    pub beneficiary_ft_account: Account<'info, TokenAccount>,
+    // The escrow account of the auction, which is only read to tell whether it has been closed.
+    /// CHECK: Checked against the vesting schedule, and only its emptiness is used.
+    #[account(constraint = escrow_account.key() == vesting_schedule.auction @ AuctionError::VestingAccountMismatch)]
+    pub escrow_account: AccountInfo<'info>,
+    // The payer of the vesting schedule and vault, which gets their rent back once they are closed.
+    /// CHECK: Checked against the vesting schedule.
+    #[account(mut)]
+    pub rent_payer: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The PDA account, which owns the vault.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Implement the ClaimVested struct.
+impl<'info> ClaimVested<'info> {
+    // Define a function to create a context for transferring the vested proceeds from the vault to the exhibitor's FT receiving account.
+    fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info().clone(),
+            to: self.beneficiary_ft_account.to_account_info().clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the empty vault, returning its rent to its payer.
+    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info().clone(),
+            destination: self.rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the EnableRental struct with associated accounts.
+#[derive(Accounts)]
+pub struct EnableRental<'info> {
+    // The exhibitor or its operator, which must be a signer.
+    pub authority: Signer<'info>,
+    // The payer of the rental, which gets its rent back once it is closed.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The escrow account, which must not have bids or have ended yet.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.is_managed_by(authority.key()) @ AuctionError::Unauthorized,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Created @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The rental PDA of the auction.
+    #[account(
+        init,
+        payer = payer,
+        space = Rental::LEN,
+        seeds = [RENTAL_SEED, escrow_account.key().as_ref()],
+        bump
+    )]
+    pub rental: Account<'info, Rental>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the EndRental struct with associated accounts.
+#[derive(Accounts)]
+pub struct EndRental<'info> {
+    // The rental to close.
+    #[account(
+        mut,
+        seeds = [RENTAL_SEED, rental.auction.as_ref()],
+        bump = rental.bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub rental: Account<'info, Rental>,
+    // The owner's NFT account, which receives the NFT back.
+    /// CHECK: Checked in the handler when the NFT is returned.
+    #[account(mut)]
+    pub owner_nft_account: AccountInfo<'info>,
+    // The exhibitor's temporary NFT account holding the rented NFT.
+    /// CHECK: Checked against the rental, and only read when the NFT is returned.
+    #[account(
+        mut,
+        constraint = nft_account.key() == rental.nft_account @ AuctionError::RentalAccountMismatch
+    )]
+    pub nft_account: AccountInfo<'info>,
+    // The account getting the temporary NFT account's rent back.
+    /// CHECK: Checked against the rental in the handler when the NFT is returned.
+    #[account(mut)]
+    pub nft_rent_destination: AccountInfo<'info>,
+    // The escrow account of the auction, which is only read to tell whether it has been closed.
+    /// CHECK: Checked against the rental, and only its emptiness is used.
+    #[account(constraint = escrow_account.key() == rental.auction @ AuctionError::RentalAccountMismatch)]
+    pub escrow_account: AccountInfo<'info>,
+    // The payer of the rental, which gets its rent back.
+    /// CHECK: Checked against the rental.
+    #[account(mut)]
+    pub rent_payer: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The PDA account, which owns the temporary NFT account.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Implement the EndRental struct.
+impl<'info> EndRental<'info> {
+    // Define a function to create a context for transferring the NFT back to its owner.
+    fn to_transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.nft_account.clone(),
+            to: self.owner_nft_account.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the temporary NFT account.
+    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.nft_account.clone(),
+            destination: self.nft_rent_destination.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the Bid struct with associated accounts and instructions.
+#[derive(Accounts)]
+#[instruction(price: u64)]
+pub struct Bid<'info> {
+    // The bidder's account, which must be a signer.
+    pub bidder: Signer<'info>,
+    // The payer of the transaction and of the bidder's temporary FT account, which gets its rent back.
+    // It may differ from the bidder so platforms can sponsor bids.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The bidder's temporary FT account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = bidder_ft_temp_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The bidder's FT account, which must hold the payment mint, have an amount greater than or equal to the bid price
+    // and any buyer's premium, and not belong to the exhibitor (who could otherwise bid through a delegate).
+    #[account(
+        mut,
+        constraint = bidder_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = bidder_ft_account.amount >= escrow_account.load()?.escrowed_amount(price)?,
+        constraint = !escrow_account.load()?.is_managed_by(bidder_ft_account.owner) @ AuctionError::SelfBid
+    )]
+    pub bidder_ft_account: Account<'info, TokenAccount>,
+    // The highest bidder's account, which must not be the same as the current bidder. It is the exhibitor
+    // until the first bid, which may be a program-owned vault.
+    /// CHECK: Checked against the escrow account.
+    #[account(
+        mut,
+        constraint = highest_bidder.key() != bidder.key()
+    )]
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account, which must be held by the PDA once there is a bid.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.escrows_bid(&highest_bidder_ft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's FT returning account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: SystemAccount<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = highest_bidder_rent_payer,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = !escrow_account.load()?.is_managed_by(bidder.key()) @ AuctionError::SelfBid,
+        constraint = escrow_account.load()?.min_next_bid(&clock)? <= price,
+        constraint = escrow_account.load()?.on_tick(price) @ AuctionError::BidOffTick,
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The receipt of the bid, at the auction's next bid index.
+    #[account(
+        init,
+        payer = payer,
+        space = BidReceipt::LEN,
+        seeds = [
+            BID_RECEIPT_SEED,
+            escrow_account.key().as_ref(),
+            bidder.key().as_ref(),
+            &escrow_account.load()?.bid_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub bid_receipt: Account<'info, BidReceipt>,
+    // The blacklist entry of the auctioned NFT's mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.nft_mint.as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.payment_mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The auction house configuration, which sets the flash loan guard, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The Instructions sysvar account, inspected for flash loans.
+    /// CHECK: Checked by its address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump = auction_house.event_authority_bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the BidWithSession struct with associated accounts and instructions.
+#[derive(Accounts)]
+#[instruction(price: u64)]
+pub struct BidWithSession<'info> {
+    // The session key, which must be a signer and the session's.
+    pub session_key: Signer<'info>,
+    // The bid session the wallet authorized the session key with, which must not have expired.
+    #[account(
+        mut,
+        seeds = [BID_SESSION_SEED, bidder.key().as_ref(), session_key.key().as_ref()],
+        bump = session.bump,
+        constraint = session.expires_at > clock.unix_timestamp @ AuctionError::SessionExpired
+    )]
+    pub session: Account<'info, BidSession>,
+    // The wallet that authorized the session, which bids.
+    /// CHECK: Checked by the session's seeds.
+    pub bidder: AccountInfo<'info>,
+    // The payer of the transaction and of the bidder's temporary FT account, which gets its rent back.
+    // It may differ from the bidder so platforms can sponsor bids.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The bidder's temporary FT account, held by the session key, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = bidder_ft_temp_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The session's FT account, which must hold the payment mint, have an amount greater than or equal to
+    // the bid price and any buyer's premium, and not belong to the exhibitor.
+    #[account(
+        mut,
+        address = session.ft_account @ AuctionError::InvalidSession,
+        constraint = bidder_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = bidder_ft_account.amount >= escrow_account.load()?.escrowed_amount(price)?,
+        constraint = !escrow_account.load()?.is_managed_by(bidder_ft_account.owner) @ AuctionError::SelfBid
+    )]
+    pub bidder_ft_account: Account<'info, TokenAccount>,
+    // The highest bidder's account, which must not be the same as the current bidder. It is the exhibitor
+    // until the first bid, which may be a program-owned vault.
+    /// CHECK: Checked against the escrow account.
+    #[account(
+        mut,
+        constraint = highest_bidder.key() != bidder.key()
+    )]
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account, which must be held by the PDA once there is a bid.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.escrows_bid(&highest_bidder_ft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's FT returning account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: SystemAccount<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = highest_bidder_rent_payer,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = !escrow_account.load()?.is_managed_by(bidder.key()) @ AuctionError::SelfBid,
+        constraint = escrow_account.load()?.min_next_bid(&clock)? <= price,
+        constraint = escrow_account.load()?.on_tick(price) @ AuctionError::BidOffTick,
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The receipt of the bid, at the auction's next bid index.
+    #[account(
+        init,
+        payer = payer,
+        space = BidReceipt::LEN,
+        seeds = [
+            BID_RECEIPT_SEED,
+            escrow_account.key().as_ref(),
+            bidder.key().as_ref(),
+            &escrow_account.load()?.bid_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub bid_receipt: Account<'info, BidReceipt>,
+    // The blacklist entry of the auctioned NFT's mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.nft_mint.as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.payment_mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The auction house configuration, which sets the flash loan guard, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The Instructions sysvar account, inspected for flash loans.
+    /// CHECK: Checked by its address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump = auction_house.event_authority_bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the BidWithSol struct with associated accounts and instructions.
+#[derive(Accounts)]
+#[instruction(price: u64)]
+pub struct BidWithSol<'info> {
+    // The bidder's account, which must be a signer and pays the bid in lamports.
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    // The payer of the transaction and of the bidder's temporary FT account, which gets its rent back.
+    // It may differ from the bidder so platforms can sponsor bids.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The bidder's temporary wSOL account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = bidder_ft_temp_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's account, which must not be the same as the current bidder.
+    #[account(
+        mut,
+        constraint = highest_bidder.key() != bidder.key()
+    )]
+    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account, which must be held by the PDA once there is a bid.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.escrows_bid(&highest_bidder_ft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.payment_mint == native_mint::id() @ AuctionError::PaymentMintMismatch,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        constraint = !escrow_account.load()?.is_managed_by(bidder.key()) @ AuctionError::SelfBid,
+        constraint = escrow_account.load()?.min_next_bid(&clock)? <= price,
+        constraint = escrow_account.load()?.on_tick(price) @ AuctionError::BidOffTick,
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The receipt of the bid, at the auction's next bid index.
+    #[account(
+        init,
+        payer = payer,
+        space = BidReceipt::LEN,
+        seeds = [
+            BID_RECEIPT_SEED,
+            escrow_account.key().as_ref(),
+            bidder.key().as_ref(),
+            &escrow_account.load()?.bid_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub bid_receipt: Account<'info, BidReceipt>,
+    // The blacklist entry of the auctioned NFT's mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.nft_mint.as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, escrow_account.load()?.payment_mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The auction house configuration, which sets the flash loan guard, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The Instructions sysvar account, inspected for flash loans.
+    /// CHECK: Checked by its address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump = auction_house.event_authority_bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the BidPlacement struct to hold the accounts needed to place a bid, whichever way it is paid.
+struct BidPlacement<'a, 'info> {
+    bidder: AccountInfo<'info>,
+    payer: Pubkey,
+    bidder_ft_temp_account: &'a Account<'info, TokenAccount>,
+    bidder_ft_temp_account_owner: AccountInfo<'info>,
+    bidder_ft_returning_account: Pubkey,
+    session: Option<&'a mut Account<'info, BidSession>>,
+    highest_bidder: AccountInfo<'info>,
+    highest_bidder_ft_temp_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_ft_returning_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_rent_payer: AccountInfo<'info>,
+    escrow_account: &'a AccountLoader<'info, Auction>,
+    bid_receipt: &'a mut Account<'info, BidReceipt>,
+    bid_receipt_bump: u8,
+    auction_house: &'a Account<'info, AuctionHouse>,
+    clock: &'a Clock,
+    instructions: &'a AccountInfo<'info>,
+    pda: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    event_authority: AccountInfo<'info>,
+    program: AccountInfo<'info>,
+}
+
+// Define the BidFunding enum to describe where a bid's funds come from.
+enum BidFunding<'a, 'info> {
+    // Tokens from an FT account, moved by its owner or delegate, which signs with the seeds given if it is a
+    // PDA.
+    Tokens {
+        ft_account: AccountInfo<'info>,
+        authority: AccountInfo<'info>,
+        signers_seeds: &'a [&'a [&'a [u8]]],
+    },
+    // Lamports from the bidder, wrapped into wSOL.
+    Lamports,
+}
+
+// Define the CreateSession struct with associated accounts.
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    // The wallet authorizing the session, which must be a signer and pays for the session account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    // The wallet's FT account the session's bids are paid from.
+    #[account(
+        mut,
+        constraint = wallet_ft_account.owner == wallet.key() @ AuctionError::InvalidSession
+    )]
+    pub wallet_ft_account: Account<'info, TokenAccount>,
+    // The session PDA of the wallet and session key.
+    #[account(
+        init,
+        payer = wallet,
+        space = BidSession::LEN,
+        seeds = [BID_SESSION_SEED, wallet.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, BidSession>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Implement the CreateSession struct.
+impl<'info> CreateSession<'info> {
+    // Define a function to create a context for approving the session PDA to spend from the wallet's FT
+    // account.
+    fn to_approve_context(&self) -> CpiContext<'_, '_, '_, 'info, Approve<'info>> {
+        let cpi_accounts = Approve {
+            to: self.wallet_ft_account.to_account_info(),
+            delegate: self.session.to_account_info(),
+            authority: self.wallet.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the CloseSession struct with associated accounts.
+#[derive(Accounts)]
+pub struct CloseSession<'info> {
+    // The wallet that authorized the session, which must be a signer and gets its rent back.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    // The wallet's FT account the session's bids are paid from.
+    #[account(mut)]
+    pub wallet_ft_account: Account<'info, TokenAccount>,
+    // The session to close.
+    #[account(
+        mut,
+        has_one = wallet,
+        constraint = session.ft_account == wallet_ft_account.key() @ AuctionError::InvalidSession,
+        close = wallet
+    )]
+    pub session: Account<'info, BidSession>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Implement the CloseSession struct.
+impl<'info> CloseSession<'info> {
+    // Define a function to create a context for revoking the session PDA's approval on the wallet's FT
+    // account.
+    fn to_revoke_context(&self) -> CpiContext<'_, '_, '_, 'info, Revoke<'info>> {
+        let cpi_accounts = Revoke {
+            source: self.wallet_ft_account.to_account_info(),
+            authority: self.wallet.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the CreateBidPool struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateBidPool<'info> {
+    // The operator of the pool, which must be a signer and alone places its bids.
+    pub operator: Signer<'info>,
+    // The payer of the transaction and of the pool and vault accounts.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The escrow account of the auction the pool bids on, which must still take bids.
+    #[account(
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The payment mint of the auction, which must not be wrapped SOL.
+    #[account(
+        address = escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = payment_mint.key() != native_mint::id() @ AuctionError::NativeMintPool
+    )]
+    pub payment_mint: Account<'info, Mint>,
+    // The pool PDA of the auction and operator.
+    #[account(
+        init,
+        payer = payer,
+        space = BidPool::LEN,
+        seeds = [BID_POOL_SEED, escrow_account.key().as_ref(), operator.key().as_ref()],
+        bump
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The vault PDA holding the pool's funds, owned by the pool.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = payment_mint,
+        token::authority = bid_pool,
+        seeds = [BID_POOL_VAULT_SEED, bid_pool.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the vault.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the ContributeToPool struct with associated accounts.
+#[derive(Accounts)]
+pub struct ContributeToPool<'info> {
+    // The contributor, which must be a signer and pays for the contribution's receipt.
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    // The contributor's FT account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = contributor_ft_account.mint == bid_pool.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub contributor_ft_account: Account<'info, TokenAccount>,
+    // The pool, which must not have been settled.
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = !bid_pool.settled @ AuctionError::BidPoolSettled
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's vault.
+    #[account(mut, address = bid_pool.vault)]
+    pub vault: Account<'info, TokenAccount>,
+    // The escrow account of the pool's auction, which must still take bids.
+    #[account(
+        address = bid_pool.auction,
+        constraint = matches!(
+            escrow_account.load()?.status(&clock)?,
+            AuctionStatus::Created | AuctionStatus::Live
+        ) @ AuctionError::InvalidAuctionStatus
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The receipt of the contribution, at the pool's next contribution index.
+    #[account(
+        init,
+        payer = contributor,
+        space = PoolContribution::LEN,
+        seeds = [
+            POOL_CONTRIBUTION_SEED,
+            bid_pool.key().as_ref(),
+            &bid_pool.contribution_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub contribution: Account<'info, PoolContribution>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the PoolBid struct with associated accounts. The bid's accounts follow as remaining accounts.
+#[derive(Accounts)]
+pub struct PoolBid<'info> {
+    // The operator of the pool, which must be a signer.
+    pub operator: Signer<'info>,
+    // The pool, which signs the bid.
+    #[account(
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        has_one = operator
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // This program, invoked to place the bid.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the BidWithSwap struct with associated accounts. The swap's and the bid's accounts follow as
+// remaining accounts.
+#[derive(Accounts)]
+pub struct BidWithSwap<'info> {
+    // The bidder, which must be a signer, and signs the swap and the bid.
+    pub bidder: Signer<'info>,
+    // The bidder's FT account of the payment mint, which the swap pays into and the bid is paid from.
+    #[account(
+        mut,
+        constraint = bidder_ft_account.owner == bidder.key() @ AuctionError::SwapAccountMismatch
+    )]
+    pub bidder_ft_account: Account<'info, TokenAccount>,
+    // The swap aggregator program.
+    /// CHECK: Checked by its address.
+    #[account(address = swaps::JUPITER_PROGRAM_ID)]
+    pub swap_program: AccountInfo<'info>,
+    // This program, invoked to place the bid.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the SettleBidPool struct with associated accounts.
+#[derive(Accounts)]
+pub struct SettleBidPool<'info> {
+    // The pool, which must not have been settled.
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = !bid_pool.settled @ AuctionError::BidPoolSettled
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's vault.
+    #[account(address = bid_pool.vault)]
+    pub vault: Account<'info, TokenAccount>,
+    // The escrow account of the pool's auction, which may have been closed.
+    /// CHECK: Checked by its address, and only read if it still belongs to this program.
+    #[account(address = bid_pool.auction)]
+    pub escrow_account: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the ClaimPoolRefund struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimPoolRefund<'info> {
+    // The pool, which must have been settled.
+    #[account(
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = bid_pool.settled @ AuctionError::BidPoolNotSettled
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's vault.
+    #[account(mut, address = bid_pool.vault)]
+    pub vault: Account<'info, TokenAccount>,
+    // The receipt of the contribution, which must not have been refunded yet.
+    #[account(
+        mut,
+        has_one = bid_pool,
+        constraint = !contribution.refunded @ AuctionError::RefundAlreadyClaimed
+    )]
+    pub contribution: Account<'info, PoolContribution>,
+    // The contributor's FT account, which must belong to the contributor and hold the payment mint.
+    #[account(
+        mut,
+        constraint = contributor_ft_account.owner == contribution.contributor @ AuctionError::Unauthorized,
+        constraint = contributor_ft_account.mint == bid_pool.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub contributor_ft_account: Account<'info, TokenAccount>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the PoolClaimItem struct with associated accounts. The claim's accounts follow as remaining
+// accounts.
+#[derive(Accounts)]
+pub struct PoolClaimItem<'info> {
+    // The pool, which signs the claim.
+    #[account(
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // This program, invoked to claim the NFT.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the FractionalizeBidPool struct with associated accounts.
+#[derive(Accounts)]
+pub struct FractionalizeBidPool<'info> {
+    // The payer of the transaction and of the fraction mint.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The pool, which must have been settled.
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = bid_pool.settled @ AuctionError::BidPoolNotSettled
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's NFT account, which must hold the NFT the pool won.
+    #[account(
+        constraint = item_account.owner == bid_pool.key() @ AuctionError::PoolItemMissing,
+        constraint = item_account.mint == bid_pool.item_mint @ AuctionError::PoolItemMissing,
+        constraint = item_account.amount > 0 @ AuctionError::PoolItemMissing
+    )]
+    pub item_account: Account<'info, TokenAccount>,
+    // The payment mint of the pool, whose decimals the fractions take.
+    #[account(address = bid_pool.payment_mint @ AuctionError::PaymentMintMismatch)]
+    pub payment_mint: Account<'info, Mint>,
+    // The fraction mint PDA of the pool, whose mint authority is the pool.
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = payment_mint.decimals,
+        mint::authority = bid_pool,
+        seeds = [BID_POOL_FRACTIONS_SEED, bid_pool.key().as_ref()],
+        bump
+    )]
+    pub fraction_mint: Account<'info, Mint>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the fraction mint.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the ClaimPoolFractions struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimPoolFractions<'info> {
+    // The pool, which must have been fractionalized.
+    #[account(
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = bid_pool.fraction_mint != Pubkey::default() @ AuctionError::BidPoolNotFractionalized
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's fraction mint.
+    #[account(mut, address = bid_pool.fraction_mint)]
+    pub fraction_mint: Account<'info, Mint>,
+    // The receipt of the contribution, whose fractions must not have been claimed yet.
+    #[account(
+        mut,
+        has_one = bid_pool,
+        constraint = !contribution.fractions_claimed @ AuctionError::FractionsAlreadyClaimed
+    )]
+    pub contribution: Account<'info, PoolContribution>,
+    // The contributor's fraction account, which must belong to the contributor and hold the fraction mint.
+    #[account(
+        mut,
+        constraint = contributor_fraction_account.owner == contribution.contributor @ AuctionError::Unauthorized,
+        constraint = contributor_fraction_account.mint == bid_pool.fraction_mint @ AuctionError::FractionMintMismatch
+    )]
+    pub contributor_fraction_account: Account<'info, TokenAccount>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the RedeemPoolItem struct with associated accounts.
+#[derive(Accounts)]
+pub struct RedeemPoolItem<'info> {
+    // The redeemer, which must be a signer and hold every fraction.
+    pub redeemer: Signer<'info>,
+    // The pool, which must have been fractionalized.
+    #[account(
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.operator.as_ref()],
+        bump = bid_pool.bump,
+        constraint = bid_pool.fraction_mint != Pubkey::default() @ AuctionError::BidPoolNotFractionalized
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    // The pool's fraction mint.
+    #[account(mut, address = bid_pool.fraction_mint)]
+    pub fraction_mint: Account<'info, Mint>,
+    // The redeemer's fraction account, which must hold the fraction mint.
+    #[account(
+        mut,
+        constraint = redeemer_fraction_account.mint == bid_pool.fraction_mint @ AuctionError::FractionMintMismatch
+    )]
+    pub redeemer_fraction_account: Account<'info, TokenAccount>,
+    // The pool's NFT account.
+    #[account(mut, address = bid_pool.item_account)]
+    pub item_account: Account<'info, TokenAccount>,
+    // The redeemer's NFT receiving account, which must be for the NFT's mint.
+    #[account(
+        mut,
+        constraint = redeemer_nft_account.mint == bid_pool.item_mint @ AuctionError::NftMintMismatch
+    )]
+    pub redeemer_nft_account: Account<'info, TokenAccount>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the Close struct with associated accounts.
+#[derive(Accounts)]
+pub struct Close<'info> {
+    // The winning bidder's account, which must be a signer.
+    #[account(mut)]
+    pub winning_bidder: Signer<'info>,
+    // The exhibitor's account, which may be a program-owned vault.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT and be held by the PDA.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch,
+        constraint = escrow_account.load()?.escrows_nft(&exhibitor_nft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The highest bidder's temporary FT account, which must hold the payment mint and be held by the PDA.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_temp_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = escrow_account.load()?.escrows_bid(&highest_bidder_ft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's NFT receiving account, which must be for the auctioned NFT's mint.
+    #[account(
+        mut,
+        constraint = highest_bidder_nft_receiving_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub highest_bidder_nft_receiving_account: Account<'info, TokenAccount>,
+    // The auction house configuration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The treasury's FT account, which receives the house fee and must hold the payment mint.
+    #[account(
+        mut,
+        constraint = treasury_ft_account.owner == auction_house.treasury,
+        constraint = treasury_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub treasury_ft_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    #[account(mut)]
+    pub exhibitor_rent_payer: SystemAccount<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: SystemAccount<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = exhibitor_ft_receiving_account,
+        constraint = escrow_account.load()?.highest_bidder == winning_bidder.key(),
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = escrow_account.load()?.installments_paid(highest_bidder_ft_temp_account.amount)? @ AuctionError::InstallmentsDue,
+        constraint = escrow_account.load()?.reserve_price_feed == price_feed.key(),
+        constraint = escrow_account.load()?.print_edition == 0 @ AuctionError::PrintSaleNeedsClaim,
+        constraint = escrow_account.load()?.dispute_window_sec == 0 @ AuctionError::ArbitrationNeedsFinalize
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The payment mint recorded at exhibit, used to convert the bid to USD.
+    #[account(constraint = payment_mint.key() == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch)]
+    pub payment_mint: Account<'info, Mint>,
+    // The Pyth price feed recorded at exhibit (the default pubkey if the auction has no USD reserve).
+    /// CHECK: Checked against the escrow account and parsed by the pyth module.
+    pub price_feed: AccountInfo<'info>,
+    // The NFT's Metaplex metadata, which sets the royalty owed to its creators.
+    /// CHECK: Checked by its address, and only read if it belongs to the metadata program.
+    #[account(address = metadata::metadata_address(&escrow_account.load()?.nft_mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The house's royalty override for the NFT's verified collection (any account if it has none).
+    /// CHECK: Checked against the NFT's collection by the royalties module.
+    pub royalty_override: AccountInfo<'info>,
+    // The stats of the NFT's verified collection in the payment mint, updated with the sale (any account
+    // if it has none).
+    /// CHECK: Checked against the NFT's collection by the collection_stats module.
+    #[account(mut)]
+    pub collection_stats: AccountInfo<'info>,
+    // The house's stats in the payment mint, updated with the sale if they have been created.
+    /// CHECK: Checked by its seeds, and only updated if it belongs to this program.
+    #[account(mut, seeds = [HOUSE_STATS_SEED, escrow_account.load()?.payment_mint.as_ref()], bump)]
+    pub house_stats: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The receipt of the sale.
+    #[account(
+        init,
+        payer = winning_bidder,
+        space = PurchaseReceipt::LEN,
+        seeds = [PURCHASE_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the SettleIfEnded struct with associated accounts.
+#[derive(Accounts)]
+pub struct SettleIfEnded<'info> {
+    // The payer of the transaction and of the purchase receipt.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The winning bidder's account, which receives the FT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub winning_bidder: AccountInfo<'info>,
+    // The exhibitor's account.
+    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
+    #[account(mut)]
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT and be held by the PDA.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch,
+        constraint = escrow_account.load()?.escrows_nft(&exhibitor_nft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The highest bidder's temporary FT account, which must hold the payment mint and be held by the PDA.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_temp_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch,
+        constraint = escrow_account.load()?.escrows_bid(&highest_bidder_ft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's NFT receiving account, which must belong to the winning bidder since they do not sign.
+    #[account(
+        mut,
+        constraint = highest_bidder_nft_receiving_account.owner == winning_bidder.key(),
+        constraint = highest_bidder_nft_receiving_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub highest_bidder_nft_receiving_account: Account<'info, TokenAccount>,
+    // The auction house configuration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The treasury's FT account, which receives the house fee and must hold the payment mint.
+    #[account(
+        mut,
+        constraint = treasury_ft_account.owner == auction_house.treasury,
+        constraint = treasury_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub treasury_ft_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = exhibitor_ft_receiving_account,
+        constraint = escrow_account.load()?.highest_bidder == winning_bidder.key(),
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = escrow_account.load()?.installments_paid(highest_bidder_ft_temp_account.amount)? @ AuctionError::InstallmentsDue,
+        constraint = escrow_account.load()?.reserve_price_feed == price_feed.key(),
+        constraint = escrow_account.load()?.print_edition == 0 @ AuctionError::PrintSaleNeedsClaim,
+        constraint = escrow_account.load()?.dispute_window_sec == 0 @ AuctionError::ArbitrationNeedsFinalize
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The payment mint recorded at exhibit, used to convert the bid to USD.
+    #[account(constraint = payment_mint.key() == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch)]
+    pub payment_mint: Account<'info, Mint>,
+    // The Pyth price feed recorded at exhibit (the default pubkey if the auction has no USD reserve).
+    /// CHECK: Checked against the escrow account and parsed by the pyth module.
+    pub price_feed: AccountInfo<'info>,
+    // The NFT's Metaplex metadata, which sets the royalty owed to its creators.
+    /// CHECK: Checked by its address, and only read if it belongs to the metadata program.
+    #[account(address = metadata::metadata_address(&escrow_account.load()?.nft_mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The house's royalty override for the NFT's verified collection (any account if it has none).
+    /// CHECK: Checked against the NFT's collection by the royalties module.
+    pub royalty_override: AccountInfo<'info>,
+    // The stats of the NFT's verified collection in the payment mint, updated with the sale (any account
+    // if it has none).
+    /// CHECK: Checked against the NFT's collection by the collection_stats module.
+    #[account(mut)]
+    pub collection_stats: AccountInfo<'info>,
+    // The house's stats in the payment mint, updated with the sale if they have been created.
+    /// CHECK: Checked by its seeds, and only updated if it belongs to this program.
+    #[account(mut, seeds = [HOUSE_STATS_SEED, escrow_account.load()?.payment_mint.as_ref()], bump)]
+    pub house_stats: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The receipt of the sale.
+    #[account(
+        init,
+        payer = payer,
+        space = PurchaseReceipt::LEN,
+        seeds = [PURCHASE_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the CloseMany struct with associated accounts. The settle_if_ended accounts of each auction
+// follow as remaining accounts.
+#[derive(Accounts)]
+pub struct CloseMany<'info> {
+    // The crank paying for the transaction, which must be a signer.
+    pub payer: Signer<'info>,
+}
+
+// Define the Finalize struct with associated accounts.
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    // The highest bidder's temporary FT account, whose balance is checked against the USD reserve.
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = escrow_account.load()?.installments_paid(highest_bidder_ft_temp_account.amount)? @ AuctionError::InstallmentsDue,
+        constraint = escrow_account.load()?.reserve_price_feed == price_feed.key()
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The payment mint recorded at exhibit, used to convert the bid to USD.
+    #[account(constraint = payment_mint.key() == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch)]
+    pub payment_mint: Account<'info, Mint>,
+    // The Pyth price feed recorded at exhibit (the default pubkey if the auction has no USD reserve).
+    /// CHECK: Checked against the escrow account and parsed by the pyth module.
+    pub price_feed: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the ClaimItem struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimItem<'info> {
+    // The winning bidder's account, which must be a signer.
+    pub winning_bidder: Signer<'info>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's NFT receiving account, which must be for the auctioned NFT's mint.
+    #[account(
+        mut,
+        constraint = highest_bidder_nft_receiving_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub highest_bidder_nft_receiving_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor_nft_temp_account,
+        constraint = escrow_account.load()?.highest_bidder == winning_bidder.key(),
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = !escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOpen,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.print_edition == 0 @ AuctionError::PrintSaleNeedsClaim
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the ClaimPrint struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimPrint<'info> {
+    // The winning bidder's account, which must be a signer, pays for the print's accounts and is the mint
+    // authority of the new mint.
+    #[account(mut)]
+    pub winning_bidder: Signer<'info>,
+    // The new mint to print the edition to, which must hold the single token of an NFT.
+    #[account(
+        mut,
+        constraint = new_mint.supply == 1 && new_mint.decimals == 0 @ AuctionError::NotAnNft
+    )]
+    pub new_mint: Box<Account<'info, Mint>>,
+    // The metadata PDA of the new mint, created by the print.
+    /// CHECK: Checked by its address, and created by the metadata program.
+    #[account(mut, address = metadata::metadata_address(&new_mint.key()) @ AuctionError::InvalidMetadata)]
+    pub new_metadata: AccountInfo<'info>,
+    // The edition PDA of the new mint, created by the print.
+    /// CHECK: Checked by its address, and created by the metadata program.
+    #[account(mut, address = metadata::edition_address(&new_mint.key()) @ AuctionError::InvalidMetadata)]
+    pub new_edition: AccountInfo<'info>,
+    // The master edition PDA of the escrowed NFT.
+    /// CHECK: Checked by its address, and by the metadata program.
+    #[account(mut, address = metadata::edition_address(&escrow_account.load()?.nft_mint) @ AuctionError::NotAMasterEdition)]
+    pub master_edition: AccountInfo<'info>,
+    // The edition marker PDA of the printed edition number, created by the print if needed.
+    /// CHECK: Checked by its address, and by the metadata program.
+    #[account(
+        mut,
+        address = metadata::edition_marker_address(&escrow_account.load()?.nft_mint, escrow_account.load()?.print_edition) @ AuctionError::InvalidMetadata
+    )]
+    pub edition_marker: AccountInfo<'info>,
+    // The escrowed NFT's Metaplex metadata, whose update authority the print inherits.
+    /// CHECK: Checked by its address, and read by the metadata module.
+    #[account(address = metadata::metadata_address(&escrow_account.load()?.nft_mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The update authority of the escrowed NFT's metadata.
+    /// CHECK: Checked against the NFT's metadata.
+    pub update_authority: AccountInfo<'info>,
+    // The exhibitor's temporary NFT account, which must hold the master edition and be held by the PDA.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch,
+        constraint = escrow_account.load()?.escrows_nft(&exhibitor_nft_temp_account, pda.key())? @ AuctionError::NotEscrowed
+    )]
+    pub exhibitor_nft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's NFT account, which gets the master edition back.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch,
+        constraint = exhibitor_nft_token_account.owner == escrow_account.load()?.exhibitor @ AuctionError::Unauthorized
+    )]
+    pub exhibitor_nft_token_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account, which must be a finalized print sale won by the winning bidder.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor_nft_temp_account,
+        constraint = escrow_account.load()?.highest_bidder == winning_bidder.key(),
+        constraint = escrow_account.load()?.print_edition > 0 @ AuctionError::InvalidPrintSale,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = !escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOpen,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, which the metadata program reads.
+    pub rent: Sysvar<'info, Rent>,
+    // The Metaplex token metadata program, which prints the edition.
+    /// CHECK: Checked by its address.
+    #[account(address = metadata::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the ClaimDomain struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimDomain<'info> {
+    // The domain's name account, whose owner becomes the winning bidder.
+    /// CHECK: Checked against the escrow account, and transferred by the sns module.
+    #[account(mut, address = escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch)]
+    pub name_account: AccountInfo<'info>,
+    // The exhibitor's rent payer, which gets the rent of the escrow account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The escrow account, which must be a finalized domain auction.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        constraint = escrow_account.load()?.item_type()? == ItemType::Domain @ AuctionError::NotADomainAuction,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which owns the escrowed domain.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The Solana Name Service program, which transfers the domain.
+    /// CHECK: Checked by its address.
+    #[account(address = sns::NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: AccountInfo<'info>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the ClaimStake struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimStake<'info> {
+    // The stake account, whose staker and withdrawer become the winning bidder.
+    /// CHECK: Checked against the escrow account, and authorized by the stake module.
+    #[account(mut, address = escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch)]
+    pub stake_account: AccountInfo<'info>,
+    // The exhibitor's rent payer, which gets the rent of the escrow account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The escrow account, which must be a finalized stake account auction.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        constraint = escrow_account.load()?.item_type()? == ItemType::Stake @ AuctionError::NotAStakeAuction,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed stake account's authorities.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The system clock account, which the stake program reads.
+    pub clock: Sysvar<'info, Clock>,
+    // The stake program, which changes the stake account's authorities.
+    /// CHECK: Checked by its address.
+    #[account(address = stake::STAKE_PROGRAM_ID)]
+    pub stake_program: AccountInfo<'info>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the ClaimProceeds struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimProceeds<'info> {
+    // The exhibitor's account, which must be a signer and receives wrapped SOL proceeds.
+    #[account(mut)]
+    pub exhibitor: Signer<'info>,
+    // The exhibitor's FT receiving account.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The highest bidder's temporary FT account.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The auction house configuration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The treasury's FT account, which receives the house fee and must hold the payment mint.
+    #[account(
+        mut,
+        constraint = treasury_ft_account.owner == auction_house.treasury,
+        constraint = treasury_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub treasury_ft_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_ft_receiving_account,
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = !escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOpen,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The NFT's Metaplex metadata, which sets the royalty owed to its creators.
+    /// CHECK: Checked by its address, and only read if it belongs to the metadata program.
+    #[account(address = metadata::metadata_address(&escrow_account.load()?.nft_mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The house's royalty override for the NFT's verified collection (any account if it has none).
+    /// CHECK: Checked against the NFT's collection by the royalties module.
+    pub royalty_override: AccountInfo<'info>,
+    // The stats of the NFT's verified collection in the payment mint, updated with the sale (any account
+    // if it has none).
+    /// CHECK: Checked against the NFT's collection by the collection_stats module.
+    #[account(mut)]
+    pub collection_stats: AccountInfo<'info>,
+    // The house's stats in the payment mint, updated with the sale if they have been created.
+    /// CHECK: Checked by its seeds, and only updated if it belongs to this program.
+    #[account(mut, seeds = [HOUSE_STATS_SEED, escrow_account.load()?.payment_mint.as_ref()], bump)]
+    pub house_stats: AccountInfo<'info>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The receipt of the sale.
+    #[account(
+        init,
+        payer = exhibitor,
+        space = PurchaseReceipt::LEN,
+        seeds = [PURCHASE_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the MigrateAuction struct with associated accounts.
+#[derive(Accounts)]
+pub struct MigrateAuction<'info> {
+    // The payer of any extra rent the larger layout needs.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The escrow account to migrate, which may be smaller than the current layout.
+    /// CHECK: Must be owned by the program; the discriminator and version are checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub escrow_account: AccountInfo<'info>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the Settlement struct to hold the accounts shared by every way of settling an auction.
+struct Settlement<'a, 'info> {
+    exhibitor: &'a AccountInfo<'info>,
+    exhibitor_rent_payer: &'a AccountInfo<'info>,
+    highest_bidder_rent_payer: &'a AccountInfo<'info>,
+    exhibitor_nft_temp_account: &'a Account<'info, TokenAccount>,
+    exhibitor_ft_receiving_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_ft_temp_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_nft_receiving_account: &'a Account<'info, TokenAccount>,
+    auction_house: &'a AuctionHouse,
+    treasury_ft_account: &'a Account<'info, TokenAccount>,
+    escrow_account: &'a Auction,
+    payment_mint: &'a Account<'info, Mint>,
+    price_feed: &'a AccountInfo<'info>,
+    nft_metadata: &'a AccountInfo<'info>,
+    royalty_override: &'a AccountInfo<'info>,
+    clock: &'a Clock,
+    pda: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+}
+
+// Define the SaleClosing struct to hold the accounts needed to settle an auction at once and record its
+// sale, as close and settle_if_ended do.
+struct SaleClosing<'a, 'info> {
+    exhibitor: &'a AccountInfo<'info>,
+    exhibitor_rent_payer: &'a AccountInfo<'info>,
+    highest_bidder_rent_payer: &'a AccountInfo<'info>,
+    exhibitor_nft_temp_account: &'a Account<'info, TokenAccount>,
+    exhibitor_ft_receiving_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_ft_temp_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_nft_receiving_account: &'a Account<'info, TokenAccount>,
+    auction_house: &'a AuctionHouse,
+    treasury_ft_account: &'a Account<'info, TokenAccount>,
+    escrow_account: &'a AccountLoader<'info, Auction>,
+    payment_mint: &'a Account<'info, Mint>,
+    price_feed: &'a AccountInfo<'info>,
+    nft_metadata: &'a AccountInfo<'info>,
+    royalty_override: &'a AccountInfo<'info>,
+    collection_stats: &'a AccountInfo<'info>,
+    house_stats: &'a AccountInfo<'info>,
+    clock: &'a Clock,
+    pda: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+    purchase_receipt: &'a mut PurchaseReceipt,
+    listing_receipt: &'a AccountInfo<'info>,
+    event_authority: &'a AccountInfo<'info>,
+    program: AccountInfo<'info>,
+}
+
+// Define the ItemDelivery struct to hold the accounts needed to deliver the NFT to the winning bidder.
+struct ItemDelivery<'a, 'info> {
+    exhibitor_nft_temp_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_nft_receiving_account: &'a Account<'info, TokenAccount>,
+    pda: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+}
+
+// Define the ProceedsDistribution struct to hold the accounts needed to distribute the winning bid.
+struct ProceedsDistribution<'a, 'info> {
+    exhibitor: AccountInfo<'info>,
+    highest_bidder_rent_payer: AccountInfo<'info>,
+    exhibitor_ft_receiving_account: &'a Account<'info, TokenAccount>,
+    highest_bidder_ft_temp_account: &'a Account<'info, TokenAccount>,
+    auction_house: &'a AuctionHouse,
+    treasury_ft_account: &'a Account<'info, TokenAccount>,
+    escrow_account: &'a Auction,
+    nft_metadata: &'a AccountInfo<'info>,
+    royalty_override: &'a AccountInfo<'info>,
+    pda: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+}
+
+// Define the ClaimCompletion struct to hold the accounts needed to settle a finalized auction once both
+// sides have claimed.
+struct ClaimCompletion<'a, 'info> {
+    escrow_account: AccountInfo<'info>,
+    listing_receipt: &'a AccountInfo<'info>,
+    exhibitor_rent_payer: &'a AccountInfo<'info>,
+    event_authority: &'a AccountInfo<'info>,
+    program: AccountInfo<'info>,
+}
+
+// Define the SettleItem struct with associated accounts.
+#[derive(Accounts)]
+pub struct SettleItem<'info> {
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's NFT receiving account, which must belong to the winning bidder since they do not
+    // sign, and be for the auctioned NFT's mint.
+    #[account(
+        mut,
+        constraint = highest_bidder_nft_receiving_account.owner == escrow_account.load()?.highest_bidder,
+        constraint = highest_bidder_nft_receiving_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub highest_bidder_nft_receiving_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor_nft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = !escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOpen,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.print_edition == 0 @ AuctionError::PrintSaleNeedsClaim
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the SettlePayment struct with associated accounts.
+#[derive(Accounts)]
+pub struct SettlePayment<'info> {
+    // The payer of the transaction and of the purchase receipt.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The exhibitor's account, which receives wrapped SOL proceeds.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's FT receiving account.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The highest bidder's temporary FT account.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The auction house configuration.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The treasury's FT account, which receives the house fee and must hold the payment mint.
+    #[account(
+        mut,
+        constraint = treasury_ft_account.owner == auction_house.treasury,
+        constraint = treasury_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub treasury_ft_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_ft_receiving_account,
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = !escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOpen,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The NFT's Metaplex metadata, which sets the royalty owed to its creators.
+    /// CHECK: Checked by its address, and only read if it belongs to the metadata program.
+    #[account(address = metadata::metadata_address(&escrow_account.load()?.nft_mint) @ AuctionError::InvalidMetadata)]
+    pub nft_metadata: AccountInfo<'info>,
+    // The house's royalty override for the NFT's verified collection (any account if it has none).
+    /// CHECK: Checked against the NFT's collection by the royalties module.
+    pub royalty_override: AccountInfo<'info>,
+    // The stats of the NFT's verified collection in the payment mint, updated with the sale (any account
+    // if it has none).
+    /// CHECK: Checked against the NFT's collection by the collection_stats module.
+    #[account(mut)]
+    pub collection_stats: AccountInfo<'info>,
+    // The house's stats in the payment mint, updated with the sale if they have been created.
+    /// CHECK: Checked by its seeds, and only updated if it belongs to this program.
+    #[account(mut, seeds = [HOUSE_STATS_SEED, escrow_account.load()?.payment_mint.as_ref()], bump)]
+    pub house_stats: AccountInfo<'info>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The receipt of the sale.
+    #[account(
+        init,
+        payer = payer,
+        space = PurchaseReceipt::LEN,
+        seeds = [PURCHASE_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the ReclaimUnclaimed struct with associated accounts.
+#[derive(Accounts)]
+pub struct ReclaimUnclaimed<'info> {
+    // The exhibitor's account, which receives the NFT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == exhibitor.key(),
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account, which receives the penalty.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The highest bidder's account, which receives the FT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's FT returning account, which receives the refund.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = exhibitor_ft_receiving_account,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.claim_deadline <= clock.unix_timestamp @ AuctionError::ClaimPeriodNotOver,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the SettleUnmetReserve struct with associated accounts.
+#[derive(Accounts)]
+pub struct SettleUnmetReserve<'info> {
+    // The exhibitor's account, which receives the NFT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == exhibitor.key(),
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's account, which receives the FT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The highest bidder's FT returning account, which receives the refund.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account with various constraints.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = highest_bidder,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = escrow_account.load()?.installments_paid(highest_bidder_ft_temp_account.amount)? @ AuctionError::InstallmentsDue,
+        constraint = escrow_account.load()?.reserve_price_feed == price_feed.key(),
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The payment mint recorded at exhibit, used to convert the bid to USD.
+    #[account(constraint = payment_mint.key() == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch)]
+    pub payment_mint: Account<'info, Mint>,
+    // The Pyth price feed recorded at exhibit.
+    /// CHECK: Checked against the escrow account and parsed by the pyth module.
+    pub price_feed: AccountInfo<'info>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the PayInstallment struct with associated accounts.
+#[derive(Accounts)]
+pub struct PayInstallment<'info> {
+    // The winning bidder, which must be a signer.
+    pub highest_bidder: Signer<'info>,
+    // The winning bidder's FT account the installment is paid from.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_account: Account<'info, TokenAccount>,
+    // The winning bidder's temporary FT account, which escrows what has been paid.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The escrow account, which must have ended with an installment plan and not been settled yet.
+    #[account(
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        constraint = escrow_account.load()?.has_installments() @ AuctionError::NoInstallmentPlan,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the DefaultInstallments struct with associated accounts.
+#[derive(Accounts)]
+pub struct DefaultInstallments<'info> {
+    // The exhibitor's account.
+    /// CHECK: Checked against the escrow account.
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == exhibitor.key(),
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Account<'info, TokenAccount>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(mut)]
+    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
+    // The exhibitor's FT receiving account, which receives the forfeited payments.
+    #[account(mut)]
+    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
+    // The winning bidder's account, which receives a wrapped SOL refund.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder: AccountInfo<'info>,
+    // The winning bidder's temporary FT account, which escrows what has been paid.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
+    // The winning bidder's FT returning account, which receives the refund.
+    #[account(mut)]
+    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The winning bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account, which must have ended with an installment missed.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = exhibitor_ft_receiving_account,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = escrow_account.load()?.has_installments() @ AuctionError::NoInstallmentPlan,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Live @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.status(&clock)? == AuctionStatus::Ended @ AuctionError::AuctionNotEnded,
+        constraint = highest_bidder_ft_temp_account.amount < escrow_account.load()?.installments_due(clock.unix_timestamp)? @ AuctionError::InstallmentNotMissed,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp.
+    pub clock: Sysvar<'info, Clock>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Define the InitializeRegistry struct with associated accounts.
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    // The payer of the registry account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The registry PDA.
+    #[account(
+        init,
+        payer = payer,
+        space = Registry::LEN,
+        seeds = [REGISTRY_SEED],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the InitializeHouse struct with associated accounts.
+#[derive(Accounts)]
+pub struct InitializeHouse<'info> {
+    // The house authority, which must be the program's upgrade authority, and pays for the configuration
+    // account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // This program, whose program data account records its upgrade authority.
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ AuctionError::NotUpgradeAuthority)]
+    pub program: Program<'info, program::AnchorAuction>,
+    // The program data account of this program, which must name the authority as the upgrade authority.
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ AuctionError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    // The treasury wallet, whose FT accounts receive the house fee.
+    /// CHECK: Only its address is recorded.
+    pub treasury: AccountInfo<'info>,
+    // The auction house configuration PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = AuctionHouse::LEN,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the UpdateHouse struct with associated accounts.
+#[derive(Accounts)]
+pub struct UpdateHouse<'info> {
+    // The house authority, which must be a signer.
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must belong to the authority.
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        has_one = authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
+// Define the CreateRoyaltyVault struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateRoyaltyVault<'info> {
+    // The payer of the vault, which must be a signer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The payment mint the vault holds.
+    pub payment_mint: Account<'info, Mint>,
+    // The royalty vault PDA of the payment mint, owned by the escrow PDA.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = payment_mint,
+        token::authority = pda,
+        seeds = [ROYALTY_VAULT_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub royalty_vault: Account<'info, TokenAccount>,
+    // The PDA account, which owns the vault.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the vault.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the DistributeRoyalties struct with associated accounts.
+#[derive(Accounts)]
+pub struct DistributeRoyalties<'info> {
+    // The purchase receipt of the sale, which records what each creator is still owed.
+    #[account(mut)]
+    pub purchase_receipt: Account<'info, PurchaseReceipt>,
+    // The house's royalty vault of the sale's payment mint.
+    #[account(mut, seeds = [ROYALTY_VAULT_SEED, purchase_receipt.payment_mint.as_ref()], bump)]
+    pub royalty_vault: Account<'info, TokenAccount>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which owns the vault.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the CreateAffiliateVault struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateAffiliateVault<'info> {
+    // The payer of the vault, which must be a signer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The payment mint the vault holds.
+    pub payment_mint: Account<'info, Mint>,
+    // The affiliate vault PDA of the payment mint, owned by the escrow PDA.
+    #[account(
+        init,
+        payer = payer,
+        token::mint = payment_mint,
+        token::authority = pda,
+        seeds = [AFFILIATE_VAULT_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub affiliate_vault: Account<'info, TokenAccount>,
+    // The PDA account, which owns the vault.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The rent sysvar account, needed to create the vault.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Define the ClaimAffiliateEarnings struct with associated accounts.
+#[derive(Accounts)]
+pub struct ClaimAffiliateEarnings<'info> {
+    // The purchase receipt of the sale, which records what its affiliate is still owed.
+    #[account(mut)]
+    pub purchase_receipt: Account<'info, PurchaseReceipt>,
+    // The house's affiliate vault of the sale's payment mint.
+    #[account(mut, seeds = [AFFILIATE_VAULT_SEED, purchase_receipt.payment_mint.as_ref()], bump)]
+    pub affiliate_vault: Account<'info, TokenAccount>,
+    // The affiliate's FT account, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = affiliate_ft_account.owner == purchase_receipt.affiliate @ AuctionError::AffiliateAccountMismatch,
+        constraint = affiliate_ft_account.mint == purchase_receipt.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub affiliate_ft_account: Account<'info, TokenAccount>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which owns the vault.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Define the EmitEvent struct with associated accounts.
+#[derive(Accounts)]
+pub struct EmitEvent<'info> {
+    // The event authority PDA, which only this program can sign for.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: Signer<'info>,
+}
+
+// Define the GetAuctionState struct with associated accounts.
+#[derive(Accounts)]
+pub struct GetAuctionState<'info> {
+    // The escrow account to report on.
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The system clock account for getting the current UNIX timestamp and slot.
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Define the UpdateHouseFees struct with associated accounts.
+#[derive(Accounts)]
+pub struct UpdateHouseFees<'info> {
+    // The house authority or its fee manager, which must be a signer.
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the fee manager role.
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::FeeManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
+// Define the PauseHouse struct with associated accounts.
+#[derive(Accounts)]
+pub struct PauseHouse<'info> {
+    // The house authority or its pauser, which must be a signer.
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the pauser role.
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::Pauser, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
+// Define the AcceptAuthority struct with associated accounts.
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    // The nominated authority, which must be a signer.
+    pub pending_authority: Signer<'info>,
+    // The auction house configuration, which must have nominated the signer.
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        has_one = pending_authority @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
+// Define the MigrateHouse struct with associated accounts.
+#[derive(Accounts)]
+pub struct MigrateHouse<'info> {
+    // The payer of any extra rent the larger layout needs.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The auction house configuration PDA to migrate, which may be smaller than the current layout.
+    /// CHECK: Checked by its seeds; only its size and the fields it grows by change.
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED],
+        bump
+    )]
+    pub auction_house: AccountInfo<'info>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Implement the Exhibit struct.
+impl<'info> Exhibit<'info> {
+    // Define a function to create a context for paying the listing fee to the treasury.
+    fn to_transfer_listing_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.payer.to_account_info(),
+            to: self.treasury.clone(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring NFTs to the PDA.
+    fn to_transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            to: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            authority: self.exhibitor.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for setting the authority of the NFT to the PDA.
+    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            current_authority: self.exhibitor.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for approving the PDA as the delegate of the NFT.
+    fn to_approve_context(&self) -> CpiContext<'_, '_, '_, 'info, Approve<'info>> {
+        let cpi_accounts = Approve {
+            to: self.exhibitor_nft_token_account.to_account_info().clone(),
+            delegate: self.pda.clone(),
+            authority: self.exhibitor.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ReuseEscrow struct.
+impl<'info> ReuseEscrow<'info> {
+    // Define a function to create a context for transferring NFTs to the PDA.
+    fn to_transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_token_account.to_account_info(),
+            to: self.exhibitor_nft_temp_account.to_account_info(),
+            authority: self.exhibitor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for setting the authority of the NFT to the PDA.
+    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.exhibitor_nft_temp_account.to_account_info(),
+            current_authority: self.exhibitor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for approving the PDA as the delegate of the NFT.
+    fn to_approve_context(&self) -> CpiContext<'_, '_, '_, 'info, Approve<'info>> {
+        let cpi_accounts = Approve {
+            to: self.exhibitor_nft_token_account.to_account_info(),
+            delegate: self.pda.clone(),
+            authority: self.exhibitor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the MintListingToken struct.
+impl<'info> MintListingToken<'info> {
+    // Define a function to create a context for minting the listing token to the exhibitor.
+    fn to_mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.listing_token_mint.to_account_info(),
+            to: self.exhibitor_listing_token_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for letting the PDA burn the listing token.
+    fn to_approve_context(&self) -> CpiContext<'_, '_, '_, 'info, Approve<'info>> {
+        let cpi_accounts = Approve {
+            to: self.exhibitor_listing_token_account.to_account_info(),
+            delegate: self.pda.clone(),
+            authority: self.exhibitor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for freezing the exhibitor's listing token account.
+    fn to_freeze_context(&self) -> CpiContext<'_, '_, '_, 'info, FreezeAccount<'info>> {
+        let cpi_accounts = FreezeAccount {
+            account: self.exhibitor_listing_token_account.to_account_info(),
+            mint: self.listing_token_mint.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ExhibitDomain struct.
+impl<'info> ExhibitDomain<'info> {
+    // Define a function to create a context for paying the listing fee to the treasury.
+    fn to_transfer_listing_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.payer.to_account_info(),
+            to: self.treasury.clone(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ExhibitStake struct.
+impl<'info> ExhibitStake<'info> {
+    // Define a function to create a context for paying the listing fee to the treasury.
+    fn to_transfer_listing_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.payer.to_account_info(),
+            to: self.treasury.clone(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the Cancel struct.
+impl<'info> Cancel<'info> {
+    // Define a function to create a context for transferring NFTs back to the exhibitor.
+    fn to_transfer_to_exhibitor_context(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the PDA-controlled escrow account.
+    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination: self.exhibitor_rent_payer.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for revoking the PDA's delegation over a delegate listing.
+    fn to_revoke_context(&self) -> CpiContext<'_, '_, '_, 'info, Revoke<'info>> {
+        let cpi_accounts = Revoke {
+            source: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            authority: self.authority.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ExpireAuction struct.
+impl<'info> ExpireAuction<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info(),
+            to: self.exhibitor_nft_token_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the PDA-controlled escrow account.
+    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info(),
+            destination: self.exhibitor_rent_payer.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the Bid struct.
+impl<'info> Bid<'info> {
+    // Define a function to gather the accounts needed to place the bid.
+    fn bid_placement(&mut self, bid_receipt_bump: u8) -> BidPlacement<'_, 'info> {
+        BidPlacement {
+            bidder: self.bidder.to_account_info(),
+            payer: self.payer.key(),
+            bidder_ft_temp_account: &self.bidder_ft_temp_account,
+            bidder_ft_temp_account_owner: self.bidder.to_account_info(),
+            bidder_ft_returning_account: self.bidder_ft_account.key(),
+            session: None,
+            highest_bidder: self.highest_bidder.clone(),
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_ft_returning_account: &self.highest_bidder_ft_returning_account,
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer.to_account_info(),
+            escrow_account: &self.escrow_account,
+            bid_receipt: &mut self.bid_receipt,
+            bid_receipt_bump,
+            auction_house: &self.auction_house,
+            clock: &self.clock,
+            instructions: &self.instructions,
+            pda: self.pda.clone(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.event_authority.clone(),
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the BidWithSession struct.
+impl<'info> BidWithSession<'info> {
+    // Define a function to gather the accounts needed to place the bid, with the session key holding the
+    // bidder's temporary FT account.
+    fn bid_placement(&mut self, bid_receipt_bump: u8) -> BidPlacement<'_, 'info> {
+        BidPlacement {
+            bidder: self.bidder.clone(),
+            payer: self.payer.key(),
+            bidder_ft_temp_account: &self.bidder_ft_temp_account,
+            bidder_ft_temp_account_owner: self.session_key.to_account_info(),
+            bidder_ft_returning_account: self.bidder_ft_account.key(),
+            session: Some(&mut self.session),
+            highest_bidder: self.highest_bidder.clone(),
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_ft_returning_account: &self.highest_bidder_ft_returning_account,
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer.to_account_info(),
+            escrow_account: &self.escrow_account,
+            bid_receipt: &mut self.bid_receipt,
+            bid_receipt_bump,
+            auction_house: &self.auction_house,
+            clock: &self.clock,
+            instructions: &self.instructions,
+            pda: self.pda.clone(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.event_authority.clone(),
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the BidWithSol struct.
+impl<'info> BidWithSol<'info> {
+    // Define a function to gather the accounts needed to place the bid. Refunds are unwrapped straight to
+    // the bidder, so the temporary wSOL account doubles as the returning account, and the previous highest
+    // bidder's stand in for its returning account and rent payer, which a wSOL refund never uses.
+    fn bid_placement(&mut self, bid_receipt_bump: u8) -> BidPlacement<'_, 'info> {
+        BidPlacement {
+            bidder: self.bidder.to_account_info(),
+            payer: self.payer.key(),
+            bidder_ft_temp_account: &self.bidder_ft_temp_account,
+            bidder_ft_temp_account_owner: self.bidder.to_account_info(),
+            bidder_ft_returning_account: self.bidder_ft_temp_account.key(),
+            session: None,
+            highest_bidder: self.highest_bidder.clone(),
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_ft_returning_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_rent_payer: self.highest_bidder.clone(),
+            escrow_account: &self.escrow_account,
+            bid_receipt: &mut self.bid_receipt,
+            bid_receipt_bump,
+            auction_house: &self.auction_house,
+            clock: &self.clock,
+            instructions: &self.instructions,
+            pda: self.pda.clone(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.event_authority.clone(),
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the BidPlacement struct.
+impl<'a, 'info> BidPlacement<'a, 'info> {
+    // Define a function to create a context for handing the bidder's temporary FT account to the PDA.
+    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.bidder_ft_temp_account.to_account_info(),
+            current_authority: self.bidder_ft_temp_account_owner.clone(),
+        };
+        CpiContext::new(self.token_program.clone(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the previous highest bidder's temporary FT account.
+    fn to_close_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.clone(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring the current highest bid amount back to the previous highest bidder.
+    fn to_transfer_to_previous_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info(),
+            to: self.highest_bidder_ft_returning_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.clone(), cpi_accounts)
+    }
+
+    // Define a function to pay an amount from the bidder's funds into an account of the payment mint.
+    fn pay(&self, funding: &BidFunding<'_, 'info>, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        match funding {
+            BidFunding::Tokens { ft_account, authority, signers_seeds } => {
+                let cpi_accounts = Transfer {
+                    from: ft_account.clone(),
+                    to: to.clone(),
+                    authority: authority.clone(),
+                };
+                token::transfer(
+                    CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signers_seeds),
+                    amount,
+                )
+            }
+            BidFunding::Lamports => {
+                let cpi_accounts = system_program::Transfer {
+                    from: self.bidder.clone(),
+                    to: to.clone(),
+                };
+                system_program::transfer(CpiContext::new(self.system_program.clone(), cpi_accounts), amount)?;
+                // Sync the wSOL account so the lamports count as its token balance.
+                sync_native(to, &self.token_program)
+            }
+        }
+    }
+}
+
+// Implement the ContributeToPool struct.
+impl<'info> ContributeToPool<'info> {
+    // Define a function to create a context for transferring the contribution to the pool's vault.
+    fn to_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.contributor_ft_account.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.contributor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ClaimPoolRefund struct.
+impl<'info> ClaimPoolRefund<'info> {
+    // Define a function to create a context for transferring a refund from the pool's vault.
+    fn to_transfer_refund_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.contributor_ft_account.to_account_info(),
+            authority: self.bid_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ClaimPoolFractions struct.
+impl<'info> ClaimPoolFractions<'info> {
+    // Define a function to create a context for minting the contribution's fractions.
+    fn to_mint_fractions_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.fraction_mint.to_account_info(),
+            to: self.contributor_fraction_account.to_account_info(),
+            authority: self.bid_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the RedeemPoolItem struct.
+impl<'info> RedeemPoolItem<'info> {
+    // Define a function to create a context for burning the redeemer's fractions.
+    fn to_burn_fractions_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.fraction_mint.to_account_info(),
+            from: self.redeemer_fraction_account.to_account_info(),
+            authority: self.redeemer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring the NFT to the redeemer.
+    fn to_transfer_item_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.item_account.to_account_info(),
+            to: self.redeemer_nft_account.to_account_info(),
+            authority: self.bid_pool.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the DistributeRoyalties struct.
+impl<'info> DistributeRoyalties<'info> {
+    // Define a function to create a context for transferring a creator's share from the royalty vault.
+    fn to_transfer_to_creator_context(
+        &self,
+        creator: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.royalty_vault.to_account_info(),
+            to: creator,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ClaimAffiliateEarnings struct.
+impl<'info> ClaimAffiliateEarnings<'info> {
+    // Define a function to create a context for transferring the affiliate fee from the affiliate vault.
+    fn to_transfer_to_affiliate_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.affiliate_vault.to_account_info(),
+            to: self.affiliate_ft_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the MigrateAuction struct.
+impl<'info> MigrateAuction<'info> {
+    // Define a function to create a context for topping up the escrow account's rent.
+    fn to_transfer_rent_context(&self) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.payer.to_account_info(),
+            to: self.escrow_account.clone(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the MigrateHouse struct.
+impl<'info> MigrateHouse<'info> {
+    // Define a function to create a context for topping up the configuration account's rent.
+    fn to_transfer_rent_context(&self) -> CpiContext<'_, '_, '_, 'info, system_program::Transfer<'info>> {
+        let cpi_accounts = system_program::Transfer {
+            from: self.payer.to_account_info(),
+            to: self.auction_house.clone(),
+        };
+        CpiContext::new(self.system_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the Close struct.
+impl<'info> Close<'info> {
+    // Define a function to gather the accounts needed to settle the auction and record the sale.
+    fn as_sale_closing(&mut self) -> SaleClosing<'_, 'info> {
+        SaleClosing {
+            exhibitor: &self.exhibitor,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            highest_bidder_rent_payer: &self.highest_bidder_rent_payer,
+            exhibitor_nft_temp_account: &self.exhibitor_nft_temp_account,
+            exhibitor_ft_receiving_account: &self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_nft_receiving_account: &self.highest_bidder_nft_receiving_account,
+            auction_house: &self.auction_house,
+            treasury_ft_account: &self.treasury_ft_account,
+            escrow_account: &self.escrow_account,
+            payment_mint: &self.payment_mint,
+            price_feed: &self.price_feed,
+            nft_metadata: &self.nft_metadata,
+            royalty_override: &self.royalty_override,
+            collection_stats: &self.collection_stats,
+            house_stats: &self.house_stats,
+            clock: &self.clock,
+            pda: &self.pda,
+            token_program: &self.token_program,
+            purchase_receipt: &mut self.purchase_receipt,
+            listing_receipt: &self.listing_receipt,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the SettleIfEnded struct.
+impl<'info> SettleIfEnded<'info> {
+    // Define a function to gather the accounts needed to settle the auction and record the sale.
+    fn as_sale_closing(&mut self) -> SaleClosing<'_, 'info> {
+        SaleClosing {
+            exhibitor: &self.exhibitor,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            highest_bidder_rent_payer: &self.highest_bidder_rent_payer,
+            exhibitor_nft_temp_account: &self.exhibitor_nft_temp_account,
+            exhibitor_ft_receiving_account: &self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            highest_bidder_nft_receiving_account: &self.highest_bidder_nft_receiving_account,
+            auction_house: &self.auction_house,
+            treasury_ft_account: &self.treasury_ft_account,
+            escrow_account: &self.escrow_account,
+            payment_mint: &self.payment_mint,
+            price_feed: &self.price_feed,
+            nft_metadata: &self.nft_metadata,
+            royalty_override: &self.royalty_override,
+            collection_stats: &self.collection_stats,
+            house_stats: &self.house_stats,
+            clock: &self.clock,
+            pda: &self.pda,
+            token_program: &self.token_program,
+            purchase_receipt: &mut self.purchase_receipt,
+            listing_receipt: &self.listing_receipt,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the ClaimItem struct.
+impl<'info> ClaimItem<'info> {
+    // Define a function to gather the accounts needed to deliver the NFT.
+    fn to_item_delivery(&self) -> ItemDelivery<'_, 'info> {
+        ItemDelivery {
+            exhibitor_nft_temp_account: &self.exhibitor_nft_temp_account,
+            highest_bidder_nft_receiving_account: &self.highest_bidder_nft_receiving_account,
+            pda: &self.pda,
+            token_program: &self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the ClaimPrint struct.
+impl<'info> ClaimPrint<'info> {
+    // Define a function to gather the accounts needed to return the master edition to the exhibitor.
+    fn to_master_return(&self) -> ItemDelivery<'_, 'info> {
+        ItemDelivery {
+            exhibitor_nft_temp_account: &self.exhibitor_nft_temp_account,
+            highest_bidder_nft_receiving_account: &self.exhibitor_nft_token_account,
+            pda: &self.pda,
+            token_program: &self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the ClaimDomain struct.
+impl<'info> ClaimDomain<'info> {
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the ClaimStake struct.
+impl<'info> ClaimStake<'info> {
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the ClaimProceeds struct.
+impl<'info> ClaimProceeds<'info> {
+    // Define a function to gather the accounts needed to distribute the proceeds.
+    fn to_proceeds_distribution<'a>(&'a self, escrow_account: &'a Auction) -> ProceedsDistribution<'a, 'info> {
+        ProceedsDistribution {
+            exhibitor: self.exhibitor.to_account_info(),
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer.clone(),
+            exhibitor_ft_receiving_account: &self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            auction_house: &self.auction_house,
+            treasury_ft_account: &self.treasury_ft_account,
+            escrow_account,
+            nft_metadata: &self.nft_metadata,
+            royalty_override: &self.royalty_override,
+            pda: &self.pda,
+            token_program: &self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the SettleItem struct.
+impl<'info> SettleItem<'info> {
+    // Define a function to gather the accounts needed to deliver the NFT.
+    fn to_item_delivery(&self) -> ItemDelivery<'_, 'info> {
+        ItemDelivery {
+            exhibitor_nft_temp_account: &self.exhibitor_nft_temp_account,
+            highest_bidder_nft_receiving_account: &self.highest_bidder_nft_receiving_account,
+            pda: &self.pda,
+            token_program: &self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the SettlePayment struct.
+impl<'info> SettlePayment<'info> {
+    // Define a function to gather the accounts needed to distribute the proceeds.
+    fn to_proceeds_distribution<'a>(&'a self, escrow_account: &'a Auction) -> ProceedsDistribution<'a, 'info> {
+        ProceedsDistribution {
+            exhibitor: self.exhibitor.clone(),
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer.clone(),
+            exhibitor_ft_receiving_account: &self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: &self.highest_bidder_ft_temp_account,
+            auction_house: &self.auction_house,
+            treasury_ft_account: &self.treasury_ft_account,
+            escrow_account,
+            nft_metadata: &self.nft_metadata,
+            royalty_override: &self.royalty_override,
+            pda: &self.pda,
+            token_program: &self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to settle the auction once both sides have claimed.
+    fn to_claim_completion(&self) -> ClaimCompletion<'_, 'info> {
+        ClaimCompletion {
+            escrow_account: self.escrow_account.to_account_info(),
+            listing_receipt: &self.listing_receipt,
+            exhibitor_rent_payer: &self.exhibitor_rent_payer,
+            event_authority: &self.event_authority,
+            program: self.program.to_account_info(),
+        }
+    }
+}
+
+// Implement the SaleClosing struct.
+impl<'a, 'info> SaleClosing<'a, 'info> {
+    // Define a function to gather the accounts needed to settle the auction.
+    fn to_settlement<'b>(&'b self, escrow_account: &'b Auction) -> Settlement<'b, 'info> {
+        Settlement {
+            exhibitor: self.exhibitor,
+            exhibitor_rent_payer: self.exhibitor_rent_payer,
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer,
+            exhibitor_nft_temp_account: self.exhibitor_nft_temp_account,
+            exhibitor_ft_receiving_account: self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: self.highest_bidder_ft_temp_account,
+            highest_bidder_nft_receiving_account: self.highest_bidder_nft_receiving_account,
+            auction_house: self.auction_house,
+            treasury_ft_account: self.treasury_ft_account,
+            escrow_account,
+            payment_mint: self.payment_mint,
+            price_feed: self.price_feed,
+            nft_metadata: self.nft_metadata,
+            royalty_override: self.royalty_override,
+            clock: self.clock,
+            pda: self.pda,
+            token_program: self.token_program,
+        }
+    }
+}
+
+// Implement the Settlement struct.
+impl<'a, 'info> Settlement<'a, 'info> {
+    // Define a function to gather the accounts needed to deliver the NFT.
+    fn to_item_delivery(&self) -> ItemDelivery<'a, 'info> {
+        ItemDelivery {
+            exhibitor_nft_temp_account: self.exhibitor_nft_temp_account,
+            highest_bidder_nft_receiving_account: self.highest_bidder_nft_receiving_account,
+            pda: self.pda,
+            token_program: self.token_program,
+        }
+    }
+
+    // Define a function to gather the accounts needed to distribute the proceeds.
+    fn to_proceeds_distribution(&self) -> ProceedsDistribution<'a, 'info> {
+        ProceedsDistribution {
+            exhibitor: self.exhibitor.clone(),
+            highest_bidder_rent_payer: self.highest_bidder_rent_payer.clone(),
+            exhibitor_ft_receiving_account: self.exhibitor_ft_receiving_account,
+            highest_bidder_ft_temp_account: self.highest_bidder_ft_temp_account,
+            auction_house: self.auction_house,
+            treasury_ft_account: self.treasury_ft_account,
+            escrow_account: self.escrow_account,
+            nft_metadata: self.nft_metadata,
+            royalty_override: self.royalty_override,
+            pda: self.pda,
+            token_program: self.token_program,
+        }
+    }
+}
+
+// Implement the ItemDelivery struct.
+impl<'a, 'info> ItemDelivery<'a, 'info> {
+    // Define a function to create a context for transferring the NFT from the escrow account to the highest bidder.
+    fn to_transfer_to_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .highest_bidder_nft_receiving_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ProceedsDistribution struct.
+impl<'a, 'info> ProceedsDistribution<'a, 'info> {
+    // Define a function to create a context for transferring the highest bid amount from the escrow account to the exhibitor.
+    fn to_transfer_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_ft_receiving_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring a share of the proceeds to a payout recipient.
+    fn to_transfer_to_recipient_context(
+        &self,
+        recipient: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: recipient,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring the house fee to the treasury.
+    fn to_transfer_to_treasury_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        self.to_transfer_to_recipient_context(self.treasury_ft_account.to_account_info())
+    }
+
+    // Define a function to create a context for closing the highest bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the ReclaimUnclaimed struct.
+impl<'info> ReclaimUnclaimed<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_nft_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring the penalty to the exhibitor.
+    fn to_transfer_penalty_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_ft_receiving_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for refunding the rest of the bid to the highest bidder.
+    fn to_refund_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .highest_bidder_ft_returning_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the highest bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination: self.exhibitor_rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the SettleUnmetReserve struct.
+impl<'info> SettleUnmetReserve<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_nft_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for refunding the highest bid to the highest bidder.
+    fn to_refund_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .highest_bidder_ft_returning_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the highest bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination: self.exhibitor_rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the PayInstallment struct.
+impl<'info> PayInstallment<'info> {
+    // Define a function to create a context for transferring an installment to the temporary FT account.
+    fn to_transfer_to_temp_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_account.to_account_info(),
+            to: self.highest_bidder_ft_temp_account.to_account_info(),
+            authority: self.highest_bidder.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Implement the DefaultInstallments struct.
+impl<'info> DefaultInstallments<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_nft_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info(),
+            to: self.exhibitor_nft_token_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for transferring the forfeited payments to the exhibitor.
+    fn to_transfer_forfeit_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info(),
+            to: self.exhibitor_ft_receiving_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for refunding the rest of the payments to the winning bidder.
+    fn to_refund_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info(),
+            to: self.highest_bidder_ft_returning_account.to_account_info(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the winning bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info(),
+            destination: self.exhibitor_rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the AdminCancel struct with associated accounts.
+#[derive(Accounts)]
+pub struct AdminCancel<'info> {
+    // The house authority or its emergency canceller, which must be a signer.
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the emergency canceller role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::EmergencyCanceller, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The exhibitor's account.
+    /// CHECK: Checked against the escrow account.
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == exhibitor.key(),
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The highest bidder's account, which receives the FT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder: AccountInfo<'info>,
+    // The highest bidder's temporary FT account.
+    #[account(mut)]
+    pub highest_bidder_ft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The highest bidder's FT returning account, which receives the refund.
     #[account(
         mut,
-        constraint = highest_bidder.key() != bidder.key()
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
     )]
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
+    pub highest_bidder_ft_returning_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account of an auction that is not over, or finalized without any claim.
+    #[account(
+        mut,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = matches!(
+            escrow_account.load()?.stored_status()?,
+            AuctionStatus::Created | AuctionStatus::Live | AuctionStatus::Ended
+        ) @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Implement the AdminCancel struct.
+impl<'info> AdminCancel<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_nft_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for refunding the highest bid to the highest bidder.
+    fn to_refund_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .highest_bidder_ft_returning_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the highest bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination: self.exhibitor_rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the ReverseSale struct with associated accounts.
+#[derive(Accounts)]
+pub struct ReverseSale<'info> {
+    // The arbiter of the auction, which must be a signer.
+    pub arbiter: Signer<'info>,
+    // The exhibitor's account.
+    /// CHECK: Checked against the escrow account.
+    pub exhibitor: AccountInfo<'info>,
+    // The exhibitor's NFT account, which receives the NFT back and must be for its mint.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_token_account.owner == exhibitor.key(),
+        constraint = exhibitor_nft_token_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_token_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's temporary NFT account, which must hold the auctioned NFT.
+    #[account(
+        mut,
+        constraint = exhibitor_nft_temp_account.mint == escrow_account.load()?.nft_mint @ AuctionError::NftMintMismatch
+    )]
+    pub exhibitor_nft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The winning bidder's account, which receives the FT temporary account rent.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
     pub highest_bidder: AccountInfo<'info>,
     // The highest bidder's temporary FT account.
     #[account(mut)]
-    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
-    // The highest bidder's FT returning account.
+    pub highest_bidder_ft_temp_account: Box<Account<'info, TokenAccount>>,
+    // The highest bidder's FT returning account, which receives the refund.
+    #[account(
+        mut,
+        constraint = highest_bidder_ft_returning_account.mint == escrow_account.load()?.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub highest_bidder_ft_returning_account: Box<Account<'info, TokenAccount>>,
+    // The exhibitor's rent payer, which gets the rent of the escrow and temporary NFT accounts back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub exhibitor_rent_payer: AccountInfo<'info>,
+    // The highest bidder's rent payer, which gets the rent of the temporary FT account back.
+    /// CHECK: Checked against the escrow account.
+    #[account(mut)]
+    pub highest_bidder_rent_payer: AccountInfo<'info>,
+    // The escrow account of an auction in arbitration mode, finalized and still in its dispute window.
+    #[account(
+        mut,
+        constraint = escrow_account.load()?.dispute_window_sec > 0 @ AuctionError::NotInArbitration,
+        has_one = arbiter @ AuctionError::Unauthorized,
+        has_one = exhibitor_rent_payer,
+        has_one = highest_bidder_rent_payer,
+        has_one = exhibitor,
+        has_one = exhibitor_nft_temp_account,
+        has_one = highest_bidder,
+        has_one = highest_bidder_ft_temp_account,
+        has_one = highest_bidder_ft_returning_account,
+        constraint = escrow_account.load()?.stored_status()? == AuctionStatus::Ended @ AuctionError::InvalidAuctionStatus,
+        constraint = escrow_account.load()?.in_dispute_window(&Clock::get()?) @ AuctionError::DisputeWindowOver,
+        constraint = escrow_account.load()?.item_claimed == 0 @ AuctionError::AlreadyClaimed,
+        constraint = escrow_account.load()?.proceeds_claimed == 0 @ AuctionError::AlreadyClaimed,
+        close = exhibitor_rent_payer
+    )]
+    pub escrow_account: AccountLoader<'info, Auction>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the escrowed tokens.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The listing receipt of the auction, tombstoned with its final status. Auctions exhibited before
+    // listing receipts have none, and the account is then left untouched.
+    /// CHECK: Checked by its seeds, and only written if it holds a listing receipt.
+    #[account(
+        mut,
+        seeds = [LISTING_RECEIPT_SEED, escrow_account.key().as_ref(), &escrow_account.load()?.round_seed()],
+        bump
+    )]
+    pub listing_receipt: AccountInfo<'info>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Implement the ReverseSale struct.
+impl<'info> ReverseSale<'info> {
+    // Define a function to create a context for transferring the NFT back to the exhibitor.
+    fn to_transfer_nft_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            to: self
+                .exhibitor_nft_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for refunding the highest bid to the highest bidder.
+    fn to_refund_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            to: self
+                .highest_bidder_ft_returning_account
+                .to_account_info()
+                .clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the highest bidder's temporary FT account.
+    fn to_close_ft_context(
+        &self,
+        destination: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
+            destination,
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for closing the exhibitor's temporary NFT account.
+    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_accounts = CloseAccount {
+            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
+            destination: self.exhibitor_rent_payer.clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the CreateEditionSale struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateEditionSale<'info> {
+    // The seller, which must be a signer and the mint authority of the edition mint.
+    pub seller: Signer<'info>,
+    // The payer of the transaction and of the sale account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The mint of the editions, which must have no decimals.
+    #[account(
+        mut,
+        constraint = edition_mint.mint_authority == COption::Some(seller.key()) @ AuctionError::InvalidEditionMint,
+        constraint = edition_mint.decimals == 0 @ AuctionError::InvalidEditionMint
+    )]
+    pub edition_mint: Account<'info, Mint>,
+    // The seller's FT receiving account, whose mint becomes the payment mint.
+    pub seller_ft_account: Account<'info, TokenAccount>,
+    // The sale PDA of the edition mint.
+    #[account(
+        init,
+        payer = payer,
+        space = EditionSale::LEN,
+        seeds = [EDITION_SALE_SEED, edition_mint.key().as_ref()],
+        bump
+    )]
+    pub edition_sale: Account<'info, EditionSale>,
+    // The blacklist entry of the edition mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, edition_mint.key().as_ref()],
+        bump,
+        constraint = nft_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub nft_blacklist_entry: AccountInfo<'info>,
+    // The blacklist entry of the payment mint, which must not exist.
+    /// CHECK: Checked by its seeds, and only its emptiness is used.
+    #[account(
+        seeds = [BLACKLIST_SEED, seller_ft_account.mint.as_ref()],
+        bump,
+        constraint = payment_blacklist_entry.data_is_empty() @ AuctionError::BlacklistedMint
+    )]
+    pub payment_blacklist_entry: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Implement the CreateEditionSale struct.
+impl<'info> CreateEditionSale<'info> {
+    // Define a function to create a context for setting the mint authority of the edition mint to the PDA.
+    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.edition_mint.to_account_info().clone(),
+            current_authority: self.seller.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the BuyEdition struct with associated accounts.
+#[derive(Accounts)]
+pub struct BuyEdition<'info> {
+    // The buyer, which must be a signer.
+    pub buyer: Signer<'info>,
+    // The payer of the transaction and of the edition receipt.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The sale account.
+    #[account(
+        mut,
+        seeds = [EDITION_SALE_SEED, edition_sale.edition_mint.as_ref()],
+        bump = edition_sale.bump,
+        has_one = edition_mint
+    )]
+    pub edition_sale: Account<'info, EditionSale>,
+    // The mint of the editions.
+    #[account(mut)]
+    pub edition_mint: Account<'info, Mint>,
+    // The buyer's account receiving the edition, which must be for the edition mint.
+    #[account(
+        mut,
+        constraint = buyer_edition_account.mint == edition_sale.edition_mint @ AuctionError::NftMintMismatch
+    )]
+    pub buyer_edition_account: Account<'info, TokenAccount>,
+    // The buyer's FT account paying for the edition, which must hold the payment mint.
+    #[account(
+        mut,
+        constraint = buyer_ft_account.mint == edition_sale.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub buyer_ft_account: Account<'info, TokenAccount>,
+    // The seller's FT receiving account recorded in the sale.
+    #[account(
+        mut,
+        constraint = seller_ft_account.key() == edition_sale.seller_ft_pubkey @ AuctionError::EditionSellerMismatch
+    )]
+    pub seller_ft_account: Account<'info, TokenAccount>,
+    // The auction house configuration, which must not be paused.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = !auction_house.paused @ AuctionError::HousePaused
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The treasury's FT account, which receives the house fee and must hold the payment mint.
+    #[account(
+        mut,
+        constraint = treasury_ft_account.owner == auction_house.treasury,
+        constraint = treasury_ft_account.mint == edition_sale.payment_mint @ AuctionError::PaymentMintMismatch
+    )]
+    pub treasury_ft_account: Account<'info, TokenAccount>,
+    // The receipt PDA of the purchase, indexed by the editions sold before it.
+    #[account(
+        init,
+        payer = payer,
+        space = EditionReceipt::LEN,
+        seeds = [EDITION_RECEIPT_SEED, edition_sale.key().as_ref(), &edition_sale.sold.to_le_bytes()],
+        bump
+    )]
+    pub edition_receipt: Account<'info, EditionReceipt>,
+    // The PDA account, which holds the mint authority of the edition mint.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+    // The event authority PDA, which signs the self-CPI that records events.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [events::EVENT_AUTHORITY_SEED], bump)]
+    pub event_authority: AccountInfo<'info>,
+    // This program, invoked to record events.
+    pub program: Program<'info, program::AnchorAuction>,
+}
+
+// Implement the BuyEdition struct.
+impl<'info> BuyEdition<'info> {
+    // Define a function to create a context for transferring part of the price from the buyer's FT account.
+    fn to_transfer_context(&self, to: AccountInfo<'info>) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.buyer_ft_account.to_account_info().clone(),
+            to,
+            authority: self.buyer.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    // Define a function to create a context for minting an edition to the buyer.
+    fn to_mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.edition_mint.to_account_info().clone(),
+            to: self.buyer_edition_account.to_account_info().clone(),
+            authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the EndEditionSale struct with associated accounts.
+#[derive(Accounts)]
+pub struct EndEditionSale<'info> {
+    // The seller, which must be a signer and gets the rent of the sale account back.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    // The sale account to close, which must belong to the seller.
+    #[account(
+        mut,
+        seeds = [EDITION_SALE_SEED, edition_sale.edition_mint.as_ref()],
+        bump = edition_sale.bump,
+        has_one = seller,
+        has_one = edition_mint,
+        close = seller
+    )]
+    pub edition_sale: Account<'info, EditionSale>,
+    // The mint of the editions.
+    #[account(mut)]
+    pub edition_mint: Account<'info, Mint>,
+    // The auction house configuration, which stores the escrow PDA's bump seed.
+    #[account(seeds = [AUCTION_HOUSE_SEED], bump = auction_house.bump)]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The PDA account, which holds the mint authority of the edition mint.
+    /// CHECK: Checked by its seeds.
+    #[account(seeds = [ESCROW_PDA_SEED], bump = auction_house.escrow_bump)]
+    pub pda: AccountInfo<'info>,
+    // The SPL token program account.
+    pub token_program: Program<'info, Token>,
+}
+
+// Implement the EndEditionSale struct.
+impl<'info> EndEditionSale<'info> {
+    // Define a function to create a context for setting the mint authority of the edition mint back to the seller.
+    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_accounts = SetAuthority {
+            account_or_mint: self.edition_mint.to_account_info().clone(),
+            current_authority: self.pda.clone(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+// Define the AddSeller struct with associated accounts.
+#[derive(Accounts)]
+pub struct AddSeller<'info> {
+    // The house authority or its whitelist manager, which must be a signer and pays for the seller
+    // allowlist entry.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The seller's wallet.
+    /// CHECK: Only its address is recorded.
+    pub seller: AccountInfo<'info>,
+    // The seller allowlist PDA of the seller.
+    #[account(
+        init,
+        payer = authority,
+        space = SellerAllowlist::LEN,
+        seeds = [SELLER_ALLOWLIST_SEED, seller.key().as_ref()],
+        bump
+    )]
+    pub seller_allowlist: Account<'info, SellerAllowlist>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the RemoveSeller struct with associated accounts.
+#[derive(Accounts)]
+pub struct RemoveSeller<'info> {
+    // The house authority or its whitelist manager, which must be a signer and gets the rent of the
+    // seller allowlist entry back.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The seller allowlist entry to close.
+    #[account(
+        mut,
+        seeds = [SELLER_ALLOWLIST_SEED, seller_allowlist.seller.as_ref()],
+        bump = seller_allowlist.bump,
+        close = authority
+    )]
+    pub seller_allowlist: Account<'info, SellerAllowlist>,
+}
+
+// Define the AddPartner struct with associated accounts.
+#[derive(Accounts)]
+pub struct AddPartner<'info> {
+    // The house authority or its whitelist manager, which must be a signer and pays for the partner
+    // entry.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The partner frontend's wallet, which owns its FT fee accounts.
+    /// CHECK: Only its address is recorded.
+    pub partner: AccountInfo<'info>,
+    // The partner entry PDA of the partner.
+    #[account(
+        init,
+        payer = authority,
+        space = PartnerEntry::LEN,
+        seeds = [PARTNER_SEED, partner.key().as_ref()],
+        bump
+    )]
+    pub partner_entry: Account<'info, PartnerEntry>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the RemovePartner struct with associated accounts.
+#[derive(Accounts)]
+pub struct RemovePartner<'info> {
+    // The house authority or its whitelist manager, which must be a signer and gets the rent of the partner
+    // entry back.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The partner entry to close.
+    #[account(
+        mut,
+        seeds = [PARTNER_SEED, partner_entry.partner.as_ref()],
+        bump = partner_entry.bump,
+        close = authority
+    )]
+    pub partner_entry: Account<'info, PartnerEntry>,
+}
+
+// Define the AddRoyaltyOverride struct with associated accounts.
+#[derive(Accounts)]
+pub struct AddRoyaltyOverride<'info> {
+    // The house authority, which must be a signer and pays for the royalty override.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must belong to the authority.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        has_one = authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The mint of the collection NFT.
+    /// CHECK: Only its address is recorded.
+    pub collection: AccountInfo<'info>,
+    // The royalty override PDA of the collection.
+    #[account(
+        init,
+        payer = authority,
+        space = RoyaltyOverride::LEN,
+        seeds = [ROYALTY_OVERRIDE_SEED, collection.key().as_ref()],
+        bump
+    )]
+    pub royalty_override: Account<'info, RoyaltyOverride>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the CreateCollectionStats struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateCollectionStats<'info> {
+    // The payer of the collection stats, which must be a signer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The mint of the collection NFT.
+    /// CHECK: Only its address is recorded.
+    pub collection: AccountInfo<'info>,
+    // The payment mint the sales are recorded in.
+    pub payment_mint: Account<'info, Mint>,
+    // The stats PDA of the collection in the payment mint.
+    #[account(
+        init,
+        payer = payer,
+        space = CollectionStats::LEN,
+        seeds = [COLLECTION_STATS_SEED, collection.key().as_ref(), payment_mint.key().as_ref()],
+        bump
+    )]
+    pub collection_stats: Account<'info, CollectionStats>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the CreateHouseStats struct with associated accounts.
+#[derive(Accounts)]
+pub struct CreateHouseStats<'info> {
+    // The payer of the house stats, which must be a signer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // The payment mint the sales are recorded in.
+    pub payment_mint: Account<'info, Mint>,
+    // The house stats PDA of the payment mint.
+    #[account(
+        init,
+        payer = payer,
+        space = HouseStats::LEN,
+        seeds = [HOUSE_STATS_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub house_stats: Account<'info, HouseStats>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the RemoveRoyaltyOverride struct with associated accounts.
+#[derive(Accounts)]
+pub struct RemoveRoyaltyOverride<'info> {
+    // The house authority, which must be a signer and gets the rent of the royalty override back.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must belong to the authority.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        has_one = authority
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The royalty override to close.
+    #[account(
+        mut,
+        seeds = [ROYALTY_OVERRIDE_SEED, royalty_override.collection.as_ref()],
+        bump = royalty_override.bump,
+        close = authority
+    )]
+    pub royalty_override: Account<'info, RoyaltyOverride>,
+}
+
+// Define the BlacklistMint struct with associated accounts.
+#[derive(Accounts)]
+pub struct BlacklistMint<'info> {
+    // The house authority or its whitelist manager, which must be a signer and pays for the blacklist
+    // entry.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The mint to ban.
+    pub mint: Account<'info, Mint>,
+    // The blacklist entry PDA of the mint.
+    #[account(
+        init,
+        payer = authority,
+        space = BlacklistEntry::LEN,
+        seeds = [BLACKLIST_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+    // The system program account.
+    pub system_program: Program<'info, System>,
+}
+
+// Define the UnblacklistMint struct with associated accounts.
+#[derive(Accounts)]
+pub struct UnblacklistMint<'info> {
+    // The house authority or its whitelist manager, which must be a signer and gets the rent of the
+    // blacklist entry back.
     #[account(mut)]
-    pub highest_bidder_ft_returning_account: Account<'info, TokenAccount>,
-    // The escrow account with various constraints.
+    pub authority: Signer<'info>,
+    // The auction house configuration, which must give the signer the whitelist manager role.
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED],
+        bump = auction_house.bump,
+        constraint = auction_house.has_role(HouseRole::WhitelistManager, &authority.key()) @ AuctionError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    // The blacklist entry to close.
     #[account(
         mut,
-        constraint = escrow_account.highest_bidder_pubkey == highest_bidder.key(),
-        constraint = escrow_account.highest_bidder_ft_temp_pubkey == highest_bidder_ft_temp_account.key(),
-        constraint = escrow_account.highest_bidder_ft_returning_pubkey == highest_bidder_ft_returning_account.key(),
-        constraint = escrow_account.price < price,
-        constraint = escrow_account.end_at > clock.unix_timestamp
+        seeds = [BLACKLIST_SEED, blacklist_entry.mint.as_ref()],
+        bump = blacklist_entry.bump,
+        close = authority
     )]
-    pub escrow_account: Box<Account<'info, Auction>>,
-    // The system clock account for getting the current UNIX timestamp.
-    pub clock: Sysvar<'info, Clock>,
-    // The PDA account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub pda: AccountInfo<'info>,
-    // The SPL token program account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub token_program: AccountInfo<'info>,
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+}
+
+// Define the Auction struct to represent the auction state. The account is zero-copy with a fixed
+// `repr(C)` layout and explicit padding, so reading it costs no deserialization and indexers can rely
+// on stable field offsets. New fields are only ever appended, behind a new version.
+#[account(zero_copy)]
+pub struct Auction {
+    // The layout version of the account (see AUCTION_VERSION).
+    pub version: u8,
+    // The number of payout recipients in use.
+    pub payout_count: u8,
+    // The charity's share of the proceeds in basis points.
+    pub charity_bps: u16,
+    // The stored status of the auction (see AuctionStatus).
+    pub status: u8,
+    // Whether end_at is a UNIX timestamp or a slot (see TimingMode, version 7).
+    pub timing_mode: u8,
+    // Whether the NFT is held in escrow or delegated from the exhibitor's wallet (see ListingMode, version 12).
+    pub listing_mode: u8,
+    // Padding to align the following fields to 8 bytes.
+    pub padding: [u8; 1],
+    // The current highest bid amount.
+    pub price: u64,
+    // The auction end, as a UNIX timestamp or a slot height depending on the timing mode.
+    pub end_at: i64,
+    // The reserve price in US cents (zero if the auction has no USD reserve).
+    pub reserve_usd_cents: u64,
+    // The exhibitor's public key.
+    pub exhibitor: Pubkey,
+    // The exhibitor's FT receiving account public key.
+    pub exhibitor_ft_receiving_account: Pubkey,
+    // The exhibitor's temporary NFT account public key.
+    pub exhibitor_nft_temp_account: Pubkey,
+    // The highest bidder's public key.
+    pub highest_bidder: Pubkey,
+    // The highest bidder's FT temporary account public key.
+    pub highest_bidder_ft_temp_account: Pubkey,
+    // The highest bidder's FT returning account public key.
+    pub highest_bidder_ft_returning_account: Pubkey,
+    // The charity's FT receiving account public key (default if there is no charity).
+    pub charity_ft_pubkey: Pubkey,
+    // The FT receiving account public key of the referrer who brought the highest bidder (default if none).
+    pub highest_bidder_referrer_ft_pubkey: Pubkey,
+    // The Pyth price feed of the payment token in USD (default if the auction has no USD reserve).
+    pub reserve_price_feed: Pubkey,
+    // The mint every bid, refund and payout of the auction is made in.
+    pub payment_mint: Pubkey,
+    // The payout recipients and their shares (changing MAX_PAYOUT_RECIPIENTS changes the layout).
+    pub payouts: [Payout; MAX_PAYOUT_RECIPIENTS],
+    // Whether the winning bidder has claimed the NFT of a finalized auction (version 3).
+    pub item_claimed: u8,
+    // Whether the exhibitor has claimed the proceeds of a finalized auction (version 3).
+    pub proceeds_claimed: u8,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding2: [u8; 6],
+    // The time by which the winning bidder must claim the NFT of a finalized auction, in UNIX
+    // timestamp (version 4).
+    pub claim_deadline: i64,
+    // The account that paid for the escrow and temporary NFT accounts and gets their rent back (version 5).
+    pub exhibitor_rent_payer: Pubkey,
+    // The account that paid for the highest bidder's temporary FT account and gets its rent back (version 5).
+    pub highest_bidder_rent_payer: Pubkey,
+    // The account allowed to manage the auction on the exhibitor's behalf (default if none, version 6).
+    pub operator: Pubkey,
+    // The mint of the auctioned NFT, or the account of an auctioned domain or stake account (version 8).
+    pub nft_mint: Pubkey,
+    // The title of the lot, UTF-8 padded with zeros (empty if none, version 9).
+    pub title: [u8; 32],
+    // The URI of the lot's description, UTF-8 padded with zeros (empty if none, version 9).
+    pub uri: [u8; 200],
+    // The sequential ID assigned by the registry, starting from 1 (zero for auctions created before
+    // the registry, version 10).
+    pub auction_id: u64,
+    // The number of bids placed since version 11, which is also the index of the next bid receipt.
+    pub bid_count: u64,
+    // The mint bidders must hold to bid (default if the auction is not gated, version 13).
+    pub gate_mint: Pubkey,
+    // The amount of the gating mint bidders must hold (version 13).
+    pub gate_amount: u64,
+    // The mint of the collection NFT whose verified members bidders must hold one of (default if the
+    // auction is not gated, version 14).
+    pub gate_collection: Pubkey,
+    // How the price of a Dutch auction decays (see DutchCurve, None for an English auction, version 15).
+    pub dutch_curve: u8,
+    // Padding to align the following fields to 8 bytes.
+    pub padding3: [u8; 7],
+    // The lowest price a Dutch auction's price decays to (version 15).
+    pub dutch_floor_price: u64,
+    // When a Dutch auction's price starts decaying from the initial price, in the auction's timing mode
+    // (version 15).
+    pub dutch_start_at: i64,
+    // The half-life or step interval of a Dutch auction's curve, in the auction's timing mode (version 15).
+    pub dutch_curve_param: u64,
+    // The amount every bid on a penny auction raises the price by (zero for an English auction, version
+    // 16).
+    pub penny_tick: u64,
+    // The fee every bid on a penny auction pays to the treasury (version 16).
+    pub penny_bid_fee: u64,
+    // The time a penny auction has left after a bid, in its timing mode (version 16).
+    pub penny_countdown: u64,
+    // The vesting schedule the exhibitor's proceeds are deposited into at settlement (default if they are
+    // paid at once, version 17).
+    pub vesting_schedule: Pubkey,
+    // The program of the fractionalization vault exhibiting the NFT, called at settlement to settle the
+    // buyout (default if the auction is not a buyout, version 18).
+    pub buyout_program: Pubkey,
+    // The rental the winning bidder rents the NFT under instead of buying it (default if the NFT is sold,
+    // version 19).
+    pub rental: Pubkey,
+    // The account also allowed to relist the auction after it ends without a bid (default if only the
+    // exhibitor and its operator may, version 20).
+    pub relist_crank: Pubkey,
+    // The duration of every relisted auction, in its timing mode (version 20).
+    pub relist_duration: u64,
+    // The share the price drops by on every relist, in basis points (version 20).
+    pub relist_reduction_bps: u16,
+    // The number of times the auction has been relisted (version 20).
+    pub relist_count: u16,
+    // The number of times the auction may be relisted (zero if it is never relisted, version 20).
+    pub max_relists: u16,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding4: [u8; 2],
+    // Whether the escrow account is kept open once the auction is sold, for reuse_escrow (version 21).
+    pub reuse_escrow: u8,
+    // Padding to align the following fields to 8 bytes.
+    pub padding5: [u8; 7],
+    // The number of times the escrow account has been reused, which its receipts are derived from
+    // (version 21).
+    pub round: u64,
+    // The FT account of the partner frontend that listed the auction, which receives a share of the house
+    // fee (default if it was listed directly, version 22).
+    pub partner_fee_ft_pubkey: Pubkey,
+    // The partner frontend's share of the house fee in basis points, fixed at exhibit (version 22).
+    pub partner_fee_bps: u16,
+    // Whether the auction was listed for free and may start at a zero price (version 23).
+    pub free_listing: u8,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding6: [u8; 5],
+    // The commitment to the hidden reserve (see reserve_commitment), zero if the auction has none
+    // (version 24).
+    pub hidden_reserve_commitment: [u8; 32],
+    // The hidden reserve, once revealed (version 24).
+    pub hidden_reserve: u64,
+    // Whether the hidden reserve has been revealed (version 24).
+    pub hidden_reserve_revealed: u8,
+    // Whether the exhibitor accepted the highest bid regardless of the reserves (version 26).
+    pub reserve_waived: u8,
+    // The buyer's premium bids pay on top as the house fee in basis points, fixed at exhibit (zero if the
+    // house fee is taken from the proceeds, version 27).
+    pub buyers_premium_bps: u16,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding7: [u8; 4],
+    // When bidding opens to bidders passing the bid gates, in the auction's timing mode (zero if at once,
+    // version 25).
+    pub allowlist_at: i64,
+    // When bidding opens to everyone, lifting the bid gates, in the auction's timing mode (zero if the gates
+    // apply until the end, version 25).
+    pub public_at: i64,
+    // The series the auction is a lot of, which opens bidding on it in turn (default if none, version 28).
+    pub series: Pubkey,
+    // The mint of the listing token minted to the exhibitor for the current round (default if none,
+    // version 29).
+    pub listing_token_mint: Pubkey,
+    // The flat bond every bid pays to the treasury, which is not refunded when outbid (zero if none,
+    // version 30).
+    pub bid_bond: u64,
+    // The bond every bid pays to the treasury as a share of the bid in basis points (zero if none, version
+    // 30).
+    pub bid_bond_bps: u16,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding8: [u8; 6],
+    // The edition of the escrowed master edition printed to the winning bidder instead of selling the NFT
+    // itself (zero if the NFT itself is sold, version 31).
+    pub print_edition: u64,
+    // What is auctioned, which decides how it is escrowed and delivered (see ItemType, version 32).
+    pub item_type: u8,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding9: [u8; 7],
+    // The arbiter who may reverse the sale during the dispute window (default if none, version 33).
+    pub arbiter: Pubkey,
+    // How long after finalize settlement is held for disputes in seconds (zero if not in arbitration mode,
+    // version 33).
+    pub dispute_window_sec: u64,
+    // When the dispute window of a finalized auction closes, as a UNIX timestamp (zero if none, version
+    // 33).
+    pub dispute_deadline: i64,
+    // The affiliate the highest bid was tagged with, who earns a share of the house fee (default if none,
+    // version 34).
+    pub highest_bidder_affiliate: Pubkey,
+    // The price at which a bid ends the auction at once, and which no bid pays more than (zero if none,
+    // version 35).
+    pub max_price: u64,
+    // The tick size English bids must be multiples of (zero if none, version 36).
+    pub tick_size: u64,
+    // A bit for each bidder of the round, indexed by the first byte of their public key, from which
+    // unique_bidders estimates how many bidders took part. Cleared when the escrow account is reused
+    // (version 37).
+    pub bidder_bitmap: [u8; 32],
+    // The share of the winning bid and premium paid upfront under an installment plan, which is all a bid
+    // escrows, in basis points (version 38).
+    pub down_payment_bps: u16,
+    // The share of the payments forfeited to the exhibitor if the winning bidder defaults, in basis points
+    // (version 38).
+    pub default_forfeit_bps: u16,
+    // The number of installments the rest is paid in (zero if there is no installment plan, version 38).
+    pub installment_count: u8,
+    // Padding to keep the account size a multiple of 8 bytes.
+    pub padding10: [u8; 3],
+    // The time between installments, the first of which is due this long after the end (version 38).
+    pub installment_interval_sec: u64,
+}
+
+// Implement the Auction struct.
+impl Auction {
+    // The size of an Auction account in the current layout, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<Auction>();
+
+    // Get the number of remaining accounts settlement expects: the payout recipients', then the charity's,
+    // the winning bid referrer's and the partner frontend's when the auction has them, the house's affiliate
+    // vault when the winning bid has an affiliate, then the vesting schedule and vault when the proceeds
+    // vest, or the vault program and buyout authority of a buyout, the rental when the NFT is rented, and
+    // last the listing token mint and the exhibitor's listing token account when the exhibitor minted one.
+    // The house's royalty vault, which follows the partner's when a royalty is owed, comes on top, since
+    // only the NFT's metadata knows whether one is.
+    pub fn settlement_account_count(&self) -> usize {
+        self.payout_count as usize
+            + (self.charity_bps > 0) as usize
+            + (self.highest_bidder_referrer_ft_pubkey != Pubkey::default()) as usize
+            + (self.partner_fee_ft_pubkey != Pubkey::default()) as usize
+            + (self.highest_bidder_affiliate != Pubkey::default()) as usize
+            + (self.vesting_schedule != Pubkey::default()) as usize * 2
+            + (self.buyout_program != Pubkey::default()) as usize * 2
+            + (self.rental != Pubkey::default()) as usize
+            + (self.listing_token_mint != Pubkey::default()) as usize * 2
+    }
+
+    // Check whether settlement pays the exhibitor's proceeds out as lamports: wrapped SOL is unwrapped to
+    // the exhibitor, except for a buyout, whose vault program is told the proceeds are in the vault's FT
+    // receiving account and so must find them there.
+    pub fn unwraps_proceeds(&self) -> bool {
+        self.payment_mint == native_mint::id() && self.buyout_program == Pubkey::default()
+    }
+
+    // Get the stored status of the auction. Accounts from older program versions must be migrated first.
+    pub fn stored_status(&self) -> Result<AuctionStatus> {
+        require!(self.version == AUCTION_VERSION, AuctionError::AuctionNeedsMigration);
+        AuctionStatus::try_from(self.status)
+    }
+
+    // Get the status of the auction at a point in time: an auction still taking bids past its end has
+    // ended.
+    pub fn status(&self, clock: &Clock) -> Result<AuctionStatus> {
+        let status = self.stored_status()?;
+        match status {
+            AuctionStatus::Created | AuctionStatus::Live if self.has_ended(clock)? => Ok(AuctionStatus::Ended),
+            _ => Ok(status),
+        }
+    }
+
+    // Check whether the auction's end has passed, in its timing mode.
+    pub fn has_ended(&self, clock: &Clock) -> Result<bool> {
+        Ok(match TimingMode::try_from(self.timing_mode)? {
+            TimingMode::Timestamp => self.end_at <= clock.unix_timestamp,
+            TimingMode::Slot => self.end_at as u64 <= clock.slot,
+        })
+    }
+
+    // Get the current time in the auction's timing mode.
+    pub fn now(&self, clock: &Clock) -> Result<i64> {
+        Ok(match TimingMode::try_from(self.timing_mode)? {
+            TimingMode::Timestamp => clock.unix_timestamp,
+            TimingMode::Slot => clock.slot as i64,
+        })
+    }
+
+    // Calculate the end of an auction lasting a duration from now, in its timing mode.
+    pub fn end_after(&self, clock: &Clock, duration: u64) -> Result<i64> {
+        add_duration(self.now(clock)?, duration)
+    }
+
+    // Check whether bidding has opened, which the allowlist phase delays.
+    pub fn bidding_open(&self, clock: &Clock) -> Result<bool> {
+        Ok(self.now(clock)? >= self.allowlist_at)
+    }
+
+    // Check whether bids must pass the bid gates: always, unless the auction's public phase has opened.
+    pub fn in_allowlist_phase(&self, clock: &Clock) -> Result<bool> {
+        Ok(self.public_at == 0 || self.now(clock)? < self.public_at)
+    }
+
+    // Check whether a lot of a series is done with, letting the next lot open: finalized, settled or
+    // cancelled, ended without a bid, or no longer in the series.
+    pub fn lot_done(&self, series: Pubkey, clock: &Clock) -> Result<bool> {
+        if self.series != series {
+            return Ok(true);
+        }
+        Ok(match self.stored_status()? {
+            AuctionStatus::Created => self.has_ended(clock)?,
+            AuctionStatus::Live => false,
+            AuctionStatus::Ended | AuctionStatus::Settled | AuctionStatus::Cancelled => true,
+        })
+    }
+
+    // Check whether the auction is a Dutch auction.
+    pub fn is_dutch(&self) -> bool {
+        self.dutch_curve != DutchCurve::None as u8
+    }
+
+    // Check whether the auction is a penny auction.
+    pub fn is_penny(&self) -> bool {
+        self.penny_tick > 0
+    }
+
+    // Get the price decay of a Dutch auction, which starts from the initial price while it has no bid.
+    pub fn dutch_pricing(&self) -> DutchPricing {
+        DutchPricing {
+            curve: DutchCurve::try_from(self.dutch_curve).unwrap_or(DutchCurve::None),
+            start_price: self.price,
+            floor_price: self.dutch_floor_price,
+            start_at: self.dutch_start_at,
+            end_at: self.end_at,
+            curve_param: self.dutch_curve_param,
+        }
+    }
+
+    // Get the current price: the decayed price of a Dutch auction without a bid, otherwise the highest
+    // bid or the initial price.
+    pub fn current_price(&self, clock: &Clock) -> Result<u64> {
+        if self.is_dutch() && self.stored_status()? == AuctionStatus::Created {
+            Ok(pricing::dutch_price(&self.dutch_pricing(), self.now(clock)?))
+        } else {
+            Ok(self.price)
+        }
+    }
+
+    // Get the lowest bid accepted now: the current price of a Dutch auction, one tick more than the
+    // current price of a penny auction, otherwise the next multiple of the tick size above the current
+    // price, capped at the maximum price.
+    pub fn min_next_bid(&self, clock: &Clock) -> Result<u64> {
+        let min_next_bid = if self.is_dutch() {
+            self.current_price(clock)?
+        } else if self.is_penny() {
+            self.price.checked_add(self.penny_tick).ok_or(AuctionError::NumericalOverflow)?
+        } else {
+            let tick_size = self.tick_size.max(1);
+            (self.price / tick_size)
+                .checked_add(1)
+                .and_then(|ticks| ticks.checked_mul(tick_size))
+                .ok_or(AuctionError::NumericalOverflow)?
+        };
+        Ok(self.capped_at_max_price(min_next_bid))
+    }
+
+    // Estimate how many unique bidders the round has had as the number of bits set in the bidder bitmap.
+    // Bidders whose keys share a first byte share a bit, so the estimate never exceeds the round's unique
+    // bidders, and is exact while no two of them collide.
+    pub fn unique_bidders(&self) -> u64 {
+        self.bidder_bitmap.iter().map(|byte| byte.count_ones() as u64).sum()
+    }
+
+    // Check whether a bid amount is on the auction's tick size. Dutch and penny auctions set the price of
+    // a bid themselves, and a bid capped at the maximum price pays it whatever the tick.
+    pub fn on_tick(&self, price: u64) -> bool {
+        self.tick_size == 0
+            || self.is_dutch()
+            || self.is_penny()
+            || price % self.tick_size == 0
+            || (self.max_price > 0 && price >= self.max_price)
+    }
+
+    // Cap a bid at the maximum price, if the auction has one.
+    pub fn capped_at_max_price(&self, price: u64) -> u64 {
+        if self.max_price > 0 {
+            price.min(self.max_price)
+        } else {
+            price
+        }
+    }
+
+    // Check whether an account may manage the auction: the exhibitor, or the operator if one is set.
+    pub fn is_managed_by(&self, authority: Pubkey) -> bool {
+        authority == self.exhibitor
+            || (self.operator != Pubkey::default() && authority == self.operator)
+    }
+
+    // Get the seed that follows the escrow account in the seeds of the current round's receipts.
+    pub fn round_seed(&self) -> Vec<u8> {
+        round_seed(self.round)
+    }
+
+    // Check whether an account may relist the auction: whoever manages it, or the opted-in crank.
+    pub fn may_relist(&self, authority: Pubkey) -> bool {
+        self.is_managed_by(authority)
+            || (self.relist_crank != Pubkey::default() && authority == self.relist_crank)
+    }
+
+    // Get how the auctioned NFT is held.
+    pub fn listing_mode(&self) -> Result<ListingMode> {
+        ListingMode::try_from(self.listing_mode)
+    }
+
+    // Get what the auction sells.
+    pub fn item_type(&self) -> Result<ItemType> {
+        ItemType::try_from(self.item_type)
+    }
+
+    // Check whether the sale is still held for disputes, which only the arbiter may act on until the
+    // dispute window closes.
+    pub fn in_dispute_window(&self, clock: &Clock) -> bool {
+        self.dispute_deadline > clock.unix_timestamp
+    }
+
+    // Check whether the highest bidder's temporary FT account is held by the PDA. Until the first bid it
+    // is the exhibitor's FT receiving account, which nothing is transferred from.
+    pub fn escrows_bid(&self, ft_temp_account: &TokenAccount, pda: Pubkey) -> Result<bool> {
+        Ok(self.stored_status()? != AuctionStatus::Live || ft_temp_account.owner == pda)
+    }
+
+    // Check whether the temporary NFT account is held by the PDA. A delegate listing leaves the NFT in the
+    // exhibitor's account, which the PDA is only the delegate of.
+    pub fn escrows_nft(&self, nft_temp_account: &TokenAccount, pda: Pubkey) -> Result<bool> {
+        Ok(self.listing_mode()? == ListingMode::Delegate || nft_temp_account.owner == pda)
+    }
+
+    // Check whether settlement must wait for the hidden reserve to be revealed: it is committed, still
+    // hidden, and the reveal period after the end, counted in the auction's timing mode, is not over.
+    pub fn awaits_reserve_reveal(&self, clock: &Clock) -> Result<bool> {
+        if self.hidden_reserve_commitment == [0; 32] || self.hidden_reserve_revealed != 0 {
+            return Ok(false);
+        }
+        Ok(self.now(clock)? < self.after_end(RESERVE_REVEAL_PERIOD_SEC)?)
+    }
+
+    // Check whether the exhibitor may still accept a highest bid that falls short of a reserve.
+    pub fn in_acceptance_period(&self, clock: &Clock) -> Result<bool> {
+        Ok(self.now(clock)? < self.after_end(ACCEPTANCE_PERIOD_SEC)?)
+    }
+
+    // Check whether the price feed grace period is over, after which the auction may be unwound without a
+    // price.
+    pub fn price_feed_grace_over(&self, clock: &Clock) -> Result<bool> {
+        Ok(self.now(clock)? >= self.after_end(PRICE_FEED_GRACE_PERIOD_SEC)?)
+    }
+
+    // Get the time a period in seconds after the end, in the auction's timing mode.
+    fn after_end(&self, period_sec: i64) -> Result<i64> {
+        let period = match TimingMode::try_from(self.timing_mode)? {
+            TimingMode::Timestamp => period_sec,
+            TimingMode::Slot => period_sec * 1_000 / DEFAULT_MS_PER_SLOT as i64,
+        };
+        self.end_at.checked_add(period).ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+
+    // Check whether the highest bid meets the hidden reserve. A reserve that was never revealed is
+    // forfeited and always met.
+    pub fn meets_hidden_reserve(&self) -> bool {
+        self.hidden_reserve_revealed == 0 || self.price >= self.hidden_reserve
+    }
+
+    // Get the title of the lot.
+    pub fn title(&self) -> String {
+        from_fixed_bytes(&self.title)
+    }
+
+    // Get the URI of the lot's description.
+    pub fn uri(&self) -> String {
+        from_fixed_bytes(&self.uri)
+    }
+
+    // Set the stored status of the auction.
+    pub fn set_status(&mut self, status: AuctionStatus) {
+        self.status = status as u8;
+    }
+
+    // Get the buyer's premium on a bid.
+    pub fn buyers_premium(&self, price: u64) -> Result<u64> {
+        share_of(price, self.buyers_premium_bps)
+    }
+
+    // Get what winning with a bid costs: the bid itself plus any buyer's premium.
+    pub fn purchase_amount(&self, price: u64) -> Result<u64> {
+        price
+            .checked_add(self.buyers_premium(price)?)
+            .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+
+    // Get the amount a bid escrows: its purchase amount, or only the down payment on it under an
+    // installment plan.
+    pub fn escrowed_amount(&self, price: u64) -> Result<u64> {
+        let purchase_amount = self.purchase_amount(price)?;
+        if self.has_installments() {
+            share_of(purchase_amount, self.down_payment_bps)
+        } else {
+            Ok(purchase_amount)
+        }
+    }
+
+    // Check whether the winning bid is paid in installments.
+    pub fn has_installments(&self) -> bool {
+        self.installment_count > 0
+    }
+
+    // Get how much of the winning bid's purchase amount must be escrowed at a UNIX timestamp: the down
+    // payment, plus an equal share of the rest for each installment due by then. The installments fall
+    // due every installment interval after the end.
+    pub fn installments_due(&self, now: i64) -> Result<u64> {
+        let purchase_amount = self.purchase_amount(self.price)?;
+        if !self.has_installments() {
+            return Ok(purchase_amount);
+        }
+        let down_payment = self.escrowed_amount(self.price)?;
+        let elapsed = now.saturating_sub(self.end_at).max(0) as u64;
+        let installments = (elapsed / self.installment_interval_sec).min(self.installment_count as u64);
+        let rest = purchase_amount.checked_sub(down_payment).ok_or(AuctionError::NumericalOverflow)?;
+        let rest_due = rest as u128 * installments as u128 / self.installment_count as u128;
+        down_payment
+            .checked_add(rest_due as u64)
+            .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+
+    // Check whether the winning bid is paid in full, given the amount escrowed for it, so the auction may
+    // settle. Without an installment plan it always is.
+    pub fn installments_paid(&self, escrowed_amount: u64) -> Result<bool> {
+        Ok(!self.has_installments() || escrowed_amount >= self.purchase_amount(self.price)?)
+    }
+
+    // Get the bond a bid pays to the treasury: the flat bond, or a share of the bid.
+    pub fn bid_bond(&self, price: u64) -> Result<u64> {
+        if self.bid_bond_bps > 0 {
+            share_of(price, self.bid_bond_bps)
+        } else {
+            Ok(self.bid_bond)
+        }
+    }
+
+    // Get what a bid pays to the treasury and never gets back: a penny auction's bid fee plus the bid bond.
+    pub fn treasury_bid_fee(&self, price: u64) -> Result<u64> {
+        let penny_bid_fee = if self.is_penny() { self.penny_bid_fee } else { 0 };
+        penny_bid_fee
+            .checked_add(self.bid_bond(price)?)
+            .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+
+    // Get everything a bid costs the bidder: the amount it escrows plus what it pays to the treasury.
+    pub fn bid_cost(&self, price: u64) -> Result<u64> {
+        self.escrowed_amount(price)?
+            .checked_add(self.treasury_bid_fee(price)?)
+            .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+
+    // Get the house fee on the winning bid, given the amount escrowed for it: the buyer's premium, or the
+    // house's share of the amount if the fee is taken from the proceeds.
+    pub fn house_fee(&self, escrowed_amount: u64, fee_bps: u16) -> Result<u64> {
+        if self.buyers_premium_bps > 0 {
+            self.buyers_premium(self.price)
+        } else {
+            share_of(escrowed_amount, fee_bps)
+        }
+    }
+
+    // Split the proceeds between the payout recipients and the charity, rounding each share down so the
+    // exhibitor keeps any dust. Returns the recipients' shares in their recorded order and the charity's.
+    pub fn proceeds_shares(&self, proceeds: u64) -> Result<(Vec<u64>, u64)> {
+        let payout_shares = self.payouts[..self.payout_count as usize]
+            .iter()
+            .map(|payout| share_of(proceeds, payout.share_bps))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((payout_shares, share_of(proceeds, self.charity_bps)?))
+    }
+
+    // Record a bid as the auction's highest and advance the bid index, returning the amount owed back to
+    // the previous highest bidder, including any buyer's premium (None if there was no bid yet). The bid
+    // handlers call this before any transfer, so the escrow account is already final if a token program
+    // ever calls back into them.
+    pub fn record_bid(&mut self, bid: &HighestBid, clock: &Clock) -> Result<Option<u64>> {
+        let refund = match self.stored_status()? {
+            AuctionStatus::Live => Some(self.escrowed_amount(self.price)?),
+            _ => None,
+        };
+        self.price = bid.price;
+        self.highest_bidder = bid.bidder;
+        self.highest_bidder_ft_temp_account = bid.ft_temp_account;
+        self.highest_bidder_ft_returning_account = bid.ft_returning_account;
+        self.highest_bidder_rent_payer = bid.rent_payer;
+        self.highest_bidder_referrer_ft_pubkey = bid.referrer;
+        self.highest_bidder_affiliate = bid.affiliate;
+        // Mark the bidder in the round's bitmap: the first byte of their key picks one of its 256 bits, so
+        // repeat bids by the same bidder set the same bit.
+        let bit = bid.bidder.to_bytes()[0];
+        self.bidder_bitmap[bit as usize / 8] |= 1 << (bit % 8);
+        // The auction is live once it has a bid.
+        self.set_status(AuctionStatus::Live);
+        // A Dutch auction ends with its first bid.
+        if self.is_dutch() {
+            self.end_at = self.now(clock)?;
+        }
+        // A penny auction's bid resets its countdown, never bringing its end forward.
+        if self.is_penny() {
+            let reset_end_at = self.end_after(clock, self.penny_countdown)?;
+            self.end_at = self.end_at.max(reset_end_at);
+        }
+        // A bid reaching the maximum price ends the auction, so it can be settled right away.
+        if self.max_price > 0 && bid.price >= self.max_price {
+            self.end_at = self.now(clock)?;
+        }
+        self.bid_count = self.bid_count.checked_add(1).ok_or(AuctionError::NumericalOverflow)?;
+        Ok(refund)
+    }
+}
+
+// Define the HighestBid struct to describe a bid taking the lead of an auction.
+pub struct HighestBid {
+    // The bidder's public key.
+    pub bidder: Pubkey,
+    // The bidder's temporary FT account, which holds the bid.
+    pub ft_temp_account: Pubkey,
+    // The bidder's FT account the bid is returned to when outbid.
+    pub ft_returning_account: Pubkey,
+    // The rent payer of the temporary FT account.
+    pub rent_payer: Pubkey,
+    // The FT receiving account of the referrer who brought the bidder (default if none).
+    pub referrer: Pubkey,
+    // The affiliate the bid is tagged with (default if none).
+    pub affiliate: Pubkey,
+    // The bid amount.
+    pub price: u64,
+}
+
+// Define the AuctionStatus enum to represent the stages of an auction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AuctionStatus {
+    // Exhibited and taking bids, but without any yet.
+    Created,
+    // Taking bids, with at least one.
+    Live,
+    // Past its end time and waiting to be settled or cancelled. Derived from the time until finalize
+    // locks in the result and stores it.
+    Ended,
+    // Settled, with or without a sale. A finalized auction is settled once both sides have claimed.
+    Settled,
+    // Cancelled by the exhibitor before any bid.
+    Cancelled,
+}
+
+// Implement the conversion from a stored status byte.
+impl TryFrom<u8> for AuctionStatus {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(status: u8) -> Result<Self> {
+        match status {
+            0 => Ok(AuctionStatus::Created),
+            1 => Ok(AuctionStatus::Live),
+            2 => Ok(AuctionStatus::Ended),
+            3 => Ok(AuctionStatus::Settled),
+            4 => Ok(AuctionStatus::Cancelled),
+            _ => err!(AuctionError::InvalidAuctionStatus),
+        }
+    }
+}
+
+// Define the AuctionState struct returned by get_auction_state.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuctionState {
+    // The status of the auction at the current time.
+    pub status: AuctionStatus,
+    // The lowest bid that would be accepted now (zero if the auction is not taking bids).
+    pub min_next_bid: u64,
+    // The seconds or slots, depending on the timing mode, until the auction ends (zero if it is not
+    // taking bids).
+    pub time_remaining: u64,
+    // The current price of the auction: the highest bid, the initial price, or the decayed price of a
+    // Dutch auction without a bid.
+    pub current_price: u64,
+    // The number of bids placed on the auction.
+    pub bid_count: u64,
+    // The estimated number of unique bidders in the current round (see Auction::unique_bidders).
+    pub unique_bidders: u64,
+}
+
+// Define the TimingMode enum to represent how the end of an auction is measured.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TimingMode {
+    // The duration is in seconds and the end is a UNIX timestamp.
+    Timestamp,
+    // The duration is in slots and the end is a slot height, for auctions that should not depend on
+    // clock drift.
+    Slot,
+}
+
+// Implement the conversion from a stored timing mode byte.
+impl TryFrom<u8> for TimingMode {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(timing_mode: u8) -> Result<Self> {
+        match timing_mode {
+            0 => Ok(TimingMode::Timestamp),
+            1 => Ok(TimingMode::Slot),
+            _ => err!(AuctionError::InvalidTimingMode),
+        }
+    }
+}
+
+// Define the ListingMode enum to represent how the auctioned NFT is held while listed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ListingMode {
+    // The NFT moves into a temporary account owned by the PDA until the auction is over.
+    Escrow,
+    // The NFT stays in the exhibitor's account, which the PDA is the delegate of, and only moves at
+    // settlement. Moving it while listed makes the auction unsellable (see settle_unmet_reserve).
+    Delegate,
+}
+
+// Implement the conversion from a stored listing mode byte.
+impl TryFrom<u8> for ListingMode {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(listing_mode: u8) -> Result<Self> {
+        match listing_mode {
+            0 => Ok(ListingMode::Escrow),
+            1 => Ok(ListingMode::Delegate),
+            _ => err!(AuctionError::InvalidListingMode),
+        }
+    }
+}
+
+// Define the ItemType enum to represent what an auction sells.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ItemType {
+    // An NFT, escrowed in a token account and delivered by a token transfer.
+    Nft,
+    // A Solana Name Service domain, escrowed by making the PDA its owner and delivered by claim_domain.
+    Domain,
+    // A stake account, escrowed by making the PDA its staker and withdrawer and delivered by claim_stake.
+    Stake,
+}
+
+// Implement the conversion from a stored item type byte.
+impl TryFrom<u8> for ItemType {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(item_type: u8) -> Result<Self> {
+        match item_type {
+            0 => Ok(ItemType::Nft),
+            1 => Ok(ItemType::Domain),
+            2 => Ok(ItemType::Stake),
+            _ => err!(AuctionError::InvalidItemType),
+        }
+    }
+}
+
+// Define the AuctionHouse struct to represent the house-wide configuration.
+#[account]
+pub struct AuctionHouse {
+    // The authority allowed to update the configuration.
+    pub authority: Pubkey,
+    // The treasury wallet, whose FT accounts receive the house fee.
+    pub treasury: Pubkey,
+    // The house fee in basis points, taken from the proceeds or charged on top of the winning bid.
+    pub fee_bps: u16,
+    // The share of the house fee paid to the referrer of the winning bid in basis points.
+    pub referral_bps: u16,
+    // The bump seed of the auction house PDA.
+    pub bump: u8,
+    // The shortest auction duration allowed in seconds (zero for no minimum).
+    pub min_duration_sec: u64,
+    // The longest auction duration allowed in seconds (zero for no maximum).
+    pub max_duration_sec: u64,
+    // Whether bids are rejected in transactions that call a known flash loan program.
+    pub flash_loan_guard: bool,
+    // Whether the winning bidder pays the house fee on top of the bid as a buyer's premium, rather than
+    // the exhibitor out of the proceeds.
+    pub buyers_premium: bool,
+    // The enabled features, as a bitset of FEATURE_ flags.
+    pub features: u32,
+    // The authority nominated to take over the configuration once it accepts (default if none).
+    pub pending_authority: Pubkey,
+    // Whether new listings, bids and edition purchases are stopped.
+    pub paused: bool,
+    // The key allowed to pause the house besides the authority (default if none).
+    pub pauser: Pubkey,
+    // The key allowed to update the fees besides the authority (default if none).
+    pub fee_manager: Pubkey,
+    // The key allowed to manage partners and the blacklist besides the authority (default if none).
+    pub whitelist_manager: Pubkey,
+    // The key allowed to cancel auctions in an emergency besides the authority (default if none).
+    pub emergency_canceller: Pubkey,
+    // Whether only sellers on the house's allowlist may list.
+    pub approved_sellers_only: bool,
+    // The flat fee in lamports every exhibited auction pays to the treasury, unless its exhibitor is an
+    // approved seller (zero for none).
+    pub listing_fee_lamports: u64,
+    // The collection every listed NFT must be a verified member of (default if any NFT may be listed).
+    pub collection: Pubkey,
+    // The bump seed of the escrow PDA, stored so bids need not search for it.
+    pub escrow_bump: u8,
+    // The bump seed of the event authority PDA, stored so bids need not search for it.
+    pub event_authority_bump: u8,
+    // The share of the house fee earned by the affiliate of the winning bid in basis points.
+    pub affiliate_bps: u16,
+}
+
+// Implement the AuctionHouse struct.
+impl AuctionHouse {
+    // The size of an AuctionHouse account, including its 8-byte discriminator.
+    pub const LEN: usize = Self::FEATURES_END + 32 + 1 + 32 + 32 + 32 + 32 + 1 + 8 + 32 + 1 + 1 + 2;
+    // The size of an AuctionHouse account up to and including its feature flags.
+    pub const FEATURES_END: usize = 8 + 32 + 32 + 2 + 2 + 1 + 8 + 8 + 1 + 1 + 4;
+
+    // Store the bump seeds of the program's fixed PDAs, which only depend on the program ID.
+    pub fn store_bumps(&mut self) {
+        self.escrow_bump = Pubkey::find_program_address(&[ESCROW_PDA_SEED], &crate::ID).1;
+        self.event_authority_bump =
+            Pubkey::find_program_address(&[events::EVENT_AUTHORITY_SEED], &crate::ID).1;
+    }
+
+    // Check whether a key may act in a role: the authority always may, as may the role's holder.
+    pub fn has_role(&self, role: HouseRole, key: &Pubkey) -> bool {
+        let holder = match role {
+            HouseRole::Pauser => self.pauser,
+            HouseRole::FeeManager => self.fee_manager,
+            HouseRole::WhitelistManager => self.whitelist_manager,
+            HouseRole::EmergencyCanceller => self.emergency_canceller,
+        };
+        *key == self.authority || (holder != Pubkey::default() && *key == holder)
+    }
+
+    // Check whether a feature is enabled.
+    pub fn enabled(&self, feature: u32) -> bool {
+        self.features & feature == feature
+    }
+
+    // Ensure a feature is enabled.
+    pub fn require_enabled(&self, feature: u32) -> Result<()> {
+        require!(self.enabled(feature), AuctionError::FeatureDisabled);
+        Ok(())
+    }
+
+    // Get the buyer's premium a newly exhibited auction charges in basis points (zero if the house fee is
+    // taken from the proceeds).
+    pub fn buyers_premium_bps(&self) -> u16 {
+        if self.buyers_premium { self.fee_bps } else { 0 }
+    }
+}
+
+// Define the HouseRole enum to represent the low-privilege roles the house authority can give out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HouseRole {
+    // Pauses and resumes the house.
+    Pauser,
+    // Updates the house fee, the referral share and the buyer's premium mode.
+    FeeManager,
+    // Adds and removes partner frontends and blacklisted mints.
+    WhitelistManager,
+    // Cancels auctions in an emergency with admin_cancel.
+    EmergencyCanceller,
+}
+
+// Define the Registry struct to number auctions.
+#[account]
+pub struct Registry {
+    // The number of auctions exhibited so far, which is also the ID of the latest one.
+    pub auction_count: u64,
+    // The bump seed of the registry PDA.
+    pub bump: u8,
+}
+
+// Implement the Registry struct.
+impl Registry {
+    // The size of a Registry account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 8 + 1;
+
+    // Get the ID the next exhibited auction will be assigned.
+    pub fn next_auction_id(&self) -> Result<u64> {
+        self.auction_count.checked_add(1).ok_or_else(|| error!(AuctionError::NumericalOverflow))
+    }
+}
+
+// Define the BidReceipt struct to record a bid on-chain after it has been outbid and its temporary
+// account closed, one per auction, bidder and bid index.
+#[account]
+pub struct BidReceipt {
+    // The escrow account of the auction bid on.
+    pub auction: Pubkey,
+    // The bidder's public key.
+    pub bidder: Pubkey,
+    // The index of the bid in the auction, starting from 0.
+    pub bid_index: u64,
+    // The bid amount.
+    pub price: u64,
+    // The FT receiving account of the referrer who brought the bidder (default if none).
+    pub referrer: Pubkey,
+    // The time the bid was placed, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the bid receipt PDA.
+    pub bump: u8,
+    // The bidder's reference for the bid, UTF-8 padded with zeros (empty if none).
+    pub memo: [u8; 32],
+}
+
+// Implement the BidReceipt struct.
+impl BidReceipt {
+    // The size of a BidReceipt account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 32 + 8 + 1 + MAX_MEMO_LEN;
+
+    // Get the bidder's reference for the bid.
+    pub fn memo(&self) -> String {
+        from_fixed_bytes(&self.memo)
+    }
+}
+
+// Define the ListingReceipt struct to record a listing permanently. It is created by exhibit and, once
+// the auction is over and its escrow account closed, tombstoned with the final status and result.
+#[account]
+pub struct ListingReceipt {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The auction's ID.
+    pub auction_id: u64,
+    // The exhibitor's public key.
+    pub exhibitor: Pubkey,
+    // The mint of the auctioned NFT.
+    pub nft_mint: Pubkey,
+    // The mint the auction was paid in.
+    pub payment_mint: Pubkey,
+    // The initial price of the auction.
+    pub initial_price: u64,
+    // The time the auction was exhibited, in UNIX timestamp.
+    pub created_at: i64,
+    // The status of the auction: Created while it runs, then Settled or Cancelled (see AuctionStatus).
+    pub status: u8,
+    // The winning bidder's public key (default if the auction ended without a sale).
+    pub winner: Pubkey,
+    // The winning bid (zero if the auction ended without a sale).
+    pub final_price: u64,
+    // The time the auction was settled or cancelled, in UNIX timestamp (zero while it runs).
+    pub closed_at: i64,
+    // The bump seed of the listing receipt PDA.
+    pub bump: u8,
+}
+
+// Implement the ListingReceipt struct.
+impl ListingReceipt {
+    // The size of a ListingReceipt account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 32 + 32 + 8 + 8 + 1 + 32 + 8 + 8 + 1;
+
+    // Set the recorded status of the auction.
+    pub fn set_status(&mut self, status: AuctionStatus) {
+        self.status = status as u8;
+    }
+}
+
+// Define the PurchaseReceipt struct to record a sale permanently, one per auction, so sales history and
+// the stats derived from it do not depend on an indexer.
+#[account]
+pub struct PurchaseReceipt {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The exhibitor's public key.
+    pub seller: Pubkey,
+    // The winning bidder's public key.
+    pub buyer: Pubkey,
+    // The mint of the NFT sold.
+    pub nft_mint: Pubkey,
+    // The mint the sale was paid in.
+    pub payment_mint: Pubkey,
+    // The winning bid.
+    pub price: u64,
+    // The house fee the treasury received from the winning bid, net of the referrer's, the partner's and
+    // the affiliate's shares.
+    pub house_fee: u64,
+    // The time of the sale, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the purchase receipt PDA.
+    pub bump: u8,
+    // The creators owed a share of the royalty, in metadata order.
+    pub royalty_creators: [Pubkey; 5],
+    // What each creator is still owed out of the house's royalty vault, until distribute_royalties pays it.
+    pub royalty_owed: [u64; 5],
+    // The affiliate the winning bid was tagged with (default if none).
+    pub affiliate: Pubkey,
+    // What the affiliate is still owed out of the house's affiliate vault, until claim_affiliate_earnings
+    // pays it.
+    pub affiliate_owed: u64,
+}
+
+// Implement the PurchaseReceipt struct.
+impl PurchaseReceipt {
+    // The size of a PurchaseReceipt account, including its 8-byte discriminator.
+    pub const LEN: usize =
+        8 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 32 * MAX_CREATORS + 8 * MAX_CREATORS + 32 + 8;
+}
+
+// Define the BlacklistEntry struct to ban a mint from being exhibited or bid in, one per mint.
+#[account]
+pub struct BlacklistEntry {
+    // The banned mint.
+    pub mint: Pubkey,
+    // The time the mint was banned, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the blacklist entry PDA.
+    pub bump: u8,
+}
+
+// Implement the BlacklistEntry struct.
+impl BlacklistEntry {
+    // The size of a BlacklistEntry account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// Define the SellerAllowlist struct to approve a seller for a house that only admits approved sellers,
+// one per seller.
+#[account]
+pub struct SellerAllowlist {
+    // The approved seller's wallet.
+    pub seller: Pubkey,
+    // The time the seller was approved, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the seller allowlist PDA.
+    pub bump: u8,
+}
+
+// Implement the SellerAllowlist struct.
+impl SellerAllowlist {
+    // The size of a SellerAllowlist account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// Define the PartnerEntry struct to whitelist a partner frontend, one per partner.
+#[account]
+pub struct PartnerEntry {
+    // The partner frontend's wallet, which must own the FT fee accounts it lists auctions with.
+    pub partner: Pubkey,
+    // The partner's share of the house fee of the auctions it lists, in basis points.
+    pub fee_share_bps: u16,
+    // The time the partner was added, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the partner entry PDA.
+    pub bump: u8,
+}
+
+// Implement the PartnerEntry struct.
+impl PartnerEntry {
+    // The size of a PartnerEntry account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 2 + 8 + 1;
+}
+
+// Define the RoyaltyOverride struct to set or cap the royalty of a verified collection's NFTs, one per
+// collection.
+#[account]
+pub struct RoyaltyOverride {
+    // The mint of the collection NFT.
+    pub collection: Pubkey,
+    // The royalty paid to the creators, or its cap, in basis points.
+    pub royalty_bps: u16,
+    // Whether the royalty only caps the seller fee of the NFT's metadata.
+    pub cap: bool,
+    // The time the override was added, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the royalty override PDA.
+    pub bump: u8,
+}
+
+// Implement the RoyaltyOverride struct.
+impl RoyaltyOverride {
+    // The size of a RoyaltyOverride account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 2 + 1 + 8 + 1;
+
+    // Get the royalty owed on an NFT whose metadata sets a seller fee.
+    pub fn apply(&self, seller_fee_bps: u16) -> u16 {
+        if self.cap {
+            seller_fee_bps.min(self.royalty_bps)
+        } else {
+            self.royalty_bps
+        }
+    }
+}
+
+// Define the CollectionStats struct to record the sales of a verified collection's NFTs in a payment mint,
+// one per collection and payment mint. Prices are winning bids, before fees and royalties.
+#[account]
+pub struct CollectionStats {
+    // The mint of the collection NFT.
+    pub collection: Pubkey,
+    // The payment mint of the recorded sales.
+    pub payment_mint: Pubkey,
+    // The sum of the recorded sale prices, saturating rather than failing settlement.
+    pub total_volume: u64,
+    // The number of recorded sales.
+    pub sale_count: u64,
+    // The price of the last recorded sale.
+    pub last_sale_price: u64,
+    // The time of the last recorded sale, in UNIX timestamp (0 before the first sale).
+    pub last_sale_at: i64,
+    // The lowest recorded sale price (0 before the first sale).
+    pub floor_price: u64,
+    // The time the stats were created, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the collection stats PDA.
+    pub bump: u8,
+}
+
+// Implement the CollectionStats struct.
+impl CollectionStats {
+    // The size of a CollectionStats account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Record a sale at a price.
+    pub fn record_sale(&mut self, price: u64, now: i64) {
+        self.floor_price = if self.sale_count == 0 { price } else { self.floor_price.min(price) };
+        self.total_volume = self.total_volume.saturating_add(price);
+        self.sale_count = self.sale_count.saturating_add(1);
+        self.last_sale_price = price;
+        self.last_sale_at = now;
+    }
+}
+
+// Define the HouseStats struct to record the house's sales and fee revenue in a payment mint, one per
+// payment mint.
+#[account]
+pub struct HouseStats {
+    // The payment mint of the recorded sales.
+    pub payment_mint: Pubkey,
+    // The sum of the winning bids, saturating rather than failing settlement.
+    pub total_volume: u64,
+    // The number of auctions sold.
+    pub total_auctions: u64,
+    // The sum of the house fees the treasury received, net of the referrers', the partners' and the
+    // affiliates' shares, saturating like the volume.
+    pub total_fees: u64,
+    // The time the stats were created, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the house stats PDA.
+    pub bump: u8,
+}
+
+// Implement the HouseStats struct.
+impl HouseStats {
+    // The size of a HouseStats account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    // Record a sale at a price, and the house fee the treasury received on it.
+    pub fn record_sale(&mut self, price: u64, house_fee: u64) {
+        self.total_volume = self.total_volume.saturating_add(price);
+        self.total_auctions = self.total_auctions.saturating_add(1);
+        self.total_fees = self.total_fees.saturating_add(house_fee);
+    }
 }
 
-// Define the Close struct with associated accounts.
-#[derive(Accounts)]
-pub struct Close<'info> {
-    // The winning bidder's account, which must be a signer.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    #[account(signer)]
-    pub winning_bidder: AccountInfo<'info>,
-    // The exhibitor's account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    #[account(mut)]
-    pub exhibitor: AccountInfo<'info>,
-    // The exhibitor's temporary NFT account.
-    #[account(mut)]
-    pub exhibitor_nft_temp_account: Account<'info, TokenAccount>,
-    // The exhibitor's FT receiving account.
-    #[account(mut)]
-    pub exhibitor_ft_receiving_account: Account<'info, TokenAccount>,
-    // The highest bidder's temporary FT account.
-    #[account(mut)]
-    pub highest_bidder_ft_temp_account: Account<'info, TokenAccount>,
-    // The highest bidder's NFT receiving account.
-    #[account(mut)]
-    pub highest_bidder_nft_receiving_account: Account<'info, TokenAccount>,
-    // The escrow account with various constraints.
-    #[account(
-        mut,
-        constraint = escrow_account.exhibitor_pubkey == exhibitor.key(),
-        constraint = escrow_account.exhibiting_nft_temp_pubkey == exhibitor_nft_temp_account.key(),
-        constraint = escrow_account.exhibitor_ft_receiving_pubkey == exhibitor_ft_receiving_account.key(),
-        constraint = escrow_account.highest_bidder_pubkey == winning_bidder.key(),
-        constraint = escrow_account.highest_bidder_ft_temp_pubkey == highest_bidder_ft_temp_account.key(),
-        constraint = escrow_account.end_at <= clock.unix_timestamp,
-        close = exhibitor
-    )]
-    pub escrow_account: Box<Account<'info, Auction>>,
-    // The system clock account for getting the current UNIX timestamp.
-    pub clock: Sysvar<'info, Clock>,
-    // The PDA account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub pda: AccountInfo<'info>,
-    // The SPL token program account.
-    /// CHECK: This is not dangerous, does not need check (ask rich or dean)
-    pub token_program: AccountInfo<'info>,
+// Define the VestingSchedule struct to represent how the exhibitor's proceeds of an auction are released
+// from their vault, one per auction.
+#[account]
+pub struct VestingSchedule {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The exhibitor's FT receiving account, which receives the vested proceeds.
+    pub beneficiary_ft_pubkey: Pubkey,
+    // The vault PDA holding the proceeds while they vest.
+    pub vault: Pubkey,
+    // The payer of the schedule and vault, which gets their rent back once they are closed.
+    pub rent_payer: Pubkey,
+    // The time after settlement before anything vests, in seconds.
+    pub cliff_sec: u64,
+    // The time after settlement until everything has vested, in seconds.
+    pub duration_sec: u64,
+    // The time of settlement, when vesting starts, in UNIX timestamp (zero until the vault is funded).
+    pub start_at: i64,
+    // The proceeds deposited in the vault.
+    pub total: u64,
+    // The proceeds released so far.
+    pub claimed: u64,
+    // The bump seed of the vesting schedule PDA.
+    pub bump: u8,
+}
+
+// Implement the VestingSchedule struct.
+impl VestingSchedule {
+    // The size of a VestingSchedule account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Get the proceeds vested at a point in time: nothing before the cliff, then linearly from the start
+    // until everything has vested at the end of the duration.
+    pub fn vested(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.start_at).max(0) as u64;
+        if elapsed < self.cliff_sec {
+            Ok(0)
+        } else if elapsed >= self.duration_sec {
+            Ok(self.total)
+        } else {
+            let vested = self.total as u128 * elapsed as u128 / self.duration_sec as u128;
+            u64::try_from(vested).map_err(|_| error!(AuctionError::NumericalOverflow))
+        }
+    }
+}
+
+// Define the Rental struct to record the usage rights to an auctioned NFT won as a rental, one per
+// auction. The NFT stays in escrow while it is rented, and the renter holds the rights until the rental
+// ends.
+#[account]
+pub struct Rental {
+    // The escrow account of the auction.
+    pub auction: Pubkey,
+    // The owner of the NFT, the auction's exhibitor, who gets it back when the rental ends.
+    pub owner: Pubkey,
+    // The mint of the rented NFT.
+    pub nft_mint: Pubkey,
+    // The exhibitor's temporary NFT account holding the NFT.
+    pub nft_account: Pubkey,
+    // The winning bidder renting the NFT (default until the auction is settled).
+    pub renter: Pubkey,
+    // The payer of the rental, which gets its rent back once it is closed.
+    pub rent_payer: Pubkey,
+    // The account getting the temporary NFT account's rent back when the rental ends (default until the
+    // auction is settled).
+    pub nft_rent_destination: Pubkey,
+    // How long the rental lasts from settlement, in seconds.
+    pub duration_sec: u64,
+    // The time the rental ends, in UNIX timestamp (zero until the auction is settled).
+    pub ends_at: i64,
+    // The bump seed of the rental PDA.
+    pub bump: u8,
+}
+
+// Implement the Rental struct.
+impl Rental {
+    // The size of a Rental account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 1;
+
+    // Check whether an account holds the usage rights at a point in time.
+    pub fn is_rented_by(&self, renter: Pubkey, now: i64) -> bool {
+        self.renter != Pubkey::default() && self.renter == renter && now < self.ends_at
+    }
+}
+
+// Define the EditionSale struct to represent an open edition sale, one per edition mint.
+#[account]
+pub struct EditionSale {
+    // The seller's public key.
+    pub seller: Pubkey,
+    // The mint of the editions, whose mint authority the escrow PDA holds during the sale.
+    pub edition_mint: Pubkey,
+    // The mint the editions are paid in.
+    pub payment_mint: Pubkey,
+    // The seller's FT receiving account public key.
+    pub seller_ft_pubkey: Pubkey,
+    // How the price grows with the editions sold (see BondingCurve).
+    pub curve: u8,
+    // The price of the first edition.
+    pub base_price: u64,
+    // The price increase factor of the curve.
+    pub curve_param: u64,
+    // The number of editions for sale (zero for no limit).
+    pub max_supply: u64,
+    // The number of editions sold, which is also the index of the next edition receipt.
+    pub sold: u64,
+    // The time the sale was created, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the sale PDA.
+    pub bump: u8,
+}
+
+// Implement the EditionSale struct.
+impl EditionSale {
+    // The size of an EditionSale account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 1 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Get the price of the next edition.
+    pub fn current_price(&self) -> Result<u64> {
+        pricing::edition_price(BondingCurve::try_from(self.curve)?, self.base_price, self.curve_param, self.sold)
+    }
+}
+
+// Define the EditionReceipt struct to record an edition purchase permanently, one per edition sold.
+#[account]
+pub struct EditionReceipt {
+    // The sale account.
+    pub sale: Pubkey,
+    // The buyer's public key.
+    pub buyer: Pubkey,
+    // The index of the edition in the sale.
+    pub index: u64,
+    // The price paid.
+    pub price: u64,
+    // The house fee taken from the price.
+    pub house_fee: u64,
+    // The time of the purchase, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the edition receipt PDA.
+    pub bump: u8,
+}
+
+// Implement the EditionReceipt struct.
+impl EditionReceipt {
+    // The size of an EditionReceipt account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+}
+
+// Define the AuctionSeries struct to represent a series of auctions opened for bidding one after the
+// other, one per authority and series ID.
+#[account]
+pub struct AuctionSeries {
+    // The authority of the series, which adds its lots.
+    pub authority: Pubkey,
+    // The ID of the series, unique among its authority's series.
+    pub series_id: u64,
+    // When the first lot opens, in UNIX timestamp.
+    pub start_at: i64,
+    // The duration of every lot, in the lot's timing mode.
+    pub lot_duration: u64,
+    // The escrow accounts of the lots, in the order they open (at most MAX_SERIES_LOTS).
+    pub lots: Vec<Pubkey>,
+    // The index of the next lot to open, equal to the number of lots once every lot has opened.
+    pub next_lot: u16,
+    // The time the series was created, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the series PDA.
+    pub bump: u8,
+}
+
+// Implement the AuctionSeries struct.
+impl AuctionSeries {
+    // The size of an AuctionSeries account with room for MAX_SERIES_LOTS lots, including its 8-byte
+    // discriminator.
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + (4 + 32 * MAX_SERIES_LOTS) + 2 + 8 + 1;
+}
+
+// Define the BidPool struct to represent a pool that contributors fund to bid on an auction together, one
+// per auction and operator.
+#[account]
+pub struct BidPool {
+    // The escrow account of the auction the pool bids on.
+    pub auction: Pubkey,
+    // The operator, which alone places the pool's bids.
+    pub operator: Pubkey,
+    // The mint the pool is funded in, the auction's payment mint.
+    pub payment_mint: Pubkey,
+    // The vault PDA holding the pool's funds.
+    pub vault: Pubkey,
+    // The total amount contributed to the pool.
+    pub total_contributed: u64,
+    // The number of contributions, which is also the index of the next contribution receipt.
+    pub contribution_count: u64,
+    // Whether the auction is over and the funds left have been locked in for refunds.
+    pub settled: bool,
+    // The funds left in the vault when the pool was settled, refunded pro rata to the contributions.
+    pub settled_balance: u64,
+    // The mint of the auctioned NFT.
+    pub item_mint: Pubkey,
+    // The pool's NFT account holding the NFT it won (default until the pool is fractionalized).
+    pub item_account: Pubkey,
+    // The fraction mint PDA of the pool (default until the pool is fractionalized).
+    pub fraction_mint: Pubkey,
+    // The time the pool was created, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the pool PDA.
+    pub bump: u8,
+}
+
+// Implement the BidPool struct.
+impl BidPool {
+    // The size of a BidPool account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 8 + 32 + 32 + 32 + 8 + 1;
+
+    // Check whether fractions held out of a fraction mint's supply redeem the NFT: every fraction must have
+    // been claimed, and be held.
+    pub fn redeems_item(&self, supply: u64, held: u64) -> bool {
+        supply == self.total_contributed && held == supply
+    }
+}
+
+// Define the PoolContribution struct to record a contribution to a bid pool, the contributor's pro-rata
+// claim on what the pool holds once its auction is over, one per contribution.
+#[account]
+pub struct PoolContribution {
+    // The pool contributed to.
+    pub bid_pool: Pubkey,
+    // The contributor's public key.
+    pub contributor: Pubkey,
+    // The index of the contribution in the pool.
+    pub index: u64,
+    // The amount contributed.
+    pub amount: u64,
+    // Whether the contribution's share of the funds left has been refunded.
+    pub refunded: bool,
+    // The amount refunded.
+    pub refund: u64,
+    // Whether the contribution's fractions of the NFT have been claimed.
+    pub fractions_claimed: bool,
+    // The time of the contribution, in UNIX timestamp.
+    pub created_at: i64,
+    // The bump seed of the contribution PDA.
+    pub bump: u8,
+}
+
+// Implement the PoolContribution struct.
+impl PoolContribution {
+    // The size of a PoolContribution account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 8 + 1;
+}
+
+// Define the BidSession struct to record a session key a wallet authorized to bid for it with a spending
+// cap and an expiry, one per wallet and session key.
+#[account]
+pub struct BidSession {
+    // The wallet that authorized the session, which the session's bids are placed for.
+    pub wallet: Pubkey,
+    // The key allowed to bid for the wallet.
+    pub session_key: Pubkey,
+    // The wallet's FT account the bids are paid from, which approved the session PDA as its delegate.
+    pub ft_account: Pubkey,
+    // The total the session's bids may cost.
+    pub spending_cap: u64,
+    // The total the session's bids have cost so far.
+    pub spent: u64,
+    // When the session key stops being accepted, in UNIX timestamp.
+    pub expires_at: i64,
+    // The bump seed of the session PDA.
+    pub bump: u8,
+}
+
+// Implement the BidSession struct.
+impl BidSession {
+    // The size of a BidSession account, including its 8-byte discriminator.
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    // Record a bid's cost against the spending cap, which it must fit in. Refunds of outbid bids are not
+    // added back, so the cap bounds everything the session ever commits.
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        let spent = self.spent.checked_add(amount).ok_or(AuctionError::NumericalOverflow)?;
+        require!(spent <= self.spending_cap, AuctionError::SessionCapExceeded);
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+// Define the Payout struct to describe a recipient's share of the auction proceeds.
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct Payout {
+    // The recipient's FT receiving account public key.
+    pub recipient_ft_pubkey: Pubkey,
+    // The recipient's share of the proceeds in basis points.
+    pub share_bps: u16,
+}
+
+// Place a bid for bid, bid_with_sol and bid_with_session, which differ only in how the bid is paid and who
+// holds the bidder's temporary FT account. The remaining accounts are those of bid.
+fn place_bid<'info>(
+    mut placement: BidPlacement<'_, 'info>,
+    funding: BidFunding<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+) -> Result<()> {
+    // Create the seeds for the signer from the escrow PDA's stored bump, since searching for it is costly.
+    let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[placement.auction_house.escrow_bump]]];
+    // Load the zero-copy escrow account for updating.
+    let mut escrow_account = placement.escrow_account.load_mut()?;
+
+    // Ensure the memo fits in the bid receipt.
+    require!(memo.len() <= MAX_MEMO_LEN, AuctionError::MemoTooLong);
+    let mut remaining_accounts = remaining_accounts.iter();
+    let session_key = placement.session.as_ref().map(|session| session.session_key);
+    // Ensure the referrer's FT receiving account, passed as the first remaining account, holds the
+    // payment mint so close can always pay it, and is not the bidder's own, which would rebate them part
+    // of the house fee.
+    if let Some(referrer) = referrer {
+        let referrer_account = remaining_accounts.next().ok_or(AuctionError::ReferrerMissing)?;
+        require_keys_eq!(referrer_account.key(), referrer, AuctionError::ReferrerMismatch);
+        check_payment_account(referrer_account, &escrow_account.payment_mint)?;
+        let referrer_owner = Account::<TokenAccount>::try_from(referrer_account)?.owner;
+        check_referrer(&referrer_owner, placement.bidder.key, session_key)?;
+    }
+    // Ensure the bidder is not their own affiliate, which would rebate them part of the house fee.
+    check_affiliate(affiliate, placement.bidder.key, session_key)?;
+    // Ensure bidding has opened.
+    require!(escrow_account.bidding_open(placement.clock)?, AuctionError::BiddingNotOpen);
+    // During the allowlist phase, ensure the bidder passes the auction's bid gate, with the gate
+    // accounts following the referrer's. The public phase takes no gate accounts.
+    if escrow_account.in_allowlist_phase(placement.clock)? {
+        check_bid_gate(&escrow_account, placement.bidder.key, &mut remaining_accounts)?;
+    }
+    // Ensure the bid is not funded by a flash loan when the house guards against them.
+    if placement.auction_house.flash_loan_guard {
+        flash_loans::ensure_no_flash_loan(placement.instructions)?;
+    }
+    // A Dutch auction sells at its current price and a penny auction at one tick above it, which the
+    // bid only caps, and no bid pays more than the maximum price.
+    let dutch = escrow_account.is_dutch();
+    let penny = escrow_account.is_penny();
+    let price = if dutch || penny {
+        escrow_account.min_next_bid(placement.clock)?
+    } else {
+        escrow_account.capped_at_max_price(price)
+    };
+    // Ensure the bid's cost fits what is left of the spending cap of the session bidding, if any.
+    if let Some(session) = placement.session.as_mut() {
+        session.spend(escrow_account.bid_cost(price)?)?;
+    }
+
+    // Record the bid as the highest in the escrow account and its receipt before moving any tokens,
+    // keeping the bid it displaces for the Outbid event.
+    let previous_bidder = escrow_account.highest_bidder;
+    let previous_price = escrow_account.price;
+    let bid_index = escrow_account.bid_count;
+    let refund = escrow_account.record_bid(
+        &HighestBid {
+            bidder: placement.bidder.key(),
+            ft_temp_account: placement.bidder_ft_temp_account.key(),
+            ft_returning_account: placement.bidder_ft_returning_account,
+            rent_payer: placement.payer,
+            referrer: referrer.unwrap_or_default(),
+            affiliate: affiliate.unwrap_or_default(),
+            price,
+        },
+        placement.clock,
+    )?;
+    let bid_receipt = &mut placement.bid_receipt;
+    bid_receipt.auction = placement.escrow_account.key();
+    bid_receipt.bidder = placement.bidder.key();
+    bid_receipt.bid_index = bid_index;
+    bid_receipt.price = price;
+    bid_receipt.referrer = referrer.unwrap_or_default();
+    bid_receipt.created_at = placement.clock.unix_timestamp;
+    bid_receipt.bump = placement.bid_receipt_bump;
+    bid_receipt.memo = to_fixed_bytes(&memo)?;
+
+    // Check if the auction had a highest bidder to refund.
+    if let Some(refund) = refund {
+        // Ensure the refund can leave escrow, which the payment mint's freeze authority may prevent.
+        require!(!placement.highest_bidder_ft_temp_account.is_frozen(), AuctionError::EscrowFrozen);
+        if escrow_account.payment_mint != native_mint::id()
+            && placement.highest_bidder_ft_returning_account.is_frozen()
+        {
+            // A frozen returning account cannot take the refund, so hand the temporary FT account holding
+            // it over to the previous highest bidder instead.
+            hand_back_bid(
+                placement.highest_bidder_ft_temp_account.to_account_info(),
+                previous_bidder,
+                placement.pda.clone(),
+                placement.token_program.clone(),
+                signers_seeds,
+            )?;
+        } else {
+            // Wrapped SOL bids are refunded as lamports by closing the temporary account, which unwraps
+            // it.
+            if escrow_account.payment_mint != native_mint::id() {
+                // Transfer the previous highest bid amount back to the previous highest bidder.
+                token::transfer(
+                    placement
+                        .to_transfer_to_previous_bidder_context()
+                        .with_signer(signers_seeds),
+                    refund
+                )?;
+            }
+
+            // Close the previous highest bidder's temporary FT account, returning its rent to its payer.
+            // A wrapped SOL account holds the refund too, so it goes to the previous highest bidder
+            // instead.
+            let rent_destination = if escrow_account.payment_mint == native_mint::id() {
+                placement.highest_bidder.clone()
+            } else {
+                placement.highest_bidder_rent_payer.clone()
+            };
+            token::close_account(
+                placement
+                    .to_close_context(rent_destination)
+                    .with_signer(signers_seeds)
+            )?;
+        }
+    }
+
+    // Set the authority of the bidder's temporary FT account to the PDA.
+    token::set_authority(
+        placement.to_set_authority_context(),
+        AuthorityType::AccountOwner,
+        Some(placement.pda.key())
+    )?;
+    // Pay the bid amount and any buyer's premium into the PDA-controlled escrow account.
+    placement.pay(
+        &funding,
+        &placement.bidder_ft_temp_account.to_account_info(),
+        escrow_account.escrowed_amount(price)?,
+    )?;
+    // A penny auction's bid also pays its fee, and any bid its bond, to the treasury, neither of which is
+    // refunded when outbid.
+    let bond = escrow_account.bid_bond(price)?;
+    if penny || bond > 0 {
+        let treasury_ft_account =
+            treasury_fee_account(&escrow_account, placement.auction_house, &mut remaining_accounts)?;
+        placement.pay(&funding, treasury_ft_account, escrow_account.treasury_bid_fee(price)?)?;
+    }
+
+    // Record the bid as an event.
+    emit_cpi(
+        &BidPlaced {
+            auction: placement.escrow_account.key(),
+            bidder: placement.bidder.key(),
+            price,
+            bond,
+            referrer: referrer.unwrap_or_default(),
+            memo,
+            affiliate: affiliate.unwrap_or_default(),
+        },
+        &placement.event_authority,
+        &placement.program,
+        placement.auction_house.event_authority_bump,
+    )?;
+    // Record the refunded highest bidder being displaced, so they can be notified.
+    if refund.is_some() {
+        emit_cpi(
+            &Outbid {
+                auction: placement.escrow_account.key(),
+                previous_bidder,
+                previous_price,
+                new_price: price,
+            },
+            &placement.event_authority,
+            &placement.program,
+            placement.auction_house.event_authority_bump,
+        )?;
+    }
+    Ok(())
+}
+
+// Settle an auction at once and record its sale, for close and settle_if_ended: settle it, record the sale
+// in its purchase receipt and in the house's and the NFT's collection's stats, mark it as settled and
+// record it as an event, then close the escrow account unless the exhibitor keeps it to reuse for another
+// item. The remaining accounts are those of settle.
+fn close_sale<'info>(
+    closing: SaleClosing<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    purchase_receipt_bump: u8,
+    event_authority_bump: u8,
+    program_id: &Pubkey,
+) -> Result<()> {
+    // Load the zero-copy escrow account for updating.
+    let mut escrow_account = closing.escrow_account.load_mut()?;
+    // Settle the auction: deliver the NFT and distribute the proceeds.
+    let sale_shares = settle(closing.to_settlement(&escrow_account), remaining_accounts)?;
+    // Record the sale in its purchase receipt.
+    record_purchase(
+        closing.purchase_receipt,
+        closing.escrow_account.key(),
+        &escrow_account,
+        &sale_shares,
+        closing.clock.unix_timestamp,
+        purchase_receipt_bump,
+    )?;
+    // Record the sale in the house's and the NFT's collection's stats.
+    record_house_sale(closing.house_stats, closing.purchase_receipt, program_id)?;
+    collection_stats::record_collection_sale(
+        closing.nft_metadata,
+        &escrow_account.nft_mint,
+        &escrow_account.payment_mint,
+        closing.collection_stats,
+        escrow_account.price,
+        closing.clock.unix_timestamp,
+    )?;
+    // Mark the auction as settled and record it as an event.
+    escrow_account.set_status(AuctionStatus::Settled);
+    tombstone_listing(
+        closing.listing_receipt,
+        AuctionStatus::Settled,
+        escrow_account.highest_bidder,
+        escrow_account.price,
+        program_id,
+    )?;
+    emit_cpi(
+        &AuctionSettled {
+            auction: closing.escrow_account.key(),
+            winner: escrow_account.highest_bidder,
+            price: escrow_account.price,
+            sold: true,
+        },
+        closing.event_authority,
+        &closing.program,
+        event_authority_bump,
+    )?;
+    // Close the escrow account, unless the exhibitor keeps it to reuse for another item.
+    close_settled_escrow(
+        &closing.escrow_account.to_account_info(),
+        &escrow_account,
+        closing.exhibitor_rent_payer,
+    )
+}
+
+// Settle an auction: deliver the NFT to the highest bidder and distribute the proceeds. Returns the
+// royalty and the affiliate fee escrowed, as distribute_proceeds does. The remaining accounts are those
+// of distribute_proceeds.
+fn settle<'info>(
+    settlement: Settlement<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<SaleShares> {
+    // Create the seeds for the signer from the escrow PDA's stored bump seed.
+    let signers_seeds: &[&[&[u8]]] = &[&[ESCROW_PDA_SEED, &[settlement.auction_house.escrow_bump]]];
+
+    // Burn the exhibitor's listing token, whose accounts are the last remaining accounts.
+    let remaining_accounts = if settlement.escrow_account.listing_token_mint != Pubkey::default() {
+        let split = remaining_accounts
+            .len()
+            .checked_sub(2)
+            .ok_or(AuctionError::ListingTokenAccountMissing)?;
+        let (remaining_accounts, listing_token_accounts) = remaining_accounts.split_at(split);
+        burn_listing_token_of(
+            settlement.escrow_account,
+            listing_token_accounts,
+            settlement.pda,
+            settlement.token_program,
+            signers_seeds,
+        )?;
+        remaining_accounts
+    } else {
+        remaining_accounts
+    };
+
+    // Ensure the winning bid meets the reserves, unless the exhibitor accepted it.
+    check_reserves(
+        settlement.escrow_account,
+        settlement.price_feed,
+        settlement.highest_bidder_ft_temp_account.amount,
+        settlement.payment_mint.decimals,
+        settlement.clock,
+    )?;
+
+    // Deliver the NFT. For wrapped SOL the exhibitor receives the FT temporary account's rent when the
+    // proceeds are unwrapped, so the NFT temporary account's rent goes to the highest bidder's rent payer
+    // in exchange.
+    let rent_destination = if settlement.escrow_account.payment_mint == native_mint::id() {
+        settlement.highest_bidder_rent_payer.clone()
+    } else {
+        settlement.exhibitor_rent_payer.clone()
+    };
+    if settlement.escrow_account.rental != Pubkey::default() {
+        // A rented NFT stays in escrow until the rental ends. The rental is the last remaining account.
+        let (rental, other_accounts) = remaining_accounts.split_last().ok_or(AuctionError::RentalAccountMissing)?;
+        start_rental(rental, settlement.escrow_account, &rent_destination.key(), settlement.clock.unix_timestamp)?;
+        return distribute_proceeds(settlement.to_proceeds_distribution(), other_accounts, signers_seeds);
+    }
+    deliver_item(
+        settlement.to_item_delivery(),
+        settlement.escrow_account.listing_mode()?,
+        rent_destination,
+        signers_seeds,
+    )?;
+
+    // Distribute the proceeds.
+    distribute_proceeds(settlement.to_proceeds_distribution(), remaining_accounts, signers_seeds)
+}
+
+// Deliver the NFT to the highest bidder and close the exhibitor's temporary NFT account. In the delegate
+// listing mode the NFT is transferred out of the exhibitor's own account, which is left open.
+fn deliver_item<'info>(
+    delivery: ItemDelivery<'_, 'info>,
+    listing_mode: ListingMode,
+    rent_destination: AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // Ensure the NFT can move: neither its account nor the highest bidder's may be frozen by the NFT's
+    // freeze authority. The winning bidder may claim to another account after finalize (see claim_item).
+    require!(!delivery.exhibitor_nft_temp_account.is_frozen(), AuctionError::EscrowFrozen);
+    require!(!delivery.highest_bidder_nft_receiving_account.is_frozen(), AuctionError::RecipientFrozen);
+    // Transfer the NFT from the escrow account to the highest bidder, the PDA signing as its owner or
+    // its delegate.
+    let amount = match listing_mode {
+        ListingMode::Escrow => delivery.exhibitor_nft_temp_account.amount,
+        ListingMode::Delegate => 1,
+    };
+    token::transfer(
+        delivery
+            .to_transfer_to_highest_bidder_context()
+            .with_signer(signers_seeds),
+        amount,
+    )?;
+
+    // Close the exhibitor's temporary NFT account.
+    if listing_mode == ListingMode::Escrow {
+        token::close_account(
+            delivery.to_close_nft_context(rent_destination)
+                .with_signer(signers_seeds),
+        )?;
+    }
+    Ok(())
+}
+
+// Start the rental of a rented auction's NFT in place of its delivery: the NFT stays in the exhibitor's
+// temporary NFT account, and the rental records the winning bidder as its renter until the rental ends,
+// and where the temporary account's rent goes once it is closed.
+fn start_rental<'info>(
+    rental: &AccountInfo<'info>,
+    auction: &Auction,
+    nft_rent_destination: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    // Ensure the rental is the one recorded in the escrow account.
+    require_keys_eq!(rental.key(), auction.rental, AuctionError::RentalAccountMismatch);
+    let mut rental = Account::<Rental>::try_from(rental)?;
+    rental.renter = auction.highest_bidder;
+    rental.nft_rent_destination = *nft_rent_destination;
+    rental.ends_at = add_duration(now, rental.duration_sec)?;
+    rental.exit(&crate::ID)
+}
+
+// Burn the listing token minted to the exhibitor of an auction. Its accounts are the listing token mint,
+// then the exhibitor's listing token account.
+fn burn_listing_token_of<'info>(
+    auction: &Auction,
+    listing_token_accounts: &[AccountInfo<'info>],
+    pda: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let (mint, token_account) = match listing_token_accounts {
+        [mint, token_account] => (mint, token_account),
+        _ => return err!(AuctionError::ListingTokenAccountMissing),
+    };
+    require_keys_eq!(mint.key(), auction.listing_token_mint, AuctionError::ListingTokenMismatch);
+    let listing_token_account = Account::<TokenAccount>::try_from(token_account)?;
+    require_keys_eq!(listing_token_account.mint, auction.listing_token_mint, AuctionError::ListingTokenMismatch);
+    require_keys_eq!(listing_token_account.owner, auction.exhibitor, AuctionError::ListingTokenMismatch);
+    thaw_and_burn(mint, token_account, pda, token_program, signers_seeds)
+}
+
+// Thaw a frozen listing token account and burn its token, the PDA signing as the freeze authority and
+// as the account's delegate.
+fn thaw_and_burn<'info>(
+    mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    pda: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token::thaw_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        ThawAccount {
+            account: token_account.clone(),
+            mint: mint.clone(),
+            authority: pda.clone(),
+        },
+        signers_seeds,
+    ))?;
+    token::burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Burn {
+                mint: mint.clone(),
+                from: token_account.clone(),
+                authority: pda.clone(),
+            },
+            signers_seeds,
+        ),
+        1,
+    )
+}
+
+// Check whether a round of an escrow account is over, so its listing token may be burned: the account was
+// closed, or has been reused since, or the round was settled or cancelled.
+fn listing_round_over(escrow_account: &AccountInfo, round: u64) -> Result<bool> {
+    if *escrow_account.owner != crate::ID {
+        return Ok(true);
+    }
+    let escrow_account = AccountLoader::<Auction>::try_from(escrow_account)?;
+    let auction = escrow_account.load()?;
+    Ok(auction.round > round
+        || matches!(auction.stored_status()?, AuctionStatus::Settled | AuctionStatus::Cancelled))
+}
+
+// Distribute the winning bid between the payout recipients, the charity, the referrer, the partner
+// frontend, the NFT's creators, the treasury and the exhibitor, and close the highest bidder's temporary
+// FT account. The FT receiving accounts of the payout recipients are passed as remaining accounts,
+// in the order they were given at exhibit, followed by the charity's FT receiving account
+// when the auction has a charity share, followed by the referrer's FT receiving account
+// when the winning bid was referred, followed by the partner's FT fee account when a partner
+// frontend listed the auction, followed by the house's royalty vault of the payment mint when a royalty
+// is owed, followed by the house's affiliate vault of the payment mint when the winning bid has an
+// affiliate (see Auction::settlement_account_count for the rest). The creators' shares of the royalty and
+// the affiliate fee are escrowed in the vaults rather than paid, and returned for the purchase receipt,
+// from which distribute_royalties and claim_affiliate_earnings pay them after settlement.
+fn distribute_proceeds<'info>(
+    distribution: ProceedsDistribution<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<SaleShares> {
+    // Ensure the proceeds can move: neither the escrowed bid nor the exhibitor's FT receiving account may be
+    // frozen by the payment mint's freeze authority. The exhibitor may switch to another receiving account
+    // (see update_proceeds_account).
+    require!(!distribution.highest_bidder_ft_temp_account.is_frozen(), AuctionError::EscrowFrozen);
+    require!(!distribution.exhibitor_ft_receiving_account.is_frozen(), AuctionError::RecipientFrozen);
+    // The gross proceeds are the full highest bid held in escrow, including any buyer's premium.
+    let gross_proceeds = distribution.highest_bidder_ft_temp_account.amount;
+    // Calculate the house fee taken from the gross proceeds, which is the whole buyer's premium if the
+    // auction charges one.
+    let house_fee = distribution
+        .escrow_account
+        .house_fee(gross_proceeds, distribution.auction_house.fee_bps)?;
+    let sale_proceeds = gross_proceeds.checked_sub(house_fee).ok_or(AuctionError::NumericalOverflow)?;
+    // Calculate the royalty owed to the NFT's creators, taken from the proceeds before they are split.
+    let royalty = royalties::load_royalty(
+        distribution.auction_house,
+        distribution.nft_metadata,
+        &distribution.escrow_account.nft_mint,
+        distribution.royalty_override,
+    )?;
+    let royalty_amount = match &royalty {
+        Some(royalty) => share_of(sale_proceeds, royalty.royalty_bps)?,
+        None => 0,
+    };
+    // The proceeds split between the payout recipients, the charity and the exhibitor.
+    let proceeds = sale_proceeds.checked_sub(royalty_amount).ok_or(AuctionError::NumericalOverflow)?;
+    // Keep track of what is left for the exhibitor after the creators and the payout recipients are paid,
+    // which includes any dust of the royalty.
+    let mut exhibitor_proceeds = sale_proceeds;
+
+    // Ensure every payout recipient's FT receiving account was supplied.
+    let payout_count = distribution.escrow_account.payout_count as usize;
+    require!(
+        remaining_accounts.len() >= payout_count,
+        AuctionError::PayoutRecipientMissing
+    );
+    let (recipient_accounts, other_accounts) = remaining_accounts.split_at(payout_count);
+    let mut other_accounts = other_accounts.iter();
+    // Pay each recipient their share of the proceeds.
+    let payouts = distribution.escrow_account.payouts;
+    let (payout_shares, charity_share) = distribution.escrow_account.proceeds_shares(proceeds)?;
+    let recipients = payouts[..payout_count].iter().zip(payout_shares).zip(recipient_accounts);
+    for ((payout, share), recipient) in recipients {
+        // Ensure the supplied account is the one recorded at exhibit.
+        require_keys_eq!(
+            recipient.key(),
+            payout.recipient_ft_pubkey,
+            AuctionError::PayoutRecipientMismatch
+        );
+        // A frozen account cannot take its share, which the exhibitor keeps instead.
+        if is_frozen(recipient) {
+            continue;
+        }
+        // Transfer the share from the escrow account to the recipient.
+        token::transfer(
+            distribution
+                .to_transfer_to_recipient_context(recipient.clone())
+                .with_signer(signers_seeds),
+            share,
+        )?;
+        exhibitor_proceeds = exhibitor_proceeds.checked_sub(share).ok_or(AuctionError::NumericalOverflow)?;
+    }
+
+    // Pay the charity its share of the proceeds, if the auction has one.
+    let charity_bps = distribution.escrow_account.charity_bps;
+    if charity_bps > 0 {
+        // Ensure the charity's FT receiving account was supplied and is the recorded one.
+        let charity = other_accounts.next().ok_or(AuctionError::CharityMissing)?;
+        require_keys_eq!(
+            charity.key(),
+            distribution.escrow_account.charity_ft_pubkey,
+            AuctionError::CharityMismatch
+        );
+        // A frozen account cannot take the charity's share, so the exhibitor keeps it instead.
+        if !is_frozen(charity) {
+            // Transfer the share from the escrow account to the charity.
+            token::transfer(
+                distribution
+                    .to_transfer_to_recipient_context(charity.clone())
+                    .with_signer(signers_seeds),
+                charity_share,
+            )?;
+            exhibitor_proceeds = exhibitor_proceeds
+                .checked_sub(charity_share)
+                .ok_or(AuctionError::NumericalOverflow)?;
+        }
+    }
+
+    // Pay the referrer of the winning bid its share of the house fee, if there is one.
+    let mut treasury_fee = house_fee;
+    let referrer_ft_pubkey = distribution.escrow_account.highest_bidder_referrer_ft_pubkey;
+    if referrer_ft_pubkey != Pubkey::default() {
+        // Ensure the referrer's FT receiving account was supplied and is the recorded one.
+        let referrer = other_accounts.next().ok_or(AuctionError::ReferrerMissing)?;
+        require_keys_eq!(referrer.key(), referrer_ft_pubkey, AuctionError::ReferrerMismatch);
+        // Calculate the referral fee as a share of the house fee. A frozen account cannot take it, so the
+        // treasury keeps it instead.
+        if !is_frozen(referrer) {
+            let referral_fee = share_of(house_fee, distribution.auction_house.referral_bps)?;
+            // Transfer the referral fee from the escrow account to the referrer.
+            token::transfer(
+                distribution
+                    .to_transfer_to_recipient_context(referrer.clone())
+                    .with_signer(signers_seeds),
+                referral_fee,
+            )?;
+            treasury_fee = treasury_fee.checked_sub(referral_fee).ok_or(AuctionError::NumericalOverflow)?;
+        }
+    }
+
+    // Pay the partner frontend that listed the auction its share of the house fee, if there is one. The
+    // share is capped by what the referrer left, since the referral share may have grown since exhibit.
+    let partner_fee_ft_pubkey = distribution.escrow_account.partner_fee_ft_pubkey;
+    if partner_fee_ft_pubkey != Pubkey::default() {
+        // Ensure the partner's FT fee account was supplied and is the recorded one.
+        let partner = other_accounts.next().ok_or(AuctionError::PartnerAccountMissing)?;
+        require_keys_eq!(partner.key(), partner_fee_ft_pubkey, AuctionError::PartnerAccountMismatch);
+        // A frozen account cannot take the partner fee, so the treasury keeps it instead.
+        if !is_frozen(partner) {
+            let partner_fee = share_of(house_fee, distribution.escrow_account.partner_fee_bps)?.min(treasury_fee);
+            // Transfer the partner fee from the escrow account to the partner.
+            token::transfer(
+                distribution
+                    .to_transfer_to_recipient_context(partner.clone())
+                    .with_signer(signers_seeds),
+                partner_fee,
+            )?;
+            treasury_fee = treasury_fee.checked_sub(partner_fee).ok_or(AuctionError::NumericalOverflow)?;
+        }
+    }
+
+    // Escrow the NFT's creators' shares of the royalty in the house's royalty vault, if one is owed. Paying
+    // up to five creators here would not fit in a settlement transaction beside everything else.
+    let mut creator_shares = Vec::new();
+    if let Some(royalty) = royalty {
+        require!(royalty.creators.len() <= MAX_CREATORS, AuctionError::TooManyCreators);
+        // Ensure the vault was supplied and is the house's royalty vault of the payment mint.
+        let vault = other_accounts.next().ok_or(AuctionError::RoyaltyVaultMissing)?;
+        require_keys_eq!(
+            vault.key(),
+            royalties::royalty_vault_address(&distribution.escrow_account.payment_mint),
+            AuctionError::RoyaltyVaultMismatch
+        );
+        require_keys_eq!(*vault.owner, token::ID, AuctionError::RoyaltyVaultMissing);
+        // A frozen vault cannot take the royalty, which the exhibitor keeps instead.
+        if !is_frozen(vault) {
+            // Calculate each creator's share, rounding down so the exhibitor keeps any dust.
+            for (creator, share_percent) in royalty.creators {
+                creator_shares.push((creator, share_of(royalty_amount, share_percent as u16 * 100)?));
+            }
+            let total = creator_shares
+                .iter()
+                .try_fold(0u64, |total, (_, share)| total.checked_add(*share))
+                .ok_or(AuctionError::NumericalOverflow)?;
+            // Transfer the creators' shares from the escrow account to the vault.
+            token::transfer(
+                distribution
+                    .to_transfer_to_recipient_context(vault.clone())
+                    .with_signer(signers_seeds),
+                total,
+            )?;
+            exhibitor_proceeds = exhibitor_proceeds.checked_sub(total).ok_or(AuctionError::NumericalOverflow)?;
+        }
+    }
+
+    // Escrow the affiliate's share of the house fee in the house's affiliate vault, if the winning bid has
+    // an affiliate. Like the partner fee, it is capped by what the referrer and the partner left.
+    let mut affiliate_fee = 0;
+    if distribution.escrow_account.highest_bidder_affiliate != Pubkey::default() {
+        // Ensure the vault was supplied and is the house's affiliate vault of the payment mint.
+        let vault = other_accounts.next().ok_or(AuctionError::AffiliateVaultMissing)?;
+        require_keys_eq!(
+            vault.key(),
+            affiliate_vault_address(&distribution.escrow_account.payment_mint),
+            AuctionError::AffiliateVaultMismatch
+        );
+        require_keys_eq!(*vault.owner, token::ID, AuctionError::AffiliateVaultMissing);
+        // A frozen vault cannot take the affiliate fee, so the treasury keeps it instead.
+        if !is_frozen(vault) {
+            affiliate_fee = share_of(house_fee, distribution.auction_house.affiliate_bps)?.min(treasury_fee);
+            // Transfer the affiliate fee from the escrow account to the vault.
+            token::transfer(
+                distribution
+                    .to_transfer_to_recipient_context(vault.clone())
+                    .with_signer(signers_seeds),
+                affiliate_fee,
+            )?;
+            treasury_fee = treasury_fee.checked_sub(affiliate_fee).ok_or(AuctionError::NumericalOverflow)?;
+        }
+    }
+    let sale_shares = SaleShares {
+        creators: creator_shares,
+        affiliate_fee,
+        treasury_fee,
+    };
+
+    // Transfer the rest of the house fee from the escrow account to the treasury.
+    token::transfer(
+        distribution
+            .to_transfer_to_treasury_context()
+            .with_signer(signers_seeds),
+        treasury_fee,
+    )?;
+
+    let vesting_schedule = distribution.escrow_account.vesting_schedule;
+    if vesting_schedule != Pubkey::default() {
+        // Ensure the vesting schedule and its vault were supplied and are the recorded ones.
+        let schedule = other_accounts.next().ok_or(AuctionError::VestingAccountMissing)?;
+        let vault = other_accounts.next().ok_or(AuctionError::VestingAccountMissing)?;
+        require_keys_eq!(schedule.key(), vesting_schedule, AuctionError::VestingAccountMismatch);
+        let mut schedule = Account::<VestingSchedule>::try_from(schedule)?;
+        require_keys_eq!(vault.key(), schedule.vault, AuctionError::VestingAccountMismatch);
+        // Start vesting the exhibitor's proceeds now.
+        schedule.start_at = Clock::get()?.unix_timestamp;
+        schedule.total = exhibitor_proceeds;
+        schedule.exit(&crate::ID)?;
+
+        // Transfer the rest of the highest bid amount from the escrow account to the vault.
+        token::transfer(
+            distribution
+                .to_transfer_to_recipient_context(vault.clone())
+                .with_signer(signers_seeds),
+            exhibitor_proceeds,
+        )?;
+        // Close the highest bidder's temporary FT account. For wrapped SOL its rent still goes to the
+        // exhibitor, as when the proceeds are unwrapped (see settle).
+        let rent_destination = if distribution.escrow_account.payment_mint == native_mint::id() {
+            distribution.exhibitor.clone()
+        } else {
+            distribution.highest_bidder_rent_payer.clone()
+        };
+        token::close_account(
+            distribution.to_close_ft_context(rent_destination)
+                .with_signer(signers_seeds),
+        )?;
+        return Ok(sale_shares);
+    }
+
+    if distribution.escrow_account.unwraps_proceeds() {
+        // For wrapped SOL, only the exhibitor's proceeds are left in the highest bidder's temporary
+        // account, so closing it to the exhibitor unwraps them.
+        token::close_account(
+            distribution.to_close_ft_context(distribution.exhibitor.clone())
+                .with_signer(signers_seeds),
+        )?;
+    } else {
+        // Transfer the rest of the highest bid amount from the escrow account to the exhibitor.
+        token::transfer(
+            distribution
+                .to_transfer_to_exhibitor_context()
+                .with_signer(signers_seeds),
+            exhibitor_proceeds,
+        )?;
+
+        // Close the highest bidder's temporary FT account, returning its rent to its payer. A buyout's
+        // wrapped SOL stays wrapped in the vault's FT receiving account, but the account's rent still goes to
+        // the exhibitor, as when the proceeds are unwrapped (see settle).
+        let rent_destination = if distribution.escrow_account.payment_mint == native_mint::id() {
+            distribution.exhibitor.clone()
+        } else {
+            distribution.highest_bidder_rent_payer.clone()
+        };
+        token::close_account(
+            distribution.to_close_ft_context(rent_destination)
+                .with_signer(signers_seeds),
+        )?;
+    }
+
+    // Settle the buyout of a fractionalization vault, if the exhibitor is one, now that it holds the
+    // proceeds. The vault's buyout authority PDA signs so the vault program knows the call comes from this
+    // program; the escrow PDA must never sign for a program the exhibitor chose.
+    let buyout_program = distribution.escrow_account.buyout_program;
+    if buyout_program != Pubkey::default() {
+        // Ensure the vault program and the buyout authority were supplied and are the expected ones.
+        let vault_program = other_accounts.next().ok_or(AuctionError::BuyoutProgramMissing)?;
+        let buyout_authority = other_accounts.next().ok_or(AuctionError::BuyoutProgramMissing)?;
+        require_keys_eq!(vault_program.key(), buyout_program, AuctionError::BuyoutProgramMismatch);
+        let (buyout_authority_pda, bump_seed) =
+            fractions::buyout_authority_address(distribution.exhibitor.key);
+        require_keys_eq!(buyout_authority.key(), buyout_authority_pda, AuctionError::BuyoutProgramMismatch);
+        let ix = fractions::settle_buyout_ix(
+            buyout_program,
+            distribution.exhibitor.key(),
+            distribution.exhibitor_ft_receiving_account.key(),
+            buyout_authority_pda,
+            &fractions::SettleBuyout {
+                auction_id: distribution.escrow_account.auction_id,
+                proceeds: exhibitor_proceeds,
+            },
+        )?;
+        anchor_lang::solana_program::program::invoke_signed(
+            &ix,
+            &[
+                distribution.exhibitor.clone(),
+                distribution.exhibitor_ft_receiving_account.to_account_info(),
+                buyout_authority.clone(),
+                vault_program.clone(),
+            ],
+            &[&[fractions::BUYOUT_AUTHORITY_SEED, distribution.exhibitor.key.as_ref(), &[bump_seed]]],
+        )?;
+    }
+    Ok(sale_shares)
+}
+
+// Define the SaleShares struct to hold what settlement escrowed for others than the exhibitor: the
+// creators' shares of the royalty in metadata order, and the fee owed to the winning bid's affiliate. It
+// also holds the part of the house fee the treasury received after the referrer, partner and affiliate
+// shares.
+struct SaleShares {
+    creators: Vec<(Pubkey, u64)>,
+    affiliate_fee: u64,
+    treasury_fee: u64,
+}
+
+// Get the address of the house's affiliate vault of a payment mint.
+fn affiliate_vault_address(payment_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AFFILIATE_VAULT_SEED, payment_mint.as_ref()], &crate::ID).0
+}
+
+// Close the escrow account of a settled auction, returning its rent to the exhibitor, unless the exhibitor
+// keeps it for reuse_escrow. The account is closed by hand since whether it closes depends on the claims
+// made and on its reuse; Anchor still rewrites its discriminator on exit, but its data is left marked as
+// settled and not kept for reuse, so even an account revived within the same transaction accepts no
+// instruction.
+fn close_settled_escrow<'info>(
+    escrow_account: &AccountInfo<'info>,
+    auction: &Auction,
+    exhibitor: &AccountInfo<'info>,
+) -> Result<()> {
+    if auction.reuse_escrow != 0 {
+        return Ok(());
+    }
+    let lamports = escrow_account.lamports();
+    **exhibitor.try_borrow_mut_lamports()? = exhibitor
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(AuctionError::NumericalOverflow)?;
+    **escrow_account.try_borrow_mut_lamports()? = 0;
+    Ok(())
+}
+
+// Settle a finalized auction once both the item and the proceeds have been claimed, in either order:
+// tombstone its listing, emit AuctionSettled and close its escrow account.
+fn complete_claim(
+    auction: &mut Auction,
+    completion: ClaimCompletion,
+    event_authority_bump: u8,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if auction.item_claimed == 0 || auction.proceeds_claimed == 0 {
+        return Ok(());
+    }
+    auction.set_status(AuctionStatus::Settled);
+    tombstone_listing(
+        completion.listing_receipt,
+        AuctionStatus::Settled,
+        auction.highest_bidder,
+        auction.price,
+        program_id,
+    )?;
+    emit_cpi(
+        &AuctionSettled {
+            auction: completion.escrow_account.key(),
+            winner: auction.highest_bidder,
+            price: auction.price,
+            sold: true,
+        },
+        completion.event_authority,
+        &completion.program,
+        event_authority_bump,
+    )?;
+    close_settled_escrow(&completion.escrow_account, auction, completion.exhibitor_rent_payer)
+}
+
+// Define the AuthorityListing struct to hold the settings of an auction whose item is escrowed by its
+// authority.
+struct AuthorityListing {
+    initial_price: u64,
+    auction_duration_sec: u64,
+    timing_mode: TimingMode,
+    title: String,
+    uri: String,
+    free_listing: bool,
+}
+
+// Set up the escrow account and listing receipt of an auction whose item is escrowed by handing its
+// authority to the PDA rather than moving it into a token account: a domain or a stake account. The
+// item's account is recorded as the auction's mint, so blacklisting it takes the item down, and the
+// auction has no payouts, charity or reserve, as if listed by reuse_escrow.
+fn init_authority_auction(
+    auction: &mut Auction,
+    listing_receipt: &mut ListingReceipt,
+    auction_key: Pubkey,
+    auction_id: u64,
+    item_type: ItemType,
+    item: Pubkey,
+    exhibitor: Pubkey,
+    payer: Pubkey,
+    exhibitor_ft_receiving_account: &Account<TokenAccount>,
+    auction_house: &AuctionHouse,
+    clock: &Clock,
+    listing: AuthorityListing,
+    listing_receipt_bump: u8,
+) -> Result<()> {
+    // Initialize the escrow account as exhibit does.
+    let payment_mint = exhibitor_ft_receiving_account.mint;
+    auction.version = AUCTION_VERSION;
+    auction.auction_id = auction_id;
+    auction.set_status(AuctionStatus::Created);
+    auction.item_type = item_type as u8;
+    auction.exhibitor = exhibitor;
+    auction.exhibitor_rent_payer = payer;
+    auction.highest_bidder_rent_payer = payer;
+    auction.exhibitor_ft_receiving_account = exhibitor_ft_receiving_account.key();
+    auction.highest_bidder = exhibitor;
+    auction.highest_bidder_ft_temp_account = exhibitor_ft_receiving_account.key();
+    auction.highest_bidder_ft_returning_account = exhibitor_ft_receiving_account.key();
+    auction.nft_mint = item;
+    auction.payment_mint = payment_mint;
+    auction.price = listing.initial_price;
+    auction.free_listing = listing.free_listing as u8;
+    auction.timing_mode = listing.timing_mode as u8;
+    auction.end_at = auction.end_after(clock, listing.auction_duration_sec)?;
+    auction.title = to_fixed_bytes(&listing.title)?;
+    auction.uri = to_fixed_bytes(&listing.uri)?;
+    auction.buyers_premium_bps = auction_house.buyers_premium_bps();
+
+    // Record the listing in its receipt, which outlives the escrow account.
+    listing_receipt.auction = auction_key;
+    listing_receipt.auction_id = auction_id;
+    listing_receipt.exhibitor = exhibitor;
+    listing_receipt.nft_mint = item;
+    listing_receipt.payment_mint = payment_mint;
+    listing_receipt.initial_price = listing.initial_price;
+    listing_receipt.created_at = clock.unix_timestamp;
+    listing_receipt.set_status(AuctionStatus::Created);
+    listing_receipt.bump = listing_receipt_bump;
+    Ok(())
+}
+
+// Record a sale in its purchase receipt, with the creators' shares of the royalty and the affiliate fee
+// escrowed at settlement, and the house fee the treasury received.
+fn record_purchase(
+    purchase_receipt: &mut PurchaseReceipt,
+    auction_key: Pubkey,
+    auction: &Auction,
+    sale_shares: &SaleShares,
+    now: i64,
+    bump: u8,
+) -> Result<()> {
+    purchase_receipt.auction = auction_key;
+    purchase_receipt.seller = auction.exhibitor;
+    purchase_receipt.buyer = auction.highest_bidder;
+    purchase_receipt.nft_mint = auction.nft_mint;
+    purchase_receipt.payment_mint = auction.payment_mint;
+    purchase_receipt.price = auction.price;
+    purchase_receipt.house_fee = sale_shares.treasury_fee;
+    for (index, (creator, share)) in sale_shares.creators.iter().enumerate() {
+        purchase_receipt.royalty_creators[index] = *creator;
+        purchase_receipt.royalty_owed[index] = *share;
+    }
+    purchase_receipt.affiliate = auction.highest_bidder_affiliate;
+    purchase_receipt.affiliate_owed = sale_shares.affiliate_fee;
+    purchase_receipt.created_at = now;
+    purchase_receipt.bump = bump;
+    Ok(())
+}
+
+// Record a sale in the house's stats in its payment mint, with the net house fee of its purchase receipt.
+// The house keeps stats in a mint only once they have been created with create_house_stats, so an account
+// this program does not own is left as it is.
+fn record_house_sale(house_stats: &AccountInfo, purchase: &PurchaseReceipt, program_id: &Pubkey) -> Result<()> {
+    if house_stats.owner != program_id {
+        return Ok(());
+    }
+    let mut stats = Account::<HouseStats>::try_from(house_stats)?;
+    stats.record_sale(purchase.price, purchase.house_fee);
+    stats.exit(program_id)
+}
+
+// Tombstone the listing receipt of an auction that is over with its final status and result. Auctions
+// exhibited before listing receipts were introduced have none, so an account this program does not own
+// is left as it is.
+fn tombstone_listing(
+    listing_receipt: &AccountInfo,
+    status: AuctionStatus,
+    winner: Pubkey,
+    final_price: u64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    if listing_receipt.owner != program_id {
+        return Ok(());
+    }
+    let mut receipt = Account::<ListingReceipt>::try_from(listing_receipt)?;
+    receipt.set_status(status);
+    receipt.winner = winner;
+    receipt.final_price = final_price;
+    receipt.closed_at = Clock::get()?.unix_timestamp;
+    receipt.exit(program_id)
+}
+
+// Ensure the winning bid of an auction meets its USD reserve and its hidden reserve, once revealed or
+// forfeited. An auction whose exhibitor accepted the highest bid skips both.
+fn check_reserves(
+    auction: &Auction,
+    price_feed: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    clock: &Clock,
+) -> Result<()> {
+    if auction.reserve_waived != 0 {
+        return Ok(());
+    }
+    require!(
+        usd_reserve_met(auction, price_feed, amount, decimals, clock.unix_timestamp)?,
+        AuctionError::ReserveNotMet
+    );
+    require!(!auction.awaits_reserve_reveal(clock)?, AuctionError::HiddenReserveNotRevealed);
+    require!(auction.meets_hidden_reserve(), AuctionError::ReserveNotMet);
+    Ok(())
+}
+
+// Check whether an amount of the payment token meets an auction's USD reserve.
+fn usd_reserve_met(
+    auction: &Auction,
+    price_feed: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    now: i64,
+) -> Result<bool> {
+    // An auction without a USD reserve always meets it.
+    if auction.reserve_usd_cents == 0 {
+        return Ok(true);
+    }
+    // The buyer's premium goes to the house and does not count towards the exhibitor's reserve.
+    let amount = amount
+        .checked_sub(auction.buyers_premium(auction.price)?)
+        .ok_or(AuctionError::NumericalOverflow)?;
+    let price = pyth::load_price(price_feed, now)?;
+    pyth::meets_usd_reserve(&price, amount, decimals, auction.reserve_usd_cents)
+}
+
+// Check whether an amount of the payment token meets an auction's USD reserve when unwinding it. Once the
+// price feed grace period is over, a feed that cannot be used counts as the reserve not being met, so an
+// outage cannot keep the NFT and the bid in escrow for good.
+fn usd_reserve_met_for_unwind(
+    auction: &Auction,
+    price_feed: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    clock: &Clock,
+) -> Result<bool> {
+    match usd_reserve_met(auction, price_feed, amount, decimals, clock.unix_timestamp) {
+        Err(_) if auction.price_feed_grace_over(clock)? => Ok(false),
+        result => result,
+    }
+}
+
+// Sync a wrapped SOL token account's balance with its lamports.
+fn sync_native<'info>(account: &AccountInfo<'info>, token_program: &AccountInfo<'info>) -> Result<()> {
+    let ix = spl_token::instruction::sync_native(token_program.key, account.key)?;
+    anchor_lang::solana_program::program::invoke(&ix, &[account.clone(), token_program.clone()])
+        .map_err(Into::into)
+}
+
+// Ensure an account is a token account holding the payment mint.
+fn check_payment_account(account: &AccountInfo, payment_mint: &Pubkey) -> Result<()> {
+    let token_account = Account::<TokenAccount>::try_from(account)?;
+    require_keys_eq!(token_account.mint, *payment_mint, AuctionError::PaymentMintMismatch);
+    Ok(())
+}
+
+// Ensure the partner frontend listing an auction is whitelisted and get its share of the house fee. The
+// partner accounts are its partner entry, then the FT fee account, which must be the partner's and hold
+// the payment mint.
+fn check_partner(partner_accounts: &[AccountInfo], fee_account: &Pubkey, payment_mint: &Pubkey) -> Result<u16> {
+    let (partner_entry, partner_accounts) = partner_accounts.split_first().ok_or(AuctionError::PartnerAccountMissing)?;
+    let fee_token_account = partner_accounts.first().ok_or(AuctionError::PartnerAccountMissing)?;
+    let partner_entry = Account::<PartnerEntry>::try_from(partner_entry)?;
+    require_keys_eq!(fee_token_account.key(), *fee_account, AuctionError::PartnerAccountMismatch);
+    let fee_token_account = Account::<TokenAccount>::try_from(fee_token_account)?;
+    require_keys_eq!(fee_token_account.owner, partner_entry.partner, AuctionError::PartnerAccountMismatch);
+    require_keys_eq!(fee_token_account.mint, *payment_mint, AuctionError::PaymentMintMismatch);
+    Ok(partner_entry.fee_share_bps)
+}
+
+// Ensure a bid's affiliate is neither the bidder nor, for a session bid, the session key bidding for them.
+fn check_affiliate(affiliate: Option<Pubkey>, bidder: &Pubkey, session_key: Option<Pubkey>) -> Result<()> {
+    if let Some(affiliate) = affiliate {
+        require_keys_neq!(affiliate, *bidder, AuctionError::SelfAffiliate);
+        require!(session_key != Some(affiliate), AuctionError::SelfAffiliate);
+    }
+    Ok(())
+}
+
+// Ensure a bid's referrer FT account is owned neither by the bidder nor, for a session bid, by the session
+// key bidding for them.
+fn check_referrer(referrer_owner: &Pubkey, bidder: &Pubkey, session_key: Option<Pubkey>) -> Result<()> {
+    require_keys_neq!(*referrer_owner, *bidder, AuctionError::SelfReferral);
+    require!(session_key != Some(*referrer_owner), AuctionError::SelfReferral);
+    Ok(())
+}
+
+// Check whether a bid pool's auction is over for the pool: settled or cancelled, ended with another highest
+// bidder, or won by the pool with the NFT claimed, so that the pool's leftover funds are final.
+fn pool_auction_over(auction: &Auction, bid_pool: &Pubkey, clock: &Clock) -> Result<bool> {
+    Ok(match auction.stored_status()? {
+        AuctionStatus::Settled | AuctionStatus::Cancelled => true,
+        _ if auction.highest_bidder == *bid_pool => auction.item_claimed != 0,
+        _ => auction.has_ended(clock)?,
+    })
+}
+
+// Ensure a bidder passes the auction's bid gates, if it has any. The gate accounts are, in order:
+// - for a token gate, a token account of the bidder holding at least the gate amount of the gating mint;
+// - for a collection gate, a token account of the bidder holding an NFT, then the NFT's metadata, which
+//   must be a verified member of the collection.
+fn check_bid_gate(auction: &Auction, bidder: &Pubkey, gate_accounts: &mut std::slice::Iter<AccountInfo>) -> Result<()> {
+    if auction.gate_mint != Pubkey::default() {
+        let gate_account = gate_accounts.next().ok_or(AuctionError::BidGateAccountMissing)?;
+        let gate_account = Account::<TokenAccount>::try_from(gate_account)?;
+        require_keys_eq!(gate_account.owner, *bidder, AuctionError::BidGateNotMet);
+        require_keys_eq!(gate_account.mint, auction.gate_mint, AuctionError::BidGateNotMet);
+        require!(gate_account.amount >= auction.gate_amount, AuctionError::BidGateNotMet);
+    }
+    if auction.gate_collection != Pubkey::default() {
+        let nft_account = gate_accounts.next().ok_or(AuctionError::BidGateAccountMissing)?;
+        let nft_metadata = gate_accounts.next().ok_or(AuctionError::BidGateAccountMissing)?;
+        let nft_account = Account::<TokenAccount>::try_from(nft_account)?;
+        require_keys_eq!(nft_account.owner, *bidder, AuctionError::BidGateNotMet);
+        require!(nft_account.amount > 0, AuctionError::BidGateNotMet);
+        let nft_metadata = metadata::load_metadata(nft_metadata, &nft_account.mint)?;
+        require!(
+            metadata::is_verified_member(&nft_metadata, &auction.gate_collection),
+            AuctionError::BidGateNotMet
+        );
+    }
+    Ok(())
+}
+
+// Find the treasury's FT account a penny auction's bid fee and a bid bond are paid to. The fee accounts are,
+// in order, the auction house configuration, which names the treasury, then the treasury's FT account of
+// the payment mint. The configuration is the one the bid already loaded, so it is only matched by key.
+fn treasury_fee_account<'a, 'info>(
+    auction: &Auction,
+    auction_house: &Account<AuctionHouse>,
+    fee_accounts: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+) -> Result<&'a AccountInfo<'info>> {
+    let auction_house_account = fee_accounts.next().ok_or(AuctionError::PennyFeeAccountMissing)?;
+    let treasury_ft_account = fee_accounts.next().ok_or(AuctionError::PennyFeeAccountMissing)?;
+    require_keys_eq!(auction_house_account.key(), auction_house.key(), AuctionError::PennyFeeAccountMismatch);
+    let treasury = Account::<TokenAccount>::try_from(treasury_ft_account)?;
+    require_keys_eq!(treasury.owner, auction_house.treasury, AuctionError::PennyFeeAccountMismatch);
+    require_keys_eq!(treasury.mint, auction.payment_mint, AuctionError::PaymentMintMismatch);
+    Ok(treasury_ft_account)
+}
+
+// Hand a bidder's temporary FT account, and the refund it holds, over to the bidder when their returning
+// account is frozen and cannot take the refund. The bidder then moves the refund to an account of their
+// choice and closes the temporary account themselves.
+fn hand_back_bid<'info>(
+    ft_temp_account: AccountInfo<'info>,
+    bidder: Pubkey,
+    pda: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = SetAuthority {
+        account_or_mint: ft_temp_account,
+        current_authority: pda,
+    };
+    token::set_authority(
+        CpiContext::new(token_program, cpi_accounts).with_signer(signers_seeds),
+        AuthorityType::AccountOwner,
+        Some(bidder),
+    )
 }
 
-// Implement the Exhibit struct.
-impl<'info> Exhibit<'info> {
-    // Define a function to create a context for transferring NFTs to the PDA.
-    fn to_transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self
-                .exhibitor_nft_token_account
-                .to_account_info()
-                .clone(),
-            to: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            authority: self.exhibitor.clone(),
+// Check whether a token account is frozen by its mint's freeze authority, which stops it taking transfers.
+// Any other account is left for the transfer to reject.
+fn is_frozen(account: &AccountInfo) -> bool {
+    matches!(Account::<TokenAccount>::try_from(account), Ok(token_account) if token_account.is_frozen())
+}
+
+// Ensure an NFT may be listed in a house scoped to a collection: its metadata must show it a verified
+// member of the collection, so a lookalike mint claiming the collection is rejected.
+fn check_house_collection(
+    auction_house: &AuctionHouse,
+    nft_metadata: &AccountInfo,
+    nft_mint: &Pubkey,
+) -> Result<()> {
+    if auction_house.collection != Pubkey::default() {
+        let metadata = metadata::load_metadata(nft_metadata, nft_mint)?;
+        require!(
+            metadata::is_verified_member(&metadata, &auction_house.collection),
+            AuctionError::NotInHouseCollection
+        );
+    }
+    Ok(())
+}
+
+// Validate a house fee configuration.
+fn validate_house_fees(fee_bps: u16, referral_bps: u16) -> Result<()> {
+    require!(fee_bps as u64 <= BASIS_POINTS, AuctionError::InvalidFee);
+    require!(referral_bps as u64 <= BASIS_POINTS, AuctionError::InvalidFee);
+    Ok(())
+}
+
+// Validate the auction duration limits of the house.
+fn validate_duration_limits(min_duration_sec: u64, max_duration_sec: u64) -> Result<()> {
+    require!(
+        max_duration_sec == 0 || min_duration_sec <= max_duration_sec,
+        AuctionError::InvalidDurationLimits
+    );
+    Ok(())
+}
+
+// Validate the initial price of an auction, which only a free listing may leave at zero.
+fn validate_initial_price(initial_price: u64, free_listing: bool) -> Result<()> {
+    require!(initial_price > 0 || free_listing, AuctionError::ZeroInitialPrice);
+    Ok(())
+}
+
+// Validate an auction duration against the program and house limits. Durations in slots are compared
+// at the target slot time.
+fn validate_duration(auction_house: &AuctionHouse, timing_mode: TimingMode, duration: u64) -> Result<()> {
+    let duration_sec = match timing_mode {
+        TimingMode::Timestamp => duration,
+        TimingMode::Slot => duration.saturating_mul(DEFAULT_MS_PER_SLOT) / 1_000,
+    };
+    require!(duration > 0, AuctionError::ZeroDuration);
+    require!(duration_sec <= MAX_AUCTION_DURATION_SEC, AuctionError::DurationTooLong);
+    require!(duration_sec >= auction_house.min_duration_sec, AuctionError::DurationOutOfRange);
+    require!(
+        auction_house.max_duration_sec == 0 || duration_sec <= auction_house.max_duration_sec,
+        AuctionError::DurationOutOfRange
+    );
+    Ok(())
+}
+
+// Copy a string into a fixed-size field, padding it with zeros.
+fn to_fixed_bytes<const N: usize>(value: &str) -> Result<[u8; N]> {
+    require!(value.len() <= N, AuctionError::MetadataTooLong);
+    let mut bytes = [0u8; N];
+    bytes[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(bytes)
+}
+
+// Read a string back from a fixed-size field, dropping the zero padding.
+fn from_fixed_bytes(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+// Ensure an auction's payout recipients fit in its escrow account and their and the charity's shares do not
+// exceed the whole of the proceeds.
+fn check_payout_shares(payouts: &[Payout], charity_bps: u16) -> Result<()> {
+    require!(payouts.len() <= MAX_PAYOUT_RECIPIENTS, AuctionError::TooManyPayoutRecipients);
+    let total_share_bps = payouts
+        .iter()
+        .map(|payout| payout.share_bps as u64)
+        .chain([charity_bps as u64])
+        .try_fold(0u64, u64::checked_add)
+        .ok_or(AuctionError::NumericalOverflow)?;
+    require!(total_share_bps <= BASIS_POINTS, AuctionError::InvalidPayoutShares);
+    Ok(())
+}
+
+// Calculate a basis-point share of an amount, rounding down.
+fn share_of(amount: u64, share_bps: u16) -> Result<u64> {
+    (amount as u128)
+        .checked_mul(share_bps as u128)
+        .map(|share| share / BASIS_POINTS as u128)
+        .and_then(|share| u64::try_from(share).ok())
+        .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+}
+
+// Add a duration to a point in time, failing rather than wrapping when the duration does not fit in an
+// i64 or the sum overflows.
+fn add_duration(start: i64, duration: u64) -> Result<i64> {
+    i64::try_from(duration)
+        .ok()
+        .and_then(|duration| start.checked_add(duration))
+        .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+}
+
+// Get the round an escrow account moves to when it is reused.
+fn next_round(round: u64) -> Result<u64> {
+    round.checked_add(1).ok_or_else(|| error!(AuctionError::NumericalOverflow))
+}
+
+// Define the custom errors returned by the auction program. Anchor numbers them by position from 6000, so
+// new errors are only ever appended at the end.
+#[error_code]
+pub enum AuctionError {
+    // More payout recipients were given than the escrow account can store.
+    #[msg("Too many payout recipients")]
+    TooManyPayoutRecipients,
+    // The payout shares add up to more than 100%.
+    #[msg("Payout shares exceed 10000 basis points")]
+    InvalidPayoutShares,
+    // A payout recipient's account was not supplied at close.
+    #[msg("Payout recipient account missing")]
+    PayoutRecipientMissing,
+    // A supplied payout recipient's account does not match the recorded one.
+    #[msg("Payout recipient account mismatch")]
+    PayoutRecipientMismatch,
+    // An arithmetic operation overflowed.
+    #[msg("Numerical overflow")]
+    NumericalOverflow,
+    // A charity share was requested without a charity account, or the account was not supplied.
+    #[msg("Charity account missing")]
+    CharityMissing,
+    // The supplied charity account does not match the recorded one.
+    #[msg("Charity account mismatch")]
+    CharityMismatch,
+    // A fee was configured above 10000 basis points.
+    #[msg("Fee exceeds 10000 basis points")]
+    InvalidFee,
+    // The winning bid was referred but the referrer's account was not supplied.
+    #[msg("Referrer account missing")]
+    ReferrerMissing,
+    // The supplied referrer account does not match the recorded one.
+    #[msg("Referrer account mismatch")]
+    ReferrerMismatch,
+    // The price feed is not a Pyth price account.
+    #[msg("Invalid price feed")]
+    InvalidPriceFeed,
+    // The price feed has no current trading price.
+    #[msg("Price unavailable")]
+    PriceUnavailable,
+    // The price feed has not been updated recently enough.
+    #[msg("Stale price")]
+    StalePrice,
+    // The price feed's confidence interval is too wide to settle against.
+    #[msg("Price confidence interval too wide")]
+    PriceTooUncertain,
+    // The highest bid does not meet the USD reserve.
+    #[msg("Reserve price not met")]
+    ReserveNotMet,
+    // The highest bid meets the USD reserve, so the auction must be closed normally.
+    #[msg("Reserve price met")]
+    ReserveMet,
+    // A token account does not hold the auction's payment mint.
+    #[msg("Token account is not for the payment mint")]
+    PaymentMintMismatch,
+    // The auction has not ended yet.
+    #[msg("Auction has not ended")]
+    AuctionNotEnded,
+    // The escrow account was written by a newer program version.
+    #[msg("Unsupported auction account version")]
+    UnsupportedAuctionVersion,
+    // The auction is not in a status that allows the instruction.
+    #[msg("Invalid auction status")]
+    InvalidAuctionStatus,
+    // The escrow account was written by an older program version and must be migrated first.
+    #[msg("Auction account must be migrated")]
+    AuctionNeedsMigration,
+    // The item or the proceeds of the auction have already been claimed.
+    #[msg("Already claimed")]
+    AlreadyClaimed,
+    // The winning bidder's claim period has not ended yet.
+    #[msg("Claim period has not ended")]
+    ClaimPeriodNotOver,
+    // The signer is neither the exhibitor nor its operator.
+    #[msg("Signer is not allowed to manage the auction")]
+    Unauthorized,
+    // The exhibitor or FT receiving account is not the governance's native treasury or its ATA.
+    #[msg("Accounts do not belong to the governance's native treasury")]
+    InvalidGovernanceTreasury,
+    // The stored timing mode is not a known TimingMode.
+    #[msg("Invalid timing mode")]
+    InvalidTimingMode,
+    // A token account does not hold the auctioned NFT's mint.
+    #[msg("NFT mint mismatch")]
+    NftMintMismatch,
+    // Migrating an auction that holds its NFT needs its temporary NFT account.
+    #[msg("Temporary NFT account missing")]
+    NftTempAccountMissing,
+    // The exhibitor or its operator cannot bid on their own auction.
+    #[msg("Exhibitor cannot bid on their own auction")]
+    SelfBid,
+    // The minimum auction duration is longer than the maximum.
+    #[msg("Minimum duration exceeds maximum duration")]
+    InvalidDurationLimits,
+    // The auction duration is outside the house limits.
+    #[msg("Auction duration outside the house limits")]
+    DurationOutOfRange,
+    // The title or URI is longer than its field in the escrow account.
+    #[msg("Title or URI too long")]
+    MetadataTooLong,
+    // The stored listing mode is not a known ListingMode.
+    #[msg("Invalid listing mode")]
+    InvalidListingMode,
+    // A delegate listing's temporary NFT account is not the exhibitor's NFT account.
+    #[msg("Delegate listings keep the NFT in the exhibitor's NFT account")]
+    InvalidDelegateListing,
+    // The NFT or payment mint is blacklisted by the house.
+    #[msg("This mint is blacklisted")]
+    BlacklistedMint,
+    // A bid gate names a mint but requires none of it.
+    #[msg("A bid gate must require a positive amount of its mint")]
+    InvalidBidGate,
+    // The auction is gated but the bidder's gate account was not passed.
+    #[msg("The bidder's gate account is missing")]
+    BidGateAccountMissing,
+    // The bidder does not hold enough of the gating mint.
+    #[msg("The bidder does not meet the auction's bid gate")]
+    BidGateNotMet,
+    // The account is not the Metaplex metadata of the expected mint.
+    #[msg("Invalid token metadata account")]
+    InvalidMetadata,
+    // More NFTs were passed to exhibit_many than it lists at once.
+    #[msg("Too many NFTs in the batch")]
+    TooManyExhibits,
+    // More auctions were passed to close_many than it settles at once.
+    #[msg("Too many auctions in the batch")]
+    TooManyCloses,
+    // The bid memo is longer than its field in the bid receipt.
+    #[msg("Bid memo too long")]
+    MemoTooLong,
+    // The Dutch curve is unknown, or its parameters do not describe a decay to a lower floor price.
+    #[msg("Invalid Dutch auction curve")]
+    InvalidDutchCurve,
+    // A penny auction has no countdown, or is also a Dutch auction.
+    #[msg("A penny auction needs a countdown and cannot be a Dutch auction")]
+    InvalidPennyAuction,
+    // The treasury accounts a penny bid fee or bid bond is paid to were not supplied.
+    #[msg("The treasury fee accounts of a penny auction or bid bond are missing")]
+    PennyFeeAccountMissing,
+    // The supplied treasury fee accounts are not the auction house treasury's.
+    #[msg("The treasury fee accounts of a penny auction or bid bond do not match the auction house treasury")]
+    PennyFeeAccountMismatch,
+    // The bonding curve is unknown or its parameters are out of range.
+    #[msg("Invalid bonding curve")]
+    InvalidBondingCurve,
+    // The edition mint has decimals, or the seller is not its mint authority.
+    #[msg("The edition mint must have no decimals and the seller as its mint authority")]
+    InvalidEditionMint,
+    // Every edition of the sale has been minted.
+    #[msg("All editions have been sold")]
+    EditionSoldOut,
+    // The current edition price is above the buyer's maximum.
+    #[msg("The edition price is above the buyer's maximum")]
+    EditionPriceTooHigh,
+    // The seller's FT receiving account is not the one recorded in the sale.
+    #[msg("The seller's FT receiving account does not match the sale")]
+    EditionSellerMismatch,
+    // The vesting schedule never ends, or ends before its cliff.
+    #[msg("A vesting schedule must end, and not before its cliff")]
+    InvalidVestingSchedule,
+    // Proceeds vest but the vesting schedule or vault was not supplied.
+    #[msg("The vesting schedule or vault is missing")]
+    VestingAccountMissing,
+    // The supplied vesting accounts are not the auction's.
+    #[msg("The vesting accounts do not match the auction")]
+    VestingAccountMismatch,
+    // No proceeds have vested since the last claim.
+    #[msg("Nothing has vested since the last claim")]
+    NothingVested,
+    // The auction has not paid its proceeds into the vesting vault yet.
+    #[msg("The auction has not funded its vesting vault yet")]
+    VestingNotStarted,
+    // A buyout vault is not a PDA, or the auction's proceeds vest.
+    #[msg("A buyout vault must be a PDA exhibiting an auction whose proceeds do not vest")]
+    InvalidBuyoutVault,
+    // The buyout vault program or authority was not supplied.
+    #[msg("The buyout vault program or authority is missing")]
+    BuyoutProgramMissing,
+    // The supplied buyout vault program or authority is not the auction's.
+    #[msg("The buyout vault program or authority does not match the auction")]
+    BuyoutProgramMismatch,
+    // A rental has no duration or does not rent an escrowed NFT.
+    #[msg("A rental must last some time and rent an escrowed NFT")]
+    InvalidRental,
+    // The auction rents out its NFT but the rental was not supplied.
+    #[msg("The rental is missing")]
+    RentalAccountMissing,
+    // The supplied rental accounts are not the auction's.
+    #[msg("The rental accounts do not match the auction")]
+    RentalAccountMismatch,
+    // The rental period has not ended yet.
+    #[msg("The rental is not over")]
+    RentalNotOver,
+    // The relist reduction is more than the whole price.
+    #[msg("The relist reduction cannot exceed the whole price")]
+    InvalidRelist,
+    // The auction has been relisted as many times as allowed.
+    #[msg("The auction cannot be relisted again")]
+    RelistLimitReached,
+    // The escrow account was closed with its auction rather than kept for reuse.
+    #[msg("The escrow account was not kept for reuse")]
+    EscrowNotReusable,
+    // A partner frontend's share is more than the whole house fee.
+    #[msg("A partner's share cannot exceed the whole house fee")]
+    InvalidPartnerShare,
+    // The sale went through a partner frontend whose accounts were not supplied.
+    #[msg("The partner frontend's accounts are missing")]
+    PartnerAccountMissing,
+    // The supplied partner frontend accounts are not the sale's.
+    #[msg("The partner frontend's accounts do not match")]
+    PartnerAccountMismatch,
+    // A temporary account is not held by the escrow PDA.
+    #[msg("The temporary account is not held by the escrow PDA")]
+    NotEscrowed,
+    // The initial price is zero on a listing that is not free.
+    #[msg("The initial price must be above zero unless the listing is free")]
+    ZeroInitialPrice,
+    // The auction duration is zero.
+    #[msg("The auction duration must be above zero")]
+    ZeroDuration,
+    // The auction duration is longer than any auction may last.
+    #[msg("The auction duration exceeds the longest duration allowed")]
+    DurationTooLong,
+    // The bid transaction borrows from a flash loan program.
+    #[msg("Bids cannot be funded by a flash loan")]
+    FlashLoanDetected,
+    // The revealed reserve and salt do not hash to the commitment.
+    #[msg("The revealed reserve does not match its commitment")]
+    InvalidReserveReveal,
+    // The hidden reserve may still be revealed, so the auction cannot settle yet.
+    #[msg("The hidden reserve has not been revealed yet")]
+    HiddenReserveNotRevealed,
+    // The allowlist phase does not open before the public phase and the end.
+    #[msg("The allowlist phase must open before the public phase and the end of the auction")]
+    InvalidBidPhases,
+    // The bidder's phase of the auction has not opened yet.
+    #[msg("Bidding on the auction has not opened yet")]
+    BiddingNotOpen,
+    // The exhibitor's period to accept a bid below the reserve has ended.
+    #[msg("The highest bid can no longer be accepted")]
+    AcceptancePeriodOver,
+    // The exhibitor's period to accept a bid below the reserve has not ended yet.
+    #[msg("The exhibitor may still accept the highest bid")]
+    AcceptancePeriodNotOver,
+    // A royalty override is more than the whole proceeds.
+    #[msg("A royalty override must not exceed the whole proceeds")]
+    InvalidRoyaltyOverride,
+    // The supplied royalty override is not the one of the NFT's collection.
+    #[msg("The royalty override is not the one of the NFT's collection")]
+    RoyaltyOverrideMismatch,
+    // A creator owed a royalty has no FT account among the supplied accounts.
+    #[msg("A creator's FT account is missing")]
+    CreatorAccountMissing,
+    // A creator's supplied FT account is owned by someone else.
+    #[msg("A creator's FT account does not belong to the creator")]
+    CreatorAccountMismatch,
+    // The auction's timing is set by its series.
+    #[msg("The auction is a lot of a series, which sets when bidding on it opens and ends")]
+    AuctionInSeries,
+    // The series already has as many lots as it can hold.
+    #[msg("The series has no room for another lot")]
+    SeriesFull,
+    // Every lot of the series has already opened.
+    #[msg("Every lot of the series has opened")]
+    SeriesComplete,
+    // The lot is neither the series' next lot nor the one before it.
+    #[msg("The lot is not the next lot of the series or the one before it")]
+    InvalidSeriesLot,
+    // The series has not started, or its current lot is still running.
+    #[msg("The series has not started or its current lot is not done yet")]
+    SeriesLotNotReady,
+    // The pooled bid's accounts are not the pool's.
+    #[msg("The bid's accounts do not match the pool")]
+    BidPoolMismatch,
+    // A bid pool was created for wrapped SOL.
+    #[msg("Bid pools cannot bid in wrapped SOL")]
+    NativeMintPool,
+    // A contribution to a bid pool is zero.
+    #[msg("A contribution must not be empty")]
+    EmptyContribution,
+    // The bid pool has already been settled.
+    #[msg("The pool has already been settled")]
+    BidPoolSettled,
+    // The bid pool has not been settled yet.
+    #[msg("The pool has not been settled yet")]
+    BidPoolNotSettled,
+    // The bid pool's auction is not over yet.
+    #[msg("The pool's auction is not over yet")]
+    PoolAuctionNotOver,
+    // The contribution has already been refunded.
+    #[msg("The contribution has already been refunded")]
+    RefundAlreadyClaimed,
+    // The auction's listing token has already been minted.
+    #[msg("The auction already has a listing token")]
+    ListingTokenMinted,
+    // The auction has a listing token but its accounts were not supplied.
+    #[msg("The listing token accounts are missing")]
+    ListingTokenAccountMissing,
+    // The supplied listing token accounts are not the auction's.
+    #[msg("The listing token accounts do not match the auction")]
+    ListingTokenMismatch,
+    // The supplied collection stats are not the ones of the NFT's collection.
+    #[msg("The collection stats account does not match the NFT's collection")]
+    CollectionStatsMismatch,
+    // The house has disabled the feature the instruction uses.
+    #[msg("The feature is disabled by the house")]
+    FeatureDisabled,
+    // The feature flags include a feature that does not exist.
+    #[msg("The feature flags include an undefined feature")]
+    InvalidFeatures,
+    // The house is paused.
+    #[msg("The house is paused")]
+    HousePaused,
+    // The house only lists approved sellers, and the exhibitor is not one.
+    #[msg("The exhibitor is not an approved seller of the house")]
+    SellerNotApproved,
+    // A bid bond is both flat and a share of the bid, or its share is too large.
+    #[msg("A bid bond is either a flat amount or a share of the bid of at most 10000 basis points")]
+    InvalidBidBond,
+    // An escrowed token account is frozen by its mint's freeze authority.
+    #[msg("An escrowed token account is frozen by its mint's freeze authority")]
+    EscrowFrozen,
+    // A receiving token account is frozen.
+    #[msg("A receiving token account is frozen; claim to another account")]
+    RecipientFrozen,
+    // The receiving token account is not frozen.
+    #[msg("The receiving token account is not frozen")]
+    RecipientNotFrozen,
+    // The NFT is not a verified member of the house's collection.
+    #[msg("The NFT is not a verified member of the house's collection")]
+    NotInHouseCollection,
+    // The exhibited mint is not a one-of-one NFT.
+    #[msg("The exhibited mint is not a one-of-one NFT with a supply of 1 and no decimals")]
+    NotAnNft,
+    // A print sale has no escrowed master edition, or it is rented out.
+    #[msg("A print sale needs an escrowed master edition that is not rented out")]
+    InvalidPrintSale,
+    // The escrowed NFT is not a master edition.
+    #[msg("The escrowed NFT is not a master edition")]
+    NotAMasterEdition,
+    // A print sale was settled without finalize and claim_print.
+    #[msg("A print sale must be finalized and its print claimed with claim_print")]
+    PrintSaleNeedsClaim,
+    // The account is not a Solana Name Service domain without a class.
+    #[msg("The name account is not a Solana Name Service domain without a class")]
+    InvalidNameAccount,
+    // The auction does not sell a domain.
+    #[msg("The auction does not sell a domain")]
+    NotADomainAuction,
+    // The setting needs an NFT, which domain and stake account auctions do not sell.
+    #[msg("Domain and stake account auctions do not support this setting")]
+    DomainUnsupported,
+    // The stored item type is not a known ItemType.
+    #[msg("Invalid item type")]
+    InvalidItemType,
+    // The account is not an initialized stake account.
+    #[msg("The account is not an initialized stake account")]
+    InvalidStakeAccount,
+    // The stake account's lockup is still in force.
+    #[msg("The stake account's lockup is still in force")]
+    StakeLockedUp,
+    // The auction does not sell a stake account.
+    #[msg("The auction does not sell a stake account")]
+    NotAStakeAuction,
+    // Royalties are owed but the house's royalty vault was not supplied.
+    #[msg("The house's royalty vault of the payment mint is missing; create it with create_royalty_vault")]
+    RoyaltyVaultMissing,
+    // The supplied royalty vault is not the house's for the payment mint.
+    #[msg("The royalty vault is not the house's royalty vault of the payment mint")]
+    RoyaltyVaultMismatch,
+    // The NFT's metadata lists more creators than a royalty record holds.
+    #[msg("The NFT's metadata lists more creators than a royalty can be owed to")]
+    TooManyCreators,
+    // The dispute window is too long, or is not zero and has no arbiter.
+    #[msg("The dispute window must be at most MAX_DISPUTE_WINDOW_SEC, with an arbiter if not zero")]
+    InvalidDisputeWindow,
+    // An auction in arbitration mode was settled without finalize.
+    #[msg("An auction in arbitration mode must be finalized and each side claimed after the dispute window")]
+    ArbitrationNeedsFinalize,
+    // The sale is still within its dispute window.
+    #[msg("The sale is held for disputes until its dispute window closes")]
+    DisputeWindowOpen,
+    // The auction has no arbiter.
+    #[msg("The auction is not in arbitration mode")]
+    NotInArbitration,
+    // The sale's dispute window has closed.
+    #[msg("The dispute window of the sale has closed")]
+    DisputeWindowOver,
+    // The session lasts too long or does not spend from the wallet's FT account.
+    #[msg("The session must expire within MAX_SESSION_DURATION_SEC and spend from the wallet's FT account")]
+    InvalidSession,
+    // The bid session has expired.
+    #[msg("The bid session has expired")]
+    SessionExpired,
+    // The bid would spend more than the session allows.
+    #[msg("The bid would exceed the session's spending cap")]
+    SessionCapExceeded,
+    // The sale has an affiliate but the house's affiliate vault was not supplied.
+    #[msg("The house's affiliate vault of the payment mint is missing; create it with create_affiliate_vault")]
+    AffiliateVaultMissing,
+    // The supplied affiliate vault is not the house's for the payment mint.
+    #[msg("The affiliate vault is not the house's affiliate vault of the payment mint")]
+    AffiliateVaultMismatch,
+    // The affiliate's FT account is owned by someone else.
+    #[msg("The FT account does not belong to the sale's affiliate")]
+    AffiliateAccountMismatch,
+    // The maximum price is not above the initial price.
+    #[msg("The maximum price must be above the initial price")]
+    InvalidMaxPrice,
+    // The bid is not a multiple of the auction's tick size.
+    #[msg("The bid must be a multiple of the auction's tick size")]
+    BidOffTick,
+    // The installment plan does not fit the auction, or has no schedule or down payment.
+    #[msg("An installment plan needs an escrowed NFT, a timestamp end, a schedule and a partial down payment")]
+    InvalidInstallmentPlan,
+    // The winning bid has not been paid in full yet.
+    #[msg("The winning bid must be paid in full before the auction settles")]
+    InstallmentsDue,
+    // The winning bid has already been paid in full.
+    #[msg("The winning bid is already paid in full")]
+    InstallmentsPaid,
+    // The auction has no installment plan.
+    #[msg("The auction has no installment plan")]
+    NoInstallmentPlan,
+    // The winner has paid every installment due so far.
+    #[msg("No installment has been missed")]
+    InstallmentNotMissed,
+    // The swap's or the bid's accounts do not match the bidder's.
+    #[msg("The swap's or the bid's accounts do not match the bidder and the bidder's FT account")]
+    SwapAccountMismatch,
+    // The swap delivered less than the bidder's minimum amount out.
+    #[msg("The swap delivered less than the minimum amount out")]
+    SwapSlippageExceeded,
+    // The house was initialized by someone other than the program's upgrade authority.
+    #[msg("Only the program's upgrade authority can initialize the house")]
+    NotUpgradeAuthority,
+    // The bid names the bidder, or the session key bidding for them, as its affiliate.
+    #[msg("A bidder cannot be their own affiliate")]
+    SelfAffiliate,
+    // The bid's referrer FT account belongs to the bidder, or to the session key bidding for them.
+    #[msg("A bidder cannot be their own referrer")]
+    SelfReferral,
+    // The pool's NFT account does not hold the NFT of the pool's auction.
+    #[msg("The bid pool does not hold the NFT")]
+    PoolItemMissing,
+    // The bid pool has no fraction mint yet.
+    #[msg("The bid pool has not been fractionalized")]
+    BidPoolNotFractionalized,
+    // The contribution's fractions have already been minted.
+    #[msg("The contribution's fractions have already been claimed")]
+    FractionsAlreadyClaimed,
+    // The fraction account is not for the bid pool's fraction mint.
+    #[msg("Fraction mint mismatch")]
+    FractionMintMismatch,
+    // The redeemer does not hold every fraction of the bid pool.
+    #[msg("Redeeming the NFT takes every fraction")]
+    IncompleteFractions,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_error::ProgramError;
+
+    // Check whether a result failed with the numerical overflow error.
+    fn overflowed<T>(result: Result<T>) -> bool {
+        match result {
+            Ok(_) => false,
+            Err(err) => ProgramError::from(err) == ProgramError::from(error!(AuctionError::NumericalOverflow)),
+        }
+    }
+
+    #[test]
+    fn share_of_rounds_down_and_takes_the_whole_amount_at_full_share() {
+        assert_eq!(share_of(9_999, 1).unwrap(), 0);
+        assert_eq!(share_of(10_000, 1).unwrap(), 1);
+        assert_eq!(share_of(0, u16::MAX).unwrap(), 0);
+        assert_eq!(share_of(u64::MAX, BASIS_POINTS as u16).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn share_of_overflows_past_the_whole_amount() {
+        assert!(overflowed(share_of(u64::MAX, BASIS_POINTS as u16 + 1)));
+        assert!(overflowed(share_of(u64::MAX, u16::MAX)));
+    }
+
+    #[test]
+    fn add_duration_rejects_durations_that_would_wrap() {
+        assert_eq!(add_duration(100, 50).unwrap(), 150);
+        assert_eq!(add_duration(-100, 50).unwrap(), -50);
+        assert_eq!(add_duration(0, i64::MAX as u64).unwrap(), i64::MAX);
+        // A duration past i64::MAX used to wrap into an end in the past.
+        assert!(overflowed(add_duration(0, i64::MAX as u64 + 1)));
+        assert!(overflowed(add_duration(0, u64::MAX)));
+        assert!(overflowed(add_duration(i64::MAX, 1)));
+    }
+
+    #[test]
+    fn next_round_overflows_at_the_last_round() {
+        assert_eq!(next_round(0).unwrap(), 1);
+        assert!(overflowed(next_round(u64::MAX)));
+    }
+
+    #[test]
+    fn round_seed_is_empty_in_the_first_round() {
+        assert!(round_seed(0).is_empty());
+        assert_eq!(round_seed(u64::MAX), u64::MAX.to_le_bytes().to_vec());
+    }
+
+    // Build a vesting schedule of a total starting at 1000.
+    fn schedule(total: u64, cliff_sec: u64, duration_sec: u64) -> VestingSchedule {
+        VestingSchedule {
+            auction: Pubkey::default(),
+            beneficiary_ft_pubkey: Pubkey::default(),
+            vault: Pubkey::default(),
+            rent_payer: Pubkey::default(),
+            cliff_sec,
+            duration_sec,
+            start_at: 1_000,
+            total,
+            claimed: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn vested_is_zero_before_the_cliff_and_total_at_the_end() {
+        let schedule = schedule(1_000, 100, 1_000);
+        assert_eq!(schedule.vested(i64::MIN).unwrap(), 0);
+        assert_eq!(schedule.vested(1_099).unwrap(), 0);
+        assert_eq!(schedule.vested(1_100).unwrap(), 100);
+        assert_eq!(schedule.vested(2_000).unwrap(), 1_000);
+        assert_eq!(schedule.vested(i64::MAX).unwrap(), 1_000);
+    }
+
+    // Build an auction bid on in UNIX timestamps, ending at 1000.
+    fn auction() -> Auction {
+        let mut auction: Auction = bytemuck::Zeroable::zeroed();
+        auction.version = AUCTION_VERSION;
+        auction.price = 100;
+        auction.end_at = 1_000;
+        auction
+    }
+
+    // Build an auction house configuration with every field zeroed.
+    fn house() -> AuctionHouse {
+        AuctionHouse::try_deserialize_unchecked(&mut &[0; AuctionHouse::LEN][..]).unwrap()
+    }
+
+    // Build a bid of a price from a bidder.
+    fn highest_bid(bidder: Pubkey, price: u64) -> HighestBid {
+        HighestBid {
+            bidder,
+            ft_temp_account: Pubkey::new_unique(),
+            ft_returning_account: Pubkey::new_unique(),
+            rent_payer: Pubkey::new_unique(),
+            referrer: Pubkey::default(),
+            affiliate: Pubkey::default(),
+            price,
+        }
+    }
+
+    // Build a clock at a UNIX timestamp.
+    fn clock_at(unix_timestamp: i64) -> Clock {
+        Clock { unix_timestamp, ..Clock::default() }
+    }
+
+    #[test]
+    fn record_bid_owes_nothing_for_the_first_bid() {
+        let mut auction = auction();
+        let bid = highest_bid(Pubkey::new_unique(), 150);
+        assert_eq!(auction.record_bid(&bid, &clock_at(500)).unwrap(), None);
+        assert_eq!(auction.stored_status().unwrap(), AuctionStatus::Live);
+        assert_eq!(auction.price, 150);
+        assert_eq!(auction.highest_bidder, bid.bidder);
+        assert_eq!(auction.highest_bidder_ft_temp_account, bid.ft_temp_account);
+        assert_eq!(auction.highest_bidder_ft_returning_account, bid.ft_returning_account);
+        assert_eq!(auction.highest_bidder_rent_payer, bid.rent_payer);
+        assert_eq!(auction.bid_count, 1);
+        assert_eq!(auction.end_at, 1_000);
+    }
+
+    #[test]
+    fn record_bid_returns_the_outbid_amount_after_updating_the_lead() {
+        let mut auction = auction();
+        auction.record_bid(&highest_bid(Pubkey::new_unique(), 150), &clock_at(500)).unwrap();
+        let bid = highest_bid(Pubkey::new_unique(), 200);
+        // The refund is the previous bid, while the escrow account already records the new one.
+        assert_eq!(auction.record_bid(&bid, &clock_at(600)).unwrap(), Some(150));
+        assert_eq!(auction.price, 200);
+        assert_eq!(auction.highest_bidder, bid.bidder);
+        assert_eq!(auction.bid_count, 2);
+    }
+
+    #[test]
+    fn record_bid_ends_dutch_auctions_and_extends_penny_auctions() {
+        let mut dutch = auction();
+        dutch.dutch_curve = DutchCurve::Linear as u8;
+        dutch.record_bid(&highest_bid(Pubkey::new_unique(), 100), &clock_at(500)).unwrap();
+        assert_eq!(dutch.end_at, 500);
+
+        let mut penny = auction();
+        penny.penny_tick = 1;
+        penny.penny_countdown = 100;
+        penny.record_bid(&highest_bid(Pubkey::new_unique(), 101), &clock_at(950)).unwrap();
+        assert_eq!(penny.end_at, 1_050);
+        // A reset never brings the end forward.
+        penny.record_bid(&highest_bid(Pubkey::new_unique(), 102), &clock_at(500)).unwrap();
+        assert_eq!(penny.end_at, 1_050);
+    }
+
+    #[test]
+    fn max_price_caps_bids_and_ends_the_auction() {
+        let mut auction = auction();
+        auction.max_price = 150;
+        assert_eq!(auction.capped_at_max_price(200), 150);
+        assert_eq!(auction.min_next_bid(&clock_at(500)).unwrap(), 101);
+
+        // A bid below the maximum price leaves the end as it is.
+        auction.record_bid(&highest_bid(Pubkey::new_unique(), 149), &clock_at(500)).unwrap();
+        assert_eq!(auction.end_at, 1_000);
+        // A penny auction's next tick never goes past the maximum price.
+        auction.penny_tick = 10;
+        auction.penny_countdown = 100;
+        assert_eq!(auction.min_next_bid(&clock_at(500)).unwrap(), 150);
+        // A bid reaching it ends the auction, even a penny auction's.
+        auction.record_bid(&highest_bid(Pubkey::new_unique(), 150), &clock_at(600)).unwrap();
+        assert_eq!(auction.end_at, 600);
+    }
+
+    #[test]
+    fn tick_size_rounds_the_next_bid_up_to_a_multiple() {
+        let mut auction = auction();
+        auction.tick_size = 1_000;
+        assert_eq!(auction.min_next_bid(&clock_at(500)).unwrap(), 1_000);
+        auction.price = 2_000;
+        assert_eq!(auction.min_next_bid(&clock_at(500)).unwrap(), 3_000);
+        assert!(auction.on_tick(3_000));
+        assert!(!auction.on_tick(3_500));
+
+        // A bid capped at the maximum price is accepted off the tick.
+        auction.max_price = 3_500;
+        assert_eq!(auction.min_next_bid(&clock_at(500)).unwrap(), 3_000);
+        assert!(auction.on_tick(3_700));
+
+        // No bid can follow the largest price, whatever the tick size.
+        auction.max_price = 0;
+        auction.price = u64::MAX;
+        for tick_size in [0, 1, 1_000] {
+            auction.tick_size = tick_size;
+            assert!(overflowed(auction.min_next_bid(&clock_at(500))));
+        }
+    }
+
+    #[test]
+    fn record_bid_counts_unique_bidders() {
+        let mut auction = auction();
+        assert_eq!(auction.unique_bidders(), 0);
+        let bidder = Pubkey::new_from_array([1; 32]);
+        auction.record_bid(&highest_bid(bidder, 101), &clock_at(500)).unwrap();
+        auction.record_bid(&highest_bid(Pubkey::new_from_array([2; 32]), 102), &clock_at(500)).unwrap();
+        // A bidder outbidding the others again is counted once.
+        auction.record_bid(&highest_bid(bidder, 103), &clock_at(500)).unwrap();
+        assert_eq!(auction.bid_count, 3);
+        assert_eq!(auction.unique_bidders(), 2);
+
+        // Bidders whose keys share a first byte are counted once.
+        let mut key = [2; 32];
+        key[31] = 3;
+        auction.record_bid(&highest_bid(Pubkey::new_from_array(key), 104), &clock_at(500)).unwrap();
+        assert_eq!(auction.unique_bidders(), 2);
+        // The estimate is bounded by the bitmap, not the lifetime bid count, which carries across rounds.
+        auction.bidder_bitmap = [u8::MAX; 32];
+        auction.bid_count = 10_000;
+        assert_eq!(auction.unique_bidders(), 256);
+    }
+
+    #[test]
+    fn check_referrer_rejects_accounts_of_the_bidder_and_their_session_key() {
+        let (bidder, session_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let self_referred = |result: Result<()>| {
+            ProgramError::from(result.unwrap_err()) == ProgramError::from(error!(AuctionError::SelfReferral))
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        assert!(check_referrer(&Pubkey::new_unique(), &bidder, Some(session_key)).is_ok());
+        assert!(self_referred(check_referrer(&bidder, &bidder, None)));
+        assert!(self_referred(check_referrer(&session_key, &bidder, Some(session_key))));
     }
 
-    // Define a function to create a context for setting the authority of the NFT to the PDA.
-    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
-        let cpi_accounts = SetAuthority {
-            account_or_mint: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            current_authority: self.exhibitor.clone(),
+    #[test]
+    fn check_affiliate_rejects_the_bidder_and_their_session_key() {
+        let (bidder, session_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let affiliate = Pubkey::new_unique();
+        let self_affiliated = |result: Result<()>| {
+            ProgramError::from(result.unwrap_err()) == ProgramError::from(error!(AuctionError::SelfAffiliate))
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        assert!(check_affiliate(None, &bidder, Some(session_key)).is_ok());
+        assert!(check_affiliate(Some(affiliate), &bidder, Some(session_key)).is_ok());
+        assert!(self_affiliated(check_affiliate(Some(bidder), &bidder, None)));
+        assert!(self_affiliated(check_affiliate(Some(session_key), &bidder, Some(session_key))));
     }
-}
 
-// Implement the Cancel struct.
-impl<'info> Cancel<'info> {
-    // Define a function to create a context for transferring NFTs back to the exhibitor.
-    fn to_transfer_to_exhibitor_context(
-        &self,
-    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            to: self
-                .exhibitor_nft_token_account
-                .to_account_info()
-                .clone(),
-            authority: self.pda.clone(),
+    // Build a payout of a share to a new recipient.
+    fn payout(share_bps: u16) -> Payout {
+        Payout { recipient_ft_pubkey: Pubkey::new_unique(), share_bps }
+    }
+
+    #[test]
+    fn check_payout_shares_caps_the_recipients_and_the_total_share() {
+        let failed_with = |result: Result<()>, error: AuctionError| {
+            ProgramError::from(result.unwrap_err()) == ProgramError::from(Error::from(error))
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        assert!(check_payout_shares(&[], 0).is_ok());
+        assert!(check_payout_shares(&[payout(2_500), payout(2_500), payout(2_500)], 2_500).is_ok());
+        assert!(failed_with(
+            check_payout_shares(&[payout(2_500), payout(2_500), payout(2_500)], 2_501),
+            AuctionError::InvalidPayoutShares
+        ));
+        // Shares that would wrap a u16 still add up past the whole.
+        assert!(failed_with(
+            check_payout_shares(&[payout(u16::MAX), payout(u16::MAX)], 2),
+            AuctionError::InvalidPayoutShares
+        ));
+        assert!(failed_with(
+            check_payout_shares(&[payout(0); MAX_PAYOUT_RECIPIENTS + 1], 0),
+            AuctionError::TooManyPayoutRecipients
+        ));
     }
 
-    // Define a function to create a context for closing the PDA-controlled escrow account.
-    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
-        let cpi_accounts = CloseAccount {
-            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            destination: self.exhibitor.clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn proceeds_shares_round_down_and_leave_the_dust_to_the_exhibitor() {
+        let mut auction = auction();
+        auction.payout_count = 2;
+        auction.payouts[0] = payout(3_333);
+        auction.payouts[1] = payout(3_333);
+        // A recorded payout past the count is not paid.
+        auction.payouts[2] = payout(1);
+        auction.charity_bps = 3_334;
+        let (payout_shares, charity_share) = auction.proceeds_shares(999).unwrap();
+        assert_eq!(payout_shares, vec![332, 332]);
+        assert_eq!(charity_share, 333);
+        // The whole of the proceeds is shared out, but the rounding leaves 2 for the exhibitor.
+        assert_eq!(999 - payout_shares.iter().sum::<u64>() - charity_share, 2);
+
+        // Shares of the whole proceeds never add up to more than the proceeds.
+        for proceeds in [0, 1, 3, 10_000, 10_001, u64::MAX] {
+            let (payout_shares, charity_share) = auction.proceeds_shares(proceeds).unwrap();
+            let total = payout_shares.iter().map(|&share| share as u128).sum::<u128>() + charity_share as u128;
+            assert!(total <= proceeds as u128);
+        }
+
+        auction.payout_count = 0;
+        auction.charity_bps = 0;
+        assert_eq!(auction.proceeds_shares(999).unwrap(), (vec![], 0));
+    }
+
+    #[test]
+    fn installment_plan_escrows_the_down_payment_and_schedules_the_rest() {
+        let mut auction = auction();
+        auction.price = 1_000;
+        auction.installment_count = 3;
+        auction.installment_interval_sec = 100;
+        auction.down_payment_bps = 4_000;
+        assert_eq!(auction.escrowed_amount(1_000).unwrap(), 400);
+        assert_eq!(auction.purchase_amount(1_000).unwrap(), 1_000);
+
+        // The down payment is due at the end, then a third of the rest every interval.
+        assert_eq!(auction.installments_due(1_000).unwrap(), 400);
+        assert_eq!(auction.installments_due(1_099).unwrap(), 400);
+        assert_eq!(auction.installments_due(1_100).unwrap(), 600);
+        assert_eq!(auction.installments_due(1_250).unwrap(), 800);
+        assert_eq!(auction.installments_due(9_000).unwrap(), 1_000);
+        assert!(!auction.installments_paid(999).unwrap());
+        assert!(auction.installments_paid(1_000).unwrap());
+
+        // Without a plan the whole purchase amount is escrowed and due.
+        auction.installment_count = 0;
+        assert_eq!(auction.escrowed_amount(1_000).unwrap(), 1_000);
+        assert!(auction.installments_paid(0).unwrap());
+    }
+
+    #[test]
+    fn record_bid_overflows_at_the_last_bid_index() {
+        let mut auction = auction();
+        auction.bid_count = u64::MAX;
+        assert!(overflowed(auction.record_bid(&highest_bid(Pubkey::new_unique(), 150), &clock_at(500))));
+    }
+
+    #[test]
+    fn reserve_commitment_depends_on_the_reserve_and_the_salt() {
+        let commitment = reserve_commitment(150, &[1; 32]);
+        assert_eq!(commitment, reserve_commitment(150, &[1; 32]));
+        assert_ne!(commitment, reserve_commitment(151, &[1; 32]));
+        assert_ne!(commitment, reserve_commitment(150, &[2; 32]));
+    }
+
+    #[test]
+    fn hidden_reserve_awaits_its_reveal_until_the_reveal_period_is_over() {
+        let mut auction = auction();
+        // An auction without a hidden reserve never waits.
+        assert!(!auction.awaits_reserve_reveal(&clock_at(1_000)).unwrap());
+
+        auction.hidden_reserve_commitment = reserve_commitment(150, &[1; 32]);
+        assert!(auction.awaits_reserve_reveal(&clock_at(1_000)).unwrap());
+        assert!(auction.awaits_reserve_reveal(&clock_at(1_000 + RESERVE_REVEAL_PERIOD_SEC - 1)).unwrap());
+        // Past the reveal period the reserve is forfeited and the highest bid wins.
+        assert!(!auction.awaits_reserve_reveal(&clock_at(1_000 + RESERVE_REVEAL_PERIOD_SEC)).unwrap());
+        assert!(auction.meets_hidden_reserve());
+
+        auction.hidden_reserve = 150;
+        auction.hidden_reserve_revealed = 1;
+        assert!(!auction.awaits_reserve_reveal(&clock_at(1_000)).unwrap());
+        assert!(!auction.meets_hidden_reserve());
+        auction.price = 150;
+        assert!(auction.meets_hidden_reserve());
+    }
+
+    #[test]
+    fn buyers_premium_is_escrowed_refunded_and_taken_as_the_house_fee() {
+        let mut auction = auction();
+        // Without a premium the house fee is a share of the escrowed amount.
+        assert_eq!(auction.escrowed_amount(1_000).unwrap(), 1_000);
+        assert_eq!(auction.house_fee(1_000, 250).unwrap(), 25);
+
+        auction.buyers_premium_bps = 250;
+        auction.record_bid(&highest_bid(Pubkey::new_unique(), 1_000), &clock_at(500)).unwrap();
+        assert_eq!(auction.escrowed_amount(auction.price).unwrap(), 1_025);
+        // The premium is the whole house fee, whatever the house fee is now.
+        assert_eq!(auction.house_fee(1_025, 500).unwrap(), 25);
+        // An outbid bidder gets their premium back too.
+        let refund = auction.record_bid(&highest_bid(Pubkey::new_unique(), 2_000), &clock_at(600)).unwrap();
+        assert_eq!(refund, Some(1_025));
+    }
+
+    #[test]
+    fn acceptance_period_follows_the_end_in_the_timing_mode() {
+        let mut auction = auction();
+        assert!(auction.in_acceptance_period(&clock_at(1_000 + ACCEPTANCE_PERIOD_SEC - 1)).unwrap());
+        assert!(!auction.in_acceptance_period(&clock_at(1_000 + ACCEPTANCE_PERIOD_SEC)).unwrap());
+
+        auction.timing_mode = TimingMode::Slot as u8;
+        let slots = ACCEPTANCE_PERIOD_SEC * 1_000 / DEFAULT_MS_PER_SLOT as i64;
+        let at_slot = |slot: i64| Clock { slot: slot as u64, ..Clock::default() };
+        assert!(auction.in_acceptance_period(&at_slot(1_000 + slots - 1)).unwrap());
+        assert!(!auction.in_acceptance_period(&at_slot(1_000 + slots)).unwrap());
+    }
+
+    #[test]
+    fn unwinding_skips_an_unusable_price_feed_after_the_grace_period() {
+        let mut auction = auction();
+        auction.reserve_usd_cents = 100;
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (mut lamports, mut data) = (0, vec![]);
+        let price_feed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let grace_over = 1_000 + PRICE_FEED_GRACE_PERIOD_SEC;
+        assert!(!auction.price_feed_grace_over(&clock_at(grace_over - 1)).unwrap());
+        assert!(auction.price_feed_grace_over(&clock_at(grace_over)).unwrap());
+        // Until then the feed's error stands, so an auction cannot be unwound on a passing outage.
+        assert!(usd_reserve_met_for_unwind(&auction, &price_feed, 200, 0, &clock_at(grace_over - 1)).is_err());
+        assert!(!usd_reserve_met_for_unwind(&auction, &price_feed, 200, 0, &clock_at(grace_over)).unwrap());
+        // An auction without a USD reserve never reads the feed.
+        auction.reserve_usd_cents = 0;
+        assert!(usd_reserve_met_for_unwind(&auction, &price_feed, 200, 0, &clock_at(grace_over)).unwrap());
+    }
+
+    #[test]
+    fn bid_phases_open_to_gated_bidders_then_to_everyone() {
+        let mut auction = auction();
+        // Without phases bidding is open and the gates apply until the end.
+        assert!(auction.bidding_open(&clock_at(0)).unwrap());
+        assert!(auction.in_allowlist_phase(&clock_at(999)).unwrap());
+
+        auction.allowlist_at = 100;
+        auction.public_at = 500;
+        assert!(!auction.bidding_open(&clock_at(99)).unwrap());
+        assert!(auction.bidding_open(&clock_at(100)).unwrap());
+        assert!(auction.in_allowlist_phase(&clock_at(499)).unwrap());
+        assert!(!auction.in_allowlist_phase(&clock_at(500)).unwrap());
+    }
+
+    #[test]
+    fn pool_bid_positions_match_the_bid_accounts() {
+        let key = Pubkey::new_unique;
+        let bid = accounts::Bid {
+            bidder: key(),
+            payer: key(),
+            bidder_ft_temp_account: key(),
+            bidder_ft_account: key(),
+            highest_bidder: key(),
+            highest_bidder_ft_temp_account: key(),
+            highest_bidder_ft_returning_account: key(),
+            highest_bidder_rent_payer: key(),
+            escrow_account: key(),
+            clock: key(),
+            bid_receipt: key(),
+            nft_blacklist_entry: key(),
+            payment_blacklist_entry: key(),
+            auction_house: key(),
+            instructions: key(),
+            pda: key(),
+            token_program: key(),
+            system_program: key(),
+            event_authority: key(),
+            program: key(),
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        let metas = anchor_lang::ToAccountMetas::to_account_metas(&bid, None);
+        assert_eq!(metas[bid_pools::BID_BIDDER_INDEX].pubkey, bid.bidder);
+        assert_eq!(metas[bid_pools::BID_BIDDER_FT_ACCOUNT_INDEX].pubkey, bid.bidder_ft_account);
+        assert_eq!(metas[bid_pools::BID_ESCROW_ACCOUNT_INDEX].pubkey, bid.escrow_account);
     }
-}
 
-// Implement the Bid struct.
-impl<'info> Bid<'info> {
-    // Define a function to create a context for setting the authority of the bidder's FT account to the PDA.
-    fn to_set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
-        let cpi_accounts = SetAuthority {
-            account_or_mint: self.bidder_ft_temp_account.to_account_info().clone(),
-            current_authority: self.bidder.clone(),
+    #[test]
+    fn pool_claim_positions_match_the_claim_accounts() {
+        let key = Pubkey::new_unique;
+        let claim = accounts::ClaimItem {
+            winning_bidder: key(),
+            exhibitor_nft_temp_account: key(),
+            highest_bidder_nft_receiving_account: key(),
+            exhibitor_rent_payer: key(),
+            highest_bidder_rent_payer: key(),
+            escrow_account: key(),
+            listing_receipt: key(),
+            clock: key(),
+            auction_house: key(),
+            pda: key(),
+            token_program: key(),
+            event_authority: key(),
+            program: key(),
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        let metas = anchor_lang::ToAccountMetas::to_account_metas(&claim, None);
+        assert_eq!(metas[bid_pools::CLAIM_WINNING_BIDDER_INDEX].pubkey, claim.winning_bidder);
+        assert_eq!(
+            metas[bid_pools::CLAIM_NFT_RECEIVING_ACCOUNT_INDEX].pubkey,
+            claim.highest_bidder_nft_receiving_account
+        );
+        assert_eq!(metas[bid_pools::CLAIM_ESCROW_ACCOUNT_INDEX].pubkey, claim.escrow_account);
     }
 
-    // Define a function to create a context for closing the previous highest bidder's temporary FT account.
-    fn to_close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
-        let cpi_accounts = CloseAccount {
-            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
-            destination: self.highest_bidder.clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn winning_pool_is_over_once_claimed_and_its_fractions_redeem_the_item() {
+        let pool_key = Pubkey::new_unique();
+        let mut pool = BidPool {
+            auction: Pubkey::new_unique(),
+            operator: Pubkey::new_unique(),
+            payment_mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            total_contributed: 300,
+            contribution_count: 2,
+            settled: false,
+            settled_balance: 0,
+            item_mint: Pubkey::new_unique(),
+            item_account: Pubkey::default(),
+            fraction_mint: Pubkey::default(),
+            created_at: 0,
+            bump: 255,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+
+        // The pool wins: the auction ends with the pool as the highest bidder and is finalized without
+        // settling, so the pool is not done until it claims the NFT.
+        let mut auction = auction();
+        auction.record_bid(&highest_bid(pool_key, 250), &clock_at(500)).unwrap();
+        assert!(!pool_auction_over(&auction, &pool_key, &clock_at(2_000)).unwrap());
+        // Another bidder's pool is done once the auction ends.
+        assert!(pool_auction_over(&auction, &Pubkey::new_unique(), &clock_at(2_000)).unwrap());
+        auction.item_claimed = 1;
+        assert!(pool_auction_over(&auction, &pool_key, &clock_at(2_000)).unwrap());
+
+        // The contributions of 100 and 200 are worth as many fractions, and whoever holds every one of them
+        // redeems the NFT, but not before every contribution has claimed its fractions.
+        pool.settled = true;
+        let fractions = [100u64, 200];
+        assert!(!pool.redeems_item(fractions[0], fractions[0]));
+        let supply: u64 = fractions.iter().sum();
+        assert!(!pool.redeems_item(supply, fractions[1]));
+        assert!(pool.redeems_item(supply, supply));
     }
 
-    // Define a function to create a context for transferring the current highest bid amount back to the previous highest bidder.
-    fn to_transfer_to_previous_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
-            to: self
-                .highest_bidder_ft_returning_account
-                .to_account_info()
-                .clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn series_lot_is_done_once_settled_or_ended_without_a_bid() {
+        let series = Pubkey::new_unique();
+        let mut lot = auction();
+        lot.series = series;
+        assert!(!lot.lot_done(series, &clock_at(999)).unwrap());
+        assert!(lot.lot_done(series, &clock_at(1_000)).unwrap());
+        // A lot taken out of the series no longer holds it up.
+        assert!(lot.lot_done(Pubkey::new_unique(), &clock_at(999)).unwrap());
+
+        lot.set_status(AuctionStatus::Live);
+        assert!(!lot.lot_done(series, &clock_at(1_000)).unwrap());
+        lot.set_status(AuctionStatus::Settled);
+        assert!(lot.lot_done(series, &clock_at(1_000)).unwrap());
+    }
+
+    #[test]
+    fn listing_token_accounts_come_last_in_settlement() {
+        let mut lot = auction();
+        let count = lot.settlement_account_count();
+        lot.listing_token_mint = Pubkey::new_unique();
+        assert_eq!(lot.settlement_account_count(), count + 2);
+    }
+
+    #[test]
+    fn wrapped_sol_buyout_proceeds_stay_in_the_vault_ft_account() {
+        let mut lot = auction();
+        // Proceeds in another mint are transferred, and wrapped SOL proceeds unwrapped.
+        assert!(!lot.unwraps_proceeds());
+        lot.payment_mint = native_mint::id();
+        assert!(lot.unwraps_proceeds());
+        // A buyout's vault program settles against its FT receiving account, so they stay wrapped there.
+        lot.buyout_program = Pubkey::new_unique();
+        assert!(!lot.unwraps_proceeds());
+    }
+
+    #[test]
+    fn collection_stats_track_volume_and_floor() {
+        let mut stats = CollectionStats {
+            collection: Pubkey::default(),
+            payment_mint: Pubkey::default(),
+            total_volume: 0,
+            sale_count: 0,
+            last_sale_price: 0,
+            last_sale_at: 0,
+            floor_price: 0,
+            created_at: 0,
+            bump: 0,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        stats.record_sale(300, 10);
+        stats.record_sale(200, 20);
+        stats.record_sale(500, 30);
+        assert_eq!(stats.total_volume, 1_000);
+        assert_eq!(stats.sale_count, 3);
+        assert_eq!(stats.last_sale_price, 500);
+        assert_eq!(stats.last_sale_at, 30);
+        assert_eq!(stats.floor_price, 200);
     }
 
-    // Define a function to create a context for transferring the bid amount from the bidder's FT account to the PDA-controlled escrow account.
-    fn to_transfer_to_pda_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.bidder_ft_account.to_account_info().clone(),
-            to: self
-                .bidder_ft_temp_account
-                .to_account_info()
-                .clone(),
-            authority: self.bidder.clone(),
+    #[test]
+    fn house_stats_saturate_instead_of_failing_settlement() {
+        let mut stats = HouseStats {
+            payment_mint: Pubkey::default(),
+            total_volume: u64::MAX - 100,
+            total_auctions: 0,
+            total_fees: 0,
+            created_at: 0,
+            bump: 0,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        stats.record_sale(1_000, 25);
+        assert_eq!(stats.total_volume, u64::MAX);
+        assert_eq!(stats.total_auctions, 1);
+        assert_eq!(stats.total_fees, 25);
     }
-}
 
-// Implement the Close struct.
-impl<'info> Close<'info> {
-    // Define a function to create a context for transferring the NFT from the escrow account to the highest bidder.
-    fn to_transfer_to_highest_bidder_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            to: self
-                .highest_bidder_nft_receiving_account
-                .to_account_info()
-                .clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn house_stats_accumulate_the_fee_the_treasury_received() {
+        let mut receipt =
+            PurchaseReceipt::try_deserialize_unchecked(&mut &[0; PurchaseReceipt::LEN][..]).unwrap();
+        let mut lot = auction();
+        lot.price = 1_000;
+        lot.highest_bidder_affiliate = Pubkey::new_unique();
+        // A 25 house fee, of which the referrer took 2 and the affiliate 3.
+        let shares = SaleShares {
+            creators: Vec::new(),
+            affiliate_fee: 3,
+            treasury_fee: 20,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        record_purchase(&mut receipt, Pubkey::new_unique(), &lot, &shares, 0, 255).unwrap();
+        let mut stats = HouseStats::try_deserialize_unchecked(&mut &[0; HouseStats::LEN][..]).unwrap();
+        stats.record_sale(receipt.price, receipt.house_fee);
+        assert_eq!(stats.total_volume, 1_000);
+        assert_eq!(stats.total_fees, 20);
     }
 
-    // Define a function to create a context for transferring the highest bid amount from the escrow account to the exhibitor.
-    fn to_transfer_to_exhibitor_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.highest_bidder_ft_temp_account.to_account_info().clone(),
-            to: self
-                .exhibitor_ft_receiving_account
-                .to_account_info()
-                .clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn feature_flags_gate_only_their_feature() {
+        let mut house = house();
+        house.features = FEATURES_ALL & !FEATURE_SOL_BIDS;
+        assert!(house.require_enabled(FEATURE_DUTCH).is_ok());
+        assert!(house.require_enabled(FEATURE_SOL_BIDS).is_err());
+        house.features = 0;
+        assert!(!house.enabled(FEATURE_ROYALTIES));
+    }
+
+    #[test]
+    fn house_roles_extend_but_never_replace_the_authority() {
+        let authority = Pubkey::new_unique();
+        let pauser = Pubkey::new_unique();
+        let mut house = house();
+        house.authority = authority;
+        assert!(!house.has_role(HouseRole::Pauser, &pauser));
+        // An unassigned role is not held by the default pubkey.
+        assert!(!house.has_role(HouseRole::Pauser, &Pubkey::default()));
+        house.pauser = pauser;
+        assert!(house.has_role(HouseRole::Pauser, &pauser));
+        assert!(!house.has_role(HouseRole::FeeManager, &pauser));
+        assert!(house.has_role(HouseRole::FeeManager, &authority));
+    }
+
+    #[test]
+    fn bid_bond_is_paid_to_the_treasury_on_top_of_the_escrowed_amount() {
+        let mut auction = auction();
+        // Without a bond a bid costs what it escrows.
+        assert_eq!(auction.bid_bond(1_000).unwrap(), 0);
+        assert_eq!(auction.bid_cost(1_000).unwrap(), 1_000);
+
+        auction.bid_bond = 10;
+        auction.buyers_premium_bps = 250;
+        assert_eq!(auction.bid_bond(1_000).unwrap(), 10);
+        assert_eq!(auction.escrowed_amount(1_000).unwrap(), 1_025);
+        assert_eq!(auction.bid_cost(1_000).unwrap(), 1_035);
+
+        // A bond in basis points is a share of the bid, not of the premium.
+        auction.bid_bond = 0;
+        auction.bid_bond_bps = 50;
+        assert_eq!(auction.bid_bond(1_000).unwrap(), 5);
+        // A penny auction's bid pays its fee on top of the bond.
+        auction.penny_tick = 1;
+        auction.penny_bid_fee = 3;
+        assert_eq!(auction.treasury_bid_fee(1_000).unwrap(), 8);
+        assert_eq!(auction.bid_cost(1_000).unwrap(), 1_033);
+    }
+
+    #[test]
+    fn print_edition_ix_numbers_the_edition_and_its_marker() {
+        let accounts = prints::PrintEdition {
+            new_metadata: Pubkey::new_unique(),
+            new_edition: Pubkey::new_unique(),
+            master_edition: Pubkey::new_unique(),
+            new_mint: Pubkey::new_unique(),
+            edition_marker: Pubkey::new_unique(),
+            new_mint_authority: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            master_token_owner: Pubkey::new_unique(),
+            master_token_account: Pubkey::new_unique(),
+            new_update_authority: Pubkey::new_unique(),
+            master_metadata: Pubkey::new_unique(),
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        let ix = prints::print_edition_ix(&accounts, 300);
+        assert_eq!(ix.data[0], 11);
+        assert_eq!(ix.data[1..], 300u64.to_le_bytes());
+        // Only the new mint authority, the payer and the master edition's owner sign.
+        let signers = ix.accounts.iter().filter(|meta| meta.is_signer).map(|meta| meta.pubkey).collect::<Vec<_>>();
+        assert_eq!(signers, vec![accounts.new_mint_authority, accounts.payer, accounts.master_token_owner]);
+
+        // Every marker records 248 editions.
+        let mint = Pubkey::new_unique();
+        assert_eq!(metadata::edition_marker_address(&mint, 1), metadata::edition_marker_address(&mint, 247));
+        assert_ne!(metadata::edition_marker_address(&mint, 247), metadata::edition_marker_address(&mint, 248));
     }
 
-    // Define a function to create a context for closing the highest bidder's temporary FT account.
-    fn to_close_ft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
-        let cpi_accounts = CloseAccount {
-            account: self.highest_bidder_ft_temp_account.to_account_info().clone(),
-            destination: self.winning_bidder.clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn domain_transfer_is_signed_by_the_current_owner_only() {
+        let (name_account, owner, new_owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = sns::transfer_ix(name_account, owner, new_owner);
+        assert_eq!(ix.program_id, sns::NAME_SERVICE_PROGRAM_ID);
+        assert_eq!(ix.data[0], 2);
+        assert_eq!(ix.data[1..], new_owner.to_bytes());
+        assert!(ix.accounts[0].is_writable && !ix.accounts[0].is_signer);
+        assert!(ix.accounts[1].pubkey == owner && ix.accounts[1].is_signer);
+
+        // Auctions from before item types sell NFTs.
+        assert_eq!(auction().item_type().unwrap(), ItemType::Nft);
+        assert!(ItemType::try_from(3).is_err());
+    }
+
+    #[test]
+    fn purchase_receipt_records_what_each_creator_is_owed() {
+        let mut receipt = PurchaseReceipt {
+            auction: Pubkey::new_unique(),
+            seller: Pubkey::new_unique(),
+            buyer: Pubkey::new_unique(),
+            nft_mint: Pubkey::new_unique(),
+            payment_mint: Pubkey::new_unique(),
+            price: 0,
+            house_fee: 0,
+            created_at: 0,
+            bump: 0,
+            royalty_creators: [Pubkey::default(); MAX_CREATORS],
+            royalty_owed: [0; MAX_CREATORS],
+            affiliate: Pubkey::default(),
+            affiliate_owed: 0,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        let mut lot = auction();
+        lot.price = 1_000;
+        let creators = [(Pubkey::new_unique(), 30), (Pubkey::new_unique(), 20)];
+        let shares = SaleShares {
+            creators: creators.to_vec(),
+            affiliate_fee: 0,
+            treasury_fee: 25,
+        };
+        record_purchase(&mut receipt, Pubkey::new_unique(), &lot, &shares, 0, 255).unwrap();
+        assert_eq!(receipt.house_fee, 25);
+        assert_eq!(receipt.royalty_creators[..2], [creators[0].0, creators[1].0]);
+        assert_eq!(receipt.royalty_owed, [30, 20, 0, 0, 0]);
+        assert_eq!(receipt.affiliate, Pubkey::default());
+
+        // A winning bid's affiliate is owed the fee escrowed for it.
+        lot.highest_bidder_affiliate = Pubkey::new_unique();
+        let shares = SaleShares {
+            creators: Vec::new(),
+            affiliate_fee: 5,
+            treasury_fee: 20,
+        };
+        record_purchase(&mut receipt, Pubkey::new_unique(), &lot, &shares, 0, 255).unwrap();
+        assert_eq!(receipt.affiliate, lot.highest_bidder_affiliate);
+        assert_eq!(receipt.affiliate_owed, 5);
+        // The house fee recorded is what the treasury received, net of the affiliate's share.
+        assert_eq!(receipt.house_fee, 20);
+
+        // The receipt fits its account.
+        let mut data = Vec::new();
+        receipt.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), PurchaseReceipt::LEN);
     }
 
-    // Define a function to create a context for closing the exhibitor's temporary NFT account.
-    fn to_close_nft_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
-        let cpi_accounts = CloseAccount {
-            account: self.exhibitor_nft_temp_account.to_account_info().clone(),
-            destination: self.exhibitor.clone(),
-            authority: self.pda.clone(),
+    #[test]
+    fn stored_bumps_rederive_the_fixed_pdas() {
+        let mut house = house();
+        house.store_bumps();
+        let seeds = [ESCROW_PDA_SEED, &[house.escrow_bump]];
+        let escrow_pda = Pubkey::create_program_address(&seeds, &crate::ID).unwrap();
+        assert_eq!(escrow_pda, Pubkey::find_program_address(&[ESCROW_PDA_SEED], &crate::ID).0);
+        let seeds = [events::EVENT_AUTHORITY_SEED, &[house.event_authority_bump]];
+        let event_authority = Pubkey::create_program_address(&seeds, &crate::ID).unwrap();
+        assert_eq!(event_authority, Pubkey::find_program_address(&[events::EVENT_AUTHORITY_SEED], &crate::ID).0);
+
+        // The bumps fit the account.
+        let mut data = Vec::new();
+        house.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), AuctionHouse::LEN);
+    }
+
+    #[test]
+    fn dispute_window_holds_the_sale_until_its_deadline() {
+        let mut auction = auction();
+        // Auctions outside arbitration mode, and those finalized before it existed, are never held.
+        assert!(!auction.in_dispute_window(&clock_at(0)));
+        assert!(!auction.in_dispute_window(&clock_at(1_000)));
+
+        auction.dispute_window_sec = 600;
+        auction.dispute_deadline = 1_600;
+        assert!(auction.in_dispute_window(&clock_at(1_000)));
+        assert!(auction.in_dispute_window(&clock_at(1_599)));
+        assert!(!auction.in_dispute_window(&clock_at(1_600)));
+    }
+
+    #[test]
+    fn bid_session_spends_within_its_cap() {
+        let mut session = BidSession {
+            wallet: Pubkey::new_unique(),
+            session_key: Pubkey::new_unique(),
+            ft_account: Pubkey::new_unique(),
+            spending_cap: 1_000,
+            spent: 0,
+            expires_at: 0,
+            bump: 0,
         };
-        CpiContext::new(self.token_program.clone(), cpi_accounts)
+        session.spend(600).unwrap();
+        session.spend(400).unwrap();
+        assert_eq!(session.spent, 1_000);
+        // A bid past the cap is refused and leaves the spent total as it was.
+        assert!(session.spend(1).is_err());
+        assert_eq!(session.spent, 1_000);
+        assert!(session.spend(u64::MAX).is_err());
+
+        // The session fits its account.
+        let mut data = Vec::new();
+        session.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), BidSession::LEN);
     }
-}
 
-// Define the Auction struct to represent the auction state.
-#[account]
-pub struct Auction {
-    // The exhibitor's public key.
-    pub exhibitor_pubkey: Pubkey,
-    // The exhibitor's FT receiving account public key.
-    pub exhibitor_ft_receiving_pubkey: Pubkey,
-    // The exhibitor's temporary NFT account public key.
-    pub exhibiting_nft_temp_pubkey: Pubkey,
-    // The highest bidder's public key.
-    pub highest_bidder_pubkey: Pubkey,
-    // The highest bidder's FT temporary account public key.
-    pub highest_bidder_ft_temp_pubkey: Pubkey,
-    // The highest bidder's FT returning account public key.
-    pub highest_bidder_ft_returning_pubkey: Pubkey,
-    // The current highest bid amount.
-    pub price: u64,
-    // The auction end time in UNIX timestamp.
-    pub end_at: i64,
+    #[test]
+    fn royalty_override_replaces_or_caps_the_seller_fee() {
+        let mut royalty_override = RoyaltyOverride {
+            collection: Pubkey::default(),
+            royalty_bps: 250,
+            cap: false,
+            created_at: 0,
+            bump: 0,
+        };
+        assert_eq!(royalty_override.apply(1_000), 250);
+        assert_eq!(royalty_override.apply(100), 250);
+
+        royalty_override.cap = true;
+        assert_eq!(royalty_override.apply(1_000), 250);
+        assert_eq!(royalty_override.apply(100), 100);
+    }
+
+    #[test]
+    fn vested_does_not_overflow_on_large_totals() {
+        let schedule = schedule(u64::MAX, 0, u64::MAX);
+        assert_eq!(schedule.vested(i64::MAX).unwrap(), i64::MAX as u64 - 1_000);
+    }
 }
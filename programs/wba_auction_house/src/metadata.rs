@@ -0,0 +1,138 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Define the Metaplex Token Metadata program that owns NFT metadata accounts.
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// Define the seed of a metadata PDA, followed by the metadata program and the mint.
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+// Define the seed following the mint in an edition PDA.
+pub const EDITION_SEED: &[u8] = b"edition";
+
+// Define the number of edition numbers each edition marker account records.
+pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+// Define the account key the metadata program tags metadata accounts with.
+const KEY_METADATA_V1: u8 = 4;
+
+// Define the account key the metadata program tags master edition accounts with.
+const KEY_MASTER_EDITION_V2: u8 = 6;
+
+// Define the Creator struct to represent a creator listed in a metadata account.
+#[derive(AnchorDeserialize)]
+pub struct Creator {
+    // The creator's public key.
+    pub address: Pubkey,
+    // Whether the creator signed the metadata.
+    pub verified: bool,
+    // The creator's share of the royalties in percent.
+    pub share: u8,
+}
+
+// Define the Collection struct to represent the collection a metadata account belongs to.
+#[derive(AnchorDeserialize)]
+pub struct Collection {
+    // Whether the collection's authority verified the membership.
+    pub verified: bool,
+    // The mint of the collection NFT.
+    pub key: Pubkey,
+}
+
+// Define the Metadata struct to hold the leading fields of a metadata account, up to its collection.
+// Later fields are not read.
+#[derive(AnchorDeserialize)]
+pub struct Metadata {
+    // The authority allowed to update the metadata.
+    pub update_authority: Pubkey,
+    // The mint the metadata describes.
+    pub mint: Pubkey,
+    // The name of the token, padded with zeros.
+    pub name: String,
+    // The symbol of the token, padded with zeros.
+    pub symbol: String,
+    // The URI of the token's JSON metadata, padded with zeros.
+    pub uri: String,
+    // The royalty taken on secondary sales in basis points.
+    pub seller_fee_basis_points: u16,
+    // The creators sharing the royalties.
+    pub creators: Option<Vec<Creator>>,
+    // Whether the token has been sold before.
+    pub primary_sale_happened: bool,
+    // Whether the metadata can still be updated.
+    pub is_mutable: bool,
+    // The bump seed of the edition PDA, if known.
+    pub edition_nonce: Option<u8>,
+    // The token standard, if set.
+    pub token_standard: Option<u8>,
+    // The collection the token belongs to, if any.
+    pub collection: Option<Collection>,
+}
+
+// Derive the metadata PDA of a mint.
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+// Derive the edition PDA of a mint, which holds its master edition or, for a print, its edition.
+pub fn edition_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref(), EDITION_SEED],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+// Derive the edition marker PDA recording whether an edition number of a master edition was printed.
+pub fn edition_marker_address(master_mint: &Pubkey, edition: u64) -> Pubkey {
+    let marker = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    Pubkey::find_program_address(
+        &[
+            METADATA_SEED,
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            master_mint.as_ref(),
+            EDITION_SEED,
+            marker.as_bytes(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+// Check whether an account is a master edition of the metadata program, which editions can be printed
+// from.
+pub fn is_master_edition(edition: &AccountInfo) -> Result<bool> {
+    let data = edition.try_borrow_data()?;
+    Ok(*edition.owner == TOKEN_METADATA_PROGRAM_ID && data.first() == Some(&KEY_MASTER_EDITION_V2))
+}
+
+// Load the metadata of a mint, ensuring the account is the mint's metadata PDA and belongs to the
+// metadata program.
+pub fn load_metadata(metadata: &AccountInfo, mint: &Pubkey) -> Result<Metadata> {
+    require_keys_eq!(*metadata.owner, TOKEN_METADATA_PROGRAM_ID, AuctionError::InvalidMetadata);
+    require_keys_eq!(metadata.key(), metadata_address(mint), AuctionError::InvalidMetadata);
+    let data = metadata.try_borrow_data()?;
+    decode_metadata(&data)
+}
+
+// Decode the data of a metadata account.
+pub fn decode_metadata(data: &[u8]) -> Result<Metadata> {
+    let (key, mut body) = data.split_first().ok_or(AuctionError::InvalidMetadata)?;
+    require!(*key == KEY_METADATA_V1, AuctionError::InvalidMetadata);
+    Metadata::deserialize(&mut body).map_err(|_| error!(AuctionError::InvalidMetadata))
+}
+
+// Check whether a metadata account belongs to a collection, with its membership verified.
+pub fn is_verified_member(metadata: &Metadata, collection: &Pubkey) -> bool {
+    metadata
+        .collection
+        .as_ref()
+        .map_or(false, |member_of| member_of.verified && member_of.key == *collection)
+}
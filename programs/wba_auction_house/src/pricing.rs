@@ -0,0 +1,193 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Define the DutchCurve enum to represent how the price of a Dutch auction decays from its starting
+// price to its floor price. The program and the client SDK both price Dutch auctions with dutch_price.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DutchCurve {
+    // The auction is not a Dutch auction: bids compete upwards until it ends.
+    None,
+    // The price falls at a constant rate, reaching the floor price at the end of the auction.
+    Linear,
+    // The price's distance to the floor price halves every curve parameter seconds or slots.
+    Exponential,
+    // The price falls in equal steps every curve parameter seconds or slots, reaching the floor price by
+    // the end of the auction.
+    Stepped,
+}
+
+// Implement the conversion from a stored Dutch curve byte.
+impl TryFrom<u8> for DutchCurve {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(dutch_curve: u8) -> Result<Self> {
+        match dutch_curve {
+            0 => Ok(DutchCurve::None),
+            1 => Ok(DutchCurve::Linear),
+            2 => Ok(DutchCurve::Exponential),
+            3 => Ok(DutchCurve::Stepped),
+            _ => err!(AuctionError::InvalidDutchCurve),
+        }
+    }
+}
+
+// Define the DutchPricing struct to hold the parameters of a Dutch auction's price decay. Times are in
+// the auction's timing mode.
+#[derive(Clone, Copy, Debug)]
+pub struct DutchPricing {
+    // The shape of the decay.
+    pub curve: DutchCurve,
+    // The price when the decay starts.
+    pub start_price: u64,
+    // The lowest price the decay reaches.
+    pub floor_price: u64,
+    // When the decay starts.
+    pub start_at: i64,
+    // When the auction ends, by which the linear and stepped curves reach the floor price.
+    pub end_at: i64,
+    // The half-life of the exponential curve or the step interval of the stepped curve (unused by the
+    // linear curve).
+    pub curve_param: u64,
+}
+
+// Implement the DutchPricing struct.
+impl DutchPricing {
+    // Ensure the parameters describe a decay the auction can follow.
+    pub fn validate(&self) -> Result<()> {
+        require!(self.floor_price <= self.start_price, AuctionError::InvalidDutchCurve);
+        require!(
+            !matches!(self.curve, DutchCurve::Exponential | DutchCurve::Stepped) || self.curve_param > 0,
+            AuctionError::InvalidDutchCurve
+        );
+        Ok(())
+    }
+}
+
+// Compute the price of a Dutch auction at a point in time, in the auction's timing mode. The price is
+// rounded in the exhibitor's favour, never falls below the floor price, and stops decaying at the end of
+// the auction.
+pub fn dutch_price(pricing: &DutchPricing, now: i64) -> u64 {
+    let range = pricing.start_price.saturating_sub(pricing.floor_price) as u128;
+    let duration = pricing.end_at.saturating_sub(pricing.start_at).max(1) as u128;
+    let elapsed = (now.saturating_sub(pricing.start_at).max(0) as u128).min(duration);
+
+    // The part of the range the price has dropped by.
+    let dropped = match pricing.curve {
+        DutchCurve::None => 0,
+        DutchCurve::Linear => range * elapsed / duration,
+        DutchCurve::Exponential => {
+            // The remaining distance to the floor halves every half-life, interpolating linearly within
+            // one.
+            let half_life = pricing.curve_param.max(1) as u128;
+            let halvings = elapsed / half_life;
+            let remaining = if halvings >= 128 {
+                0
+            } else {
+                let current = range >> halvings;
+                let next = current >> 1;
+                current - (current - next) * (elapsed % half_life) / half_life
+            };
+            range - remaining
+        }
+        DutchCurve::Stepped => {
+            let interval = pricing.curve_param.max(1) as u128;
+            let steps = (duration / interval).max(1);
+            let taken = (elapsed / interval).min(steps);
+            range * taken / steps
+        }
+    };
+    pricing.start_price - dropped as u64
+}
+
+// Define the BondingCurve enum to represent how the price of an open edition sale grows with the number of
+// editions sold. The program and the client SDK both price editions with edition_price.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BondingCurve {
+    // The price grows by the curve parameter with every edition sold.
+    Linear,
+    // The price grows by the curve parameter times the square of the number of editions sold.
+    Quadratic,
+}
+
+// Implement the conversion from a stored bonding curve byte.
+impl TryFrom<u8> for BondingCurve {
+    type Error = anchor_lang::error::Error;
+
+    fn try_from(bonding_curve: u8) -> Result<Self> {
+        match bonding_curve {
+            0 => Ok(BondingCurve::Linear),
+            1 => Ok(BondingCurve::Quadratic),
+            _ => err!(AuctionError::InvalidBondingCurve),
+        }
+    }
+}
+
+// Compute the price of the next edition of an open edition sale that has sold a number of editions.
+pub fn edition_price(curve: BondingCurve, base_price: u64, curve_param: u64, sold: u64) -> Result<u64> {
+    let steps = match curve {
+        BondingCurve::Linear => sold as u128,
+        BondingCurve::Quadratic => sold as u128 * sold as u128,
+    };
+    (curve_param as u128)
+        .checked_mul(steps)
+        .and_then(|increase| increase.checked_add(base_price as u128))
+        .and_then(|price| u64::try_from(price).ok())
+        .ok_or_else(|| error!(AuctionError::NumericalOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build the pricing of a Dutch auction decaying from u64::MAX to zero between 0 and 1000.
+    fn pricing(curve: DutchCurve, curve_param: u64) -> DutchPricing {
+        DutchPricing {
+            curve,
+            start_price: u64::MAX,
+            floor_price: 0,
+            start_at: 0,
+            end_at: 1_000,
+            curve_param,
+        }
+    }
+
+    #[test]
+    fn dutch_price_stays_within_the_start_and_floor_prices() {
+        for curve in [DutchCurve::Linear, DutchCurve::Exponential, DutchCurve::Stepped] {
+            let pricing = pricing(curve, 100);
+            assert_eq!(dutch_price(&pricing, i64::MIN), u64::MAX);
+            assert_eq!(dutch_price(&pricing, 0), u64::MAX);
+            assert!(dutch_price(&pricing, 500) < u64::MAX);
+            assert_eq!(dutch_price(&pricing, i64::MAX), dutch_price(&pricing, 1_000));
+        }
+        assert_eq!(dutch_price(&pricing(DutchCurve::Linear, 0), 1_000), 0);
+        assert_eq!(dutch_price(&pricing(DutchCurve::Stepped, 100), 1_000), 0);
+    }
+
+    #[test]
+    fn dutch_price_reaches_the_floor_after_many_halvings() {
+        let pricing = pricing(DutchCurve::Exponential, 1);
+        assert_eq!(dutch_price(&pricing, 1_000), 0);
+    }
+
+    #[test]
+    fn dutch_price_handles_an_empty_duration() {
+        let pricing = DutchPricing { end_at: 0, ..pricing(DutchCurve::Linear, 0) };
+        assert_eq!(dutch_price(&pricing, 0), u64::MAX);
+        assert_eq!(dutch_price(&pricing, 1), 0);
+    }
+
+    #[test]
+    fn edition_price_overflows_past_u64() {
+        assert_eq!(edition_price(BondingCurve::Linear, u64::MAX, 0, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(edition_price(BondingCurve::Linear, u64::MAX - 1, 1, 1).unwrap(), u64::MAX);
+        assert!(edition_price(BondingCurve::Linear, u64::MAX, 1, 1).is_err());
+        assert_eq!(edition_price(BondingCurve::Quadratic, 0, 1, u32::MAX as u64).unwrap(), (u32::MAX as u64).pow(2));
+        assert!(edition_price(BondingCurve::Quadratic, 0, 1, u32::MAX as u64 + 1).is_err());
+        assert!(edition_price(BondingCurve::Quadratic, 0, u64::MAX, u64::MAX).is_err());
+    }
+}
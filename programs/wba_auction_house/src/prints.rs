@@ -0,0 +1,63 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction type from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+// Import the Metaplex metadata program ID.
+use crate::metadata::TOKEN_METADATA_PROGRAM_ID;
+
+// Define the index of the metadata program's MintNewEditionFromMasterEditionViaToken instruction.
+const MINT_NEW_EDITION_FROM_MASTER_EDITION_VIA_TOKEN: u8 = 11;
+
+// Define the PrintEdition struct to hold the accounts of a metadata program instruction printing an
+// edition of a master edition.
+pub struct PrintEdition {
+    // The metadata PDA of the new mint, created by the print.
+    pub new_metadata: Pubkey,
+    // The edition PDA of the new mint, created by the print.
+    pub new_edition: Pubkey,
+    // The master edition PDA of the exhibited mint.
+    pub master_edition: Pubkey,
+    // The new mint, which must have a supply of 1. The metadata program takes over its authorities.
+    pub new_mint: Pubkey,
+    // The edition marker PDA recording which edition numbers have been printed.
+    pub edition_marker: Pubkey,
+    // The mint authority of the new mint, which signs.
+    pub new_mint_authority: Pubkey,
+    // The payer of the new metadata, edition and marker accounts, which signs.
+    pub payer: Pubkey,
+    // The owner of the token account holding the master edition, which signs.
+    pub master_token_owner: Pubkey,
+    // The token account holding the master edition.
+    pub master_token_account: Pubkey,
+    // The update authority of the new metadata, which must be the master edition's.
+    pub new_update_authority: Pubkey,
+    // The metadata PDA of the exhibited mint.
+    pub master_metadata: Pubkey,
+}
+
+// Build the metadata program instruction printing the given edition number of a master edition to a new
+// mint, authorized by the owner of the token account holding the master edition.
+pub fn print_edition_ix(accounts: &PrintEdition, edition: u64) -> Instruction {
+    let mut data = vec![MINT_NEW_EDITION_FROM_MASTER_EDITION_VIA_TOKEN];
+    data.extend_from_slice(&edition.to_le_bytes());
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.new_metadata, false),
+            AccountMeta::new(accounts.new_edition, false),
+            AccountMeta::new(accounts.master_edition, false),
+            AccountMeta::new(accounts.new_mint, false),
+            AccountMeta::new(accounts.edition_marker, false),
+            AccountMeta::new_readonly(accounts.new_mint_authority, true),
+            AccountMeta::new(accounts.payer, true),
+            AccountMeta::new_readonly(accounts.master_token_owner, true),
+            AccountMeta::new_readonly(accounts.master_token_account, false),
+            AccountMeta::new_readonly(accounts.new_update_authority, false),
+            AccountMeta::new_readonly(accounts.master_metadata, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(solana_program::system_program::ID, false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+        ],
+        data,
+    }
+}
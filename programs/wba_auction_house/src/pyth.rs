@@ -0,0 +1,228 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Define the Pyth oracle program that owns the price accounts.
+#[cfg(not(feature = "devnet"))]
+pub const PYTH_PROGRAM_ID: Pubkey = solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+#[cfg(feature = "devnet")]
+pub const PYTH_PROGRAM_ID: Pubkey = solana_program::pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+
+// Define the maximum age of a price, in seconds, before it is considered stale.
+pub const MAX_PRICE_AGE_SEC: i64 = 60;
+// Define the maximum confidence interval, in basis points of the price, before it is considered too uncertain.
+pub const MAX_CONFIDENCE_BPS: u64 = 200;
+
+// Define the layout constants of a Pyth v2 price account.
+const MAGIC: u32 = 0xa1b2_c3d4;
+const VERSION_2: u32 = 2;
+const ACCOUNT_TYPE_PRICE: u32 = 3;
+const STATUS_TRADING: u32 = 1;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+// Define the PythPrice struct to represent the aggregate price of a feed.
+pub struct PythPrice {
+    // The aggregate price, scaled by 10^expo.
+    pub price: i64,
+    // The confidence interval around the price, scaled by 10^expo.
+    pub conf: u64,
+    // The price exponent.
+    pub expo: i32,
+    // The time the aggregate price was published, in UNIX timestamp.
+    pub publish_time: i64,
+}
+
+// Load the aggregate price from a Pyth price account, rejecting anything that is not a live, fresh and
+// reasonably certain price.
+pub fn load_price(price_feed: &AccountInfo, now: i64) -> Result<PythPrice> {
+    // Ensure the account belongs to the Pyth oracle program.
+    require_keys_eq!(*price_feed.owner, PYTH_PROGRAM_ID, AuctionError::InvalidPriceFeed);
+    let data = price_feed.try_borrow_data()?;
+    require!(data.len() >= PRICE_ACCOUNT_MIN_LEN, AuctionError::InvalidPriceFeed);
+
+    // Ensure the account is a Pyth v2 price account.
+    require!(read_u32(&data, 0) == MAGIC, AuctionError::InvalidPriceFeed);
+    require!(read_u32(&data, 4) == VERSION_2, AuctionError::InvalidPriceFeed);
+    require!(read_u32(&data, 8) == ACCOUNT_TYPE_PRICE, AuctionError::InvalidPriceFeed);
+
+    // Read the aggregate price.
+    let price = PythPrice {
+        price: read_u64(&data, AGG_PRICE_OFFSET) as i64,
+        conf: read_u64(&data, AGG_CONF_OFFSET),
+        expo: read_u32(&data, EXPO_OFFSET) as i32,
+        publish_time: read_u64(&data, TIMESTAMP_OFFSET) as i64,
+    };
+
+    // Ensure the price is currently trading, positive and recent.
+    require!(
+        read_u32(&data, AGG_STATUS_OFFSET) == STATUS_TRADING && price.price > 0,
+        AuctionError::PriceUnavailable
+    );
+    require!(
+        now.saturating_sub(price.publish_time) <= MAX_PRICE_AGE_SEC,
+        AuctionError::StalePrice
+    );
+    // Ensure the confidence interval is narrow enough to settle against.
+    require!(
+        price.conf as u128 * crate::BASIS_POINTS as u128
+            <= price.price as u128 * MAX_CONFIDENCE_BPS as u128,
+        AuctionError::PriceTooUncertain
+    );
+
+    Ok(price)
+}
+
+// Check whether an amount of tokens is worth at least a USD amount (in cents), valuing the tokens at
+// the bottom of the confidence interval so the reserve is only met with certainty.
+pub fn meets_usd_reserve(
+    price: &PythPrice,
+    amount: u64,
+    decimals: u8,
+    reserve_usd_cents: u64,
+) -> Result<bool> {
+    // The lowest price the token could plausibly be trading at.
+    let low_price = (price.price as u64).saturating_sub(price.conf) as u128;
+    // The amount's value in cents is amount * low_price * 100 / 10^(decimals - expo).
+    let value = (amount as u128)
+        .checked_mul(low_price)
+        .and_then(|value| value.checked_mul(100))
+        .ok_or(AuctionError::NumericalOverflow)?;
+    let scale = decimals as i32 - price.expo;
+    if scale >= 0 {
+        let reserve = 10u128
+            .checked_pow(scale as u32)
+            .and_then(|power| power.checked_mul(reserve_usd_cents as u128))
+            .ok_or(AuctionError::NumericalOverflow)?;
+        Ok(value >= reserve)
+    } else {
+        let value = 10u128
+            .checked_pow(scale.unsigned_abs())
+            .and_then(|power| power.checked_mul(value))
+            .ok_or(AuctionError::NumericalOverflow)?;
+        Ok(value >= reserve_usd_cents as u128)
+    }
+}
+
+// Read a little-endian u32 at an offset.
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+// Read a little-endian u64 at an offset.
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_error::ProgramError;
+
+    // Check whether a result failed with an auction error.
+    fn failed_with<T>(result: Result<T>, error: AuctionError) -> bool {
+        match result {
+            Ok(_) => false,
+            Err(err) => ProgramError::from(err) == ProgramError::from(Error::from(error)),
+        }
+    }
+
+    // Build the data of a v2 price account of a price and confidence at an exponent, published at a time.
+    fn price_account(price: i64, conf: u64, expo: i32, publish_time: i64, status: u32) -> Vec<u8> {
+        let mut data = vec![0; PRICE_ACCOUNT_MIN_LEN];
+        data[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&VERSION_2.to_le_bytes());
+        data[8..12].copy_from_slice(&ACCOUNT_TYPE_PRICE.to_le_bytes());
+        data[EXPO_OFFSET..EXPO_OFFSET + 4].copy_from_slice(&expo.to_le_bytes());
+        data[TIMESTAMP_OFFSET..TIMESTAMP_OFFSET + 8].copy_from_slice(&publish_time.to_le_bytes());
+        data[AGG_PRICE_OFFSET..AGG_PRICE_OFFSET + 8].copy_from_slice(&price.to_le_bytes());
+        data[AGG_CONF_OFFSET..AGG_CONF_OFFSET + 8].copy_from_slice(&conf.to_le_bytes());
+        data[AGG_STATUS_OFFSET..AGG_STATUS_OFFSET + 4].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    // Load the price from an account with some data, owned by a program.
+    fn load(mut data: Vec<u8>, owner: Pubkey, now: i64) -> Result<PythPrice> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let price_feed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        load_price(&price_feed, now)
+    }
+
+    #[test]
+    fn load_price_reads_the_aggregate_price() {
+        let price = load(price_account(2_000, 10, -2, 1_000, STATUS_TRADING), PYTH_PROGRAM_ID, 1_060).unwrap();
+        assert_eq!(price.price, 2_000);
+        assert_eq!(price.conf, 10);
+        assert_eq!(price.expo, -2);
+        assert_eq!(price.publish_time, 1_000);
+    }
+
+    #[test]
+    fn load_price_rejects_other_accounts() {
+        let account = || price_account(2_000, 10, -2, 1_000, STATUS_TRADING);
+        assert!(failed_with(load(account(), Pubkey::new_unique(), 1_000), AuctionError::InvalidPriceFeed));
+        assert!(failed_with(
+            load(account()[..PRICE_ACCOUNT_MIN_LEN - 1].to_vec(), PYTH_PROGRAM_ID, 1_000),
+            AuctionError::InvalidPriceFeed
+        ));
+        for offset in [0, 4, 8] {
+            let mut data = account();
+            data[offset] ^= 1;
+            assert!(failed_with(load(data, PYTH_PROGRAM_ID, 1_000), AuctionError::InvalidPriceFeed));
+        }
+    }
+
+    #[test]
+    fn load_price_rejects_halted_stale_and_uncertain_prices() {
+        let load_at = |data, now| load(data, PYTH_PROGRAM_ID, now);
+        // A halted feed, or one without a positive price.
+        assert!(failed_with(
+            load_at(price_account(2_000, 10, -2, 1_000, 2), 1_000),
+            AuctionError::PriceUnavailable
+        ));
+        assert!(failed_with(
+            load_at(price_account(-2_000, 10, -2, 1_000, STATUS_TRADING), 1_000),
+            AuctionError::PriceUnavailable
+        ));
+        // A price older than the maximum age.
+        assert!(failed_with(
+            load_at(price_account(2_000, 10, -2, 1_000, STATUS_TRADING), 1_000 + MAX_PRICE_AGE_SEC + 1),
+            AuctionError::StalePrice
+        ));
+        // A confidence interval just past the maximum, 2% of the price.
+        assert!(load_at(price_account(2_000, 40, -2, 1_000, STATUS_TRADING), 1_000).is_ok());
+        assert!(failed_with(
+            load_at(price_account(2_000, 41, -2, 1_000, STATUS_TRADING), 1_000),
+            AuctionError::PriceTooUncertain
+        ));
+    }
+
+    #[test]
+    fn meets_usd_reserve_values_the_amount_at_the_bottom_of_the_confidence_interval() {
+        // 1.5 tokens of 6 decimals at $20.00 are worth $30.00.
+        let price = PythPrice { price: 2_000, conf: 0, expo: -2, publish_time: 0 };
+        assert!(meets_usd_reserve(&price, 1_500_000, 6, 3_000).unwrap());
+        assert!(!meets_usd_reserve(&price, 1_500_000, 6, 3_001).unwrap());
+        // At $19.90, the bottom of the confidence interval, they are worth $29.85.
+        let price = PythPrice { conf: 10, ..price };
+        assert!(meets_usd_reserve(&price, 1_500_000, 6, 2_985).unwrap());
+        assert!(!meets_usd_reserve(&price, 1_500_000, 6, 2_986).unwrap());
+    }
+
+    #[test]
+    fn meets_usd_reserve_scales_positive_exponents() {
+        // 2 tokens without decimals at 5 * 10^1 dollars are worth $100.00.
+        let price = PythPrice { price: 5, conf: 0, expo: 1, publish_time: 0 };
+        assert!(meets_usd_reserve(&price, 2, 0, 10_000).unwrap());
+        assert!(!meets_usd_reserve(&price, 2, 0, 10_001).unwrap());
+        // An exponent past what the value can be scaled by overflows rather than wrapping.
+        let price = PythPrice { expo: 39, ..price };
+        assert!(failed_with(meets_usd_reserve(&price, 2, 0, 1), AuctionError::NumericalOverflow));
+    }
+}
@@ -0,0 +1,69 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the Metaplex metadata reader.
+use crate::metadata::{self, TOKEN_METADATA_PROGRAM_ID};
+// Import the error type, the royalty override account and the seeds of the auction program.
+use crate::{
+    AuctionError, AuctionHouse, RoyaltyOverride, FEATURE_ROYALTIES, ROYALTY_OVERRIDE_SEED, ROYALTY_VAULT_SEED,
+};
+
+// Define the Royalty struct to represent the royalty owed to an NFT's creators on its sale.
+pub struct Royalty {
+    // The royalty in basis points of the proceeds, after the house's override for the collection.
+    pub royalty_bps: u16,
+    // The creators owed a share of the royalty, and their shares in percent, in metadata order.
+    pub creators: Vec<(Pubkey, u8)>,
+}
+
+// Derive the royalty override PDA of a collection.
+pub fn royalty_override_address(collection: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ROYALTY_OVERRIDE_SEED, collection.as_ref()], &crate::ID).0
+}
+
+// Derive the royalty vault PDA of a payment mint, which holds the royalties escrowed at settlement until
+// distribute_royalties pays them to the creators.
+pub fn royalty_vault_address(payment_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ROYALTY_VAULT_SEED, payment_mint.as_ref()], &crate::ID).0
+}
+
+// Load the royalty owed on the sale of an NFT: the seller fee of its metadata, overridden or capped by the
+// house for its verified collection, shared between its creators. An NFT without metadata, on its primary
+// sale or without creators owes none, and so does any NFT while the house has royalties turned off. The royalty override must be the PDA of the NFT's verified
+// collection, and only applies if it holds an override; for an NFT outside a verified collection it is
+// not read.
+pub fn load_royalty(
+    auction_house: &AuctionHouse,
+    nft_metadata: &AccountInfo,
+    nft_mint: &Pubkey,
+    royalty_override: &AccountInfo,
+) -> Result<Option<Royalty>> {
+    if !auction_house.enabled(FEATURE_ROYALTIES) || *nft_metadata.owner != TOKEN_METADATA_PROGRAM_ID {
+        return Ok(None);
+    }
+    let metadata = metadata::load_metadata(nft_metadata, nft_mint)?;
+    let creators = metadata
+        .creators
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|creator| creator.share > 0)
+        .map(|creator| (creator.address, creator.share))
+        .collect::<Vec<_>>();
+    if !metadata.primary_sale_happened || creators.is_empty() {
+        return Ok(None);
+    }
+
+    // Apply the house's override for the NFT's verified collection, if it has one.
+    let mut royalty_bps = metadata.seller_fee_basis_points;
+    if let Some(collection) = metadata.collection.filter(|collection| collection.verified) {
+        require_keys_eq!(
+            royalty_override.key(),
+            royalty_override_address(&collection.key),
+            AuctionError::RoyaltyOverrideMismatch
+        );
+        if *royalty_override.owner == crate::ID {
+            let royalty_override = Account::<RoyaltyOverride>::try_from(royalty_override)?;
+            royalty_bps = royalty_override.apply(royalty_bps);
+        }
+    }
+    Ok(Some(Royalty { royalty_bps, creators }))
+}
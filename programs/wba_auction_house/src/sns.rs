@@ -0,0 +1,57 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction type and the CPI helper from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Define the ID of the Solana Name Service program, which owns domain name accounts.
+pub const NAME_SERVICE_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+
+// Define the length of the header every name account starts with: the parent name, the owner and the
+// class, followed by the name's data.
+pub const NAME_RECORD_HEADER_LEN: usize = 96;
+
+// Define the index of the name service program's Transfer instruction.
+const TRANSFER: u8 = 2;
+
+// Read the owner of a domain name account. The account must belong to the name service program and have
+// no class, since a class would have to sign every transfer alongside the owner.
+pub fn name_owner(name_account: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(*name_account.owner, NAME_SERVICE_PROGRAM_ID, AuctionError::InvalidNameAccount);
+    let data = name_account.try_borrow_data()?;
+    require!(data.len() >= NAME_RECORD_HEADER_LEN, AuctionError::InvalidNameAccount);
+    require!(data[64..96] == Pubkey::default().to_bytes(), AuctionError::InvalidNameAccount);
+    Ok(Pubkey::try_from_slice(&data[32..64])?)
+}
+
+// Build the name service program instruction transferring a name account to a new owner, signed by its
+// current owner.
+pub fn transfer_ix(name_account: Pubkey, owner: Pubkey, new_owner: Pubkey) -> Instruction {
+    let mut data = vec![TRANSFER];
+    data.extend_from_slice(new_owner.as_ref());
+    Instruction {
+        program_id: NAME_SERVICE_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(name_account, false), AccountMeta::new_readonly(owner, true)],
+        data,
+    }
+}
+
+// Transfer a name account to a new owner through the name service program. The current owner signs,
+// either as a signer of the transaction or, for the PDA, through the signer seeds.
+pub fn transfer_name<'info>(
+    name_account: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    new_owner: &Pubkey,
+    name_service_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_signed(
+        &transfer_ix(name_account.key(), owner.key(), *new_owner),
+        &[name_account.clone(), owner.clone(), name_service_program.clone()],
+        signers_seeds,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the stake program's instructions and account state, and the CPI helper, from the solana_program
+// library.
+use anchor_lang::solana_program::borsh::try_from_slice_unchecked;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake::instruction::authorize;
+use anchor_lang::solana_program::stake::state::{Meta, StakeAuthorize, StakeState};
+// Import the error type of the auction program.
+use crate::AuctionError;
+
+// Re-export the ID of the stake program, which owns stake accounts.
+pub use anchor_lang::solana_program::stake::program::ID as STAKE_PROGRAM_ID;
+
+// Load the authorities and lockup of a stake account. The account must belong to the stake program and be
+// initialized, whether or not it is delegated.
+pub fn load_stake_meta(stake_account: &AccountInfo) -> Result<Meta> {
+    require_keys_eq!(*stake_account.owner, STAKE_PROGRAM_ID, AuctionError::InvalidStakeAccount);
+    let data = stake_account.try_borrow_data()?;
+    match try_from_slice_unchecked::<StakeState>(&data) {
+        Ok(StakeState::Initialized(meta)) | Ok(StakeState::Stake(meta, _)) => Ok(meta),
+        _ => err!(AuctionError::InvalidStakeAccount),
+    }
+}
+
+// Hand both the staker and the withdrawer authority of a stake account to a new authority through the
+// stake program. The current authority signs, either as a signer of the transaction or, for the PDA,
+// through the signer seeds. The withdrawer can only change while the lockup is not in force.
+pub fn authorize_both<'info>(
+    stake_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    new_authority: &Pubkey,
+    clock: &AccountInfo<'info>,
+    stake_program: &AccountInfo<'info>,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for stake_authorize in [StakeAuthorize::Staker, StakeAuthorize::Withdrawer] {
+        invoke_signed(
+            &authorize(&stake_account.key(), &authority.key(), new_authority, stake_authorize, None),
+            &[stake_account.clone(), clock.clone(), authority.clone(), stake_program.clone()],
+            signers_seeds,
+        )?;
+    }
+    Ok(())
+}
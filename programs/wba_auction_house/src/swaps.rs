@@ -0,0 +1,64 @@
+// Import necessary modules from the anchor_lang library.
+use anchor_lang::prelude::*;
+// Import the instruction type from the solana_program library.
+use anchor_lang::solana_program::instruction::Instruction;
+// Import the trait encoding instruction arguments.
+use anchor_lang::InstructionData;
+// Import the positions of the bid's accounts.
+use crate::bid_pools::{BID_BIDDER_FT_ACCOUNT_INDEX, BID_BIDDER_INDEX};
+// Import the error type and the generated instructions of the auction program.
+use crate::{instruction, AuctionError};
+
+// Define the ID of the Jupiter aggregator program, which routes a swap through the best pools.
+pub const JUPITER_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+// Build the aggregator instruction from its accounts, passed to bid_with_swap as remaining accounts, and
+// the route data its API quoted. The accounts keep the signer and writable flags they have in the
+// transaction, so the swap can only spend what the bidder signed for.
+pub fn swap_ix(accounts: &[AccountInfo], data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: JUPITER_PROGRAM_ID,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    }
+}
+
+// Build the bid instruction placed through a self-CPI once the swap is done, from the bid's accounts. The
+// bidder and the bidder's FT account must be the ones the swap paid into.
+pub fn swap_bid_ix(
+    bidder: &Pubkey,
+    bidder_ft_account: &Pubkey,
+    accounts: &[AccountInfo],
+    price: u64,
+    referrer: Option<Pubkey>,
+    memo: String,
+    affiliate: Option<Pubkey>,
+) -> Result<Instruction> {
+    require!(accounts.len() > BID_BIDDER_FT_ACCOUNT_INDEX, AuctionError::SwapAccountMismatch);
+    require_keys_eq!(accounts[BID_BIDDER_INDEX].key(), *bidder, AuctionError::SwapAccountMismatch);
+    require_keys_eq!(
+        accounts[BID_BIDDER_FT_ACCOUNT_INDEX].key(),
+        *bidder_ft_account,
+        AuctionError::SwapAccountMismatch
+    );
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: instruction::Bid { price, referrer, memo, affiliate }.data(),
+    })
+}
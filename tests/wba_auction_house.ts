@@ -15,7 +15,9 @@ import {
   createAccount,
   createInitializeAccountInstruction,
   createMint,
+  createWrappedNativeAccount,
   mintTo,
+  NATIVE_MINT,
   setAuthority,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
@@ -34,7 +36,6 @@ describe("anchor-auction", () => {
   const exhibitorAccount = anchor.web3.Keypair.generate();
   const bidder1Account = anchor.web3.Keypair.generate();
   const bidder2Account = anchor.web3.Keypair.generate();
-  const escrowAccount = anchor.web3.Keypair.generate();
 
   let nftMintPubkey: PublicKey;
   let ftMintPubkey: PublicKey;
@@ -42,6 +43,7 @@ describe("anchor-auction", () => {
   let exhibitorFtTokenAccountPubkey: PublicKey;
   let bidder1FtTokenAccountPubkey: PublicKey;
   let bidder2FtTokenAccountPubkey: PublicKey;
+  let treasuryFtTokenAccountPubkey: PublicKey;
   it("Setup", async () => {
     await connection.requestAirdrop(
       exhibitorAccount.publicKey,
@@ -167,6 +169,16 @@ describe("anchor-auction", () => {
       TOKEN_PROGRAM_ID
     );
 
+    treasuryFtTokenAccountPubkey = await createAccount(
+      connection,
+      payerAccount,
+      ftMintPubkey,
+      payerAccount.publicKey,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
     // sleep to allow time to update
     await new Promise((resolve) => setTimeout(resolve, 1000));
 
@@ -196,24 +208,137 @@ describe("anchor-auction", () => {
     console.table(data);
   });
 
+  let auctionHousePubkey: PublicKey;
+  let registryPubkey: PublicKey;
+  let escrowAccountPubkey: PublicKey;
+  let eventAuthorityPubkey: PublicKey;
+  let listingReceiptPubkey: PublicKey;
+
+  const blacklistEntry = async function (mintPubkey: PublicKey) {
+    const [blacklistEntryPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("blacklist"), mintPubkey.toBuffer()],
+      program.programId
+    );
+    return blacklistEntryPubkey;
+  };
+
+  it("Initialize registry", async () => {
+    [eventAuthorityPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
+    [registryPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("registry")],
+      program.programId
+    );
+    const signature = await program.rpc.initializeRegistry({
+      accounts: {
+        payer: payerAccount.publicKey,
+        registry: registryPubkey,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+    console.log(`initialize registry tx = ${signature}`);
+  });
+
+  it("Initialize house", async () => {
+    [auctionHousePubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("auction_house")],
+      program.programId
+    );
+    // Only the upgrade authority, the local wallet that deployed the program, can initialize the house.
+    const [programData] = await PublicKey.findProgramAddress(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    const signature = await program.rpc.initializeHouse(0, 0, new anchor.BN(0), new anchor.BN(0), {
+      accounts: {
+        authority: payerAccount.publicKey,
+        program: program.programId,
+        programData,
+        treasury: payerAccount.publicKey,
+        auctionHouse: auctionHousePubkey,
+        systemProgram: SystemProgram.programId,
+      },
+    });
+    console.log(`initialize house tx = ${signature}`);
+  });
+
   let exhibitorNftTempAccount = anchor.web3.Keypair.generate();
   const initialPrice = 200;
   const duration = 10;
 
   // transaction fee payer is local wallet
   it("Exhibit", async () => {
+    const registry = await program.account.registry.fetch(registryPubkey);
+    const auctionId = registry.auctionCount.addn(1);
+    [escrowAccountPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("auction"), auctionId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [listingReceiptPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("listing_receipt"), escrowAccountPubkey.toBuffer()],
+      program.programId
+    );
     const signature = await program.rpc.exhibit(
       new anchor.BN(initialPrice),
       new anchor.BN(duration),
+      [],
+      null,
+      0,
+      new anchor.BN(0),
+      PublicKey.default,
+      { timestamp: {} },
+      "",
+      "",
+      { escrow: {} },
+      null,
+      false,
       {
         accounts: {
           exhibitor: exhibitorAccount.publicKey,
+          payer: exhibitorAccount.publicKey,
           exhibitorNftTokenAccount: exhibitorNftTokenAccountPubkey,
           exhibitorNftTempAccount: exhibitorNftTempAccount.publicKey,
           exhibitorFtReceivingAccount: exhibitorFtTokenAccountPubkey,
-          escrowAccount: escrowAccount.publicKey,
+          registry: registryPubkey,
+          escrowAccount: escrowAccountPubkey,
+          listingReceipt: listingReceiptPubkey,
+          auctionHouse: auctionHousePubkey,
+          nftBlacklistEntry: await blacklistEntry(nftMintPubkey),
+          paymentBlacklistEntry: await blacklistEntry(ftMintPubkey),
+          sellerAllowlistEntry: (
+            await PublicKey.findProgramAddress(
+              [
+                Buffer.from("seller_allowlist"),
+                exhibitorAccount.publicKey.toBuffer(),
+              ],
+              program.programId
+            )
+          )[0],
+          nftMint: nftMintPubkey,
+          nftMetadata: (
+            await PublicKey.findProgramAddress(
+              [
+                Buffer.from("metadata"),
+                new PublicKey(
+                  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+                ).toBuffer(),
+                nftMintPubkey.toBuffer(),
+              ],
+              new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s")
+            )
+          )[0],
+          treasury: payerAccount.publicKey,
+          pda: (
+            await PublicKey.findProgramAddress(
+              [Buffer.from("escrow")],
+              program.programId
+            )
+          )[0],
           clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         },
         instructions: [
           ...(await accountInstructions(
@@ -222,16 +347,15 @@ describe("anchor-auction", () => {
             exhibitorNftTempAccount.publicKey,
             exhibitorAccount.publicKey
           )),
-          await program.account.auction.createInstruction(escrowAccount),
         ],
-        signers: [exhibitorAccount, exhibitorNftTempAccount, escrowAccount],
+        signers: [exhibitorAccount, exhibitorNftTempAccount],
       }
     );
     console.log(`exhibit tx = ${signature}`);
 
     await new Promise((resolve) => setTimeout(resolve, 1500));
 
-    await logAuction(connection, escrowAccount.publicKey, program);
+    await logAuction(connection, escrowAccountPubkey, program);
     assert.equal(
       await getTokenBalance(exhibitorNftTokenAccountPubkey, connection),
       0
@@ -250,24 +374,43 @@ describe("anchor-auction", () => {
   ) {
     const bidderFtTempAccountKeypair = anchor.web3.Keypair.generate();
     const auction = await program.account.auction.fetch(
-      escrowAccount.publicKey
+      escrowAccountPubkey
     );
     const pda = await PublicKey.findProgramAddress(
       [Buffer.from("escrow")],
       program.programId
     );
-    const signature = await program.rpc.bid(new anchor.BN(price), {
+    const [bidReceiptPubkey] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from("bid_receipt"),
+        escrowAccountPubkey.toBuffer(),
+        bidder.publicKey.toBuffer(),
+        auction.bidCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const signature = await program.rpc.bid(new anchor.BN(price), null, "", null, {
       accounts: {
         bidder: bidder.publicKey,
+        payer: bidder.publicKey,
         bidderFtTempAccount: bidderFtTempAccountKeypair.publicKey,
         bidderFtAccount: bidderFtPubkey,
-        highestBidder: auction.highestBidderPubkey,
-        highestBidderFtTempAccount: auction.highestBidderFtTempPubkey,
-        highestBidderFtReturningAccount: auction.highestBidderFtReturningPubkey,
-        escrowAccount: escrowAccount.publicKey,
+        highestBidder: auction.highestBidder,
+        highestBidderFtTempAccount: auction.highestBidderFtTempAccount,
+        highestBidderFtReturningAccount: auction.highestBidderFtReturningAccount,
+        highestBidderRentPayer: auction.highestBidderRentPayer,
+        escrowAccount: escrowAccountPubkey,
         clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        bidReceipt: bidReceiptPubkey,
+        nftBlacklistEntry: await blacklistEntry(auction.nftMint),
+        paymentBlacklistEntry: await blacklistEntry(auction.paymentMint),
+        auctionHouse: auctionHousePubkey,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
         pda: pda[0],
         tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        eventAuthority: eventAuthorityPubkey,
+        program: program.programId,
       },
       instructions: [
         ...(await accountInstructions(
@@ -280,8 +423,22 @@ describe("anchor-auction", () => {
       signers: [bidder, bidderFtTempAccountKeypair],
     });
     console.log(`bidder tx = ${signature}`);
+    // Catch regressions in the hottest instruction's compute cost.
+    const unitsConsumed = await computeUnitsConsumed(
+      connection,
+      signature,
+      program.programId
+    );
+    console.log(`bid compute units = ${unitsConsumed}`);
+    assert.ok(
+      unitsConsumed <= BID_COMPUTE_UNIT_BUDGET,
+      `bid consumed ${unitsConsumed} compute units`
+    );
     await new Promise((resolve) => setTimeout(resolve, 1000));
-    await logAuction(connection, escrowAccount.publicKey, program);
+    await logAuction(connection, escrowAccountPubkey, program);
+    const bidReceipt = await program.account.bidReceipt.fetch(bidReceiptPubkey);
+    assert.equal(bidReceipt.price.toNumber(), price);
+    assert.ok(bidReceipt.bidder.equals(bidder.publicKey));
     assert.equal(
       await getTokenBalance(bidderFtTempAccountKeypair.publicKey, connection),
       price
@@ -317,26 +474,60 @@ describe("anchor-auction", () => {
   it("Receive", async () => {
     await new Promise((resolve) => setTimeout(resolve, (duration - 3) * 1000));
     const auction = await program.account.auction.fetch(
-      escrowAccount.publicKey
+      escrowAccountPubkey
     );
     const pda = await PublicKey.findProgramAddress(
       [Buffer.from("escrow")],
       program.programId
     );
     const winningBidderNftReceivingAccount = anchor.web3.Keypair.generate();
+    const [purchaseReceiptPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("purchase_receipt"), escrowAccountPubkey.toBuffer()],
+      program.programId
+    );
+    const [houseStatsPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("house_stats"), ftMintPubkey.toBuffer()],
+      program.programId
+    );
+    const metadataProgramId = new PublicKey(
+      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    );
+    const [nftMetadataPubkey] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        metadataProgramId.toBuffer(),
+        nftMintPubkey.toBuffer(),
+      ],
+      metadataProgramId
+    );
     const signature = await program.rpc.close({
       accounts: {
-        winningBidder: auction.highestBidderPubkey,
-        exhibitor: auction.exhibitorPubkey,
-        exhibitorNftTempAccount: auction.exhibitingNftTempPubkey,
-        exhibitorFtReceivingAccount: auction.exhibitorFtReceivingPubkey,
-        highestBidderFtTempAccount: auction.highestBidderFtTempPubkey,
+        winningBidder: auction.highestBidder,
+        exhibitor: auction.exhibitor,
+        exhibitorRentPayer: auction.exhibitorRentPayer,
+        highestBidderRentPayer: auction.highestBidderRentPayer,
+        exhibitorNftTempAccount: auction.exhibitorNftTempAccount,
+        exhibitorFtReceivingAccount: auction.exhibitorFtReceivingAccount,
+        highestBidderFtTempAccount: auction.highestBidderFtTempAccount,
         highestBidderNftReceivingAccount:
           winningBidderNftReceivingAccount.publicKey,
-        escrowAccount: escrowAccount.publicKey,
+        auctionHouse: auctionHousePubkey,
+        treasuryFtAccount: treasuryFtTokenAccountPubkey,
+        paymentMint: ftMintPubkey,
+        priceFeed: PublicKey.default,
+        nftMetadata: nftMetadataPubkey,
+        royaltyOverride: PublicKey.default,
+        collectionStats: PublicKey.default,
+        houseStats: houseStatsPubkey,
+        escrowAccount: escrowAccountPubkey,
         clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
         pda: pda[0],
         tokenProgram: TOKEN_PROGRAM_ID,
+        listingReceipt: listingReceiptPubkey,
+        purchaseReceipt: purchaseReceiptPubkey,
+        systemProgram: SystemProgram.programId,
+        eventAuthority: eventAuthorityPubkey,
+        program: program.programId,
       },
       instructions: [
         ...(await accountInstructions(
@@ -352,6 +543,17 @@ describe("anchor-auction", () => {
 
     await new Promise((resolve) => setTimeout(resolve, 1500));
 
+    const listingReceipt = await program.account.listingReceipt.fetch(
+      listingReceiptPubkey
+    );
+    assert.ok(listingReceipt.winner.equals(bidder2Account.publicKey));
+    assert.ok(listingReceipt.closedAt.toNumber() > 0);
+    const purchaseReceipt = await program.account.purchaseReceipt.fetch(
+      purchaseReceiptPubkey
+    );
+    assert.ok(purchaseReceipt.buyer.equals(bidder2Account.publicKey));
+    assert.ok(purchaseReceipt.price.eq(auction.price));
+
     const data = {
       NFT: {
         exhibitor: await getTokenBalance(
@@ -403,11 +605,362 @@ describe("anchor-auction", () => {
     );
     assert.ok(
       isNaN(
-        await getTokenBalance(auction.highestBidderFtTempPubkey, connection)
+        await getTokenBalance(auction.highestBidderFtTempAccount, connection)
+      )
+    );
+    assert.ok(
+      isNaN(await getTokenBalance(auction.exhibitorNftTempAccount, connection))
+    );
+  });
+
+  // A second auction paid in wrapped SOL, bid on in lamports, with a quarter of the proceeds paid out.
+  const solNftTempAccount = anchor.web3.Keypair.generate();
+  const payoutRecipientAccount = anchor.web3.Keypair.generate();
+  const solPrice = LAMPORTS_PER_SOL / 100;
+  const payoutShareBps = 2_500;
+  let solNftMintPubkey: PublicKey;
+  let solEscrowAccountPubkey: PublicKey;
+  let solListingReceiptPubkey: PublicKey;
+  let exhibitorWsolAccountPubkey: PublicKey;
+  let payoutWsolAccountPubkey: PublicKey;
+  let treasuryWsolAccountPubkey: PublicKey;
+
+  it("Exhibit for wSOL", async () => {
+    solNftMintPubkey = await createMint(
+      connection,
+      payerAccount,
+      payerAccount.publicKey,
+      null,
+      0,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const exhibitorSolNftAccountPubkey = await createAccount(
+      connection,
+      payerAccount,
+      solNftMintPubkey,
+      exhibitorAccount.publicKey,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      payerAccount,
+      solNftMintPubkey,
+      exhibitorSolNftAccountPubkey,
+      payerAccount,
+      1,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    exhibitorWsolAccountPubkey = await createWrappedNativeAccount(
+      connection,
+      payerAccount,
+      exhibitorAccount.publicKey,
+      0
+    );
+    payoutWsolAccountPubkey = await createWrappedNativeAccount(
+      connection,
+      payerAccount,
+      payoutRecipientAccount.publicKey,
+      0
+    );
+    treasuryWsolAccountPubkey = await createWrappedNativeAccount(
+      connection,
+      payerAccount,
+      payerAccount.publicKey,
+      0
+    );
+
+    const registry = await program.account.registry.fetch(registryPubkey);
+    const auctionId = registry.auctionCount.addn(1);
+    [solEscrowAccountPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("auction"), auctionId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [solListingReceiptPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("listing_receipt"), solEscrowAccountPubkey.toBuffer()],
+      program.programId
+    );
+    const metadataProgramId = new PublicKey(
+      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    );
+    const signature = await program.rpc.exhibit(
+      new anchor.BN(solPrice),
+      new anchor.BN(duration),
+      [
+        {
+          recipientFtPubkey: payoutWsolAccountPubkey,
+          shareBps: payoutShareBps,
+        },
+      ],
+      null,
+      0,
+      new anchor.BN(0),
+      PublicKey.default,
+      { timestamp: {} },
+      "",
+      "",
+      { escrow: {} },
+      null,
+      false,
+      {
+        accounts: {
+          exhibitor: exhibitorAccount.publicKey,
+          payer: exhibitorAccount.publicKey,
+          exhibitorNftTokenAccount: exhibitorSolNftAccountPubkey,
+          exhibitorNftTempAccount: solNftTempAccount.publicKey,
+          exhibitorFtReceivingAccount: exhibitorWsolAccountPubkey,
+          registry: registryPubkey,
+          escrowAccount: solEscrowAccountPubkey,
+          listingReceipt: solListingReceiptPubkey,
+          auctionHouse: auctionHousePubkey,
+          nftBlacklistEntry: await blacklistEntry(solNftMintPubkey),
+          paymentBlacklistEntry: await blacklistEntry(NATIVE_MINT),
+          sellerAllowlistEntry: (
+            await PublicKey.findProgramAddress(
+              [
+                Buffer.from("seller_allowlist"),
+                exhibitorAccount.publicKey.toBuffer(),
+              ],
+              program.programId
+            )
+          )[0],
+          nftMint: solNftMintPubkey,
+          nftMetadata: (
+            await PublicKey.findProgramAddress(
+              [
+                Buffer.from("metadata"),
+                metadataProgramId.toBuffer(),
+                solNftMintPubkey.toBuffer(),
+              ],
+              metadataProgramId
+            )
+          )[0],
+          treasury: payerAccount.publicKey,
+          pda: (
+            await PublicKey.findProgramAddress(
+              [Buffer.from("escrow")],
+              program.programId
+            )
+          )[0],
+          clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts: [
+          {
+            pubkey: payoutWsolAccountPubkey,
+            isSigner: false,
+            isWritable: false,
+          },
+        ],
+        instructions: [
+          ...(await accountInstructions(
+            connection,
+            solNftMintPubkey,
+            solNftTempAccount.publicKey,
+            exhibitorAccount.publicKey
+          )),
+        ],
+        signers: [exhibitorAccount, solNftTempAccount],
+      }
+    );
+    console.log(`exhibit for wSOL tx = ${signature}`);
+
+    await new Promise((resolve) => setTimeout(resolve, 1500));
+
+    const auction = await program.account.auction.fetch(solEscrowAccountPubkey);
+    assert.ok(auction.paymentMint.equals(NATIVE_MINT));
+    assert.equal(auction.payoutCount, 1);
+    assert.ok(
+      auction.payouts[0].recipientFtPubkey.equals(payoutWsolAccountPubkey)
+    );
+    assert.equal(auction.payouts[0].shareBps, payoutShareBps);
+  });
+
+  const solBidder = async function (
+    price: number,
+    bidder: anchor.web3.Keypair
+  ) {
+    const bidderWsolTempAccountKeypair = anchor.web3.Keypair.generate();
+    const auction = await program.account.auction.fetch(solEscrowAccountPubkey);
+    const [bidReceiptPubkey] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from("bid_receipt"),
+        solEscrowAccountPubkey.toBuffer(),
+        bidder.publicKey.toBuffer(),
+        auction.bidCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const signature = await program.rpc.bidWithSol(new anchor.BN(price), null, "", null, {
+      accounts: {
+        bidder: bidder.publicKey,
+        payer: bidder.publicKey,
+        bidderFtTempAccount: bidderWsolTempAccountKeypair.publicKey,
+        highestBidder: auction.highestBidder,
+        highestBidderFtTempAccount: auction.highestBidderFtTempAccount,
+        escrowAccount: solEscrowAccountPubkey,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        bidReceipt: bidReceiptPubkey,
+        nftBlacklistEntry: await blacklistEntry(auction.nftMint),
+        paymentBlacklistEntry: await blacklistEntry(NATIVE_MINT),
+        auctionHouse: auctionHousePubkey,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        pda: (
+          await PublicKey.findProgramAddress(
+            [Buffer.from("escrow")],
+            program.programId
+          )
+        )[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        eventAuthority: eventAuthorityPubkey,
+        program: program.programId,
+      },
+      instructions: [
+        ...(await accountInstructions(
+          connection,
+          NATIVE_MINT,
+          bidderWsolTempAccountKeypair.publicKey,
+          bidder.publicKey
+        )),
+      ],
+      signers: [bidder, bidderWsolTempAccountKeypair],
+    });
+    console.log(`bid with SOL tx = ${signature}`);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await logAuction(connection, solEscrowAccountPubkey, program);
+    // The lamports were wrapped into the temporary wSOL account.
+    assert.equal(
+      await getTokenBalance(bidderWsolTempAccountKeypair.publicKey, connection),
+      price
+    );
+  };
+
+  it("Bid with SOL", async () => {
+    await solBidder(solPrice + 1, bidder1Account);
+  });
+
+  it("Outbid with SOL", async () => {
+    const bidder1Lamports = await connection.getBalance(
+      bidder1Account.publicKey
+    );
+    const auction = await program.account.auction.fetch(solEscrowAccountPubkey);
+    await solBidder(solPrice + 2, bidder2Account);
+    // The outbid bid is unwrapped back to bidder1 as lamports, with the temporary account's rent.
+    assert.ok(
+      isNaN(
+        await getTokenBalance(auction.highestBidderFtTempAccount, connection)
       )
     );
     assert.ok(
-      isNaN(await getTokenBalance(auction.exhibitingNftTempPubkey, connection))
+      (await connection.getBalance(bidder1Account.publicKey)) >=
+        bidder1Lamports + solPrice + 1
+    );
+  });
+
+  it("Receive wSOL", async () => {
+    await new Promise((resolve) => setTimeout(resolve, (duration - 2) * 1000));
+    const auction = await program.account.auction.fetch(solEscrowAccountPubkey);
+    const winningBidderNftReceivingAccount = anchor.web3.Keypair.generate();
+    const [purchaseReceiptPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("purchase_receipt"), solEscrowAccountPubkey.toBuffer()],
+      program.programId
+    );
+    const [houseStatsPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("house_stats"), NATIVE_MINT.toBuffer()],
+      program.programId
+    );
+    const metadataProgramId = new PublicKey(
+      "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+    );
+    const [nftMetadataPubkey] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        metadataProgramId.toBuffer(),
+        solNftMintPubkey.toBuffer(),
+      ],
+      metadataProgramId
+    );
+    const signature = await program.rpc.close({
+      accounts: {
+        winningBidder: auction.highestBidder,
+        exhibitor: auction.exhibitor,
+        exhibitorRentPayer: auction.exhibitorRentPayer,
+        highestBidderRentPayer: auction.highestBidderRentPayer,
+        exhibitorNftTempAccount: auction.exhibitorNftTempAccount,
+        exhibitorFtReceivingAccount: auction.exhibitorFtReceivingAccount,
+        highestBidderFtTempAccount: auction.highestBidderFtTempAccount,
+        highestBidderNftReceivingAccount:
+          winningBidderNftReceivingAccount.publicKey,
+        auctionHouse: auctionHousePubkey,
+        treasuryFtAccount: treasuryWsolAccountPubkey,
+        paymentMint: NATIVE_MINT,
+        priceFeed: PublicKey.default,
+        nftMetadata: nftMetadataPubkey,
+        royaltyOverride: PublicKey.default,
+        collectionStats: PublicKey.default,
+        houseStats: houseStatsPubkey,
+        escrowAccount: solEscrowAccountPubkey,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        pda: (
+          await PublicKey.findProgramAddress(
+            [Buffer.from("escrow")],
+            program.programId
+          )
+        )[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        listingReceipt: solListingReceiptPubkey,
+        purchaseReceipt: purchaseReceiptPubkey,
+        systemProgram: SystemProgram.programId,
+        eventAuthority: eventAuthorityPubkey,
+        program: program.programId,
+      },
+      // The payout recipient's wSOL account comes first among the settlement accounts.
+      remainingAccounts: [
+        { pubkey: payoutWsolAccountPubkey, isSigner: false, isWritable: true },
+      ],
+      instructions: [
+        ...(await accountInstructions(
+          connection,
+          solNftMintPubkey,
+          winningBidderNftReceivingAccount.publicKey,
+          bidder2Account.publicKey
+        )),
+      ],
+      signers: [bidder2Account, winningBidderNftReceivingAccount],
+    });
+    console.log(`receive wSOL tx = ${signature}`);
+
+    await new Promise((resolve) => setTimeout(resolve, 1500));
+
+    // The winning bid is split between the payout recipient and the exhibitor, who keeps any dust.
+    const winningBid = solPrice + 2;
+    const payoutShare = Math.floor((winningBid * payoutShareBps) / 10_000);
+    assert.equal(
+      await getTokenBalance(
+        winningBidderNftReceivingAccount.publicKey,
+        connection
+      ),
+      1
+    );
+    assert.equal(
+      await getTokenBalance(payoutWsolAccountPubkey, connection),
+      payoutShare
+    );
+    assert.equal(
+      await getTokenBalance(exhibitorWsolAccountPubkey, connection),
+      winningBid - payoutShare
+    );
+    assert.ok(
+      isNaN(
+        await getTokenBalance(auction.highestBidderFtTempAccount, connection)
+      )
     );
   });
 });
@@ -436,6 +989,29 @@ async function accountInstructions(
   return [createAccount, initAccount];
 }
 
+// The most compute units a bid may consume, a first bid and an outbidding one alike.
+const BID_COMPUTE_UNIT_BUDGET = 80_000;
+
+// Read the compute units a program's top-level invocation consumed in a transaction from its logs. The
+// program's own self-CPIs log their consumption first, so the last line is the top-level one.
+async function computeUnitsConsumed(
+  connection: Connection,
+  signature: string,
+  programId: PublicKey
+) {
+  const tx = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+  });
+  const pattern = new RegExp(
+    `^Program ${programId.toBase58()} consumed (\\d+) of \\d+ compute units$`
+  );
+  const units = tx.meta.logMessages
+    .map((line) => line.match(pattern))
+    .filter((match) => match !== null)
+    .map((match) => parseInt(match[1]));
+  return units[units.length - 1];
+}
+
 const getTokenBalance = async (pubkey: PublicKey, connection: Connection) => {
   try {
     return parseInt(
@@ -454,16 +1030,16 @@ async function logAuction(
 ) {
   const auction = await program.account.auction.fetch(escrowPubkey);
   console.table({
-    exhibitorPubkey: auction.exhibitorPubkey.toBase58(),
-    exhibitingNftTempPubkey: auction.exhibitingNftTempPubkey.toBase58(),
-    exhibitorFtReceivingPubkey: auction.exhibitorFtReceivingPubkey.toBase58(),
+    exhibitor: auction.exhibitor.toBase58(),
+    exhibitorNftTempAccount: auction.exhibitorNftTempAccount.toBase58(),
+    exhibitorFtReceivingAccount: auction.exhibitorFtReceivingAccount.toBase58(),
     price: new anchor.BN(auction.price, 10, "le").toNumber(),
     endAt: new Date(
       new anchor.BN(auction.endAt, 10, "le").toNumber() * 1000
     ).toISOString(),
-    highestBidderPubkey: auction.highestBidderPubkey.toBase58(),
-    highestBidderFtTempPubkey: auction.highestBidderFtTempPubkey.toBase58(),
-    highestBidderFtReturningPubkey:
-      auction.highestBidderFtReturningPubkey.toBase58(),
+    highestBidder: auction.highestBidder.toBase58(),
+    highestBidderFtTempAccount: auction.highestBidderFtTempAccount.toBase58(),
+    highestBidderFtReturningAccount:
+      auction.highestBidderFtReturningAccount.toBase58(),
   });
 }